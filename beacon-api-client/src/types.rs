@@ -14,6 +14,8 @@ use ethereum_consensus::{
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt, str::FromStr};
 
+pub use ethereum_consensus::deposit_tree::DepositTreeSnapshot as DepositSnapshot;
+
 #[derive(Serialize, Deserialize)]
 pub struct VersionData {
     pub version: String,
@@ -34,17 +36,6 @@ pub struct DepositContract {
     pub address: ExecutionAddress,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct DepositSnapshot {
-    pub finalized: Vec<Hash32>,
-    pub deposit_root: Hash32,
-    #[serde(with = "crate::serde::as_str")]
-    pub deposit_count: u64,
-    pub execution_block_hash: Hash32,
-    #[serde(with = "crate::serde::as_str")]
-    pub execution_block_height: u64,
-}
-
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct GenesisDetails {
     #[serde(with = "crate::serde::as_str")]
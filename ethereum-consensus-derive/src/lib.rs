@@ -0,0 +1,101 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Derives `Prove` for a SSZ container from the order and names of its fields.
+///
+/// Each field is one chunk of the container so the generalized index of a field follows from its
+/// position, matching the layout `Merkleized` uses for the container. A newtype with a single
+/// unnamed field is proven as that field.
+#[proc_macro_derive(Prove)]
+pub fn derive_prove(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return syn::Error::new_spanned(name, "`Prove` can only be derived for structs")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    // NOTE: paths are absolute so the expansion does not depend on what is in scope where it is
+    // used
+    let prove = quote!(crate::ssz::prelude::Prove);
+    let merkleized = quote!(crate::ssz::prelude::Merkleized);
+    let path_element = quote!(crate::ssz::prelude::PathElement);
+    let node = quote!(crate::ssz::prelude::Node);
+    let error = quote!(crate::ssz::prelude::MerkleizationError);
+    let result = quote!(::core::result::Result);
+    let option = quote!(::core::option::Option);
+    let vec = quote!(::std::vec::Vec);
+
+    let body = match fields {
+        Fields::Named(fields) => {
+            let idents = fields.named.iter().flat_map(|field| &field.ident).collect::<Vec<_>>();
+            let names = idents.iter().map(|ident| ident.to_string()).collect::<Vec<_>>();
+            let indices = (0..idents.len()).collect::<Vec<_>>();
+            let count = idents.len();
+            quote! {
+                fn chunk_count(&self) -> usize {
+                    #count
+                }
+
+                fn chunks(&mut self) -> #result<#vec<#node>, #error> {
+                    #result::Ok(::std::vec![#(#merkleized::hash_tree_root(&mut self.#idents)?),*])
+                }
+
+                fn chunk_index(&self, element: &#path_element) -> #option<usize> {
+                    match element {
+                        #(#path_element::Field(#names) => #option::Some(#indices),)*
+                        _ => #option::None,
+                    }
+                }
+
+                fn child(&mut self, index: usize) -> #option<&mut dyn #prove> {
+                    match index {
+                        #(#indices => #option::Some(&mut self.#idents),)*
+                        _ => #option::None,
+                    }
+                }
+            }
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+            fn chunk_count(&self) -> usize {
+                #prove::chunk_count(&self.0)
+            }
+
+            fn chunks(&mut self) -> #result<#vec<#node>, #error> {
+                #prove::chunks(&mut self.0)
+            }
+
+            fn length(&self) -> #option<usize> {
+                #prove::length(&self.0)
+            }
+
+            fn chunk_index(&self, element: &#path_element) -> #option<usize> {
+                #prove::chunk_index(&self.0, element)
+            }
+
+            fn child(&mut self, index: usize) -> #option<&mut dyn #prove> {
+                #prove::child(&mut self.0, index)
+            }
+        },
+        _ => return syn::Error::new_spanned(
+            name,
+            "`Prove` can only be derived for structs with named fields or a single unnamed field",
+        )
+        .to_compile_error()
+        .into(),
+    };
+
+    let expanded = quote! {
+        impl #impl_generics #prove for #name #ty_generics #where_clause {
+            #body
+        }
+    };
+    expanded.into()
+}
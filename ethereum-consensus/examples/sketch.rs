@@ -16,7 +16,13 @@ fn main() {
     let previous_epoch = phase0::get_previous_epoch(&state, &context);
     dbg!(previous_epoch);
 
-    let _ = phase0::state_transition(&mut state, &mut signed_block, Validation::Enabled, &context);
+    let _ = phase0::state_transition(
+        &mut state,
+        &mut signed_block,
+        Validation::enabled(),
+        &mut (),
+        &context,
+    );
     dbg!(state.fork);
 
     // altair transition
@@ -28,7 +34,13 @@ fn main() {
     let current_epoch = altair::get_current_epoch(&state, &context);
     dbg!(current_epoch);
 
-    let _ = altair::state_transition(&mut state, &mut signed_block, Validation::Enabled, &context);
+    let _ = altair::state_transition(
+        &mut state,
+        &mut signed_block,
+        Validation::enabled(),
+        &mut (),
+        &context,
+    );
     dbg!(state.fork);
 
     // bellatrix transition
@@ -45,7 +57,8 @@ fn main() {
         &mut state,
         &mut signed_block,
         &execution_engine,
-        Validation::Enabled,
+        Validation::enabled(),
+        &mut (),
         &context,
     );
     dbg!(state.fork);
@@ -1,6 +1,8 @@
 use ethereum_consensus::{
     altair::mainnet as altair,
     bellatrix::mainnet as bellatrix,
+    capella::mainnet as capella,
+    deneb::mainnet as deneb,
     phase0::mainnet as phase0,
     ssz::prelude::*,
     state_transition::mainnet::{Context, ExecutionEngine, Executor},
@@ -13,7 +15,11 @@ fn main() -> std::result::Result<(), Box<dyn Error>> {
 
     let genesis_state = phase0::BeaconState::default();
     let context = Context::for_mainnet();
-    let execution_engine = ExecutionEngine::Bellatrix(bellatrix::DefaultExecutionEngine::default());
+    let execution_engine = ExecutionEngine::new(
+        bellatrix::DefaultExecutionEngine::default(),
+        capella::DefaultExecutionEngine::default(),
+        deneb::DefaultExecutionEngine::default(),
+    );
     let mut executor = Executor::new(genesis_state.into(), execution_engine, context);
 
     let mut block = phase0::SignedBeaconBlock::default();
@@ -28,7 +34,15 @@ fn main() -> std::result::Result<(), Box<dyn Error>> {
     block.message.slot = executor.context.bellatrix_fork_epoch * executor.context.slots_per_epoch;
     executor.apply_block(&mut block.into())?;
 
-    let mut state = executor.state.bellatrix().unwrap();
+    let mut block = capella::SignedBeaconBlock::default();
+    block.message.slot = executor.context.capella_fork_epoch * executor.context.slots_per_epoch;
+    executor.apply_block(&mut block.into())?;
+
+    let mut block = deneb::SignedBeaconBlock::default();
+    block.message.slot = executor.context.deneb_fork_epoch * executor.context.slots_per_epoch;
+    executor.apply_block(&mut block.into())?;
+
+    let mut state = executor.state.deneb().unwrap();
     let state_root = state.hash_tree_root()?;
     dbg!(state_root);
     Ok(())
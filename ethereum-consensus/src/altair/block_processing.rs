@@ -14,26 +14,26 @@ use crate::{
             add_flag, get_attestation_participation_flag_indices, get_base_reward_per_increment,
             has_flag,
         },
-        increase_balance, is_valid_indexed_attestation, process_block_header, process_eth1_data,
-        process_operations, process_randao,
+        increase_balance, is_valid_indexed_attestation, process_attester_slashing,
+        process_block_header, process_eth1_data, process_operations, process_proposer_slashing,
+        process_randao, process_voluntary_exit,
         sync::SyncAggregate,
         Attestation, Deposit, DepositMessage, DEPOSIT_CONTRACT_TREE_DEPTH,
     },
-    crypto::{eth_fast_aggregate_verify, verify_signature},
+    crypto::verify_signature,
     domains::DomainType,
     error::{
         invalid_operation_error, InvalidAttestation, InvalidDeposit, InvalidOperation,
         InvalidSyncAggregate,
     },
-    primitives::{BlsPublicKey, ParticipationFlags, ValidatorIndex},
+    primitives::{ParticipationFlags, ValidatorIndex},
+    rewards::{BlockRewards, SyncCommitteeReward},
     signing::compute_signing_root,
     ssz::prelude::*,
-    state_transition::{Context, Result},
-};
-use std::{
-    collections::{HashMap, HashSet},
-    iter::zip,
+    state_transition::{Context, Event, Observer, Result, SignatureSets, SignatureStrategy},
+    Error,
 };
+use std::{collections::HashSet, iter::zip};
 
 pub fn process_attestation<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -56,6 +56,7 @@ pub fn process_attestation<
         SYNC_COMMITTEE_SIZE,
     >,
     attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let data = &attestation.data;
@@ -124,6 +125,7 @@ pub fn process_attestation<
     is_valid_indexed_attestation(
         state,
         &mut get_indexed_attestation(state, attestation, context)?,
+        signatures,
         context,
     )?;
 
@@ -180,6 +182,7 @@ pub fn process_deposit<
         SYNC_COMMITTEE_SIZE,
     >,
     deposit: &mut Deposit,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let branch = deposit
@@ -201,9 +204,15 @@ pub fn process_deposit<
 
     let public_key = &deposit.data.public_key;
     let amount = deposit.data.amount;
-    let validator_public_keys: HashSet<&BlsPublicKey> =
-        HashSet::from_iter(state.validators.iter().map(|v| &v.public_key));
-    if !validator_public_keys.contains(public_key) {
+    if let Some(index) = context.pubkey_cache.get_index(public_key, &state.validators) {
+        increase_balance(state, index, amount);
+        observer.on_event(&Event::DepositApplied {
+            index,
+            public_key: public_key.clone(),
+            amount,
+            is_new_validator: false,
+        });
+    } else {
         let mut deposit_message = DepositMessage {
             public_key: public_key.clone(),
             withdrawal_credentials: deposit.data.withdrawal_credentials.clone(),
@@ -216,15 +225,18 @@ pub fn process_deposit<
             // NOTE: explicitly return with no error and also no further mutations to `state`
             return Ok(())
         }
+        let index = state.validators.len();
         state.validators.push(get_validator_from_deposit(deposit, context));
         state.balances.push(amount);
         state.previous_epoch_participation.push(ParticipationFlags::default());
         state.current_epoch_participation.push(ParticipationFlags::default());
-        state.inactivity_scores.push(0)
-    } else {
-        let index = state.validators.iter().position(|v| &v.public_key == public_key).unwrap();
-
-        increase_balance(state, index, amount);
+        state.inactivity_scores.push(0);
+        observer.on_event(&Event::DepositApplied {
+            index,
+            public_key: public_key.clone(),
+            amount,
+            is_new_validator: true,
+        });
     }
 
     Ok(())
@@ -251,13 +263,23 @@ pub fn process_sync_aggregate<
         SYNC_COMMITTEE_SIZE,
     >,
     sync_aggregate: &SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
+    let mut committee_indices: Vec<ValidatorIndex> = Vec::default();
+    for public_key in state.current_sync_committee.public_keys.iter() {
+        committee_indices.push(
+            context
+                .pubkey_cache
+                .get_index(public_key, &state.validators)
+                .expect("validator public_key should exist"),
+        );
+    }
+
     // Verify sync committee aggregate signature signing over the previous slot block root
-    let committee_public_keys = &state.current_sync_committee.public_keys;
-    let participant_public_keys =
-        zip(committee_public_keys.iter(), sync_aggregate.sync_committee_bits.iter())
-            .filter_map(|(public_key, bit)| if *bit { Some(public_key) } else { None })
+    let participant_indices =
+        zip(committee_indices.iter(), sync_aggregate.sync_committee_bits.iter())
+            .filter_map(|(index, bit)| if *bit { Some(*index) } else { None })
             .collect::<Vec<_>>();
     let previous_slot = u64::max(state.slot, 1) - 1;
     let domain = get_domain(
@@ -268,20 +290,20 @@ pub fn process_sync_aggregate<
     )?;
     let mut root_at_slot = *get_block_root_at_slot(state, previous_slot)?;
     let signing_root = compute_signing_root(&mut root_at_slot, domain)?;
-    if eth_fast_aggregate_verify(
-        participant_public_keys.as_slice(),
+    let signature_set = context.pubkey_cache.aggregate_signature_set(
+        &state.validators,
+        &participant_indices,
         signing_root.as_ref(),
         &sync_aggregate.sync_committee_signature,
-    )
-    .is_err()
-    {
-        return Err(invalid_operation_error(InvalidOperation::SyncAggregate(
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::SyncAggregate(
             InvalidSyncAggregate::InvalidSignature {
                 signature: sync_aggregate.sync_committee_signature.clone(),
                 root: signing_root,
             },
-        )))
-    }
+        ))
+    })?;
 
     // Compute participant and proposer rewards
     let total_active_increments =
@@ -295,17 +317,6 @@ pub fn process_sync_aggregate<
         participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
 
     // Apply participant and proposer rewards
-    let all_public_keys = state
-        .validators
-        .iter()
-        .enumerate()
-        .map(|(i, v)| (&v.public_key, i))
-        .collect::<HashMap<&BlsPublicKey, usize>>();
-    let mut committee_indices: Vec<ValidatorIndex> = Vec::default();
-    for public_key in state.current_sync_committee.public_keys.iter() {
-        committee_indices
-            .push(*all_public_keys.get(public_key).expect("validator public_key should exist"));
-    }
     for (participant_index, participation_bit) in
         zip(committee_indices.iter(), sync_aggregate.sync_committee_bits.iter())
     {
@@ -354,12 +365,159 @@ pub fn process_block<
         MAX_VOLUNTARY_EXITS,
         SYNC_COMMITTEE_SIZE,
     >,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     process_block_header(state, block, context)?;
-    process_randao(state, &block.body, context)?;
+    process_randao(state, &block.body, signatures, context)?;
     process_eth1_data(state, &block.body, context);
-    process_operations(state, &mut block.body, context)?;
-    process_sync_aggregate(state, &block.body.sync_aggregate, context)?;
+    process_operations(state, &mut block.body, signatures, observer, context)?;
+    process_sync_aggregate(state, &block.body.sync_aggregate, signatures, context)?;
     Ok(())
 }
+
+// Return the rewards paid to the proposer of `block` split by the operations that pay them, along
+// with the rewards and penalties of each sync committee member for its participation in the
+// `SyncAggregate`. The eth1 vote and each operation are applied to `state` in the order of
+// `process_block`, with `state` expected to be at the slot of `block`, measuring balances around
+// them.
+pub fn get_block_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    context: &Context,
+) -> Result<(BlockRewards, Vec<SyncCommitteeReward>)> {
+    let proposer_index = block.proposer_index;
+    let body = &mut block.body;
+    let signatures = &mut SignatureSets::new(SignatureStrategy::Skip);
+    let mut rewards = BlockRewards { proposer_index, ..Default::default() };
+    process_eth1_data(state, body, context);
+    for op in body.proposer_slashings.iter_mut() {
+        let balance = state.balances[proposer_index];
+        process_proposer_slashing(state, op, signatures, &mut (), context)?;
+        rewards.proposer_slashings += state.balances[proposer_index].saturating_sub(balance);
+    }
+    for op in body.attester_slashings.iter_mut() {
+        let balance = state.balances[proposer_index];
+        process_attester_slashing(state, op, signatures, &mut (), context)?;
+        rewards.attester_slashings += state.balances[proposer_index].saturating_sub(balance);
+    }
+    for op in body.attestations.iter() {
+        let balance = state.balances[proposer_index];
+        process_attestation(state, op, signatures, context)?;
+        rewards.attestations += state.balances[proposer_index].saturating_sub(balance);
+    }
+    // Deposits and exits do not pay the proposer but precede the `SyncAggregate`
+    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, &mut (), context))?;
+    body.voluntary_exits
+        .iter_mut()
+        .try_for_each(|op| process_voluntary_exit(state, op, signatures, &mut (), context))?;
+
+    let sync_committee_rewards =
+        get_sync_aggregate_rewards(state, &body.sync_aggregate, &mut rewards, signatures, context)?;
+    rewards.total = rewards.attestations +
+        rewards.sync_aggregate +
+        rewards.proposer_slashings +
+        rewards.attester_slashings;
+    Ok((rewards, sync_committee_rewards))
+}
+
+// Apply `sync_aggregate` to `state`, recording the reward of the proposer in `rewards`, and return
+// the rewards and penalties of each sync committee member for its participation.
+pub fn get_sync_aggregate_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    sync_aggregate: &SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    rewards: &mut BlockRewards,
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<Vec<SyncCommitteeReward>> {
+    let proposer_index = rewards.proposer_index;
+    let mut committee_indices: Vec<ValidatorIndex> = Vec::default();
+    for public_key in state.current_sync_committee.public_keys.iter() {
+        let index = context
+            .pubkey_cache
+            .get_index(public_key, &state.validators)
+            .ok_or_else(|| Error::UnknownValidator(public_key.clone()))?;
+        committee_indices.push(index);
+    }
+    let total_active_increments =
+        get_total_active_balance(state, context)? / context.effective_balance_increment;
+    let total_base_rewards =
+        get_base_reward_per_increment(state, context)? * total_active_increments;
+    let max_participant_rewards =
+        total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR / context.slots_per_epoch;
+    let participant_reward = max_participant_rewards / context.sync_committee_size as u64;
+    let proposer_reward =
+        participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
+    let participant_count =
+        sync_aggregate.sync_committee_bits.iter().filter(|bit| **bit).count() as u64;
+    rewards.sync_aggregate = proposer_reward * participant_count;
+
+    let mut members = Vec::new();
+    for index in committee_indices {
+        if !members.iter().any(|(member, _)| *member == index) {
+            members.push((index, state.balances[index]));
+        }
+    }
+    process_sync_aggregate(state, sync_aggregate, signatures, context)?;
+    let sync_committee_rewards = members
+        .into_iter()
+        .map(|(validator_index, balance)| {
+            let mut reward = state.balances[validator_index] as i64 - balance as i64;
+            if validator_index == proposer_index {
+                reward -= rewards.sync_aggregate as i64;
+            }
+            SyncCommitteeReward { validator_index, reward }
+        })
+        .collect();
+    Ok(sync_committee_rewards)
+}
@@ -0,0 +1,325 @@
+use crate::{
+    altair::{
+        beacon_block::BeaconBlock,
+        beacon_state::BeaconState,
+        constants::{PARTICIPATION_FLAG_WEIGHTS, SYNC_COMMITTEE_SUBNET_COUNT},
+        get_attesting_indices, get_base_reward_per_increment, get_beacon_proposer_index,
+        get_block_root_at_slot, get_current_epoch,
+        helpers::{get_attestation_participation_flag_indices, has_flag},
+        process_attestation, process_block_header, process_eth1_data, process_randao,
+        process_slots, process_sync_aggregate, select_operations,
+        sync::SyncAggregate,
+        validator::SyncCommitteeContribution,
+        Attestation, AttesterSlashing, Deposit, Eth1Data, ProposerSlashing, SignedVoluntaryExit,
+    },
+    crypto::aggregate,
+    primitives::{BlsSignature, Bytes32, Slot},
+    ssz::prelude::*,
+    state_transition::{Context, Result, SignatureSets},
+};
+use std::collections::HashSet;
+
+/// The candidate operations to include in a block, in order of preference.
+#[derive(Default, Debug, Clone)]
+pub struct BlockOperations<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub attestations: Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub deposits: Vec<Deposit>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
+    pub sync_committee_contributions: Vec<SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>,
+    // The vote to make instead of repeating the current vote of the state
+    pub eth1_data: Option<Eth1Data>,
+}
+
+/// Selects up to `MAX_ATTESTATIONS` of the candidate `attestations` for a block on top of `state`,
+/// greedily taking the attestation that sets the participation flags worth the most base reward
+/// that are not yet set in `state` or by the attestations selected so far.
+///
+/// Each selected attestation is checked with `process_attestation` against a copy of `state`;
+/// invalid candidates are skipped.
+pub fn select_attestations<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    attestations: &[Attestation<MAX_VALIDATORS_PER_COMMITTEE>],
+    context: &Context,
+) -> Result<Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>> {
+    let current_epoch = get_current_epoch(state, context);
+    // NOTE: `get_base_reward` recomputes the total active balance for each validator
+    let base_reward_per_increment = get_base_reward_per_increment(state, context)?;
+    let mut candidates = vec![];
+    for attestation in attestations {
+        let data = &attestation.data;
+        if data.slot + context.min_attestation_inclusion_delay > state.slot {
+            continue
+        }
+        let inclusion_delay = state.slot - data.slot;
+        let participation_flag_indices =
+            match get_attestation_participation_flag_indices(state, data, inclusion_delay, context)
+            {
+                Ok(participation_flag_indices) => participation_flag_indices,
+                Err(_) => continue,
+            };
+        let attesting_indices =
+            match get_attesting_indices(state, data, &attestation.aggregation_bits, context) {
+                Ok(attesting_indices) => attesting_indices,
+                Err(_) => continue,
+            };
+        let epoch_participation = if data.target.epoch == current_epoch {
+            &state.current_epoch_participation
+        } else {
+            &state.previous_epoch_participation
+        };
+        let mut cover = vec![];
+        for index in attesting_indices {
+            for flag_index in &participation_flag_indices {
+                if !has_flag(epoch_participation[index], *flag_index) {
+                    let increments = state.validators[index].effective_balance /
+                        context.effective_balance_increment;
+                    let weight = increments *
+                        base_reward_per_increment *
+                        PARTICIPATION_FLAG_WEIGHTS[*flag_index];
+                    cover.push(((data.target.epoch, index, *flag_index), weight));
+                }
+            }
+        }
+        candidates.push((attestation, cover));
+    }
+
+    let mut state = state.clone();
+    let mut covered = HashSet::new();
+    let mut selected = vec![];
+    let signatures = &mut SignatureSets::default();
+    while selected.len() < context.max_attestations {
+        let mut best = None;
+        let mut best_weight = 0;
+        for (i, (_, cover)) in candidates.iter().enumerate() {
+            let weight = cover
+                .iter()
+                .filter(|(key, _)| !covered.contains(key))
+                .map(|(_, weight)| weight)
+                .sum::<u64>();
+            if weight > best_weight {
+                best = Some(i);
+                best_weight = weight;
+            }
+        }
+        let i = match best {
+            Some(i) => i,
+            None => break,
+        };
+        let (attestation, cover) = candidates.remove(i);
+        if process_attestation(&mut state, attestation, signatures, context).is_ok() {
+            covered.extend(cover.into_iter().map(|(key, _)| key));
+            selected.push(attestation.clone());
+        }
+    }
+    Ok(selected)
+}
+
+/// Aggregates the `contributions` for the block root at the slot before `state.slot`, taking the
+/// contribution with the most participants for each subcommittee.
+pub fn get_sync_aggregate<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    contributions: &[SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>],
+    context: &Context,
+) -> Result<SyncAggregate<SYNC_COMMITTEE_SIZE>> {
+    let previous_slot = u64::max(state.slot, 1) - 1;
+    let root = get_block_root_at_slot(state, previous_slot)?;
+    let subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+
+    let mut best_contributions: Vec<Option<&SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>> =
+        vec![None; SYNC_COMMITTEE_SUBNET_COUNT];
+    for contribution in contributions {
+        let subcommittee_index = contribution.subcommittee_index as usize;
+        if contribution.slot != previous_slot ||
+            &contribution.beacon_block_root != root ||
+            subcommittee_index >= SYNC_COMMITTEE_SUBNET_COUNT
+        {
+            continue
+        }
+        let participants = contribution.aggregation_bits.count_ones();
+        let is_better = match best_contributions[subcommittee_index] {
+            Some(best) => best.aggregation_bits.count_ones() < participants,
+            None => true,
+        };
+        if is_better {
+            best_contributions[subcommittee_index] = Some(contribution);
+        }
+    }
+
+    let mut sync_aggregate = SyncAggregate::<SYNC_COMMITTEE_SIZE>::default();
+    let mut signatures = vec![];
+    for (subcommittee_index, contribution) in best_contributions.into_iter().enumerate() {
+        if let Some(contribution) = contribution {
+            for (i, bit) in contribution.aggregation_bits.iter().enumerate() {
+                if *bit {
+                    sync_aggregate
+                        .sync_committee_bits
+                        .set(subcommittee_index * subcommittee_size + i, true);
+                }
+            }
+            signatures.push(contribution.signature.clone());
+        }
+    }
+    sync_aggregate.sync_committee_signature =
+        if signatures.is_empty() { BlsSignature::infinity() } else { aggregate(&signatures)? };
+    Ok(sync_aggregate)
+}
+
+/// Applies the aggregate of the `contributions` to `state` as in `process_sync_aggregate` and
+/// returns it. If the aggregate signature is invalid, an empty aggregate is applied instead.
+pub fn select_sync_aggregate<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    contributions: &[SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>],
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<SyncAggregate<SYNC_COMMITTEE_SIZE>> {
+    let sync_aggregate = get_sync_aggregate(state, contributions, context)?;
+    if process_sync_aggregate(state, &sync_aggregate, signatures, context).is_ok() {
+        return Ok(sync_aggregate)
+    }
+    let sync_aggregate =
+        SyncAggregate { sync_committee_signature: BlsSignature::infinity(), ..Default::default() };
+    process_sync_aggregate(state, &sync_aggregate, signatures, context)?;
+    Ok(sync_aggregate)
+}
+
+/// Produces an unsigned block for `slot` on top of `state`, including as many of the candidate
+/// `operations` as are valid. The `eth1_data` of the block is the vote in `operations`, if any, or
+/// repeats the current vote of `state`.
+///
+/// The signature of each candidate is verified so that candidates with invalid signatures are
+/// skipped.
+pub fn produce_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+> {
+    let mut state = state.clone();
+    if state.slot < slot {
+        process_slots(&mut state, slot, &mut (), context)?;
+    }
+
+    let mut block = BeaconBlock {
+        slot,
+        proposer_index: get_beacon_proposer_index(&state, context)?,
+        parent_root: state.latest_block_header.hash_tree_root()?,
+        ..Default::default()
+    };
+    block.body.randao_reveal = randao_reveal;
+    block.body.eth1_data = operations.eth1_data.clone().unwrap_or_else(|| state.eth1_data.clone());
+    block.body.graffiti = graffiti;
+
+    let signatures = &mut SignatureSets::default();
+    process_block_header(&mut state, &mut block, context)?;
+    process_randao(&mut state, &block.body, signatures, context)?;
+    process_eth1_data(&mut state, &block.body, context);
+    select_operations(&mut state, &mut block.body, operations, signatures, context)?;
+    block.body.sync_aggregate = select_sync_aggregate(
+        &mut state,
+        &operations.sync_committee_contributions,
+        signatures,
+        context,
+    )?;
+
+    // NOTE: the header was processed before the operations were selected
+    state.latest_block_header.body_root = block.body.hash_tree_root()?;
+    block.state_root = state.hash_tree_root()?;
+    Ok(block)
+}
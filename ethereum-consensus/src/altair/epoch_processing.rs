@@ -1,21 +1,29 @@
 use crate::{
     altair::{
-        constants::{PARTICIPATION_FLAG_WEIGHTS, TIMELY_TARGET_FLAG_INDEX},
+        constants::{
+            PARTICIPATION_FLAG_WEIGHTS, TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX,
+            TIMELY_TARGET_FLAG_INDEX, WEIGHT_DENOMINATOR,
+        },
         decrease_balance, get_current_epoch, get_eligible_validator_indices,
         get_inactivity_penalty_deltas, get_previous_epoch, get_total_active_balance,
-        get_total_balance,
+        get_total_balance, get_validator_churn_limit, has_flag,
         helpers::{
             get_base_reward_per_increment, get_flag_index_deltas, get_next_sync_committee,
             get_unslashed_participating_indices,
         },
-        increase_balance, is_in_inactivity_leak, process_effective_balance_updates,
-        process_eth1_data_reset, process_historical_roots_update, process_randao_mixes_reset,
-        process_registry_updates, process_slashings_reset, weigh_justification_and_finalization,
-        BeaconState,
+        increase_balance, is_active_validator, is_in_inactivity_leak, process_activation_queue,
+        process_effective_balance_updates, process_eth1_data_reset,
+        process_historical_roots_update, process_justification_and_finalization_with_balances,
+        process_randao_mixes_reset, process_registry_updates, process_slashings_reset,
+        process_validator_updates, weigh_justification_and_finalization, BeaconState,
+        ProgressiveBalances, SinglePassParameters,
     },
     primitives::{Gwei, ParticipationFlags, ValidatorIndex, GENESIS_EPOCH},
-    state_transition::{Context, Result},
+    rewards::{AttestationRewards, IdealAttestationRewards, TotalAttestationRewards},
+    state_transition::{Context, Event, Observer, Result},
+    Error,
 };
+use integer_sqrt::IntegerSquareRoot;
 use std::mem;
 
 // Return the base reward for the validator defined by `index` with respect to the current `state`
@@ -67,6 +75,7 @@ pub fn process_justification_and_finalization<
         MAX_VALIDATORS_PER_COMMITTEE,
         SYNC_COMMITTEE_SIZE,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     // Initial FFG checkpoint values have a `0x00` stub for `root`.
@@ -97,6 +106,7 @@ pub fn process_justification_and_finalization<
         total_active_balance,
         previous_target_balance,
         current_target_balance,
+        observer,
         context,
     )
 }
@@ -198,6 +208,99 @@ pub fn process_rewards_and_penalties<
     Ok(())
 }
 
+// Return the attestation rewards and penalties for each validator, as applied by
+// `process_rewards_and_penalties` to `state`, along with the ideal rewards for each effective
+// balance.
+pub fn get_attestation_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    context: &Context,
+) -> Result<AttestationRewards> {
+    let validator_count = state.validators.len();
+    if get_current_epoch(state, context) == GENESIS_EPOCH {
+        let total_rewards = (0..validator_count)
+            .map(|validator_index| TotalAttestationRewards {
+                validator_index,
+                ..Default::default()
+            })
+            .collect();
+        return Ok(AttestationRewards { ideal_rewards: vec![], total_rewards })
+    }
+
+    let previous_epoch = get_previous_epoch(state, context);
+    let increment = context.effective_balance_increment;
+    let base_reward_per_increment = get_base_reward_per_increment(state, context)?;
+    let active_increments = get_total_active_balance(state, context)? / increment;
+    let in_inactivity_leak = is_in_inactivity_leak(state, context);
+    let mut participating_increments = [0; PARTICIPATION_FLAG_WEIGHTS.len()];
+    let mut deltas = Vec::with_capacity(PARTICIPATION_FLAG_WEIGHTS.len());
+    for (flag_index, increments) in participating_increments.iter_mut().enumerate() {
+        let participating_indices =
+            get_unslashed_participating_indices(state, flag_index, previous_epoch, context)?;
+        *increments = get_total_balance(state, &participating_indices, context)? / increment;
+        deltas.push(get_flag_index_deltas(state, flag_index, context)?);
+    }
+    let (_, inactivity_penalties) = get_inactivity_penalty_deltas(state, context)?;
+
+    let ideal_rewards = (1..=context.max_effective_balance / increment)
+        .map(|increments| {
+            let base_reward = increments * base_reward_per_increment;
+            let flag_reward = |flag_index: usize| {
+                if in_inactivity_leak {
+                    0
+                } else {
+                    let reward_numerator = base_reward *
+                        PARTICIPATION_FLAG_WEIGHTS[flag_index] *
+                        participating_increments[flag_index];
+                    reward_numerator / (active_increments * WEIGHT_DENOMINATOR)
+                }
+            };
+            IdealAttestationRewards {
+                effective_balance: increments * increment,
+                head: flag_reward(TIMELY_HEAD_FLAG_INDEX),
+                target: flag_reward(TIMELY_TARGET_FLAG_INDEX),
+                source: flag_reward(TIMELY_SOURCE_FLAG_INDEX),
+                inclusion_delay: None,
+                inactivity: 0,
+            }
+        })
+        .collect();
+
+    let flag_delta = |flag_index: usize, i: ValidatorIndex| {
+        let (rewards, penalties) = &deltas[flag_index];
+        rewards[i] as i64 - penalties[i] as i64
+    };
+    let total_rewards = (0..validator_count)
+        .map(|i| TotalAttestationRewards {
+            validator_index: i,
+            head: flag_delta(TIMELY_HEAD_FLAG_INDEX, i),
+            target: flag_delta(TIMELY_TARGET_FLAG_INDEX, i),
+            source: flag_delta(TIMELY_SOURCE_FLAG_INDEX, i),
+            inclusion_delay: None,
+            inactivity: -(inactivity_penalties[i] as i64),
+        })
+        .collect();
+
+    Ok(AttestationRewards { ideal_rewards, total_rewards })
+}
+
 pub fn process_participation_flag_updates<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -290,6 +393,7 @@ pub fn process_sync_committee_updates<
         MAX_VALIDATORS_PER_COMMITTEE,
         SYNC_COMMITTEE_SIZE,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let next_epoch = get_current_epoch(state, context) + 1;
@@ -298,6 +402,7 @@ pub fn process_sync_committee_updates<
         let current_sync_committee =
             mem::replace(&mut state.next_sync_committee, next_sync_committee);
         state.current_sync_committee = current_sync_committee;
+        observer.on_event(&Event::SyncCommitteeRotated { epoch: next_epoch });
     }
     Ok(())
 }
@@ -322,19 +427,238 @@ pub fn process_epoch<
         MAX_VALIDATORS_PER_COMMITTEE,
         SYNC_COMMITTEE_SIZE,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_justification_and_finalization(state, context)?;
+    process_justification_and_finalization(state, observer, context)?;
     process_inactivity_updates(state, context)?;
     process_rewards_and_penalties(state, context)?;
-    process_registry_updates(state, context);
+    process_registry_updates(state, observer, context);
     process_slashings(state, context)?;
     process_eth1_data_reset(state, context);
-    process_effective_balance_updates(state, context);
+    process_effective_balance_updates(state, observer, context);
+    process_slashings_reset(state, context);
+    process_randao_mixes_reset(state, context);
+    process_historical_roots_update(state, context)?;
+    process_participation_flag_updates(state)?;
+    process_sync_committee_updates(state, observer, context)?;
+    Ok(())
+}
+
+pub fn get_progressive_balances<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    context: &Context,
+) -> Result<ProgressiveBalances> {
+    let previous_epoch = get_previous_epoch(state, context);
+    let current_epoch = get_current_epoch(state, context);
+    let previous_epoch_participation = if previous_epoch == current_epoch {
+        &state.current_epoch_participation
+    } else {
+        &state.previous_epoch_participation
+    };
+
+    let mut balances = ProgressiveBalances::default();
+    for (i, validator) in state.validators.iter().enumerate() {
+        let effective_balance = validator.effective_balance;
+        let add = |balance: &mut Gwei| -> Result<()> {
+            *balance = balance.checked_add(effective_balance).ok_or(Error::Overflow)?;
+            Ok(())
+        };
+        if is_active_validator(validator, current_epoch) {
+            add(&mut balances.total_active_balance)?;
+            if !validator.slashed &&
+                has_flag(state.current_epoch_participation[i], TIMELY_TARGET_FLAG_INDEX)
+            {
+                add(&mut balances.current_epoch_target_balance)?;
+            }
+        }
+        if !validator.slashed && is_active_validator(validator, previous_epoch) {
+            for (flag_index, balance) in
+                balances.previous_epoch_participating_balances.iter_mut().enumerate()
+            {
+                if has_flag(previous_epoch_participation[i], flag_index) {
+                    add(balance)?;
+                }
+            }
+        }
+    }
+    Ok(balances.with_minimum_balance(context))
+}
+
+// Apply the inactivity updates, rewards and penalties, registry updates, slashings and effective
+// balance updates of `process_epoch` in a single pass over the registry.
+pub fn process_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    balances: &ProgressiveBalances,
+    parameters: &SinglePassParameters,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let current_epoch = get_current_epoch(state, context);
+    let total_active_balance = balances.total_active_balance;
+    let adjusted_total_slashing_balance = Gwei::min(
+        state.slashings.iter().sum::<Gwei>() * parameters.proportional_slashing_multiplier,
+        total_active_balance,
+    );
+
+    // Skip the genesis epoch as score updates, rewards and penalties are based on the previous
+    // epoch participation
+    let process_rewards = current_epoch != GENESIS_EPOCH;
+    let previous_epoch = get_previous_epoch(state, context);
+    let is_leaking = is_in_inactivity_leak(state, context);
+    let increment = context.effective_balance_increment;
+    let base_reward_per_increment =
+        increment * context.base_reward_factor / total_active_balance.integer_sqrt();
+    let active_increments = total_active_balance / increment;
+    let participating_increments =
+        balances.previous_epoch_participating_balances.map(|balance| balance / increment);
+    let inactivity_penalty_denominator =
+        context.inactivity_score_bias * parameters.inactivity_penalty_quotient;
+
+    let mut activation_queue = vec![];
+    for i in 0..state.validators.len() {
+        let validator = &state.validators[i];
+        let is_eligible = is_active_validator(validator, previous_epoch) ||
+            (validator.slashed && previous_epoch + 1 < validator.withdrawable_epoch);
+        if process_rewards && is_eligible {
+            let effective_balance = validator.effective_balance;
+            let is_participating =
+                !validator.slashed && is_active_validator(validator, previous_epoch);
+            let flags = state.previous_epoch_participation[i];
+            let has_participation_flag =
+                |flag_index| is_participating && has_flag(flags, flag_index);
+
+            // Inactivity updates
+            let inactivity_score = &mut state.inactivity_scores[i];
+            if has_participation_flag(TIMELY_TARGET_FLAG_INDEX) {
+                *inactivity_score -= u64::min(1, *inactivity_score);
+            } else {
+                *inactivity_score += context.inactivity_score_bias;
+            }
+            if !is_leaking {
+                *inactivity_score -=
+                    u64::min(context.inactivity_score_recovery_rate, *inactivity_score);
+            }
+            let inactivity_score = *inactivity_score;
+
+            // Rewards and penalties, applied in the order of `process_rewards_and_penalties`
+            let base_reward = effective_balance / increment * base_reward_per_increment;
+            for (flag_index, weight) in PARTICIPATION_FLAG_WEIGHTS.into_iter().enumerate() {
+                if has_participation_flag(flag_index) {
+                    if !is_leaking {
+                        let reward_numerator =
+                            base_reward * weight * participating_increments[flag_index];
+                        let reward = reward_numerator / (active_increments * WEIGHT_DENOMINATOR);
+                        increase_balance(state, i, reward);
+                    }
+                } else if flag_index != TIMELY_HEAD_FLAG_INDEX {
+                    decrease_balance(state, i, base_reward * weight / WEIGHT_DENOMINATOR);
+                }
+            }
+            if !has_participation_flag(TIMELY_TARGET_FLAG_INDEX) {
+                let penalty_numerator = effective_balance * inactivity_score;
+                decrease_balance(state, i, penalty_numerator / inactivity_penalty_denominator);
+            }
+        }
+
+        if process_validator_updates(
+            state,
+            i,
+            total_active_balance,
+            adjusted_total_slashing_balance,
+            observer,
+            context,
+        ) {
+            activation_queue.push(i);
+        }
+    }
+
+    process_activation_queue(
+        state,
+        activation_queue,
+        parameters.activation_churn_limit,
+        observer,
+        context,
+    );
+    Ok(())
+}
+
+/// An optimized `process_epoch` which visits each validator once for the inactivity updates,
+/// rewards and penalties, registry updates, slashings and effective balance updates.
+///
+/// The result is identical to `process_epoch`, which is kept for differential testing.
+pub fn process_epoch_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let balances = get_progressive_balances(state, context)?;
+    process_justification_and_finalization_with_balances(state, &balances, observer, context)?;
+    let parameters = SinglePassParameters {
+        inactivity_penalty_quotient: context.inactivity_penalty_quotient_altair,
+        proportional_slashing_multiplier: context.proportional_slashing_multiplier_altair,
+        activation_churn_limit: get_validator_churn_limit(state, context),
+    };
+    process_single_pass(state, &balances, &parameters, observer, context)?;
+    process_eth1_data_reset(state, context);
     process_slashings_reset(state, context);
     process_randao_mixes_reset(state, context);
     process_historical_roots_update(state, context)?;
     process_participation_flag_updates(state)?;
-    process_sync_committee_updates(state, context)?;
+    process_sync_committee_updates(state, observer, context)?;
     Ok(())
 }
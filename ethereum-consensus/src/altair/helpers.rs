@@ -18,7 +18,7 @@ use crate::{
     error::{invalid_operation_error, Error, InvalidAttestation, InvalidOperation},
     primitives::{BlsPublicKey, Epoch, Gwei, ParticipationFlags, ValidatorIndex},
     ssz::prelude::Vector,
-    state_transition::{Context, Result},
+    state_transition::{Context, Event, Observer, Result},
 };
 use integer_sqrt::IntegerSquareRoot;
 use std::collections::HashSet;
@@ -379,10 +379,11 @@ pub fn slash_validator<
     >,
     slashed_index: ValidatorIndex,
     whistleblower_index: Option<ValidatorIndex>,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let epoch = get_current_epoch(state, context);
-    initiate_validator_exit(state, slashed_index, context);
+    initiate_validator_exit(state, slashed_index, observer, context);
     state.validators[slashed_index].slashed = true;
     state.validators[slashed_index].withdrawable_epoch = u64::max(
         state.validators[slashed_index].withdrawable_epoch,
@@ -400,6 +401,7 @@ pub fn slash_validator<
     let proposer_index = get_beacon_proposer_index(state, context)?;
 
     let whistleblower_index = whistleblower_index.unwrap_or(proposer_index);
+    observer.on_event(&Event::ValidatorSlashed { index: slashed_index, whistleblower_index });
 
     let whistleblower_reward =
         state.validators[slashed_index].effective_balance / context.whistleblower_reward_quotient;
@@ -1,11 +1,18 @@
 use crate::{
     altair::{
+        compute_domain, compute_epoch_at_slot,
         sync::{SyncAggregate, SyncCommittee},
-        BeaconBlockHeader,
+        BeaconBlockHeader, BeaconState, SignedBeaconBlock,
     },
-    primitives::{Bytes32, Slot},
+    crypto::eth_fast_aggregate_verify,
+    domains::DomainType,
+    error::LightClientError,
+    primitives::{Bytes32, Root, Slot, GENESIS_SLOT},
+    signing::compute_signing_root,
     ssz::prelude::*,
+    state_transition::{Context, Result},
 };
+use std::{cmp, collections::HashMap, iter::zip};
 
 pub const FINALIZED_ROOT_INDEX: usize = 105;
 pub const FINALIZED_ROOT_INDEX_FLOOR_LOG_2: usize = 6;
@@ -16,7 +23,9 @@ pub const CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2: usize = 5;
 pub const NEXT_SYNC_COMMITTEE_INDEX: usize = 55;
 pub const NEXT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2: usize = 5;
 
-#[derive(Default, Debug, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
 pub struct LightClientHeader {
     pub beacon: BeaconBlockHeader,
 }
@@ -65,3 +74,1058 @@ pub struct LightClientStore<const SYNC_COMMITTEE_SIZE: usize> {
     pub previous_max_active_participants: u64,
     pub current_max_active_participants: u64,
 }
+
+pub fn compute_sync_committee_period_at_slot(slot: Slot, context: &Context) -> u64 {
+    compute_epoch_at_slot(slot, context) / context.epochs_per_sync_committee_period
+}
+
+pub fn get_subtree_index(generalized_index: usize) -> usize {
+    generalized_index % 2usize.pow(generalized_index.ilog2())
+}
+
+pub fn is_valid_light_client_header(_header: &LightClientHeader, _context: &Context) -> bool {
+    true
+}
+
+pub fn is_sync_committee_update<const SYNC_COMMITTEE_SIZE: usize>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+) -> bool {
+    update.next_sync_committee_branch.iter().any(|node| node != &Bytes32::default())
+}
+
+pub fn is_finality_update<const SYNC_COMMITTEE_SIZE: usize>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+) -> bool {
+    update.finality_branch.iter().any(|node| node != &Bytes32::default())
+}
+
+pub fn is_better_update<const SYNC_COMMITTEE_SIZE: usize>(
+    new_update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    old_update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    context: &Context,
+) -> bool {
+    // Compare supermajority (> 2/3) sync committee participation
+    let max_active_participants = new_update.sync_aggregate.sync_committee_bits.len();
+    let new_num_active_participants = new_update.sync_aggregate.sync_committee_bits.count_ones();
+    let old_num_active_participants = old_update.sync_aggregate.sync_committee_bits.count_ones();
+    let new_has_supermajority = new_num_active_participants * 3 >= max_active_participants * 2;
+    let old_has_supermajority = old_num_active_participants * 3 >= max_active_participants * 2;
+    if new_has_supermajority != old_has_supermajority {
+        return new_has_supermajority
+    }
+    if !new_has_supermajority && new_num_active_participants != old_num_active_participants {
+        return new_num_active_participants > old_num_active_participants
+    }
+
+    // Compare presence of relevant sync committee
+    let new_has_relevant_sync_committee = is_sync_committee_update(new_update) &&
+        compute_sync_committee_period_at_slot(new_update.attested_header.beacon.slot, context) ==
+            compute_sync_committee_period_at_slot(new_update.signature_slot, context);
+    let old_has_relevant_sync_committee = is_sync_committee_update(old_update) &&
+        compute_sync_committee_period_at_slot(old_update.attested_header.beacon.slot, context) ==
+            compute_sync_committee_period_at_slot(old_update.signature_slot, context);
+    if new_has_relevant_sync_committee != old_has_relevant_sync_committee {
+        return new_has_relevant_sync_committee
+    }
+
+    // Compare indication of any finality
+    let new_has_finality = is_finality_update(new_update);
+    let old_has_finality = is_finality_update(old_update);
+    if new_has_finality != old_has_finality {
+        return new_has_finality
+    }
+
+    // Compare sync committee finality
+    if new_has_finality {
+        let new_has_sync_committee_finality = compute_sync_committee_period_at_slot(
+            new_update.finalized_header.beacon.slot,
+            context,
+        ) == compute_sync_committee_period_at_slot(
+            new_update.attested_header.beacon.slot,
+            context,
+        );
+        let old_has_sync_committee_finality = compute_sync_committee_period_at_slot(
+            old_update.finalized_header.beacon.slot,
+            context,
+        ) == compute_sync_committee_period_at_slot(
+            old_update.attested_header.beacon.slot,
+            context,
+        );
+        if new_has_sync_committee_finality != old_has_sync_committee_finality {
+            return new_has_sync_committee_finality
+        }
+    }
+
+    // Tiebreaker 1: Sync committee participation beyond supermajority
+    if new_num_active_participants != old_num_active_participants {
+        return new_num_active_participants > old_num_active_participants
+    }
+
+    // Tiebreaker 2: Prefer older data (fewer changes to best)
+    if new_update.attested_header.beacon.slot != old_update.attested_header.beacon.slot {
+        return new_update.attested_header.beacon.slot < old_update.attested_header.beacon.slot
+    }
+    new_update.signature_slot < old_update.signature_slot
+}
+
+pub fn is_next_sync_committee_known<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &LightClientStore<SYNC_COMMITTEE_SIZE>,
+) -> bool {
+    store.next_sync_committee != SyncCommittee::default()
+}
+
+pub fn get_safety_threshold<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &LightClientStore<SYNC_COMMITTEE_SIZE>,
+) -> u64 {
+    cmp::max(store.previous_max_active_participants, store.current_max_active_participants) / 2
+}
+
+pub fn initialize_light_client_store<const SYNC_COMMITTEE_SIZE: usize>(
+    trusted_block_root: Root,
+    bootstrap: &LightClientBootstrap<SYNC_COMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<LightClientStore<SYNC_COMMITTEE_SIZE>> {
+    if !is_valid_light_client_header(&bootstrap.header, context) {
+        return Err(LightClientError::InvalidHeader(bootstrap.header.beacon.slot).into())
+    }
+    let header_root = bootstrap.header.beacon.clone().hash_tree_root()?;
+    if header_root != trusted_block_root {
+        return Err(LightClientError::UntrustedBootstrap {
+            provided: header_root,
+            expected: trusted_block_root,
+        }
+        .into())
+    }
+
+    let leaf = bootstrap.current_sync_committee.clone().hash_tree_root()?;
+    let branch = bootstrap
+        .current_sync_committee_branch
+        .iter()
+        .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+        .collect::<Vec<_>>();
+    let depth = CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2;
+    let index = get_subtree_index(CURRENT_SYNC_COMMITTEE_INDEX);
+    let root = &bootstrap.header.beacon.state_root;
+    if !is_valid_merkle_branch(&leaf, branch.iter(), depth, index, root) {
+        return Err(LightClientError::InvalidCurrentSyncCommitteeProof(*root).into())
+    }
+
+    Ok(LightClientStore {
+        finalized_header: bootstrap.header.clone(),
+        current_sync_committee: bootstrap.current_sync_committee.clone(),
+        next_sync_committee: SyncCommittee::default(),
+        best_valid_update: None,
+        optimistic_header: bootstrap.header.clone(),
+        previous_max_active_participants: 0,
+        current_max_active_participants: 0,
+    })
+}
+
+pub fn validate_light_client_update<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &LightClientStore<SYNC_COMMITTEE_SIZE>,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    // Verify sync committee has sufficient participants
+    let sync_aggregate = &update.sync_aggregate;
+    let participants = sync_aggregate.sync_committee_bits.count_ones();
+    if participants < context.min_sync_committee_participants {
+        return Err(LightClientError::InsufficientParticipation {
+            participants,
+            minimum: context.min_sync_committee_participants,
+        }
+        .into())
+    }
+
+    // Verify update does not skip a sync committee period
+    if !is_valid_light_client_header(&update.attested_header, context) {
+        return Err(LightClientError::InvalidHeader(update.attested_header.beacon.slot).into())
+    }
+    let update_attested_slot = update.attested_header.beacon.slot;
+    let update_finalized_slot = update.finalized_header.beacon.slot;
+    if !(current_slot >= update.signature_slot &&
+        update.signature_slot > update_attested_slot &&
+        update_attested_slot >= update_finalized_slot)
+    {
+        return Err(LightClientError::InvalidSlots {
+            current_slot,
+            signature_slot: update.signature_slot,
+            attested_slot: update_attested_slot,
+            finalized_slot: update_finalized_slot,
+        }
+        .into())
+    }
+    let store_period =
+        compute_sync_committee_period_at_slot(store.finalized_header.beacon.slot, context);
+    let update_signature_period =
+        compute_sync_committee_period_at_slot(update.signature_slot, context);
+    let is_valid_signature_period = if is_next_sync_committee_known(store) {
+        update_signature_period == store_period || update_signature_period == store_period + 1
+    } else {
+        update_signature_period == store_period
+    };
+    if !is_valid_signature_period {
+        return Err(LightClientError::InvalidSignaturePeriod {
+            signature_period: update_signature_period,
+            store_period,
+        }
+        .into())
+    }
+
+    // Verify update is relevant
+    let update_attested_period =
+        compute_sync_committee_period_at_slot(update_attested_slot, context);
+    let update_has_next_sync_committee = !is_next_sync_committee_known(store) &&
+        is_sync_committee_update(update) &&
+        update_attested_period == store_period;
+    if !(update_attested_slot > store.finalized_header.beacon.slot ||
+        update_has_next_sync_committee)
+    {
+        return Err(LightClientError::IrrelevantUpdate {
+            attested_slot: update_attested_slot,
+            finalized_slot: store.finalized_header.beacon.slot,
+        }
+        .into())
+    }
+
+    // Verify that the `finality_branch`, if present, confirms `finalized_header`
+    // to match the finalized checkpoint root saved in the state of `attested_header`.
+    // Note that the genesis finalized checkpoint root is represented as a zero hash.
+    if !is_finality_update(update) {
+        if update.finalized_header != LightClientHeader::default() {
+            return Err(LightClientError::UnexpectedFinalizedHeader.into())
+        }
+    } else {
+        let finalized_root = if update_finalized_slot == GENESIS_SLOT {
+            if update.finalized_header != LightClientHeader::default() {
+                return Err(LightClientError::UnexpectedFinalizedHeader.into())
+            }
+            Node::default()
+        } else {
+            if !is_valid_light_client_header(&update.finalized_header, context) {
+                return Err(LightClientError::InvalidHeader(update_finalized_slot).into())
+            }
+            update.finalized_header.beacon.clone().hash_tree_root()?
+        };
+        let branch = update
+            .finality_branch
+            .iter()
+            .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        let depth = FINALIZED_ROOT_INDEX_FLOOR_LOG_2;
+        let index = get_subtree_index(FINALIZED_ROOT_INDEX);
+        let root = &update.attested_header.beacon.state_root;
+        if !is_valid_merkle_branch(&finalized_root, branch.iter(), depth, index, root) {
+            return Err(LightClientError::InvalidFinalityProof(finalized_root).into())
+        }
+    }
+
+    // Verify that the `next_sync_committee`, if present, actually is the next sync committee
+    // saved in the state of the `attested_header`
+    if !is_sync_committee_update(update) {
+        if update.next_sync_committee != SyncCommittee::default() {
+            return Err(LightClientError::UnexpectedNextSyncCommittee.into())
+        }
+    } else {
+        if update_attested_period == store_period &&
+            is_next_sync_committee_known(store) &&
+            update.next_sync_committee != store.next_sync_committee
+        {
+            return Err(LightClientError::InconsistentNextSyncCommittee.into())
+        }
+        let leaf = update.next_sync_committee.clone().hash_tree_root()?;
+        let branch = update
+            .next_sync_committee_branch
+            .iter()
+            .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        let depth = NEXT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2;
+        let index = get_subtree_index(NEXT_SYNC_COMMITTEE_INDEX);
+        let root = &update.attested_header.beacon.state_root;
+        if !is_valid_merkle_branch(&leaf, branch.iter(), depth, index, root) {
+            return Err(LightClientError::InvalidNextSyncCommitteeProof.into())
+        }
+    }
+
+    // Verify sync committee aggregate signature
+    let sync_committee = if update_signature_period == store_period {
+        &store.current_sync_committee
+    } else {
+        &store.next_sync_committee
+    };
+    let participant_public_keys =
+        zip(sync_committee.public_keys.iter(), sync_aggregate.sync_committee_bits.iter())
+            .filter_map(|(public_key, bit)| if *bit { Some(public_key) } else { None })
+            .collect::<Vec<_>>();
+    let fork_version_slot = cmp::max(update.signature_slot, 1) - 1;
+    let fork_version = context.fork_version_for(context.fork_for(fork_version_slot));
+    let domain = compute_domain(
+        DomainType::SyncCommittee,
+        Some(fork_version),
+        Some(genesis_validators_root),
+        context,
+    )?;
+    let signing_root = compute_signing_root(&mut update.attested_header.beacon.clone(), domain)?;
+    if eth_fast_aggregate_verify(
+        participant_public_keys.as_slice(),
+        signing_root.as_ref(),
+        &sync_aggregate.sync_committee_signature,
+    )
+    .is_err()
+    {
+        return Err(LightClientError::InvalidSignature {
+            signature: sync_aggregate.sync_committee_signature.clone(),
+            root: signing_root,
+        }
+        .into())
+    }
+    Ok(())
+}
+
+pub fn apply_light_client_update<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE>,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<()> {
+    let store_period =
+        compute_sync_committee_period_at_slot(store.finalized_header.beacon.slot, context);
+    let update_finalized_period =
+        compute_sync_committee_period_at_slot(update.finalized_header.beacon.slot, context);
+    if !is_next_sync_committee_known(store) {
+        if update_finalized_period != store_period {
+            return Err(LightClientError::InvalidFinalizedPeriod {
+                finalized_period: update_finalized_period,
+                store_period,
+            }
+            .into())
+        }
+        store.next_sync_committee = update.next_sync_committee.clone();
+    } else if update_finalized_period == store_period + 1 {
+        store.current_sync_committee =
+            std::mem::replace(&mut store.next_sync_committee, update.next_sync_committee.clone());
+        store.previous_max_active_participants = store.current_max_active_participants;
+        store.current_max_active_participants = 0;
+    }
+    if update.finalized_header.beacon.slot > store.finalized_header.beacon.slot {
+        store.finalized_header = update.finalized_header.clone();
+        if store.finalized_header.beacon.slot > store.optimistic_header.beacon.slot {
+            store.optimistic_header = store.finalized_header.clone();
+        }
+    }
+    Ok(())
+}
+
+pub fn process_light_client_store_force_update<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE>,
+    current_slot: Slot,
+    context: &Context,
+) -> Result<()> {
+    if current_slot > store.finalized_header.beacon.slot + context.update_timeout as Slot {
+        if let Some(mut best_valid_update) = store.best_valid_update.clone() {
+            // Forced best update when the update timeout has elapsed.
+            // Because the apply logic waits for `finalized_header.beacon.slot` to indicate sync
+            // committee finality, the `attested_header` may be treated as `finalized_header` in
+            // extended periods of non-finality to guarantee progression into later sync committee
+            // periods according to `is_better_update`.
+            if best_valid_update.finalized_header.beacon.slot <= store.finalized_header.beacon.slot
+            {
+                best_valid_update.finalized_header = best_valid_update.attested_header.clone();
+            }
+            apply_light_client_update(store, &best_valid_update, context)?;
+            store.best_valid_update = None;
+        }
+    }
+    Ok(())
+}
+
+pub fn process_light_client_update<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE>,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    validate_light_client_update(store, update, current_slot, genesis_validators_root, context)?;
+
+    let sync_committee_bits = &update.sync_aggregate.sync_committee_bits;
+    let participants = sync_committee_bits.count_ones() as u64;
+
+    // Update the best update in case we have to force-update to it if the timeout elapses
+    let is_better = match store.best_valid_update.as_ref() {
+        Some(best_valid_update) => is_better_update(update, best_valid_update, context),
+        None => true,
+    };
+    if is_better {
+        store.best_valid_update = Some(update.clone());
+    }
+
+    // Track the maximum number of active participants in the committee signatures
+    store.current_max_active_participants =
+        cmp::max(store.current_max_active_participants, participants);
+
+    // Update the optimistic header
+    if participants > get_safety_threshold(store) &&
+        update.attested_header.beacon.slot > store.optimistic_header.beacon.slot
+    {
+        store.optimistic_header = update.attested_header.clone();
+    }
+
+    // Update finalized header
+    let update_has_finalized_next_sync_committee = !is_next_sync_committee_known(store) &&
+        is_sync_committee_update(update) &&
+        is_finality_update(update) &&
+        compute_sync_committee_period_at_slot(update.finalized_header.beacon.slot, context) ==
+            compute_sync_committee_period_at_slot(
+                update.attested_header.beacon.slot,
+                context,
+            );
+    if participants * 3 >= sync_committee_bits.len() as u64 * 2 &&
+        (update.finalized_header.beacon.slot > store.finalized_header.beacon.slot ||
+            update_has_finalized_next_sync_committee)
+    {
+        // Normal update through 2/3 threshold
+        apply_light_client_update(store, update, context)?;
+        store.best_valid_update = None;
+    }
+    Ok(())
+}
+
+pub fn process_light_client_finality_update<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE>,
+    finality_update: &LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE>,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    let update = LightClientUpdate {
+        attested_header: finality_update.attested_header.clone(),
+        next_sync_committee: SyncCommittee::default(),
+        next_sync_committee_branch: Default::default(),
+        finalized_header: finality_update.finalized_header.clone(),
+        finality_branch: finality_update.finality_branch.clone(),
+        sync_aggregate: finality_update.sync_aggregate.clone(),
+        signature_slot: finality_update.signature_slot,
+    };
+    process_light_client_update(store, &update, current_slot, genesis_validators_root, context)
+}
+
+pub fn process_light_client_optimistic_update<const SYNC_COMMITTEE_SIZE: usize>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE>,
+    optimistic_update: &LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE>,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    let update = LightClientUpdate {
+        attested_header: optimistic_update.attested_header.clone(),
+        next_sync_committee: SyncCommittee::default(),
+        next_sync_committee_branch: Default::default(),
+        finalized_header: LightClientHeader::default(),
+        finality_branch: Default::default(),
+        sync_aggregate: optimistic_update.sync_aggregate.clone(),
+        signature_slot: optimistic_update.signature_slot,
+    };
+    process_light_client_update(store, &update, current_slot, genesis_validators_root, context)
+}
+
+// Tracks the best `LightClientUpdate` for each sync committee period a server has seen,
+// ranked by `is_better_update`.
+#[derive(Default, Debug, Clone)]
+pub struct BestLightClientUpdates<const SYNC_COMMITTEE_SIZE: usize> {
+    pub updates: HashMap<u64, LightClientUpdate<SYNC_COMMITTEE_SIZE>>,
+}
+
+pub fn block_to_light_client_header<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    _context: &Context,
+) -> Result<LightClientHeader> {
+    let block = &block.message;
+    Ok(LightClientHeader {
+        beacon: BeaconBlockHeader {
+            slot: block.slot,
+            proposer_index: block.proposer_index,
+            parent_root: block.parent_root,
+            state_root: block.state_root,
+            body_root: block.body.clone().hash_tree_root()?,
+        },
+    })
+}
+
+// Returns the root of the block which produced `state`, provided no empty slots
+// have been processed since.
+pub fn get_latest_block_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    context: &Context,
+) -> Result<Root> {
+    if compute_epoch_at_slot(state.slot, context) < context.altair_fork_epoch {
+        return Err(LightClientError::UnsupportedSlot(state.slot).into())
+    }
+    if state.slot != state.latest_block_header.slot {
+        return Err(LightClientError::UnexpectedStateSlot {
+            state_slot: state.slot,
+            header_slot: state.latest_block_header.slot,
+        }
+        .into())
+    }
+    let mut header = state.latest_block_header.clone();
+    header.state_root = state.hash_tree_root()?;
+    Ok(header.hash_tree_root()?)
+}
+
+pub fn create_light_client_bootstrap<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    context: &Context,
+) -> Result<LightClientBootstrap<SYNC_COMMITTEE_SIZE>> {
+    let expected = get_latest_block_root(state, context)?;
+    let block_root = block.message.clone().hash_tree_root()?;
+    if block_root != expected {
+        return Err(LightClientError::InconsistentBlock { provided: block_root, expected }.into())
+    }
+
+    let current_sync_committee_branch = compute_merkle_proof(state, CURRENT_SYNC_COMMITTEE_INDEX)?
+        .iter()
+        .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+        .collect::<Vec<_>>();
+    Ok(LightClientBootstrap {
+        header: block_to_light_client_header(block, context)?,
+        current_sync_committee: state.current_sync_committee.clone(),
+        current_sync_committee_branch: Vector::try_from(current_sync_committee_branch)
+            .map_err(|(_, err)| err)?,
+    })
+}
+
+pub fn create_light_client_update<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    attested_state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    attested_block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    finalized_block: Option<
+        &SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+        >,
+    >,
+    context: &Context,
+) -> Result<LightClientUpdate<SYNC_COMMITTEE_SIZE>> {
+    let sync_aggregate = &block.message.body.sync_aggregate;
+    let participants = sync_aggregate.sync_committee_bits.count_ones();
+    if participants < context.min_sync_committee_participants {
+        return Err(LightClientError::InsufficientParticipation {
+            participants,
+            minimum: context.min_sync_committee_participants,
+        }
+        .into())
+    }
+
+    let expected = get_latest_block_root(state, context)?;
+    let block_root = block.message.clone().hash_tree_root()?;
+    if block_root != expected {
+        return Err(LightClientError::InconsistentBlock { provided: block_root, expected }.into())
+    }
+    let update_signature_period =
+        compute_sync_committee_period_at_slot(block.message.slot, context);
+
+    let expected = get_latest_block_root(attested_state, context)?;
+    let attested_block_root = attested_block.message.clone().hash_tree_root()?;
+    if attested_block_root != expected {
+        return Err(
+            LightClientError::InconsistentBlock { provided: attested_block_root, expected }.into()
+        )
+    }
+    if attested_block_root != block.message.parent_root {
+        return Err(LightClientError::InconsistentAttestedBlock {
+            provided: attested_block_root,
+            expected: block.message.parent_root,
+        }
+        .into())
+    }
+    let update_attested_period =
+        compute_sync_committee_period_at_slot(attested_block.message.slot, context);
+
+    let mut update = LightClientUpdate {
+        attested_header: block_to_light_client_header(attested_block, context)?,
+        sync_aggregate: sync_aggregate.clone(),
+        signature_slot: block.message.slot,
+        ..Default::default()
+    };
+
+    // `next_sync_committee` is only useful if the message is signed by the current sync committee
+    if update_attested_period == update_signature_period {
+        let next_sync_committee_branch =
+            compute_merkle_proof(attested_state, NEXT_SYNC_COMMITTEE_INDEX)?
+                .iter()
+                .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+                .collect::<Vec<_>>();
+        update.next_sync_committee = attested_state.next_sync_committee.clone();
+        update.next_sync_committee_branch =
+            Vector::try_from(next_sync_committee_branch).map_err(|(_, err)| err)?;
+    }
+
+    // Indicate finality whenever possible
+    if let Some(finalized_block) = finalized_block {
+        let expected = attested_state.finalized_checkpoint.root;
+        if finalized_block.message.slot != GENESIS_SLOT {
+            update.finalized_header = block_to_light_client_header(finalized_block, context)?;
+            let finalized_root = update.finalized_header.beacon.clone().hash_tree_root()?;
+            if finalized_root != expected {
+                return Err(LightClientError::InconsistentFinalizedBlock {
+                    provided: finalized_root,
+                    expected,
+                }
+                .into())
+            }
+        } else if expected != Root::default() {
+            return Err(LightClientError::InconsistentFinalizedBlock {
+                provided: Root::default(),
+                expected,
+            }
+            .into())
+        }
+        let finality_branch = compute_merkle_proof(attested_state, FINALIZED_ROOT_INDEX)?
+            .iter()
+            .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        update.finality_branch = Vector::try_from(finality_branch).map_err(|(_, err)| err)?;
+    }
+
+    Ok(update)
+}
+
+pub fn create_light_client_finality_update<const SYNC_COMMITTEE_SIZE: usize>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+) -> LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE> {
+    LightClientFinalityUpdate {
+        attested_header: update.attested_header.clone(),
+        finalized_header: update.finalized_header.clone(),
+        finality_branch: update.finality_branch.clone(),
+        sync_aggregate: update.sync_aggregate.clone(),
+        signature_slot: update.signature_slot,
+    }
+}
+
+pub fn create_light_client_optimistic_update<const SYNC_COMMITTEE_SIZE: usize>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+) -> LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE> {
+    LightClientOptimisticUpdate {
+        attested_header: update.attested_header.clone(),
+        sync_aggregate: update.sync_aggregate.clone(),
+        signature_slot: update.signature_slot,
+    }
+}
+
+// Records `update` as the best update for its sync committee period if it ranks higher
+// than the current best under `is_better_update`. Returns `true` if `update` was recorded.
+pub fn process_best_light_client_update<const SYNC_COMMITTEE_SIZE: usize>(
+    best_updates: &mut BestLightClientUpdates<SYNC_COMMITTEE_SIZE>,
+    update: LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    context: &Context,
+) -> bool {
+    let period = compute_sync_committee_period_at_slot(update.attested_header.beacon.slot, context);
+    let is_better = match best_updates.updates.get(&period) {
+        Some(best_update) => is_better_update(&update, best_update, context),
+        None => true,
+    };
+    if is_better {
+        best_updates.updates.insert(period, update);
+    }
+    is_better
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{altair::minimal::SYNC_COMMITTEE_SIZE, harness::minimal::Harness, Error};
+
+    struct Chain {
+        context: Context,
+        genesis_validators_root: Root,
+        bootstrap_root: Root,
+        bootstrap: LightClientBootstrap<SYNC_COMMITTEE_SIZE>,
+        update: LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+    }
+
+    // Builds an Altair chain of three blocks with a bootstrap for the first one and an update
+    // attesting to the second one, signed in the third one.
+    fn make_chain() -> Chain {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        let mut harness = Harness::new(64, context).unwrap();
+        let roots = harness.extend_chain(3).unwrap();
+        let context = harness.context().clone();
+        let state = |root: &Root| harness.state(root).unwrap().altair().unwrap().clone();
+        let block = |root: &Root| harness.block(root).unwrap().altair().unwrap().clone();
+
+        let mut bootstrap_state = state(&roots[0]);
+        let bootstrap =
+            create_light_client_bootstrap(&mut bootstrap_state, &block(&roots[0]), &context)
+                .unwrap();
+        // the finalized checkpoint of the attested state is still the genesis checkpoint
+        let update = create_light_client_update(
+            &mut state(&roots[2]),
+            &block(&roots[2]),
+            &mut state(&roots[1]),
+            &block(&roots[1]),
+            Some(&SignedBeaconBlock::default()),
+            &context,
+        )
+        .unwrap();
+        Chain {
+            context,
+            genesis_validators_root: bootstrap_state.genesis_validators_root,
+            bootstrap_root: roots[0],
+            bootstrap,
+            update,
+        }
+    }
+
+    fn tamper<const N: usize>(branch: &Vector<Bytes32, N>) -> Vector<Bytes32, N> {
+        let mut nodes = branch.iter().cloned().collect::<Vec<_>>();
+        nodes[0] = Bytes32::try_from([1u8; 32].as_ref()).unwrap();
+        Vector::try_from(nodes).unwrap()
+    }
+
+    fn non_empty_branch<const N: usize>() -> Vector<Bytes32, N> {
+        tamper(&Vector::default())
+    }
+
+    #[test]
+    fn test_light_client_sync() {
+        let Chain { context, genesis_validators_root, bootstrap_root, bootstrap, update } =
+            make_chain();
+        let mut store =
+            initialize_light_client_store(bootstrap_root, &bootstrap, &context).unwrap();
+        assert_eq!(store.finalized_header, bootstrap.header);
+        assert!(!is_next_sync_committee_known(&store));
+
+        // the update proves the next sync committee with finality in the same period
+        process_light_client_update(&mut store, &update, 3, genesis_validators_root, &context)
+            .unwrap();
+        assert_eq!(store.next_sync_committee, update.next_sync_committee);
+        assert_eq!(store.finalized_header, bootstrap.header);
+        assert_eq!(store.optimistic_header, update.attested_header);
+        assert_eq!(store.current_max_active_participants, SYNC_COMMITTEE_SIZE as u64);
+        assert!(store.best_valid_update.is_none());
+
+        // without finality, the update is only applied once the update timeout has elapsed
+        let optimistic_update = create_light_client_optimistic_update(&update);
+        process_light_client_optimistic_update(
+            &mut store,
+            &optimistic_update,
+            3,
+            genesis_validators_root,
+            &context,
+        )
+        .unwrap();
+        assert!(store.best_valid_update.is_some());
+        assert_eq!(store.finalized_header, bootstrap.header);
+
+        let timeout_slot = bootstrap.header.beacon.slot + context.update_timeout as Slot;
+        process_light_client_store_force_update(&mut store, timeout_slot, &context).unwrap();
+        assert!(store.best_valid_update.is_some());
+        process_light_client_store_force_update(&mut store, timeout_slot + 1, &context).unwrap();
+        assert!(store.best_valid_update.is_none());
+        assert_eq!(store.finalized_header, update.attested_header);
+    }
+
+    #[test]
+    fn test_invalid_light_client_bootstrap() {
+        let Chain { context, bootstrap_root, bootstrap, .. } = make_chain();
+        let result = initialize_light_client_store(Root::default(), &bootstrap, &context);
+        assert!(matches!(
+            result,
+            Err(Error::LightClient(LightClientError::UntrustedBootstrap { .. }))
+        ));
+
+        let mut bootstrap = bootstrap;
+        bootstrap.current_sync_committee_branch = tamper(&bootstrap.current_sync_committee_branch);
+        let result = initialize_light_client_store(bootstrap_root, &bootstrap, &context);
+        assert!(matches!(
+            result,
+            Err(Error::LightClient(LightClientError::InvalidCurrentSyncCommitteeProof(..)))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_light_client_updates() {
+        let Chain { context, genesis_validators_root, bootstrap_root, bootstrap, update } =
+            make_chain();
+        let store = initialize_light_client_store(bootstrap_root, &bootstrap, &context).unwrap();
+        let validate = |store: &LightClientStore<SYNC_COMMITTEE_SIZE>,
+                        update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+                        current_slot: Slot| {
+            match validate_light_client_update(
+                store,
+                update,
+                current_slot,
+                genesis_validators_root,
+                &context,
+            ) {
+                Ok(()) => None,
+                Err(Error::LightClient(err)) => Some(err),
+                Err(err) => panic!("unexpected error: {err}"),
+            }
+        };
+        assert!(validate(&store, &update, 3).is_none());
+
+        let mut invalid_update = update.clone();
+        invalid_update.sync_aggregate.sync_committee_bits = Default::default();
+        assert!(matches!(
+            validate(&store, &invalid_update, 3),
+            Some(LightClientError::InsufficientParticipation { participants: 0, .. })
+        ));
+
+        assert!(matches!(
+            validate(&store, &update, 2),
+            Some(LightClientError::InvalidSlots { current_slot: 2, .. })
+        ));
+
+        // the next sync committee is not known to the store to verify the signature
+        let next_period_slot = context.slots_per_epoch * context.epochs_per_sync_committee_period;
+        let mut invalid_update = update.clone();
+        invalid_update.signature_slot = next_period_slot;
+        assert!(matches!(
+            validate(&store, &invalid_update, next_period_slot),
+            Some(LightClientError::InvalidSignaturePeriod { signature_period: 1, store_period: 0 })
+        ));
+
+        let mut finalized_store = store.clone();
+        finalized_store.finalized_header = update.attested_header.clone();
+        finalized_store.next_sync_committee = update.next_sync_committee.clone();
+        assert!(matches!(
+            validate(&finalized_store, &update, 3),
+            Some(LightClientError::IrrelevantUpdate { attested_slot: 2, finalized_slot: 2 })
+        ));
+
+        let mut invalid_update = update.clone();
+        invalid_update.finality_branch = Default::default();
+        invalid_update.finalized_header = update.attested_header.clone();
+        assert!(matches!(
+            validate(&store, &invalid_update, 3),
+            Some(LightClientError::UnexpectedFinalizedHeader)
+        ));
+
+        let mut invalid_update = update.clone();
+        invalid_update.finality_branch = tamper(&update.finality_branch);
+        assert!(matches!(
+            validate(&store, &invalid_update, 3),
+            Some(LightClientError::InvalidFinalityProof(..))
+        ));
+
+        let mut invalid_update = update.clone();
+        invalid_update.next_sync_committee_branch = Default::default();
+        assert!(matches!(
+            validate(&store, &invalid_update, 3),
+            Some(LightClientError::UnexpectedNextSyncCommittee)
+        ));
+
+        let mut invalid_update = update.clone();
+        invalid_update.next_sync_committee_branch = tamper(&update.next_sync_committee_branch);
+        assert!(matches!(
+            validate(&store, &invalid_update, 3),
+            Some(LightClientError::InvalidNextSyncCommitteeProof)
+        ));
+
+        let mut inconsistent_store = store.clone();
+        inconsistent_store.next_sync_committee = update.next_sync_committee.clone();
+        inconsistent_store.next_sync_committee.aggregate_public_key = Default::default();
+        assert!(matches!(
+            validate(&inconsistent_store, &update, 3),
+            Some(LightClientError::InconsistentNextSyncCommittee)
+        ));
+
+        // the attested header is not the one signed by the sync committee
+        let mut invalid_update = update;
+        invalid_update.attested_header.beacon.proposer_index += 1;
+        assert!(matches!(
+            validate(&store, &invalid_update, 3),
+            Some(LightClientError::InvalidSignature { .. })
+        ));
+    }
+
+    #[test]
+    fn test_light_client_update_ranking() {
+        let context = Context::for_minimal();
+        let period_slots = context.slots_per_epoch * context.epochs_per_sync_committee_period;
+        let update_with = |participants: usize| {
+            let mut update = LightClientUpdate::<SYNC_COMMITTEE_SIZE>::default();
+            for i in 0..participants {
+                update.sync_aggregate.sync_committee_bits.set(i, true);
+            }
+            update.attested_header.beacon.slot = 2;
+            update.signature_slot = 3;
+            update
+        };
+        let is_better =
+            |new_update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+             old_update: &LightClientUpdate<SYNC_COMMITTEE_SIZE>| {
+                is_better_update(new_update, old_update, &context)
+            };
+
+        // a supermajority of the sync committee is at least 22 of its 32 members
+        assert!(is_better(&update_with(22), &update_with(21)));
+        assert!(!is_better(&update_with(21), &update_with(22)));
+        assert!(is_better(&update_with(10), &update_with(5)));
+
+        let mut with_sync_committee = update_with(22);
+        with_sync_committee.next_sync_committee_branch = non_empty_branch();
+        assert!(is_better(&with_sync_committee, &update_with(32)));
+        // the next sync committee is only relevant if signed in the period it was attested in
+        let mut with_irrelevant_sync_committee = with_sync_committee.clone();
+        with_irrelevant_sync_committee.signature_slot = period_slots;
+        assert!(is_better(&with_sync_committee, &with_irrelevant_sync_committee));
+        assert!(!is_better(&with_irrelevant_sync_committee, &update_with(22)));
+
+        let mut with_finality = update_with(22);
+        with_finality.finality_branch = non_empty_branch();
+        assert!(is_better(&with_finality, &update_with(32)));
+        assert!(!is_better(&update_with(32), &with_finality));
+
+        // finality within the attested period beats finality from an earlier period
+        let mut with_sync_committee_finality = with_finality.clone();
+        with_sync_committee_finality.attested_header.beacon.slot = period_slots + 2;
+        with_sync_committee_finality.signature_slot = period_slots + 3;
+        with_sync_committee_finality.finalized_header.beacon.slot = period_slots;
+        let mut with_earlier_finality = with_sync_committee_finality.clone();
+        with_earlier_finality.finalized_header.beacon.slot = period_slots - 1;
+        assert!(is_better(&with_sync_committee_finality, &with_earlier_finality));
+        assert!(!is_better(&with_earlier_finality, &with_sync_committee_finality));
+
+        // ties are broken by participation, then by the oldest attested and signature slots
+        assert!(is_better(&update_with(32), &update_with(22)));
+        let mut later_attested = update_with(32);
+        later_attested.attested_header.beacon.slot = 3;
+        later_attested.signature_slot = 4;
+        assert!(is_better(&update_with(32), &later_attested));
+        let mut later_signature = update_with(32);
+        later_signature.signature_slot = 4;
+        assert!(is_better(&update_with(32), &later_signature));
+        assert!(!is_better(&update_with(32), &update_with(32)));
+
+        let mut best_updates = BestLightClientUpdates::default();
+        assert!(process_best_light_client_update(&mut best_updates, update_with(22), &context));
+        assert!(!process_best_light_client_update(&mut best_updates, update_with(21), &context));
+        assert!(process_best_light_client_update(&mut best_updates, with_finality, &context));
+        assert_eq!(best_updates.updates.len(), 1);
+    }
+}
@@ -4,6 +4,7 @@
 pub mod beacon_block;
 pub mod beacon_state;
 pub mod block_processing;
+pub mod block_production;
 pub mod constants;
 pub mod epoch_processing;
 pub mod fork;
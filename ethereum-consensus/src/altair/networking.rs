@@ -1,12 +1,41 @@
 use crate::{
-    altair::constants::SYNC_COMMITTEE_SUBNET_COUNT, phase0::networking::ATTESTATION_SUBNET_COUNT,
-    ssz::prelude::Bitvector,
+    altair::constants::SYNC_COMMITTEE_SUBNET_COUNT,
+    bellatrix::networking::GOSSIP_MAX_SIZE_BELLATRIX,
+    phase0::networking::{
+        decompress_message, message_id_from_parts, MessageId, ATTESTATION_SUBNET_COUNT,
+        GOSSIP_MAX_SIZE,
+    },
+    ssz::prelude::*,
+    state_transition::Context,
+    Fork,
 };
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
 pub struct MetaData {
     #[serde(with = "crate::serde::as_str")]
     pub seq_number: u64,
     pub attnets: Bitvector<ATTESTATION_SUBNET_COUNT>,
     pub syncnets: Bitvector<SYNC_COMMITTEE_SUBNET_COUNT>,
 }
+
+// Return the sync committee subnet the member at `index_in_committee` of a sync committee
+// gossips its messages on.
+pub fn compute_subnet_for_sync_committee_index(
+    index_in_committee: usize,
+    context: &Context,
+) -> u64 {
+    let sync_subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+    (index_in_committee / sync_subcommittee_size) as u64
+}
+
+// Return the `message-id` of a gossip message with the (snappy compressed) `message_data`
+// published on `topic` during `fork`, as used from the altair fork onwards.
+pub fn compute_message_id(topic: &str, message_data: &[u8], fork: Fork) -> MessageId {
+    let max_size =
+        if fork >= Fork::Bellatrix { GOSSIP_MAX_SIZE_BELLATRIX } else { GOSSIP_MAX_SIZE };
+    let (domain, data) = decompress_message(message_data, max_size);
+    let topic_len = (topic.len() as u64).to_le_bytes();
+    message_id_from_parts(&[domain.as_slice(), topic_len.as_slice(), topic.as_bytes(), &data[..]])
+}
@@ -32,6 +32,7 @@ pub const PRESET: Preset = Preset {
     epochs_per_sync_committee_period: EPOCHS_PER_SYNC_COMMITTEE_PERIOD,
     min_sync_committee_participants: MIN_SYNC_COMMITTEE_PARTICIPANTS,
     update_timeout: UPDATE_TIMEOUT,
+    target_aggregators_per_sync_subcommittee: TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE as u64,
 };
 
 pub type SyncAggregate = spec::SyncAggregate<SYNC_COMMITTEE_SIZE>;
@@ -44,6 +45,9 @@ pub type LightClientFinalityUpdate =
     crate::altair::light_client::LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE>;
 pub type LightClientOptimisticUpdate =
     crate::altair::light_client::LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE>;
+pub type LightClientStore = crate::altair::light_client::LightClientStore<SYNC_COMMITTEE_SIZE>;
+pub type BestLightClientUpdates =
+    crate::altair::light_client::BestLightClientUpdates<SYNC_COMMITTEE_SIZE>;
 
 pub type BeaconState = spec::BeaconState<
     SLOTS_PER_HISTORICAL_ROOT,
@@ -4,7 +4,12 @@ pub use crate::{
         beacon_block::{BeaconBlock, BeaconBlockBody, SignedBeaconBlock},
         beacon_state::BeaconState,
         block_processing::{
-            process_attestation, process_block, process_deposit, process_sync_aggregate,
+            get_block_rewards, get_sync_aggregate_rewards, process_attestation, process_block,
+            process_deposit, process_sync_aggregate,
+        },
+        block_production::{
+            get_sync_aggregate, produce_block, select_attestations, select_sync_aggregate,
+            BlockOperations,
         },
         constants::{
             PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, SYNC_COMMITTEE_SUBNET_COUNT,
@@ -13,9 +18,11 @@ pub use crate::{
             TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
         },
         epoch_processing::{
-            get_base_reward, process_epoch, process_inactivity_updates,
+            get_attestation_rewards, get_base_reward, get_progressive_balances, process_epoch,
+            process_epoch_single_pass, process_inactivity_updates,
             process_justification_and_finalization, process_participation_flag_updates,
-            process_rewards_and_penalties, process_slashings, process_sync_committee_updates,
+            process_rewards_and_penalties, process_single_pass, process_slashings,
+            process_sync_committee_updates,
         },
         fork::upgrade_to_altair,
         genesis::initialize_beacon_state_from_eth1,
@@ -26,6 +33,15 @@ pub use crate::{
             slash_validator,
         },
         light_client::{
+            apply_light_client_update, block_to_light_client_header,
+            compute_sync_committee_period_at_slot, create_light_client_bootstrap,
+            create_light_client_finality_update, create_light_client_optimistic_update,
+            create_light_client_update, get_latest_block_root, get_safety_threshold,
+            get_subtree_index, initialize_light_client_store, is_better_update, is_finality_update,
+            is_next_sync_committee_known, is_sync_committee_update, is_valid_light_client_header,
+            process_best_light_client_update, process_light_client_finality_update,
+            process_light_client_optimistic_update, process_light_client_store_force_update,
+            process_light_client_update, validate_light_client_update, BestLightClientUpdates,
             LightClientBootstrap, LightClientFinalityUpdate, LightClientHeader,
             LightClientOptimisticUpdate, LightClientStore, LightClientUpdate,
             CURRENT_SYNC_COMMITTEE_INDEX, CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2,
@@ -34,8 +50,9 @@ pub use crate::{
         },
         sync::{SyncAggregate, SyncCommittee},
         validator::{
-            ContributionAndProof, SignedContributionAndProof, SyncAggregatorSelectionData,
-            SyncCommitteeContribution, SyncCommitteeMessage,
+            compute_subnets_for_sync_committee, get_sync_subcommittee_pubkeys,
+            is_sync_committee_aggregator, ContributionAndProof, SignedContributionAndProof,
+            SyncAggregatorSelectionData, SyncCommitteeContribution, SyncCommitteeMessage,
         },
     },
     error::*,
@@ -46,6 +63,7 @@ pub use crate::{
             BASE_REWARDS_PER_EPOCH, DEPOSIT_CONTRACT_TREE_DEPTH, DEPOSIT_DATA_LIST_BOUND,
             JUSTIFICATION_BITS_LENGTH,
         },
+        epoch_processing::{ProgressiveBalances, SinglePassParameters, ValidatorParticipation},
         operations::{
             Attestation, AttestationData, AttesterSlashing, Checkpoint, Deposit, DepositData,
             DepositMessage, Eth1Data, IndexedAttestation, PendingAttestation, ProposerSlashing,
@@ -55,12 +73,11 @@ pub use crate::{
     },
     primitives::*,
     signing::*,
-    state_transition::{Context, Result, Validation},
-};
-use crate::{
-    crypto::{fast_aggregate_verify, hash},
-    ssz::prelude::*,
+    state_transition::{
+        Context, Event, Observer, Result, SignatureSets, SignatureStrategy, Validation,
+    },
 };
+use crate::{crypto::hash, ssz::prelude::*};
 use std::{cmp, collections::HashSet};
 pub fn process_proposer_slashing<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -83,6 +100,8 @@ pub fn process_proposer_slashing<
         SYNC_COMMITTEE_SIZE,
     >,
     proposer_slashing: &mut ProposerSlashing,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let header_1 = &proposer_slashing.signed_header_1.message;
@@ -122,14 +141,19 @@ pub fn process_proposer_slashing<
         [&mut proposer_slashing.signed_header_1, &mut proposer_slashing.signed_header_2]
     {
         let signing_root = compute_signing_root(&mut signed_header.message, domain)?;
-        let public_key = &proposer.public_key;
-        if verify_signature(public_key, signing_root.as_ref(), &signed_header.signature).is_err() {
-            return Err(invalid_operation_error(InvalidOperation::ProposerSlashing(
+        let signature_set = context.pubkey_cache.signature_set(
+            &state.validators,
+            proposer_index,
+            signing_root.as_ref(),
+            &signed_header.signature,
+        );
+        signatures.verify(signature_set, || {
+            invalid_operation_error(InvalidOperation::ProposerSlashing(
                 InvalidProposerSlashing::InvalidSignature(signed_header.signature.clone()),
-            )))
-        }
+            ))
+        })?;
     }
-    slash_validator(state, proposer_index, None, context)
+    slash_validator(state, proposer_index, None, observer, context)
 }
 pub fn process_attester_slashing<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -152,6 +176,8 @@ pub fn process_attester_slashing<
         SYNC_COMMITTEE_SIZE,
     >,
     attester_slashing: &mut AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let attestation_1 = &mut attester_slashing.attestation_1;
@@ -164,8 +190,8 @@ pub fn process_attester_slashing<
             ),
         )))
     }
-    is_valid_indexed_attestation(state, attestation_1, context)?;
-    is_valid_indexed_attestation(state, attestation_2, context)?;
+    is_valid_indexed_attestation(state, attestation_1, signatures, context)?;
+    is_valid_indexed_attestation(state, attestation_2, signatures, context)?;
     let indices_1: HashSet<ValidatorIndex> =
         HashSet::from_iter(attestation_1.attesting_indices.iter().cloned());
     let indices_2 = HashSet::from_iter(attestation_2.attesting_indices.iter().cloned());
@@ -175,7 +201,7 @@ pub fn process_attester_slashing<
     let current_epoch = get_current_epoch(state, context);
     for &index in &indices {
         if is_slashable_validator(&state.validators[index], current_epoch) {
-            slash_validator(state, index, None, context)?;
+            slash_validator(state, index, None, observer, context)?;
             slashed_any = true;
         }
     }
@@ -225,6 +251,8 @@ pub fn process_voluntary_exit<
         SYNC_COMMITTEE_SIZE,
     >,
     signed_voluntary_exit: &mut SignedVoluntaryExit,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let voluntary_exit = &mut signed_voluntary_exit.message;
@@ -263,14 +291,19 @@ pub fn process_voluntary_exit<
         )))
     }
     let domain = get_domain(state, DomainType::VoluntaryExit, Some(voluntary_exit.epoch), context)?;
-    let public_key = &validator.public_key;
-    verify_signed_data(voluntary_exit, &signed_voluntary_exit.signature, public_key, domain)
-        .map_err(|_| {
-            invalid_operation_error(InvalidOperation::VoluntaryExit(
-                InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
-            ))
-        })?;
-    initiate_validator_exit(state, voluntary_exit.validator_index, context);
+    let signing_root = compute_signing_root(voluntary_exit, domain)?;
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        voluntary_exit.validator_index,
+        signing_root.as_ref(),
+        &signed_voluntary_exit.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::VoluntaryExit(
+            InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
+        ))
+    })?;
+    initiate_validator_exit(state, voluntary_exit.validator_index, observer, context);
     Ok(())
 }
 pub fn process_block_header<
@@ -386,16 +419,22 @@ pub fn process_randao<
         MAX_VOLUNTARY_EXITS,
         SYNC_COMMITTEE_SIZE,
     >,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let mut epoch = get_current_epoch(state, context);
     let proposer_index = get_beacon_proposer_index(state, context)?;
-    let proposer = &state.validators[proposer_index];
     let domain = get_domain(state, DomainType::Randao, Some(epoch), context)?;
     let signing_root = compute_signing_root(&mut epoch, domain)?;
-    if verify_signature(&proposer.public_key, signing_root.as_ref(), &body.randao_reveal).is_err() {
-        return Err(invalid_operation_error(InvalidOperation::Randao(body.randao_reveal.clone())))
-    }
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        proposer_index,
+        signing_root.as_ref(),
+        &body.randao_reveal,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::Randao(body.randao_reveal.clone()))
+    })?;
     let mix = xor(get_randao_mix(state, epoch), &hash(body.randao_reveal.as_ref()));
     let mix_index = epoch % context.epochs_per_historical_vector;
     state.randao_mixes[mix_index as usize] = mix;
@@ -478,6 +517,8 @@ pub fn process_operations<
         MAX_VOLUNTARY_EXITS,
         SYNC_COMMITTEE_SIZE,
     >,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let expected_deposit_count = usize::min(
@@ -494,15 +535,119 @@ pub fn process_operations<
     }
     body.proposer_slashings
         .iter_mut()
-        .try_for_each(|op| process_proposer_slashing(state, op, context))?;
+        .try_for_each(|op| process_proposer_slashing(state, op, signatures, observer, context))?;
     body.attester_slashings
         .iter_mut()
-        .try_for_each(|op| process_attester_slashing(state, op, context))?;
-    body.attestations.iter().try_for_each(|op| process_attestation(state, op, context))?;
-    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, context))?;
+        .try_for_each(|op| process_attester_slashing(state, op, signatures, observer, context))?;
+    body.attestations
+        .iter()
+        .try_for_each(|op| process_attestation(state, op, signatures, context))?;
+    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, observer, context))?;
     body.voluntary_exits
         .iter_mut()
-        .try_for_each(|op| process_voluntary_exit(state, op, context))?;
+        .try_for_each(|op| process_voluntary_exit(state, op, signatures, observer, context))?;
+    Ok(())
+}
+/// Applies the candidate `operations` to `state` in the order of `process_operations`, adding
+/// each one that is valid to `body` until the limit for its kind is reached.
+///
+/// Invalid candidates are skipped and leave `state` unchanged. Returns an error if there are not
+/// enough valid deposits to include all of those pending up to `MAX_DEPOSITS`.
+pub fn select_operations<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    body: &mut BeaconBlockBody<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<()> {
+    for operation in &operations.proposer_slashings {
+        if body.proposer_slashings.len() == context.max_proposer_slashings {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_proposer_slashing(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.proposer_slashings.push(operation);
+        }
+    }
+    for operation in &operations.attester_slashings {
+        if body.attester_slashings.len() == context.max_attester_slashings {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_attester_slashing(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.attester_slashings.push(operation);
+        }
+    }
+    for operation in &operations.attestations {
+        if body.attestations.len() == context.max_attestations {
+            break
+        }
+        if process_attestation(state, operation, signatures, context).is_ok() {
+            body.attestations.push(operation.clone());
+        }
+    }
+    let expected_deposit_count = usize::min(
+        context.max_deposits,
+        (state.eth1_data.deposit_count - state.eth1_deposit_index) as usize,
+    );
+    for operation in &operations.deposits {
+        if body.deposits.len() == expected_deposit_count {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_deposit(state, &mut operation, &mut (), context).is_ok() {
+            body.deposits.push(operation);
+        }
+    }
+    if body.deposits.len() != expected_deposit_count {
+        return Err(invalid_operation_error(InvalidOperation::Deposit(
+            InvalidDeposit::IncorrectCount {
+                expected: expected_deposit_count,
+                count: body.deposits.len(),
+            },
+        )))
+    }
+    for operation in &operations.voluntary_exits {
+        if body.voluntary_exits.len() == context.max_voluntary_exits {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_voluntary_exit(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.voluntary_exits.push(operation);
+        }
+    }
     Ok(())
 }
 pub fn process_registry_updates<
@@ -525,6 +670,7 @@ pub fn process_registry_updates<
         MAX_VALIDATORS_PER_COMMITTEE,
         SYNC_COMMITTEE_SIZE,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     let current_epoch = get_current_epoch(state, context);
@@ -536,7 +682,7 @@ pub fn process_registry_updates<
         if is_active_validator(validator, current_epoch) &&
             validator.effective_balance <= context.ejection_balance
         {
-            initiate_validator_exit(state, i, context);
+            initiate_validator_exit(state, i, observer, context);
         }
     }
     let mut activation_queue =
@@ -561,6 +707,10 @@ pub fn process_registry_updates<
     for i in activation_queue.into_iter().take(get_validator_churn_limit(state, context)) {
         let validator = &mut state.validators[i];
         validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
     }
 }
 pub fn process_eth1_data_reset<
@@ -610,6 +760,7 @@ pub fn process_effective_balance_updates<
         MAX_VALIDATORS_PER_COMMITTEE,
         SYNC_COMMITTEE_SIZE,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     let hysteresis_increment = context.effective_balance_increment / context.hysteresis_quotient;
@@ -621,10 +772,18 @@ pub fn process_effective_balance_updates<
         if balance + downward_threshold < validator.effective_balance ||
             validator.effective_balance + upward_threshold < balance
         {
+            let previous = validator.effective_balance;
             validator.effective_balance = Gwei::min(
                 balance - balance % context.effective_balance_increment,
                 context.max_effective_balance,
             );
+            if validator.effective_balance != previous {
+                observer.on_event(&Event::EffectiveBalanceChanged {
+                    index: i,
+                    previous,
+                    current: validator.effective_balance,
+                });
+            }
         }
     }
 }
@@ -737,6 +896,7 @@ pub fn weigh_justification_and_finalization<
     total_active_balance: Gwei,
     previous_epoch_target_balance: Gwei,
     current_epoch_target_balance: Gwei,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let previous_epoch = get_previous_epoch(state, context);
@@ -760,6 +920,15 @@ pub fn weigh_justification_and_finalization<
         };
         state.justification_bits.set(0, true);
     }
+    if state.previous_justified_checkpoint != old_previous_justified_checkpoint ||
+        state.current_justified_checkpoint != old_current_justified_checkpoint
+    {
+        observer.on_event(&Event::JustificationChanged {
+            previous_justified: state.previous_justified_checkpoint.clone(),
+            current_justified: state.current_justified_checkpoint.clone(),
+        });
+    }
+    let old_finalized_checkpoint = state.finalized_checkpoint.clone();
     let bits = &state.justification_bits;
     if bits[1..4].all() && old_previous_justified_checkpoint.epoch + 3 == current_epoch {
         state.finalized_checkpoint = old_previous_justified_checkpoint.clone();
@@ -773,6 +942,11 @@ pub fn weigh_justification_and_finalization<
     if bits[0..2].all() && old_current_justified_checkpoint.epoch + 1 == current_epoch {
         state.finalized_checkpoint = old_current_justified_checkpoint;
     }
+    if state.finalized_checkpoint != old_finalized_checkpoint {
+        observer.on_event(&Event::FinalizationChanged {
+            finalized: state.finalized_checkpoint.clone(),
+        });
+    }
     Ok(())
 }
 pub fn get_proposer_reward<
@@ -848,6 +1022,153 @@ pub fn is_in_inactivity_leak<
 ) -> bool {
     get_finality_delay(state, context) > context.min_epochs_to_inactivity_penalty
 }
+pub fn process_justification_and_finalization_with_balances<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    balances: &ProgressiveBalances,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    if get_current_epoch(state, context) <= GENESIS_EPOCH + 1 {
+        return Ok(())
+    }
+    weigh_justification_and_finalization(
+        state,
+        balances.total_active_balance,
+        balances.previous_epoch_target_balance(),
+        balances.current_epoch_target_balance,
+        observer,
+        context,
+    )
+}
+pub fn process_validator_updates<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    index: ValidatorIndex,
+    total_active_balance: Gwei,
+    adjusted_total_slashing_balance: Gwei,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> bool {
+    let current_epoch = get_current_epoch(state, context);
+    let validator = &mut state.validators[index];
+    if is_eligible_for_activation_queue(validator, context) {
+        validator.activation_eligibility_epoch = current_epoch + 1;
+    }
+    if is_active_validator(validator, current_epoch) &&
+        validator.effective_balance <= context.ejection_balance
+    {
+        initiate_validator_exit(state, index, observer, context);
+    }
+    let validator = &state.validators[index];
+    let is_eligible_for_activation = is_eligible_for_activation(state, validator);
+    if validator.slashed &&
+        (current_epoch + context.epochs_per_slashings_vector / 2) == validator.withdrawable_epoch
+    {
+        let increment = context.effective_balance_increment;
+        let penalty_numerator =
+            validator.effective_balance / increment * adjusted_total_slashing_balance;
+        let penalty = penalty_numerator / total_active_balance * increment;
+        decrease_balance(state, index, penalty);
+    }
+    let hysteresis_increment = context.effective_balance_increment / context.hysteresis_quotient;
+    let downward_threshold = hysteresis_increment * context.hysteresis_downward_multiplier;
+    let upward_threshold = hysteresis_increment * context.hysteresis_upward_multiplier;
+    let validator = &mut state.validators[index];
+    let balance = state.balances[index];
+    if balance + downward_threshold < validator.effective_balance ||
+        validator.effective_balance + upward_threshold < balance
+    {
+        let previous = validator.effective_balance;
+        validator.effective_balance = Gwei::min(
+            balance - balance % context.effective_balance_increment,
+            context.max_effective_balance,
+        );
+        if validator.effective_balance != previous {
+            observer.on_event(&Event::EffectiveBalanceChanged {
+                index,
+                previous,
+                current: validator.effective_balance,
+            });
+        }
+    }
+    is_eligible_for_activation
+}
+pub fn process_activation_queue<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    mut activation_queue: Vec<ValidatorIndex>,
+    activation_churn_limit: usize,
+    observer: &mut dyn Observer,
+    context: &Context,
+) {
+    activation_queue.sort_by(|&i, &j| {
+        let a = &state.validators[i];
+        let b = &state.validators[j];
+        (a.activation_eligibility_epoch, i).cmp(&(b.activation_eligibility_epoch, j))
+    });
+    let current_epoch = get_current_epoch(state, context);
+    let activation_exit_epoch = compute_activation_exit_epoch(current_epoch, context);
+    for i in activation_queue.into_iter().take(activation_churn_limit) {
+        let validator = &mut state.validators[i];
+        validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
+    }
+}
 pub fn is_valid_genesis_state<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -978,6 +1299,7 @@ pub fn is_valid_indexed_attestation<
         SYNC_COMMITTEE_SIZE,
     >,
     indexed_attestation: &mut IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let attesting_indices = &indexed_attestation.attesting_indices;
@@ -1004,14 +1326,12 @@ pub fn is_valid_indexed_attestation<
             InvalidIndexedAttestation::DuplicateIndices(Vec::from_iter(duplicates)),
         )))
     }
-    let mut public_keys = vec![];
     for &index in &attesting_indices[..] {
-        let public_key = state.validators.get(index).map(|v| &v.public_key).ok_or_else(|| {
-            invalid_operation_error(InvalidOperation::IndexedAttestation(
+        if index >= state.validators.len() {
+            return Err(invalid_operation_error(InvalidOperation::IndexedAttestation(
                 InvalidIndexedAttestation::InvalidIndex(index),
-            ))
-        })?;
-        public_keys.push(public_key);
+            )))
+        }
     }
     let domain = get_domain(
         state,
@@ -1020,8 +1340,17 @@ pub fn is_valid_indexed_attestation<
         context,
     )?;
     let signing_root = compute_signing_root(&mut indexed_attestation.data, domain)?;
-    fast_aggregate_verify(&public_keys, signing_root.as_ref(), &indexed_attestation.signature)
-        .map_err(Into::into)
+    let signature_set = context.pubkey_cache.aggregate_signature_set(
+        &state.validators,
+        attesting_indices,
+        signing_root.as_ref(),
+        &indexed_attestation.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::IndexedAttestation(
+            InvalidIndexedAttestation::InvalidSignature(indexed_attestation.signature.clone()),
+        ))
+    })
 }
 pub fn verify_block_signature<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -1057,17 +1386,19 @@ pub fn verify_block_signature<
         MAX_VOLUNTARY_EXITS,
         SYNC_COMMITTEE_SIZE,
     >,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let proposer_index = signed_block.message.proposer_index;
-    let proposer = state
-        .validators
-        .get(proposer_index)
-        .ok_or(Error::OutOfBounds { requested: proposer_index, bound: state.validators.len() })?;
     let domain = get_domain(state, DomainType::BeaconProposer, None, context)?;
     let signing_root = compute_signing_root(&mut signed_block.message, domain)?;
-    let public_key = &proposer.public_key;
-    verify_signature(public_key, signing_root.as_ref(), &signed_block.signature).map_err(Into::into)
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        proposer_index,
+        signing_root.as_ref(),
+        &signed_block.signature,
+    );
+    signatures.verify(signature_set, || crate::crypto::Error::InvalidSignature.into())
 }
 pub fn get_domain<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -1154,6 +1485,51 @@ pub fn compute_shuffled_index(
     }
     Ok(index)
 }
+/// Shuffles the entire list of `indices` with the "swap-or-not" network so that
+/// `compute_shuffled_list(indices, seed, context)[i] ==
+/// indices[compute_shuffled_index(i, indices.len(), seed, context)]`.
+pub fn compute_shuffled_list(
+    indices: &[ValidatorIndex],
+    seed: &Bytes32,
+    context: &Context,
+) -> Vec<ValidatorIndex> {
+    let mut list = indices.to_vec();
+    let index_count = list.len();
+    if index_count < 2 {
+        return list
+    }
+    let mut pivot_input = [0u8; 33];
+    pivot_input[..32].copy_from_slice(seed.as_ref());
+    let mut source_input = [0u8; 37];
+    source_input[..32].copy_from_slice(seed.as_ref());
+    for current_round in (0..context.shuffle_round_count).rev() {
+        pivot_input[32] = current_round as u8;
+        let pivot_bytes: [u8; 8] = hash(pivot_input).as_ref()[..8].try_into().unwrap();
+        let pivot = (u64::from_le_bytes(pivot_bytes) as usize) % index_count;
+        source_input[32] = current_round as u8;
+        let mut source_position = None;
+        let mut source = Bytes32::default();
+        for index in 0..index_count {
+            let flip = (pivot + index_count - index) % index_count;
+            if index >= flip {
+                continue
+            }
+            let position = flip;
+            if source_position != Some(position / 256) {
+                let position_bytes: [u8; 4] = ((position / 256) as u32).to_le_bytes();
+                source_input[33..].copy_from_slice(&position_bytes);
+                source = hash(source_input);
+                source_position = Some(position / 256);
+            }
+            let byte = source.as_ref()[(position % 256) / 8];
+            let bit = (byte >> (position % 8)) % 2;
+            if bit != 0 {
+                list.swap(index, flip);
+            }
+        }
+    }
+    list
+}
 pub fn compute_proposer_index<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -1514,12 +1890,26 @@ pub fn get_beacon_committee<
     context: &Context,
 ) -> Result<Vec<ValidatorIndex>> {
     let epoch = compute_epoch_at_slot(slot, context);
-    let committees_per_slot = get_committee_count_per_slot(state, epoch, context);
-    let indices = get_active_validator_indices(state, epoch);
     let seed = get_seed(state, epoch, DomainType::BeaconAttester, context);
-    let index = (slot % context.slots_per_epoch) * committees_per_slot as u64 + index as u64;
-    let count = committees_per_slot as u64 * context.slots_per_epoch;
-    compute_committee(&indices, &seed, index as usize, count as usize, context)
+    let shuffled_indices = context.shuffling_cache.get_or_compute_shuffling(
+        epoch,
+        &seed,
+        || get_active_validator_indices(state, epoch),
+        context,
+    );
+    let active_validator_count = shuffled_indices.len();
+    let committees_per_slot = u64::max(
+        1,
+        u64::min(
+            context.max_committees_per_slot,
+            active_validator_count as u64 / context.slots_per_epoch / context.target_committee_size,
+        ),
+    );
+    let index = (slot % context.slots_per_epoch) * committees_per_slot + index as u64;
+    let count = committees_per_slot * context.slots_per_epoch;
+    let start = (active_validator_count * index as usize) / count as usize;
+    let end = active_validator_count * (index as usize + 1) / count as usize;
+    Ok(shuffled_indices[start..end].to_vec())
 }
 pub fn get_beacon_proposer_index<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -1549,8 +1939,12 @@ pub fn get_beacon_proposer_index<
         .copy_from_slice(get_seed(state, epoch, DomainType::BeaconProposer, context).as_ref());
     input[32..40].copy_from_slice(&state.slot.to_le_bytes());
     let seed = hash(input);
-    let indices = get_active_validator_indices(state, epoch);
-    compute_proposer_index(state, &indices, &seed, context)
+    context.shuffling_cache.get_or_compute_proposer(
+        &seed,
+        || get_active_validator_indices(state, epoch),
+        |index| state.validators[index].effective_balance,
+        context,
+    )
 }
 pub fn get_total_balance<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -1754,6 +2148,7 @@ pub fn initiate_validator_exit<
         SYNC_COMMITTEE_SIZE,
     >,
     index: ValidatorIndex,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     if state.validators[index].exit_epoch != FAR_FUTURE_EPOCH {
@@ -1775,6 +2170,11 @@ pub fn initiate_validator_exit<
     state.validators[index].exit_epoch = exit_queue_epoch;
     state.validators[index].withdrawable_epoch =
         state.validators[index].exit_epoch + context.min_validator_withdrawability_delay;
+    observer.on_event(&Event::ValidatorExited {
+        index,
+        exit_epoch: exit_queue_epoch,
+        withdrawable_epoch: state.validators[index].withdrawable_epoch,
+    });
 }
 pub fn get_eligible_validator_indices<
     'a,
@@ -1831,6 +2231,7 @@ pub fn process_slots<
         SYNC_COMMITTEE_SIZE,
     >,
     slot: Slot,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     if state.slot >= slot {
@@ -1839,7 +2240,7 @@ pub fn process_slots<
     while state.slot < slot {
         process_slot(state, context)?;
         if (state.slot + 1) % context.slots_per_epoch == 0 {
-            process_epoch(state, context)?;
+            process_epoch_single_pass(state, observer, context)?;
         }
         state.slot += 1;
     }
@@ -1868,6 +2269,33 @@ pub fn process_slot<
     context: &Context,
 ) -> Result<()> {
     let previous_state_root = state.hash_tree_root()?;
+    process_slot_with_state_root(state, previous_state_root, context)
+}
+/// Performs `process_slot` with the known root of `state` as `previous_state_root`, rather than
+/// computing it.
+pub fn process_slot_with_state_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    previous_state_root: Root,
+    context: &Context,
+) -> Result<()> {
     let root_index = state.slot % context.slots_per_historical_root;
     state.state_roots[root_index as usize] = previous_state_root;
     if state.latest_block_header.state_root == Root::default() {
@@ -1878,6 +2306,46 @@ pub fn process_slot<
     state.block_roots[root_index as usize] = previous_block_root;
     Ok(())
 }
+/// Performs `process_slots` with the known root of `state` as `state_root`, which saves hashing
+/// the state for the first slot processed.
+pub fn process_slots_with_state_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    slot: Slot,
+    state_root: Root,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    if state.slot >= slot {
+        return Err(Error::TransitionToPreviousSlot { requested: slot, current: state.slot })
+    }
+    process_slot_with_state_root(state, state_root, context)?;
+    if (state.slot + 1) % context.slots_per_epoch == 0 {
+        process_epoch_single_pass(state, observer, context)?;
+    }
+    state.slot += 1;
+    if state.slot < slot {
+        process_slots(state, slot, observer, context)?;
+    }
+    Ok(())
+}
 pub fn state_transition_block_in_slot<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -1913,18 +2381,21 @@ pub fn state_transition_block_in_slot<
         SYNC_COMMITTEE_SIZE,
     >,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    let validate_result = match validation {
-        Validation::Enabled => true,
-        Validation::Disabled => false,
+    let mut signatures = SignatureSets::for_validation(&validation);
+    let result = if validation.signatures {
+        verify_block_signature(state, signed_block, &mut signatures, context)
+    } else {
+        Ok(())
     };
-    if validate_result {
-        verify_block_signature(state, signed_block, context)?;
-    }
-    let block = &mut signed_block.message;
-    process_block(state, block, context)?;
-    if validate_result && block.state_root != state.hash_tree_root()? {
+    let result = result.and_then(|_| {
+        process_block(state, &mut signed_block.message, &mut signatures, observer, context)
+    });
+    signatures.finish(result)?;
+    let block = &signed_block.message;
+    if validation.state_root && block.state_root != state.hash_tree_root()? {
         Err(Error::InvalidStateRoot)
     } else {
         Ok(())
@@ -1965,8 +2436,39 @@ pub fn state_transition<
         SYNC_COMMITTEE_SIZE,
     >,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_slots(state, signed_block.message.slot, context)?;
-    state_transition_block_in_slot(state, signed_block, validation, context)
+    process_slots(state, signed_block.message.slot, observer, context)?;
+    state_transition_block_in_slot(state, signed_block, validation, observer, context)
+}
+pub fn is_aggregator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    slot: Slot,
+    index: CommitteeIndex,
+    slot_signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool> {
+    let committee = get_beacon_committee(state, slot, index, context)?;
+    let modulo = u64::max(1, committee.len() as u64 / context.target_aggregators_per_committee);
+    let signature_hash: [u8; 8] = hash(slot_signature.as_ref()).as_ref()[..8].try_into().unwrap();
+    Ok(u64::from_le_bytes(signature_hash) % modulo == 0)
 }
@@ -12,7 +12,15 @@ pub struct SyncAggregate<const SYNC_COMMITTEE_SIZE: usize> {
 }
 
 #[derive(
-    Default, Debug, SimpleSerialize, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+    Default,
+    Debug,
+    SimpleSerialize,
+    Prove,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 pub struct SyncCommittee<const SYNC_COMMITTEE_SIZE: usize> {
     #[serde(rename = "pubkeys")]
@@ -1,7 +1,13 @@
 use crate::{
-    primitives::{BlsSignature, Root, Slot, ValidatorIndex},
+    altair::{beacon_state::BeaconState, constants::SYNC_COMMITTEE_SUBNET_COUNT},
+    crypto::hash,
+    phase0::compute_epoch_at_slot,
+    primitives::{BlsPublicKey, BlsSignature, Root, Slot, ValidatorIndex},
     ssz::prelude::*,
+    state_transition::{Context, Result},
+    Error,
 };
+use std::collections::HashSet;
 
 #[derive(Debug, Default, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
 pub struct SyncCommitteeMessage {
@@ -43,3 +49,101 @@ pub struct SyncAggregatorSelectionData {
     pub slot: Slot,
     pub subcommittee_index: u64,
 }
+
+// Return whether the validator with ``signature`` as its selection proof is an aggregator of
+// its sync subcommittee.
+pub fn is_sync_committee_aggregator(signature: &BlsSignature, context: &Context) -> bool {
+    let modulo = u64::max(
+        1,
+        (context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT) as u64 /
+            context.target_aggregators_per_sync_subcommittee,
+    );
+    let signature_hash: [u8; 8] = hash(signature.as_ref()).as_ref()[..8].try_into().unwrap();
+    u64::from_le_bytes(signature_hash) % modulo == 0
+}
+
+// Return the public keys of the sync subcommittee with ``subcommittee_index``.
+pub fn get_sync_subcommittee_pubkeys<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    subcommittee_index: u64,
+    context: &Context,
+) -> Vec<BlsPublicKey> {
+    let current_period =
+        compute_epoch_at_slot(state.slot, context) / context.epochs_per_sync_committee_period;
+    let next_slot_period =
+        compute_epoch_at_slot(state.slot + 1, context) / context.epochs_per_sync_committee_period;
+    let sync_committee = if current_period == next_slot_period {
+        &state.current_sync_committee
+    } else {
+        &state.next_sync_committee
+    };
+    let sync_subcommittee_size = SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT;
+    let start = subcommittee_index as usize * sync_subcommittee_size;
+    sync_committee.public_keys[start..start + sync_subcommittee_size].to_vec()
+}
+
+// Return the sync committee subnets the validator with ``validator_index`` is assigned to.
+pub fn compute_subnets_for_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+    >,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<HashSet<u64>> {
+    let target_public_key = &state
+        .validators
+        .get(validator_index)
+        .ok_or(Error::OutOfBounds { requested: validator_index, bound: state.validators.len() })?
+        .public_key;
+    let current_period =
+        compute_epoch_at_slot(state.slot, context) / context.epochs_per_sync_committee_period;
+    let next_slot_period =
+        compute_epoch_at_slot(state.slot + 1, context) / context.epochs_per_sync_committee_period;
+    let sync_committee = if current_period == next_slot_period {
+        &state.current_sync_committee
+    } else {
+        &state.next_sync_committee
+    };
+    let sync_subcommittee_size = SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT;
+    Ok(sync_committee
+        .public_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, public_key)| *public_key == target_public_key)
+        .map(|(index, _)| (index / sync_subcommittee_size) as u64)
+        .collect())
+}
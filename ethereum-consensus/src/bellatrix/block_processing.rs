@@ -7,7 +7,7 @@ use crate::{
     },
     error::{invalid_operation_error, InvalidExecutionPayload},
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{Context, Observer, Result, SignatureSets, Validation},
 };
 
 pub fn process_execution_payload<
@@ -49,6 +49,7 @@ pub fn process_execution_payload<
         MAX_TRANSACTIONS_PER_PAYLOAD,
     >,
     execution_engine: &E,
+    validation: Validation,
     context: &Context,
 ) -> Result<()> {
     let parent_hash_invalid =
@@ -86,8 +87,10 @@ pub fn process_execution_payload<
         ))
     }
 
-    let new_payload_request = NewPayloadRequest(payload);
-    execution_engine.verify_and_notify_new_payload(&new_payload_request)?;
+    if validation.execution_payload {
+        let new_payload_request = NewPayloadRequest(payload);
+        execution_engine.verify_and_notify_new_payload(&new_payload_request)?;
+    }
 
     state.latest_execution_payload_header = ExecutionPayloadHeader {
         parent_hash: payload.parent_hash.clone(),
@@ -160,6 +163,9 @@ pub fn process_block<
         MAX_TRANSACTIONS_PER_PAYLOAD,
     >,
     execution_engine: &E,
+    validation: Validation,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     process_block_header(state, block, context)?;
@@ -168,12 +174,13 @@ pub fn process_block<
             state,
             &mut block.body.execution_payload,
             execution_engine,
+            validation,
             context,
         )?;
     }
-    process_randao(state, &block.body, context)?;
+    process_randao(state, &block.body, signatures, context)?;
     process_eth1_data(state, &block.body, context);
-    process_operations(state, &mut block.body, context)?;
-    process_sync_aggregate(state, &block.body.sync_aggregate, context)?;
+    process_operations(state, &mut block.body, signatures, observer, context)?;
+    process_sync_aggregate(state, &block.body.sync_aggregate, signatures, context)?;
     Ok(())
 }
@@ -1,14 +1,16 @@
 use crate::{
     bellatrix::{
-        decrease_balance, get_current_epoch, get_total_active_balance,
-        process_effective_balance_updates, process_eth1_data_reset,
+        decrease_balance, get_current_epoch, get_progressive_balances, get_total_active_balance,
+        get_validator_churn_limit, process_effective_balance_updates, process_eth1_data_reset,
         process_historical_roots_update, process_inactivity_updates,
-        process_justification_and_finalization, process_participation_flag_updates,
+        process_justification_and_finalization,
+        process_justification_and_finalization_with_balances, process_participation_flag_updates,
         process_randao_mixes_reset, process_registry_updates, process_rewards_and_penalties,
-        process_slashings_reset, process_sync_committee_updates, BeaconState,
+        process_single_pass, process_slashings_reset, process_sync_committee_updates, BeaconState,
+        SinglePassParameters,
     },
     primitives::Gwei,
-    state_transition::{Context, Result},
+    state_transition::{Context, Observer, Result},
 };
 
 pub fn process_slashings<
@@ -82,19 +84,68 @@ pub fn process_epoch<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_justification_and_finalization(state, context)?;
+    process_justification_and_finalization(state, observer, context)?;
     process_inactivity_updates(state, context)?;
     process_rewards_and_penalties(state, context)?;
-    process_registry_updates(state, context);
+    process_registry_updates(state, observer, context);
     process_slashings(state, context)?;
     process_eth1_data_reset(state, context);
-    process_effective_balance_updates(state, context);
+    process_effective_balance_updates(state, observer, context);
     process_slashings_reset(state, context);
     process_randao_mixes_reset(state, context);
     process_historical_roots_update(state, context)?;
     process_participation_flag_updates(state)?;
-    process_sync_committee_updates(state, context)?;
+    process_sync_committee_updates(state, observer, context)?;
+    Ok(())
+}
+
+/// An optimized `process_epoch` which visits each validator once for the inactivity updates,
+/// rewards and penalties, registry updates, slashings and effective balance updates.
+///
+/// The result is identical to `process_epoch`, which is kept for differential testing.
+pub fn process_epoch_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let balances = get_progressive_balances(state, context)?;
+    process_justification_and_finalization_with_balances(state, &balances, observer, context)?;
+    let parameters = SinglePassParameters {
+        inactivity_penalty_quotient: context.inactivity_penalty_quotient_bellatrix,
+        proportional_slashing_multiplier: context.proportional_slashing_multiplier_bellatrix,
+        activation_churn_limit: get_validator_churn_limit(state, context),
+    };
+    process_single_pass(state, &balances, &parameters, observer, context)?;
+    process_eth1_data_reset(state, context);
+    process_slashings_reset(state, context);
+    process_randao_mixes_reset(state, context);
+    process_historical_roots_update(state, context)?;
+    process_participation_flag_updates(state)?;
+    process_sync_committee_updates(state, observer, context)?;
     Ok(())
 }
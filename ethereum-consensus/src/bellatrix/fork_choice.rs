@@ -1,8 +1,82 @@
-use crate::{primitives::Hash32, ssz::prelude::*};
+use crate::{
+    bellatrix::{compute_epoch_at_slot, BeaconBlock},
+    error::ForkChoiceError,
+    primitives::Hash32,
+    ssz::prelude::*,
+    state_transition::{Context, Result},
+};
 
 #[derive(Default, Debug, SimpleSerialize, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PowBlock {
-    block_hash: Hash32,
-    parent_hash: Hash32,
-    total_difficulty: U256,
+    pub block_hash: Hash32,
+    pub parent_hash: Hash32,
+    pub total_difficulty: U256,
+}
+
+pub fn is_valid_terminal_pow_block(block: &PowBlock, parent: &PowBlock, context: &Context) -> bool {
+    let is_total_difficulty_reached = block.total_difficulty >= context.terminal_total_difficulty;
+    let is_parent_total_difficulty_valid =
+        parent.total_difficulty < context.terminal_total_difficulty;
+    is_total_difficulty_reached && is_parent_total_difficulty_valid
+}
+
+// NOTE: the spec fetches proof-of-work blocks with an external `get_pow_block`;
+// callers provide that lookup here with `get_pow_block`.
+pub fn validate_merge_block<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    F: Fn(&Hash32) -> Option<PowBlock>,
+>(
+    block: &BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+    >,
+    get_pow_block: F,
+    context: &Context,
+) -> Result<()> {
+    let parent_hash = &block.body.execution_payload.parent_hash;
+    if context.terminal_block_hash != Hash32::default() {
+        let epoch = compute_epoch_at_slot(block.slot, context);
+        let activation_epoch = context.terminal_block_hash_activation_epoch;
+        if epoch < activation_epoch {
+            return Err(
+                ForkChoiceError::TerminalBlockHashNotActivated { epoch, activation_epoch }.into()
+            )
+        }
+        if parent_hash != &context.terminal_block_hash {
+            return Err(ForkChoiceError::InvalidTerminalBlockHash {
+                provided: parent_hash.clone(),
+                expected: context.terminal_block_hash.clone(),
+            }
+            .into())
+        }
+        return Ok(())
+    }
+
+    let pow_block = get_pow_block(parent_hash)
+        .ok_or_else(|| ForkChoiceError::UnknownPowBlock(parent_hash.clone()))?;
+    let pow_parent = get_pow_block(&pow_block.parent_hash)
+        .ok_or_else(|| ForkChoiceError::UnknownPowBlock(pow_block.parent_hash.clone()))?;
+    if !is_valid_terminal_pow_block(&pow_block, &pow_parent, context) {
+        return Err(ForkChoiceError::InvalidTerminalPowBlock(pow_block.block_hash).into())
+    }
+    Ok(())
 }
@@ -6,7 +6,7 @@ use crate::{
         ExecutionPayloadHeader, PROPOSER_WEIGHT, TIMELY_TARGET_FLAG_INDEX, WEIGHT_DENOMINATOR,
     },
     primitives::{Gwei, Slot, ValidatorIndex, GENESIS_SLOT},
-    state_transition::{Context, Result},
+    state_transition::{Context, Event, Observer, Result},
     Error,
 };
 
@@ -84,10 +84,11 @@ pub fn slash_validator<
     >,
     slashed_index: ValidatorIndex,
     whistleblower_index: Option<ValidatorIndex>,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let epoch = get_current_epoch(state, context);
-    initiate_validator_exit(state, slashed_index, context);
+    initiate_validator_exit(state, slashed_index, observer, context);
     state.validators[slashed_index].slashed = true;
     state.validators[slashed_index].withdrawable_epoch = u64::max(
         state.validators[slashed_index].withdrawable_epoch,
@@ -103,6 +104,7 @@ pub fn slash_validator<
     );
     let proposer_index = get_beacon_proposer_index(state, context)?;
     let whistleblower_index = whistleblower_index.unwrap_or(proposer_index);
+    observer.on_event(&Event::ValidatorSlashed { index: slashed_index, whistleblower_index });
     let whistleblower_reward =
         state.validators[slashed_index].effective_balance / context.whistleblower_reward_quotient;
     let proposer_reward_scaling_factor = PROPOSER_WEIGHT / WEIGHT_DENOMINATOR;
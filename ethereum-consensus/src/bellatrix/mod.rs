@@ -5,6 +5,7 @@ pub mod beacon_block;
 pub mod beacon_state;
 pub mod blinded_beacon_block;
 pub mod block_processing;
+pub mod block_production;
 pub mod epoch_processing;
 pub mod execution_engine;
 pub mod execution_payload;
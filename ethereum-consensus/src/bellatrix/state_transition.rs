@@ -4,7 +4,7 @@ use crate::{
         SignedBeaconBlock,
     },
     ssz::prelude::Merkleized,
-    state_transition::{Context, Result, Validation},
+    state_transition::{Context, Observer, Result, SignatureSets, Validation},
     Error,
 };
 
@@ -63,18 +63,29 @@ pub fn state_transition_block_in_slot<
     >,
     execution_engine: &E,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    let validate_result = match validation {
-        Validation::Enabled => true,
-        Validation::Disabled => false,
+    let mut signatures = SignatureSets::for_validation(&validation);
+    let result = if validation.signatures {
+        verify_block_signature(state, signed_block, &mut signatures, context)
+    } else {
+        Ok(())
     };
-    if validate_result {
-        verify_block_signature(state, signed_block, context)?;
-    }
-    let block = &mut signed_block.message;
-    process_block(state, block, execution_engine, context)?;
-    if validate_result && block.state_root != state.hash_tree_root()? {
+    let result = result.and_then(|_| {
+        process_block(
+            state,
+            &mut signed_block.message,
+            execution_engine,
+            validation,
+            &mut signatures,
+            observer,
+            context,
+        )
+    });
+    signatures.finish(result)?;
+    let block = &signed_block.message;
+    if validation.state_root && block.state_root != state.hash_tree_root()? {
         Err(Error::InvalidStateRoot)
     } else {
         Ok(())
@@ -133,9 +144,17 @@ pub fn state_transition<
     >,
     execution_engine: &E,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_slots(state, signed_block.message.slot, context)?;
+    process_slots(state, signed_block.message.slot, observer, context)?;
 
-    state_transition_block_in_slot(state, signed_block, execution_engine, validation, context)
+    state_transition_block_in_slot(
+        state,
+        signed_block,
+        execution_engine,
+        validation,
+        observer,
+        context,
+    )
 }
@@ -0,0 +1,343 @@
+use clap::Args;
+use ethereum_consensus::{
+    configs::Config,
+    genesis::{
+        initialize_genesis_state, initialize_genesis_state_from_validators, interop_deposit_data,
+        GenesisValidator,
+    },
+    phase0::DepositData,
+    primitives::GENESIS_SLOT,
+    ssz::prelude::serialize,
+    state_transition::Context,
+    types::{BeaconState, ExecutionPayloadHeader},
+    Fork,
+};
+use std::{
+    fs::File,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Args)]
+#[clap(
+    about = "generate the genesis state of a local devnet, at the fork active at genesis in its config"
+)]
+pub struct Command {
+    #[clap(long, help = "network config file; defaults to the mainnet config")]
+    config: Option<PathBuf>,
+    #[clap(
+        long,
+        conflicts_with_all = ["deposits", "validators"],
+        required_unless_present_any = ["deposits", "validators"],
+        help = "number of interop validators to deposit"
+    )]
+    validator_count: Option<usize>,
+    #[clap(
+        long,
+        conflicts_with = "validators",
+        help = "JSON file with a list of deposit data to make the validators from"
+    )]
+    deposits: Option<PathBuf>,
+    #[clap(
+        long,
+        help = "JSON file with a list of validators, as `pubkey`, `withdrawal_credentials` and `balance`"
+    )]
+    validators: Option<PathBuf>,
+    #[clap(long, help = "JSON file with the execution payload header of the genesis state")]
+    execution_payload_header: Option<PathBuf>,
+    #[clap(long, help = "genesis time in seconds since the UNIX epoch; defaults to now")]
+    genesis_time: Option<u64>,
+    #[clap(long, default_value = "genesis.ssz", help = "file to write the SSZ-encoded state to")]
+    output: PathBuf,
+}
+
+impl Command {
+    pub fn execute(self) -> eyre::Result<()> {
+        let (context, preset_base) = match &self.config {
+            Some(path) => {
+                let config: Config = serde_yaml::from_reader(File::open(path)?)?;
+                (Context::try_from_file(path)?, config.preset_base)
+            }
+            None => (Context::for_mainnet(), "mainnet".to_string()),
+        };
+        let fork = context.fork_for(GENESIS_SLOT);
+
+        let validators = match (&self.validators, &self.deposits, self.validator_count) {
+            (Some(path), _, _) => {
+                Validators::Validators(serde_json::from_reader(File::open(path)?)?)
+            }
+            (None, Some(path), _) => {
+                Validators::Deposits(serde_json::from_reader(File::open(path)?)?)
+            }
+            (None, None, Some(validator_count)) => {
+                Validators::Deposits(interop_deposit_data(validator_count, &context)?)
+            }
+            (None, None, None) => unreachable!("clap requires one of the validator sources"),
+        };
+        let execution_payload_header = match &self.execution_payload_header {
+            Some(path) => {
+                let file = File::open(path)?;
+                let header = match fork {
+                    Fork::Bellatrix => {
+                        ExecutionPayloadHeader::Bellatrix(serde_json::from_reader(file)?)
+                    }
+                    Fork::Capella => {
+                        ExecutionPayloadHeader::Capella(serde_json::from_reader(file)?)
+                    }
+                    Fork::Deneb => ExecutionPayloadHeader::Deneb(serde_json::from_reader(file)?),
+                    fork => {
+                        eyre::bail!("a genesis state at {fork:?} has no execution payload header")
+                    }
+                };
+                Some(header)
+            }
+            None => None,
+        };
+        let genesis_time = match self.genesis_time {
+            Some(genesis_time) => genesis_time,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        };
+
+        let encoding = match preset_base.as_ref() {
+            "mainnet" => {
+                use ethereum_consensus::{
+                    altair::mainnet::SYNC_COMMITTEE_SIZE,
+                    bellatrix::mainnet::{
+                        BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+                        MAX_TRANSACTIONS_PER_PAYLOAD,
+                    },
+                    capella::mainnet::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD},
+                    deneb::mainnet::MAX_BLOB_COMMITMENTS_PER_BLOCK,
+                    phase0::mainnet::{
+                        EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR,
+                        ETH1_DATA_VOTES_BOUND, HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS,
+                        MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS, MAX_PROPOSER_SLASHINGS,
+                        MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+                        PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT,
+                        VALIDATOR_REGISTRY_LIMIT,
+                    },
+                };
+
+                let state = genesis_state::<
+                    SLOTS_PER_HISTORICAL_ROOT,
+                    HISTORICAL_ROOTS_LIMIT,
+                    ETH1_DATA_VOTES_BOUND,
+                    VALIDATOR_REGISTRY_LIMIT,
+                    EPOCHS_PER_HISTORICAL_VECTOR,
+                    EPOCHS_PER_SLASHINGS_VECTOR,
+                    MAX_VALIDATORS_PER_COMMITTEE,
+                    PENDING_ATTESTATIONS_BOUND,
+                    SYNC_COMMITTEE_SIZE,
+                    BYTES_PER_LOGS_BLOOM,
+                    MAX_EXTRA_DATA_BYTES,
+                    MAX_BYTES_PER_TRANSACTION,
+                    MAX_TRANSACTIONS_PER_PAYLOAD,
+                    MAX_PROPOSER_SLASHINGS,
+                    MAX_ATTESTER_SLASHINGS,
+                    MAX_ATTESTATIONS,
+                    MAX_DEPOSITS,
+                    MAX_VOLUNTARY_EXITS,
+                    MAX_WITHDRAWALS_PER_PAYLOAD,
+                    MAX_BLS_TO_EXECUTION_CHANGES,
+                    MAX_BLOB_COMMITMENTS_PER_BLOCK,
+                >(
+                    &validators, execution_payload_header, genesis_time, &context
+                )?;
+                encode(&state)?
+            }
+            "minimal" => {
+                use ethereum_consensus::{
+                    altair::minimal::SYNC_COMMITTEE_SIZE,
+                    bellatrix::minimal::{
+                        BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+                        MAX_TRANSACTIONS_PER_PAYLOAD,
+                    },
+                    capella::minimal::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD},
+                    deneb::minimal::MAX_BLOB_COMMITMENTS_PER_BLOCK,
+                    phase0::minimal::{
+                        EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR,
+                        ETH1_DATA_VOTES_BOUND, HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS,
+                        MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS, MAX_PROPOSER_SLASHINGS,
+                        MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+                        PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT,
+                        VALIDATOR_REGISTRY_LIMIT,
+                    },
+                };
+
+                let state = genesis_state::<
+                    SLOTS_PER_HISTORICAL_ROOT,
+                    HISTORICAL_ROOTS_LIMIT,
+                    ETH1_DATA_VOTES_BOUND,
+                    VALIDATOR_REGISTRY_LIMIT,
+                    EPOCHS_PER_HISTORICAL_VECTOR,
+                    EPOCHS_PER_SLASHINGS_VECTOR,
+                    MAX_VALIDATORS_PER_COMMITTEE,
+                    PENDING_ATTESTATIONS_BOUND,
+                    SYNC_COMMITTEE_SIZE,
+                    BYTES_PER_LOGS_BLOOM,
+                    MAX_EXTRA_DATA_BYTES,
+                    MAX_BYTES_PER_TRANSACTION,
+                    MAX_TRANSACTIONS_PER_PAYLOAD,
+                    MAX_PROPOSER_SLASHINGS,
+                    MAX_ATTESTER_SLASHINGS,
+                    MAX_ATTESTATIONS,
+                    MAX_DEPOSITS,
+                    MAX_VOLUNTARY_EXITS,
+                    MAX_WITHDRAWALS_PER_PAYLOAD,
+                    MAX_BLS_TO_EXECUTION_CHANGES,
+                    MAX_BLOB_COMMITMENTS_PER_BLOCK,
+                >(
+                    &validators, execution_payload_header, genesis_time, &context
+                )?;
+                encode(&state)?
+            }
+            other => eyre::bail!("unknown preset {other}"),
+        };
+        std::fs::write(&self.output, encoding)?;
+        println!("wrote genesis state at {fork:?} to {}", self.output.display());
+        Ok(())
+    }
+}
+
+// The validators of the genesis state, either deposited or given directly.
+enum Validators {
+    Deposits(Vec<DepositData>),
+    Validators(Vec<GenesisValidator>),
+}
+
+fn genesis_state<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    validators: &Validators,
+    execution_payload_header: Option<
+        ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    >,
+    genesis_time: u64,
+    context: &Context,
+) -> eyre::Result<
+    BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+> {
+    let state = match validators {
+        Validators::Deposits(deposit_data) => {
+            initialize_genesis_state::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >(deposit_data, execution_payload_header, genesis_time, context)?
+        }
+        Validators::Validators(validators) => {
+            initialize_genesis_state_from_validators::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >(validators, execution_payload_header, genesis_time, context)?
+        }
+    };
+    Ok(state)
+}
+
+// Encodes the state of the fork it is at, without a prefix for the fork.
+fn encode<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> eyre::Result<Vec<u8>> {
+    let encoding = match state {
+        BeaconState::Phase0(state) => serialize(state)?,
+        BeaconState::Altair(state) => serialize(state)?,
+        BeaconState::Bellatrix(state) => serialize(state)?,
+        BeaconState::Capella(state) => serialize(state)?,
+        BeaconState::Deneb(state) => serialize(state)?,
+    };
+    Ok(encoding)
+}
@@ -0,0 +1,647 @@
+//! Production of unsigned blocks for any fork from a pre-state and candidate operations.
+use crate::{
+    altair,
+    altair::SyncCommitteeContribution,
+    bellatrix, capella,
+    capella::SignedBlsToExecutionChange,
+    deneb,
+    deneb::polynomial_commitments::KzgCommitment,
+    phase0,
+    phase0::{
+        Attestation, AttesterSlashing, Deposit, Eth1Data, ProposerSlashing, SignedVoluntaryExit,
+    },
+    primitives::{BlsSignature, Bytes32, Slot},
+    ssz::prelude::*,
+    state_transition::Context,
+    types::{
+        BeaconBlock, BeaconState, BlindedBeaconBlock, ExecutionPayload, ExecutionPayloadHeader,
+    },
+    Error, Fork,
+};
+
+/// The candidate operations to include in a block of any fork, in order of preference.
+///
+/// A block only considers the kinds of operations defined in its fork; the others are ignored.
+#[derive(Default, Debug, Clone)]
+pub struct BlockOperations<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub attestations: Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub deposits: Vec<Deposit>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
+    // From Altair
+    pub sync_committee_contributions: Vec<SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>,
+    // From Capella
+    pub bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+    // The vote to make instead of repeating the current vote of the state
+    pub eth1_data: Option<Eth1Data>,
+}
+
+/// Produces an unsigned block for `slot` on top of `state`, dispatching to the `produce_block` of
+/// the fork of `state`, which must also be the fork of `slot`.
+///
+/// The block carries `execution_payload` from Bellatrix and `blob_kzg_commitments` from Deneb. The
+/// payload may only be `None` for a Bellatrix block before the merge, which carries an empty
+/// payload instead. Only the kinds of `operations` defined in the fork of `state` are considered.
+#[allow(clippy::too_many_arguments)]
+pub fn produce_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    execution_payload: Option<
+        ExecutionPayload<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
+    >,
+    blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    Error,
+> {
+    if let Some(execution_payload) = execution_payload.as_ref() {
+        if execution_payload.version() != state.version() {
+            return Err(Error::IncompatibleFork {
+                source_fork: state.version(),
+                destination_fork: execution_payload.version(),
+            })
+        }
+    }
+    match state {
+        BeaconState::Phase0(state) => {
+            let block = phase0::produce_block(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                &to_phase0_operations(operations),
+                context,
+            )?;
+            Ok(BeaconBlock::Phase0(block))
+        }
+        BeaconState::Altair(state) => {
+            let block = altair::produce_block(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                &to_altair_operations(operations),
+                context,
+            )?;
+            Ok(BeaconBlock::Altair(block))
+        }
+        BeaconState::Bellatrix(state) => {
+            let execution_payload = match execution_payload {
+                Some(ExecutionPayload::Bellatrix(execution_payload)) => execution_payload,
+                None if !bellatrix::is_merge_transition_complete(state) => Default::default(),
+                _ => return Err(Error::MissingExecutionPayload(Fork::Bellatrix)),
+            };
+            let block = bellatrix::produce_block(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                execution_payload,
+                &to_altair_operations(operations),
+                context,
+            )?;
+            Ok(BeaconBlock::Bellatrix(block))
+        }
+        BeaconState::Capella(state) => {
+            let Some(ExecutionPayload::Capella(execution_payload)) = execution_payload else {
+                return Err(Error::MissingExecutionPayload(Fork::Capella))
+            };
+            let block = capella::produce_block(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                execution_payload,
+                &to_capella_operations(operations),
+                context,
+            )?;
+            Ok(BeaconBlock::Capella(block))
+        }
+        BeaconState::Deneb(state) => {
+            let Some(ExecutionPayload::Deneb(execution_payload)) = execution_payload else {
+                return Err(Error::MissingExecutionPayload(Fork::Deneb))
+            };
+            let block = deneb::produce_block(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                execution_payload,
+                blob_kzg_commitments,
+                &to_capella_operations(operations),
+                context,
+            )?;
+            Ok(BeaconBlock::Deneb(block))
+        }
+    }
+}
+
+/// Produces an unsigned blinded block for `slot` on top of `state`, dispatching to the
+/// `produce_blinded_block` of the fork of `state`, which must also be the fork of
+/// `execution_payload_header`.
+///
+/// The block carries `blob_kzg_commitments` from Deneb. The bounds of the payload that are not part
+/// of the block must be given explicitly.
+#[allow(clippy::too_many_arguments)]
+pub fn produce_blinded_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    execution_payload_header: ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BlindedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    Error,
+> {
+    match (state, execution_payload_header) {
+        (BeaconState::Bellatrix(state), ExecutionPayloadHeader::Bellatrix(header)) => {
+            let block = bellatrix::produce_blinded_block::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                SYNC_COMMITTEE_SIZE,
+                SYNC_SUBCOMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+            >(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                header,
+                &to_altair_operations(operations),
+                context,
+            )?;
+            Ok(BlindedBeaconBlock::Bellatrix(block))
+        }
+        (BeaconState::Capella(state), ExecutionPayloadHeader::Capella(header)) => {
+            let block = capella::produce_blinded_block::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                SYNC_COMMITTEE_SIZE,
+                SYNC_SUBCOMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+            >(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                header,
+                &to_capella_operations(operations),
+                context,
+            )?;
+            Ok(BlindedBeaconBlock::Capella(block))
+        }
+        (BeaconState::Deneb(state), ExecutionPayloadHeader::Deneb(header)) => {
+            let block = deneb::produce_blinded_block::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                SYNC_COMMITTEE_SIZE,
+                SYNC_SUBCOMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >(
+                state,
+                slot,
+                randao_reveal,
+                graffiti,
+                header,
+                blob_kzg_commitments,
+                &to_capella_operations(operations),
+                context,
+            )?;
+            Ok(BlindedBeaconBlock::Deneb(block))
+        }
+        (state, execution_payload_header) => Err(Error::IncompatibleFork {
+            source_fork: state.version(),
+            destination_fork: execution_payload_header.version(),
+        }),
+    }
+}
+
+fn to_phase0_operations<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+) -> phase0::BlockOperations<MAX_VALIDATORS_PER_COMMITTEE> {
+    phase0::BlockOperations {
+        proposer_slashings: operations.proposer_slashings.clone(),
+        attester_slashings: operations.attester_slashings.clone(),
+        attestations: operations.attestations.clone(),
+        deposits: operations.deposits.clone(),
+        voluntary_exits: operations.voluntary_exits.clone(),
+        eth1_data: operations.eth1_data.clone(),
+    }
+}
+
+fn to_altair_operations<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+) -> altair::BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE> {
+    altair::BlockOperations {
+        proposer_slashings: operations.proposer_slashings.clone(),
+        attester_slashings: operations.attester_slashings.clone(),
+        attestations: operations.attestations.clone(),
+        deposits: operations.deposits.clone(),
+        voluntary_exits: operations.voluntary_exits.clone(),
+        sync_committee_contributions: operations.sync_committee_contributions.clone(),
+        eth1_data: operations.eth1_data.clone(),
+    }
+}
+
+fn to_capella_operations<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+) -> capella::BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE> {
+    capella::BlockOperations {
+        proposer_slashings: operations.proposer_slashings.clone(),
+        attester_slashings: operations.attester_slashings.clone(),
+        attestations: operations.attestations.clone(),
+        deposits: operations.deposits.clone(),
+        voluntary_exits: operations.voluntary_exits.clone(),
+        sync_committee_contributions: operations.sync_committee_contributions.clone(),
+        bls_to_execution_changes: operations.bls_to_execution_changes.clone(),
+        eth1_data: operations.eth1_data.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::minimal::{SYNC_COMMITTEE_SIZE, SYNC_SUBCOMMITTEE_SIZE},
+        bellatrix::minimal::{
+            BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+        },
+        capella::minimal::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD},
+        deneb::minimal::MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        harness::minimal::Harness,
+        phase0::minimal::{
+            EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
+            HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS,
+            MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+            PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT, VALIDATOR_REGISTRY_LIMIT,
+        },
+        types::{minimal, SignedBeaconBlock},
+    };
+
+    fn context_at_genesis(fork: Fork) -> Context {
+        let mut context = Context::for_minimal();
+        if fork >= Fork::Altair {
+            context.altair_fork_epoch = 0;
+        }
+        if fork >= Fork::Bellatrix {
+            context.bellatrix_fork_epoch = 0;
+        }
+        if fork >= Fork::Capella {
+            context.capella_fork_epoch = 0;
+        }
+        if fork >= Fork::Deneb {
+            context.deneb_fork_epoch = 0;
+        }
+        context
+    }
+
+    #[test]
+    fn test_produced_blocks_skip_invalid_operations() {
+        for fork in [Fork::Phase0, Fork::Altair, Fork::Bellatrix, Fork::Capella, Fork::Deneb] {
+            let mut harness = Harness::new(64, context_at_genesis(fork)).unwrap();
+            harness.extend_chain(2).unwrap();
+            let slot = harness.head_state().slot() + 1;
+
+            let proposer_slashing = harness.make_proposer_slashing(1, slot).unwrap();
+            let mut forged_proposer_slashing = harness.make_proposer_slashing(2, slot).unwrap();
+            forged_proposer_slashing.signed_header_2.signature =
+                forged_proposer_slashing.signed_header_1.signature.clone();
+            // validator 1 is no longer slashable once the proposer slashing is included
+            let attester_slashing = harness.make_attester_slashing(&[1], 0).unwrap();
+            // validators may not exit before they have been active long enough
+            let voluntary_exit = harness.make_voluntary_exit(3, 0).unwrap();
+            harness.pool.insert_proposer_slashing(proposer_slashing.clone());
+            harness.pool.insert_proposer_slashing(forged_proposer_slashing);
+            harness.pool.insert_attester_slashing(attester_slashing);
+            harness.pool.insert_voluntary_exit(voluntary_exit);
+
+            let signed_block = harness.produce_block(harness.head_root(), slot).unwrap();
+            let body = signed_block.message().body();
+            assert_eq!(body.proposer_slashings().as_ref(), &[proposer_slashing]);
+            assert!(body.attester_slashings().is_empty());
+            assert!(body.voluntary_exits().is_empty());
+            assert!(!body.attestations().is_empty());
+
+            // the block is valid under the full state transition
+            let root = harness.apply_block(signed_block).unwrap();
+            assert_eq!(harness.head_root(), root);
+            assert_eq!(harness.head_state().version(), fork);
+            assert!(harness.head_state().validators()[1].slashed);
+            assert!(!harness.head_state().validators()[2].slashed);
+        }
+    }
+
+    #[test]
+    fn test_missing_execution_payload_is_rejected() {
+        for fork in [Fork::Bellatrix, Fork::Capella, Fork::Deneb] {
+            let mut harness = Harness::new(64, context_at_genesis(fork)).unwrap();
+            let state = harness.advance(harness.head_root(), 1).unwrap();
+            let result: Result<minimal::BeaconBlock, _> = produce_block(
+                &state,
+                1,
+                Default::default(),
+                Bytes32::default(),
+                None,
+                List::default(),
+                &Default::default(),
+                harness.context(),
+            );
+            assert!(
+                matches!(result, Err(Error::MissingExecutionPayload(version)) if version == fork)
+            );
+        }
+    }
+
+    #[test]
+    fn test_blinded_blocks_match_full_blocks() {
+        for fork in [Fork::Bellatrix, Fork::Capella, Fork::Deneb] {
+            let mut harness = Harness::new(64, context_at_genesis(fork)).unwrap();
+            harness.extend_chain(2).unwrap();
+            let slot = harness.head_state().slot() + 1;
+            let signed_block = harness.produce_block(harness.head_root(), slot).unwrap();
+            let randao_reveal = signed_block.message().body().randao_reveal().clone();
+            let (execution_payload, execution_payload_header) = match signed_block {
+                SignedBeaconBlock::Bellatrix(signed_block) => {
+                    let mut payload = signed_block.message.body.execution_payload;
+                    let header = bellatrix::ExecutionPayloadHeader::try_from(&mut payload).unwrap();
+                    (
+                        ExecutionPayload::Bellatrix(payload),
+                        ExecutionPayloadHeader::Bellatrix(header),
+                    )
+                }
+                SignedBeaconBlock::Capella(signed_block) => {
+                    let mut payload = signed_block.message.body.execution_payload;
+                    let header = capella::ExecutionPayloadHeader::try_from(&mut payload).unwrap();
+                    (ExecutionPayload::Capella(payload), ExecutionPayloadHeader::Capella(header))
+                }
+                SignedBeaconBlock::Deneb(signed_block) => {
+                    let mut payload = signed_block.message.body.execution_payload;
+                    let header = deneb::ExecutionPayloadHeader::try_from(&mut payload).unwrap();
+                    (ExecutionPayload::Deneb(payload), ExecutionPayloadHeader::Deneb(header))
+                }
+                _ => unreachable!("block is from a fork with execution payloads"),
+            };
+
+            let state = harness.advance(harness.head_root(), slot).unwrap();
+            let operations =
+                harness.pool.get_block_operations(&state, slot, harness.context()).unwrap();
+            let mut block: minimal::BeaconBlock = produce_block(
+                &state,
+                slot,
+                randao_reveal.clone(),
+                Bytes32::default(),
+                Some(execution_payload),
+                List::default(),
+                &operations,
+                harness.context(),
+            )
+            .unwrap();
+            let mut blinded_block = produce_blinded_block::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                SYNC_SUBCOMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >(
+                &state,
+                slot,
+                randao_reveal,
+                Bytes32::default(),
+                execution_payload_header.clone(),
+                List::default(),
+                &operations,
+                harness.context(),
+            )
+            .unwrap();
+            assert!(!block.body().attestations().is_empty());
+            assert_eq!(blinded_block.state_root(), block.state_root());
+            assert_eq!(blinded_block.hash_tree_root().unwrap(), block.hash_tree_root().unwrap());
+
+            // a header from another fork is not accepted
+            let result = produce_blinded_block::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                SYNC_SUBCOMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >(
+                &state,
+                slot,
+                Default::default(),
+                Bytes32::default(),
+                match execution_payload_header {
+                    ExecutionPayloadHeader::Bellatrix(_) => {
+                        ExecutionPayloadHeader::Capella(Default::default())
+                    }
+                    _ => ExecutionPayloadHeader::Bellatrix(Default::default()),
+                },
+                List::default(),
+                &Default::default(),
+                harness.context(),
+            );
+            assert!(matches!(result, Err(Error::IncompatibleFork { .. })));
+        }
+    }
+}
@@ -0,0 +1,608 @@
+//! Reconstructs historical states by replaying blocks on top of an earlier state.
+use crate::{
+    altair, bellatrix, capella, deneb, phase0,
+    primitives::{Root, Slot},
+    state_transition::{Context, Validation},
+    types::{BeaconState, SignedBeaconBlock},
+    Error, Fork,
+};
+
+type StateCallback<
+    'a,
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> = Box<
+    dyn FnMut(
+            &BeaconState<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+            >,
+        ) -> Result<(), Error>
+        + 'a,
+>;
+
+/// Replays blocks on top of a starting state, like a checkpoint state, to reach the state at a
+/// later slot, processing any empty slots and upgrading the state at each fork boundary on the
+/// way.
+///
+/// Execution payloads and blob KZG commitments are not verified. Signatures are only verified if
+/// requested with `verify_signatures`, and the state root of each block is checked against the
+/// post-state unless `trust_state_roots` is set.
+pub struct BlockReplayer<
+    'a,
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+> {
+    state: BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    // The root of `state` if it is known from the block applied last, which saves hashing the
+    // state when processing the next slot.
+    state_root: Option<Root>,
+    context: &'a Context,
+    verify_signatures: bool,
+    trust_state_roots: bool,
+    on_slot: Option<
+        StateCallback<
+            'a,
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >,
+    on_epoch: Option<
+        StateCallback<
+            'a,
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >,
+}
+
+impl<
+        'a,
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    >
+    BlockReplayer<
+        'a,
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >
+{
+    pub fn new(
+        state: BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        context: &'a Context,
+    ) -> Self {
+        Self {
+            state,
+            state_root: None,
+            context,
+            verify_signatures: false,
+            trust_state_roots: false,
+            on_slot: None,
+            on_epoch: None,
+        }
+    }
+
+    /// Verify the signatures of every block replayed. The signatures of each block are verified
+    /// together in one batch, in parallel with the `rayon` feature, before the replay moves on
+    /// from the block, so the callbacks never see a state built on a block with an invalid
+    /// signature.
+    pub fn verify_signatures(mut self, verify_signatures: bool) -> Self {
+        self.verify_signatures = verify_signatures;
+        self
+    }
+
+    /// Take the state root of each block replayed as the root of its post-state, rather than
+    /// checking it. The post-state of each block is then never hashed.
+    pub fn trust_state_roots(mut self, trust_state_roots: bool) -> Self {
+        self.trust_state_roots = trust_state_roots;
+        self
+    }
+
+    /// Calls `f` with the state at each slot the replay advances to, after the block of that
+    /// slot, if any, has been applied.
+    pub fn on_slot<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(
+                &BeaconState<
+                    SLOTS_PER_HISTORICAL_ROOT,
+                    HISTORICAL_ROOTS_LIMIT,
+                    ETH1_DATA_VOTES_BOUND,
+                    VALIDATOR_REGISTRY_LIMIT,
+                    EPOCHS_PER_HISTORICAL_VECTOR,
+                    EPOCHS_PER_SLASHINGS_VECTOR,
+                    MAX_VALIDATORS_PER_COMMITTEE,
+                    PENDING_ATTESTATIONS_BOUND,
+                    SYNC_COMMITTEE_SIZE,
+                    BYTES_PER_LOGS_BLOOM,
+                    MAX_EXTRA_DATA_BYTES,
+                >,
+            ) -> Result<(), Error>
+            + 'a,
+    {
+        self.on_slot = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` with the state at the last slot of each epoch right before the epoch is
+    /// processed.
+    pub fn on_epoch<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(
+                &BeaconState<
+                    SLOTS_PER_HISTORICAL_ROOT,
+                    HISTORICAL_ROOTS_LIMIT,
+                    ETH1_DATA_VOTES_BOUND,
+                    VALIDATOR_REGISTRY_LIMIT,
+                    EPOCHS_PER_HISTORICAL_VECTOR,
+                    EPOCHS_PER_SLASHINGS_VECTOR,
+                    MAX_VALIDATORS_PER_COMMITTEE,
+                    PENDING_ATTESTATIONS_BOUND,
+                    SYNC_COMMITTEE_SIZE,
+                    BYTES_PER_LOGS_BLOOM,
+                    MAX_EXTRA_DATA_BYTES,
+                >,
+            ) -> Result<(), Error>
+            + 'a,
+    {
+        self.on_epoch = Some(Box::new(f));
+        self
+    }
+
+    /// Applies `blocks`, in order of slot, to the starting state and then advances it to
+    /// `target_slot`, returning the state at `target_slot`. Blocks after `target_slot` are not
+    /// applied.
+    pub fn apply_blocks<I>(
+        mut self,
+        blocks: I,
+        target_slot: Slot,
+    ) -> Result<
+        BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        Error,
+    >
+    where
+        I: IntoIterator<
+            Item = SignedBeaconBlock<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >,
+        >,
+    {
+        if target_slot < self.state.slot() {
+            return Err(Error::TransitionToPreviousSlot {
+                requested: target_slot,
+                current: self.state.slot(),
+            })
+        }
+        let validation = Validation {
+            signatures: self.verify_signatures,
+            batch_signatures: true,
+            state_root: !self.trust_state_roots,
+            execution_payload: false,
+            kzg_commitments: false,
+        };
+        self.replay(blocks, target_slot, validation)?;
+        Ok(self.state)
+    }
+
+    fn replay<I>(
+        &mut self,
+        blocks: I,
+        target_slot: Slot,
+        validation: Validation,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<
+            Item = SignedBeaconBlock<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >,
+        >,
+    {
+        // NOTE: the starting state may be at a fork boundary without having been upgraded yet
+        self.upgrade()?;
+        for mut signed_block in blocks {
+            let slot = signed_block.message().slot();
+            if slot > target_slot {
+                break
+            }
+            if slot < self.state.slot() {
+                return Err(Error::TransitionToPreviousSlot {
+                    requested: slot,
+                    current: self.state.slot(),
+                })
+            }
+            while self.state.slot() < slot {
+                self.process_slot()?;
+                if self.state.slot() < slot {
+                    self.notify_slot()?;
+                }
+            }
+            // NOTE: the signatures of the block are verified here, before any callback sees the
+            // post-state
+            self.apply_block(&mut signed_block, validation)?;
+            self.state_root = Some(signed_block.message().state_root());
+            self.notify_slot()?;
+        }
+        while self.state.slot() < target_slot {
+            self.process_slot()?;
+            self.notify_slot()?;
+        }
+        Ok(())
+    }
+
+    fn notify_slot(&mut self) -> Result<(), Error> {
+        match self.on_slot.as_mut() {
+            Some(on_slot) => on_slot(&self.state),
+            None => Ok(()),
+        }
+    }
+
+    // Advances the state by one slot, upgrading it if the slot is the first of a new fork.
+    fn process_slot(&mut self) -> Result<(), Error> {
+        let slot = self.state.slot() + 1;
+        if slot % self.context.slots_per_epoch == 0 {
+            if let Some(on_epoch) = self.on_epoch.as_mut() {
+                on_epoch(&self.state)?;
+            }
+        }
+        let context = self.context;
+        match (&mut self.state, self.state_root.take()) {
+            (BeaconState::Phase0(state), Some(state_root)) => {
+                phase0::process_slots_with_state_root(state, slot, state_root, &mut (), context)?
+            }
+            (BeaconState::Phase0(state), None) => {
+                phase0::process_slots(state, slot, &mut (), context)?
+            }
+            (BeaconState::Altair(state), Some(state_root)) => {
+                altair::process_slots_with_state_root(state, slot, state_root, &mut (), context)?
+            }
+            (BeaconState::Altair(state), None) => {
+                altair::process_slots(state, slot, &mut (), context)?
+            }
+            (BeaconState::Bellatrix(state), Some(state_root)) => {
+                bellatrix::process_slots_with_state_root(state, slot, state_root, &mut (), context)?
+            }
+            (BeaconState::Bellatrix(state), None) => {
+                bellatrix::process_slots(state, slot, &mut (), context)?
+            }
+            (BeaconState::Capella(state), Some(state_root)) => {
+                capella::process_slots_with_state_root(state, slot, state_root, &mut (), context)?
+            }
+            (BeaconState::Capella(state), None) => {
+                capella::process_slots(state, slot, &mut (), context)?
+            }
+            (BeaconState::Deneb(state), Some(state_root)) => {
+                deneb::process_slots_with_state_root(state, slot, state_root, &mut (), context)?
+            }
+            (BeaconState::Deneb(state), None) => {
+                deneb::process_slots(state, slot, &mut (), context)?
+            }
+        }
+        self.upgrade()
+    }
+
+    // Upgrades the state through each fork scheduled at or before its slot.
+    fn upgrade(&mut self) -> Result<(), Error> {
+        let fork = self.context.fork_for(self.state.slot());
+        loop {
+            let state = match &self.state {
+                BeaconState::Phase0(state) if fork > Fork::Phase0 => {
+                    BeaconState::Altair(altair::upgrade_to_altair(state, self.context)?)
+                }
+                BeaconState::Altair(state) if fork > Fork::Altair => {
+                    BeaconState::Bellatrix(bellatrix::upgrade_to_bellatrix(state, self.context))
+                }
+                BeaconState::Bellatrix(state) if fork > Fork::Bellatrix => {
+                    BeaconState::Capella(capella::upgrade_to_capella(state, self.context))
+                }
+                BeaconState::Capella(state) if fork > Fork::Capella => {
+                    BeaconState::Deneb(deneb::upgrade_to_deneb(state, self.context))
+                }
+                _ => return Ok(()),
+            };
+            self.state = state;
+        }
+    }
+
+    fn apply_block(
+        &mut self,
+        signed_block: &mut SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+        validation: Validation,
+    ) -> Result<(), Error> {
+        let context = self.context;
+        match (&mut self.state, signed_block) {
+            (BeaconState::Phase0(state), SignedBeaconBlock::Phase0(signed_block)) => {
+                phase0::state_transition_block_in_slot(
+                    state,
+                    signed_block,
+                    validation,
+                    &mut (),
+                    context,
+                )
+            }
+            (BeaconState::Altair(state), SignedBeaconBlock::Altair(signed_block)) => {
+                altair::state_transition_block_in_slot(
+                    state,
+                    signed_block,
+                    validation,
+                    &mut (),
+                    context,
+                )
+            }
+            (BeaconState::Bellatrix(state), SignedBeaconBlock::Bellatrix(signed_block)) => {
+                bellatrix::state_transition_block_in_slot(
+                    state,
+                    signed_block,
+                    &bellatrix::DefaultExecutionEngine::default(),
+                    validation,
+                    &mut (),
+                    context,
+                )
+            }
+            (BeaconState::Capella(state), SignedBeaconBlock::Capella(signed_block)) => {
+                capella::state_transition_block_in_slot(
+                    state,
+                    signed_block,
+                    &capella::DefaultExecutionEngine::default(),
+                    validation,
+                    &mut (),
+                    context,
+                )
+            }
+            (BeaconState::Deneb(state), SignedBeaconBlock::Deneb(signed_block)) => {
+                deneb::state_transition_block_in_slot(
+                    state,
+                    signed_block,
+                    &deneb::DefaultExecutionEngine::default(),
+                    validation,
+                    &mut (),
+                    context,
+                )
+            }
+            (state, signed_block) => Err(Error::IncompatibleFork {
+                source_fork: state.version(),
+                destination_fork: signed_block.version(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{harness::minimal::Harness, ssz::prelude::Merkleized};
+
+    #[test]
+    fn test_replay_across_fork_and_skipped_slots() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 1;
+        let mut harness = Harness::new(64, context).unwrap();
+        let genesis_root = harness.genesis_root();
+        let genesis_state = harness.state(&genesis_root).unwrap().clone();
+
+        let mut roots = harness.extend_chain(5).unwrap();
+        // skip the slots up to the first slot of altair
+        let root = harness.add_block(roots[4], 9).unwrap();
+        roots.push(root);
+        roots.extend(harness.extend_chain(2).unwrap());
+        let blocks =
+            roots.iter().map(|root| harness.block(root).unwrap().clone()).collect::<Vec<_>>();
+        let head_root = harness.head_root();
+        let target_slot = 13;
+        let mut expected = harness.advance(head_root, target_slot).unwrap();
+
+        for trust_state_roots in [false, true] {
+            let mut slots = vec![];
+            let mut epochs = vec![];
+            let mut state = BlockReplayer::new(genesis_state.clone(), harness.context())
+                .verify_signatures(true)
+                .trust_state_roots(trust_state_roots)
+                .on_slot(|state| {
+                    slots.push(state.slot());
+                    Ok(())
+                })
+                .on_epoch(|state| {
+                    epochs.push(state.slot());
+                    Ok(())
+                })
+                .apply_blocks(blocks.clone(), target_slot)
+                .unwrap();
+            assert_eq!(state.version(), Fork::Altair);
+            assert_eq!(state.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+            assert_eq!(slots, (1..=target_slot).collect::<Vec<_>>());
+            assert_eq!(epochs, vec![7]);
+        }
+
+        // blocks after the target slot are left out
+        let state = BlockReplayer::new(genesis_state.clone(), harness.context())
+            .apply_blocks(blocks.clone(), 3)
+            .unwrap();
+        assert_eq!(state.latest_block_header().slot, 3);
+
+        // a block with an invalid signature fails the replay before any callback sees it
+        let mut blocks = blocks;
+        let signature = blocks[1].signature().clone();
+        *blocks[2].signature_mut() = signature;
+        let mut slots = vec![];
+        let result = BlockReplayer::new(genesis_state.clone(), harness.context())
+            .verify_signatures(true)
+            .on_slot(|state| {
+                slots.push(state.slot());
+                Ok(())
+            })
+            .apply_blocks(blocks.clone(), target_slot);
+        assert!(result.is_err());
+        assert_eq!(slots, vec![1, 2]);
+        assert!(BlockReplayer::new(genesis_state, harness.context())
+            .apply_blocks(blocks, target_slot)
+            .is_ok());
+    }
+}
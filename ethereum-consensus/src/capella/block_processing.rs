@@ -1,22 +1,25 @@
 use crate::{
     capella::{
         compute_domain, compute_signing_root, compute_timestamp_at_slot, decrease_balance,
-        get_current_epoch, get_randao_mix, is_fully_withdrawable_validator,
-        is_partially_withdrawable_validator, process_attestation, process_attester_slashing,
-        process_block_header, process_deposit, process_eth1_data, process_proposer_slashing,
-        process_randao, process_sync_aggregate, process_voluntary_exit, BeaconBlock,
-        BeaconBlockBody, BeaconState, DomainType, ExecutionAddress, ExecutionEngine,
+        get_current_epoch, get_randao_mix, get_sync_aggregate_rewards,
+        is_fully_withdrawable_validator, is_partially_withdrawable_validator, process_attestation,
+        process_attester_slashing, process_block_header, process_deposit, process_eth1_data,
+        process_proposer_slashing, process_randao, process_sync_aggregate, process_voluntary_exit,
+        BeaconBlock, BeaconBlockBody, BeaconState, DomainType, ExecutionAddress, ExecutionEngine,
         ExecutionPayload, ExecutionPayloadHeader, NewPayloadRequest, SignedBlsToExecutionChange,
         Withdrawal,
     },
-    crypto::{hash, verify_signature},
+    crypto::{hash, SignatureSet},
     error::{
         invalid_operation_error, InvalidBlsToExecutionChange, InvalidDeposit,
         InvalidExecutionPayload, InvalidOperation, InvalidWithdrawals,
     },
     primitives::{BLS_WITHDRAWAL_PREFIX, ETH1_ADDRESS_WITHDRAWAL_PREFIX},
+    rewards::{BlockRewards, SyncCommitteeReward},
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{
+        Context, Event, Observer, Result, SignatureSets, SignatureStrategy, Validation,
+    },
 };
 
 pub fn process_bls_to_execution_change<
@@ -44,6 +47,8 @@ pub fn process_bls_to_execution_change<
         MAX_EXTRA_DATA_BYTES,
     >,
     signed_address_change: &mut SignedBlsToExecutionChange,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let address_change = &mut signed_address_change.message;
@@ -79,11 +84,17 @@ pub fn process_bls_to_execution_change<
         )))
     }
 
-    verify_signature(public_key, signing_root.as_ref(), signature)?;
+    let signature_set = SignatureSet::from_public_key(public_key, signing_root.as_ref(), signature)
+        .map_err(Into::into);
+    signatures.verify(signature_set, || crate::crypto::Error::InvalidSignature.into())?;
 
     withdrawal_credentials[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
     withdrawal_credentials[1..12].fill(0);
     withdrawal_credentials[12..].copy_from_slice(address_change.to_execution_address.as_ref());
+    observer.on_event(&Event::BlsToExecutionChangeApplied {
+        index: address_change.validator_index,
+        address: address_change.to_execution_address.clone(),
+    });
 
     Ok(())
 }
@@ -136,6 +147,8 @@ pub fn process_operations<
         MAX_WITHDRAWALS_PER_PAYLOAD,
         MAX_BLS_TO_EXECUTION_CHANGES,
     >,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let expected_deposit_count = usize::min(
@@ -152,18 +165,20 @@ pub fn process_operations<
     }
     body.proposer_slashings
         .iter_mut()
-        .try_for_each(|op| process_proposer_slashing(state, op, context))?;
+        .try_for_each(|op| process_proposer_slashing(state, op, signatures, observer, context))?;
     body.attester_slashings
         .iter_mut()
-        .try_for_each(|op| process_attester_slashing(state, op, context))?;
-    body.attestations.iter().try_for_each(|op| process_attestation(state, op, context))?;
-    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, context))?;
+        .try_for_each(|op| process_attester_slashing(state, op, signatures, observer, context))?;
+    body.attestations
+        .iter()
+        .try_for_each(|op| process_attestation(state, op, signatures, context))?;
+    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, observer, context))?;
     body.voluntary_exits
         .iter_mut()
-        .try_for_each(|op| process_voluntary_exit(state, op, context))?;
-    body.bls_to_execution_changes
-        .iter_mut()
-        .try_for_each(|op| process_bls_to_execution_change(state, op, context))?;
+        .try_for_each(|op| process_voluntary_exit(state, op, signatures, observer, context))?;
+    body.bls_to_execution_changes.iter_mut().try_for_each(|op| {
+        process_bls_to_execution_change(state, op, signatures, observer, context)
+    })?;
     Ok(())
 }
 
@@ -209,6 +224,7 @@ pub fn process_execution_payload<
         MAX_WITHDRAWALS_PER_PAYLOAD,
     >,
     execution_engine: &E,
+    validation: Validation,
     context: &Context,
 ) -> Result<()> {
     let parent_hash_invalid =
@@ -246,8 +262,10 @@ pub fn process_execution_payload<
         ))
     }
 
-    let new_payload_request = NewPayloadRequest(payload);
-    execution_engine.verify_and_notify_new_payload(&new_payload_request)?;
+    if validation.execution_payload {
+        let new_payload_request = NewPayloadRequest(payload);
+        execution_engine.verify_and_notify_new_payload(&new_payload_request)?;
+    }
 
     state.latest_execution_payload_header = ExecutionPayloadHeader {
         parent_hash: payload.parent_hash.clone(),
@@ -304,6 +322,7 @@ pub fn process_withdrawals<
         MAX_TRANSACTIONS_PER_PAYLOAD,
         MAX_WITHDRAWALS_PER_PAYLOAD,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let expected_withdrawals = get_expected_withdrawals(state, context);
@@ -319,6 +338,7 @@ pub fn process_withdrawals<
 
     for withdrawal in &expected_withdrawals {
         decrease_balance(state, withdrawal.validator_index, withdrawal.amount);
+        observer.on_event(&Event::WithdrawalProcessed(withdrawal.clone()));
     }
 
     // Update the next withdrawal index if this block contained withdrawals
@@ -462,14 +482,118 @@ pub fn process_block<
         MAX_BLS_TO_EXECUTION_CHANGES,
     >,
     execution_engine: &E,
+    validation: Validation,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     process_block_header(state, block, context)?;
-    process_withdrawals(state, &block.body.execution_payload, context)?;
-    process_execution_payload(state, &mut block.body.execution_payload, execution_engine, context)?;
-    process_randao(state, &block.body, context)?;
+    process_withdrawals(state, &block.body.execution_payload, observer, context)?;
+    process_execution_payload(
+        state,
+        &mut block.body.execution_payload,
+        execution_engine,
+        validation,
+        context,
+    )?;
+    process_randao(state, &block.body, signatures, context)?;
     process_eth1_data(state, &block.body, context);
-    process_operations(state, &mut block.body, context)?;
-    process_sync_aggregate(state, &block.body.sync_aggregate, context)?;
+    process_operations(state, &mut block.body, signatures, observer, context)?;
+    process_sync_aggregate(state, &block.body.sync_aggregate, signatures, context)?;
     Ok(())
 }
+
+// Return the rewards paid to the proposer of `block` split by the operations that pay them, along
+// with the rewards and penalties of each sync committee member for its participation in the
+// `SyncAggregate`. Withdrawals, the eth1 vote and each operation are applied to `state` in the
+// order of `process_block`, with `state` expected to be at the slot of `block`, measuring balances
+// around them.
+pub fn get_block_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+    context: &Context,
+) -> Result<(BlockRewards, Vec<SyncCommitteeReward>)> {
+    let proposer_index = block.proposer_index;
+    let body = &mut block.body;
+    let signatures = &mut SignatureSets::new(SignatureStrategy::Skip);
+    let mut rewards = BlockRewards { proposer_index, ..Default::default() };
+    process_withdrawals(state, &body.execution_payload, &mut (), context)?;
+    process_eth1_data(state, body, context);
+    for op in body.proposer_slashings.iter_mut() {
+        let balance = state.balances[proposer_index];
+        process_proposer_slashing(state, op, signatures, &mut (), context)?;
+        rewards.proposer_slashings += state.balances[proposer_index].saturating_sub(balance);
+    }
+    for op in body.attester_slashings.iter_mut() {
+        let balance = state.balances[proposer_index];
+        process_attester_slashing(state, op, signatures, &mut (), context)?;
+        rewards.attester_slashings += state.balances[proposer_index].saturating_sub(balance);
+    }
+    for op in body.attestations.iter() {
+        let balance = state.balances[proposer_index];
+        process_attestation(state, op, signatures, context)?;
+        rewards.attestations += state.balances[proposer_index].saturating_sub(balance);
+    }
+    // Deposits, exits and BLS to execution changes do not pay the proposer but precede the
+    // `SyncAggregate`
+    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, &mut (), context))?;
+    body.voluntary_exits
+        .iter_mut()
+        .try_for_each(|op| process_voluntary_exit(state, op, signatures, &mut (), context))?;
+    body.bls_to_execution_changes.iter_mut().try_for_each(|op| {
+        process_bls_to_execution_change(state, op, signatures, &mut (), context)
+    })?;
+
+    let sync_committee_rewards =
+        get_sync_aggregate_rewards(state, &body.sync_aggregate, &mut rewards, signatures, context)?;
+    rewards.total = rewards.attestations +
+        rewards.sync_aggregate +
+        rewards.proposer_slashings +
+        rewards.attester_slashings;
+    Ok((rewards, sync_committee_rewards))
+}
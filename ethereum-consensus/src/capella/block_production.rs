@@ -0,0 +1,493 @@
+use crate::{
+    capella::{
+        compute_timestamp_at_slot, get_beacon_proposer_index, get_current_epoch,
+        get_expected_withdrawals, get_randao_mix, process_attestation, process_attester_slashing,
+        process_block_header, process_bls_to_execution_change, process_deposit, process_eth1_data,
+        process_execution_payload, process_proposer_slashing, process_randao, process_slots,
+        process_voluntary_exit, process_withdrawals, select_sync_aggregate, Attestation,
+        AttesterSlashing, BeaconBlock, BeaconBlockBody, BeaconState, BlindedBeaconBlock,
+        BlindedBeaconBlockBody, DefaultExecutionEngine, Deposit, Eth1Data, ExecutionPayload,
+        ExecutionPayloadHeader, ProposerSlashing, SignedBlsToExecutionChange, SignedVoluntaryExit,
+        SyncCommitteeContribution,
+    },
+    error::{
+        invalid_operation_error, InvalidDeposit, InvalidExecutionPayload, InvalidOperation,
+        InvalidWithdrawals,
+    },
+    primitives::{BlsSignature, Bytes32, Root, Slot},
+    ssz::prelude::*,
+    state_transition::{Context, Result, SignatureSets, Validation},
+};
+
+/// The candidate operations to include in a block, in order of preference.
+#[derive(Default, Debug, Clone)]
+pub struct BlockOperations<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub attestations: Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub deposits: Vec<Deposit>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
+    pub sync_committee_contributions: Vec<SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>,
+    pub bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+    // The vote to make instead of repeating the current vote of the state
+    pub eth1_data: Option<Eth1Data>,
+}
+
+/// Applies the candidate `operations` to `state` in the order of `process_operations`, adding
+/// each one that is valid to `body` until the limit for its kind is reached.
+///
+/// Invalid candidates are skipped and leave `state` unchanged. Returns an error if there are not
+/// enough valid deposits to include all of those pending up to `MAX_DEPOSITS`.
+pub fn select_operations<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    body: &mut BeaconBlockBody<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<()> {
+    for operation in &operations.proposer_slashings {
+        if body.proposer_slashings.len() == context.max_proposer_slashings {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_proposer_slashing(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.proposer_slashings.push(operation);
+        }
+    }
+    for operation in &operations.attester_slashings {
+        if body.attester_slashings.len() == context.max_attester_slashings {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_attester_slashing(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.attester_slashings.push(operation);
+        }
+    }
+    for operation in &operations.attestations {
+        if body.attestations.len() == context.max_attestations {
+            break
+        }
+        if process_attestation(state, operation, signatures, context).is_ok() {
+            body.attestations.push(operation.clone());
+        }
+    }
+    // NOTE: a block must include every pending deposit up to the limit, so only deposits at the
+    // next deposit index are accepted
+    let expected_deposit_count = usize::min(
+        context.max_deposits,
+        (state.eth1_data.deposit_count - state.eth1_deposit_index) as usize,
+    );
+    for operation in &operations.deposits {
+        if body.deposits.len() == expected_deposit_count {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_deposit(state, &mut operation, &mut (), context).is_ok() {
+            body.deposits.push(operation);
+        }
+    }
+    if body.deposits.len() != expected_deposit_count {
+        return Err(invalid_operation_error(InvalidOperation::Deposit(
+            InvalidDeposit::IncorrectCount {
+                expected: expected_deposit_count,
+                count: body.deposits.len(),
+            },
+        )))
+    }
+    for operation in &operations.voluntary_exits {
+        if body.voluntary_exits.len() == context.max_voluntary_exits {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_voluntary_exit(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.voluntary_exits.push(operation);
+        }
+    }
+    for operation in &operations.bls_to_execution_changes {
+        if body.bls_to_execution_changes.len() == context.max_bls_to_execution_changes {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_bls_to_execution_change(state, &mut operation, signatures, &mut (), context)
+            .is_ok()
+        {
+            body.bls_to_execution_changes.push(operation);
+        }
+    }
+    Ok(())
+}
+
+/// Produces an unsigned block for `slot` on top of `state` with the given `execution_payload`,
+/// including as many of the candidate `operations` as are valid. The `eth1_data` of the block is
+/// the vote in `operations`, if any, or repeats the current vote of `state`.
+///
+/// The signature of each candidate is verified so that candidates with invalid signatures are
+/// skipped. The `execution_payload` is expected to come from the execution engine and is not sent
+/// back to it.
+pub fn produce_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    execution_payload: ExecutionPayload<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+> {
+    let mut state = state.clone();
+    if state.slot < slot {
+        process_slots(&mut state, slot, &mut (), context)?;
+    }
+
+    let mut block = BeaconBlock {
+        slot,
+        proposer_index: get_beacon_proposer_index(&state, context)?,
+        parent_root: state.latest_block_header.hash_tree_root()?,
+        ..Default::default()
+    };
+    block.body.randao_reveal = randao_reveal;
+    block.body.eth1_data = operations.eth1_data.clone().unwrap_or_else(|| state.eth1_data.clone());
+    block.body.graffiti = graffiti;
+    block.body.execution_payload = execution_payload;
+
+    let signatures = &mut SignatureSets::default();
+    let validation = Validation { execution_payload: false, ..Validation::enabled() };
+    process_block_header(&mut state, &mut block, context)?;
+    process_withdrawals(&mut state, &block.body.execution_payload, &mut (), context)?;
+    process_execution_payload(
+        &mut state,
+        &mut block.body.execution_payload,
+        &DefaultExecutionEngine::default(),
+        validation,
+        context,
+    )?;
+    process_randao(&mut state, &block.body, signatures, context)?;
+    process_eth1_data(&mut state, &block.body, context);
+    select_operations(&mut state, &mut block.body, operations, signatures, context)?;
+    block.body.sync_aggregate = select_sync_aggregate(
+        &mut state,
+        &operations.sync_committee_contributions,
+        signatures,
+        context,
+    )?;
+
+    // NOTE: the header was processed before the operations were selected
+    state.latest_block_header.body_root = block.body.hash_tree_root()?;
+    block.state_root = state.hash_tree_root()?;
+    Ok(block)
+}
+
+fn process_execution_payload_header<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    header: &ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    withdrawals_root: Root,
+    context: &Context,
+) -> Result<()> {
+    if header.withdrawals_root != withdrawals_root {
+        return Err(invalid_operation_error(InvalidOperation::Withdrawal(
+            InvalidWithdrawals::IncorrectWithdrawalsRoot {
+                provided: header.withdrawals_root,
+                expected: withdrawals_root,
+            },
+        )))
+    }
+
+    if header.parent_hash != state.latest_execution_payload_header.block_hash {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidParentHash {
+                provided: header.parent_hash.clone(),
+                expected: state.latest_execution_payload_header.block_hash.clone(),
+            }
+            .into(),
+        ))
+    }
+
+    let current_epoch = get_current_epoch(state, context);
+    let randao_mix = get_randao_mix(state, current_epoch);
+    if &header.prev_randao != randao_mix {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidPrevRandao {
+                provided: header.prev_randao.clone(),
+                expected: randao_mix.clone(),
+            }
+            .into(),
+        ))
+    }
+
+    let timestamp = compute_timestamp_at_slot(state, state.slot, context)?;
+    if header.timestamp != timestamp {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidTimestamp {
+                provided: header.timestamp,
+                expected: timestamp,
+            }
+            .into(),
+        ))
+    }
+
+    state.latest_execution_payload_header = header.clone();
+    Ok(())
+}
+
+/// Produces an unsigned blinded block for `slot` on top of `state` with the given
+/// `execution_payload_header`, including as many of the candidate `operations` as are valid.
+///
+/// The header is checked against `state` as its payload would be in `produce_block`, and its
+/// `withdrawals_root` against the expected withdrawals. The bounds of the transactions and
+/// withdrawals of the payload are not part of the block and must be given explicitly.
+pub fn produce_blinded_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    execution_payload_header: ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BlindedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+> {
+    let mut state = state.clone();
+    if state.slot < slot {
+        process_slots(&mut state, slot, &mut (), context)?;
+    }
+
+    // NOTE: operations are selected into a full block whose payload only has the withdrawals
+    let mut block = BeaconBlock::<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    > {
+        slot,
+        proposer_index: get_beacon_proposer_index(&state, context)?,
+        parent_root: state.latest_block_header.hash_tree_root()?,
+        ..Default::default()
+    };
+    block.body.randao_reveal = randao_reveal;
+    block.body.eth1_data = operations.eth1_data.clone().unwrap_or_else(|| state.eth1_data.clone());
+    block.body.graffiti = graffiti;
+    block.body.execution_payload.withdrawals =
+        List::try_from(get_expected_withdrawals(&state, context)).map_err(|(_, err)| err)?;
+
+    let signatures = &mut SignatureSets::default();
+    process_block_header(&mut state, &mut block, context)?;
+    process_withdrawals(&mut state, &block.body.execution_payload, &mut (), context)?;
+    let withdrawals_root = block.body.execution_payload.withdrawals.hash_tree_root()?;
+    process_execution_payload_header(
+        &mut state,
+        &execution_payload_header,
+        withdrawals_root,
+        context,
+    )?;
+    process_randao(&mut state, &block.body, signatures, context)?;
+    process_eth1_data(&mut state, &block.body, context);
+    select_operations(&mut state, &mut block.body, operations, signatures, context)?;
+    block.body.sync_aggregate = select_sync_aggregate(
+        &mut state,
+        &operations.sync_committee_contributions,
+        signatures,
+        context,
+    )?;
+
+    let body = block.body;
+    let mut block = BlindedBeaconBlock {
+        slot: block.slot,
+        proposer_index: block.proposer_index,
+        parent_root: block.parent_root,
+        state_root: Default::default(),
+        body: BlindedBeaconBlockBody {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload_header,
+            bls_to_execution_changes: body.bls_to_execution_changes,
+        },
+    };
+
+    // NOTE: the header was processed before the operations were selected
+    state.latest_block_header.body_root = block.body.hash_tree_root()?;
+    block.state_root = state.hash_tree_root()?;
+    Ok(block)
+}
@@ -1,6 +1,12 @@
 use crate::{
-    bellatrix,
-    capella::{BeaconState, ExecutionPayloadHeader, Fork},
+    altair, bellatrix,
+    capella::{
+        light_client::{
+            LightClientBootstrap, LightClientFinalityUpdate, LightClientHeader,
+            LightClientOptimisticUpdate, LightClientStore, LightClientUpdate,
+        },
+        BeaconState, ExecutionPayloadHeader, Fork,
+    },
     state_transition::Context,
 };
 
@@ -95,3 +101,184 @@ pub fn upgrade_to_capella<
         historical_summaries: Default::default(),
     }
 }
+
+pub fn upgrade_lc_header_to_capella<
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &altair::light_client::LightClientHeader,
+) -> LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientHeader {
+        beacon: pre.beacon.clone(),
+        execution: Default::default(),
+        execution_branch: Default::default(),
+    }
+}
+
+pub fn upgrade_lc_bootstrap_to_capella<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &altair::light_client::LightClientBootstrap<SYNC_COMMITTEE_SIZE>,
+) -> LightClientBootstrap<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientBootstrap {
+        header: upgrade_lc_header_to_capella(&pre.header),
+        current_sync_committee: pre.current_sync_committee.clone(),
+        current_sync_committee_branch: pre.current_sync_committee_branch.clone(),
+    }
+}
+
+pub fn upgrade_lc_update_to_capella<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &altair::light_client::LightClientUpdate<SYNC_COMMITTEE_SIZE>,
+) -> LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientUpdate {
+        attested_header: upgrade_lc_header_to_capella(&pre.attested_header),
+        next_sync_committee: pre.next_sync_committee.clone(),
+        next_sync_committee_branch: pre.next_sync_committee_branch.clone(),
+        finalized_header: upgrade_lc_header_to_capella(&pre.finalized_header),
+        finality_branch: pre.finality_branch.clone(),
+        sync_aggregate: pre.sync_aggregate.clone(),
+        signature_slot: pre.signature_slot,
+    }
+}
+
+pub fn upgrade_lc_finality_update_to_capella<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &altair::light_client::LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE>,
+) -> LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientFinalityUpdate {
+        attested_header: upgrade_lc_header_to_capella(&pre.attested_header),
+        finalized_header: upgrade_lc_header_to_capella(&pre.finalized_header),
+        finality_branch: pre.finality_branch.clone(),
+        sync_aggregate: pre.sync_aggregate.clone(),
+        signature_slot: pre.signature_slot,
+    }
+}
+
+pub fn upgrade_lc_optimistic_update_to_capella<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &altair::light_client::LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE>,
+) -> LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientOptimisticUpdate {
+        attested_header: upgrade_lc_header_to_capella(&pre.attested_header),
+        sync_aggregate: pre.sync_aggregate.clone(),
+        signature_slot: pre.signature_slot,
+    }
+}
+
+pub fn upgrade_lc_store_to_capella<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &altair::light_client::LightClientStore<SYNC_COMMITTEE_SIZE>,
+) -> LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientStore {
+        finalized_header: upgrade_lc_header_to_capella(&pre.finalized_header),
+        current_sync_committee: pre.current_sync_committee.clone(),
+        next_sync_committee: pre.next_sync_committee.clone(),
+        best_valid_update: pre.best_valid_update.as_ref().map(upgrade_lc_update_to_capella),
+        optimistic_header: upgrade_lc_header_to_capella(&pre.optimistic_header),
+        previous_max_active_participants: pre.previous_max_active_participants,
+        current_max_active_participants: pre.current_max_active_participants,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        capella::{
+            initialize_light_client_store, is_valid_light_client_header,
+            process_light_client_finality_update, process_light_client_optimistic_update,
+            process_light_client_update,
+        },
+        harness::minimal::Harness,
+        primitives::Root,
+    };
+
+    #[test]
+    fn test_upgraded_light_client_data_is_processed() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        let mut harness = Harness::new(64, context).unwrap();
+        let roots = harness.extend_chain(3).unwrap();
+        let context = harness.context();
+        let state = |root: &Root| harness.state(root).unwrap().altair().unwrap().clone();
+        let block = |root: &Root| harness.block(root).unwrap().altair().unwrap().clone();
+
+        let mut bootstrap_state = state(&roots[0]);
+        let genesis_validators_root = bootstrap_state.genesis_validators_root;
+        let bootstrap =
+            altair::create_light_client_bootstrap(&mut bootstrap_state, &block(&roots[0]), context)
+                .unwrap();
+        let update = altair::create_light_client_update(
+            &mut state(&roots[2]),
+            &block(&roots[2]),
+            &mut state(&roots[1]),
+            &block(&roots[1]),
+            Some(&altair::SignedBeaconBlock::default()),
+            context,
+        )
+        .unwrap();
+        let finality_update = altair::create_light_client_finality_update(&update);
+        let optimistic_update = altair::create_light_client_optimistic_update(&update);
+
+        let mut altair_store =
+            altair::initialize_light_client_store(roots[0], &bootstrap, context).unwrap();
+        altair::process_light_client_update(
+            &mut altair_store,
+            &update,
+            3,
+            genesis_validators_root,
+            context,
+        )
+        .unwrap();
+
+        // data produced before the fork is still verified after upgrading it
+        let bootstrap = upgrade_lc_bootstrap_to_capella(&bootstrap);
+        let update = upgrade_lc_update_to_capella(&update);
+        assert!(is_valid_light_client_header(&update.attested_header, context));
+        assert_eq!(update.attested_header.beacon.clone().hash_tree_root().unwrap(), roots[1]);
+        let mut store = initialize_light_client_store(roots[0], &bootstrap, context).unwrap();
+        process_light_client_update(&mut store, &update, 3, genesis_validators_root, context)
+            .unwrap();
+        process_light_client_finality_update(
+            &mut store,
+            &upgrade_lc_finality_update_to_capella(&finality_update),
+            3,
+            genesis_validators_root,
+            context,
+        )
+        .unwrap();
+        process_light_client_optimistic_update(
+            &mut store,
+            &upgrade_lc_optimistic_update_to_capella(&optimistic_update),
+            3,
+            genesis_validators_root,
+            context,
+        )
+        .unwrap();
+
+        let upgraded_store = upgrade_lc_store_to_capella(&altair_store);
+        assert_eq!(upgraded_store.finalized_header, store.finalized_header);
+        assert_eq!(upgraded_store.optimistic_header, store.optimistic_header);
+        assert_eq!(upgraded_store.current_sync_committee, store.current_sync_committee);
+        assert_eq!(upgraded_store.next_sync_committee, store.next_sync_committee);
+        assert_eq!(
+            upgraded_store.current_max_active_participants,
+            store.current_max_active_participants
+        );
+    }
+}
@@ -5,6 +5,7 @@ pub mod beacon_block;
 pub mod beacon_state;
 pub mod blinded_beacon_block;
 pub mod block_processing;
+pub mod block_production;
 pub mod bls_to_execution_change;
 pub mod epoch_processing;
 pub mod execution_engine;
@@ -12,6 +13,7 @@ pub mod execution_payload;
 pub mod fork;
 pub mod genesis;
 pub mod helpers;
+pub mod light_client;
 pub mod presets;
 pub mod spec;
 pub mod withdrawal;
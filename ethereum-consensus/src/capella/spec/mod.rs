@@ -8,8 +8,6 @@ pub use crate::{
             TIMELY_TARGET_WEIGHT, WEIGHT_DENOMINATOR,
         },
         light_client::{
-            LightClientBootstrap, LightClientFinalityUpdate, LightClientHeader,
-            LightClientOptimisticUpdate, LightClientStore, LightClientUpdate,
             CURRENT_SYNC_COMMITTEE_INDEX, CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2,
             FINALIZED_ROOT_INDEX, FINALIZED_ROOT_INDEX_FLOOR_LOG_2, NEXT_SYNC_COMMITTEE_INDEX,
             NEXT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2,
@@ -28,11 +26,17 @@ pub use crate::{
             BlindedBeaconBlock, BlindedBeaconBlockBody, SignedBlindedBeaconBlock,
         },
         block_processing::{
-            get_expected_withdrawals, process_block, process_bls_to_execution_change,
-            process_execution_payload, process_operations, process_withdrawals,
+            get_block_rewards, get_expected_withdrawals, process_block,
+            process_bls_to_execution_change, process_execution_payload, process_operations,
+            process_withdrawals,
+        },
+        block_production::{
+            produce_blinded_block, produce_block, select_operations, BlockOperations,
         },
         bls_to_execution_change::{BlsToExecutionChange, SignedBlsToExecutionChange},
-        epoch_processing::{process_epoch, process_historical_summaries_update},
+        epoch_processing::{
+            process_epoch, process_epoch_single_pass, process_historical_summaries_update,
+        },
         execution_engine::{DefaultExecutionEngine, NewPayloadRequest},
         execution_payload::{ExecutionPayload, ExecutionPayloadHeader},
         fork::upgrade_to_capella,
@@ -41,6 +45,12 @@ pub use crate::{
             has_eth1_withdrawal_credential, is_fully_withdrawable_validator,
             is_partially_withdrawable_validator,
         },
+        light_client::{
+            block_to_light_client_header, get_lc_execution_root, is_valid_light_client_header,
+            BestLightClientUpdates, LightClientBootstrap, LightClientFinalityUpdate,
+            LightClientHeader, LightClientOptimisticUpdate, LightClientStore, LightClientUpdate,
+            EXECUTION_PAYLOAD_INDEX, EXECUTION_PAYLOAD_INDEX_FLOOR_LOG_2,
+        },
         withdrawal::Withdrawal,
     },
     error::*,
@@ -51,6 +61,7 @@ pub use crate::{
             BASE_REWARDS_PER_EPOCH, DEPOSIT_CONTRACT_TREE_DEPTH, DEPOSIT_DATA_LIST_BOUND,
             JUSTIFICATION_BITS_LENGTH,
         },
+        epoch_processing::{ProgressiveBalances, SinglePassParameters, ValidatorParticipation},
         operations::{
             Attestation, AttestationData, AttesterSlashing, Checkpoint, Deposit, DepositData,
             DepositMessage, Eth1Data, IndexedAttestation, PendingAttestation, ProposerSlashing,
@@ -60,19 +71,20 @@ pub use crate::{
     },
     primitives::*,
     signing::*,
-    state_transition::{Context, Result, Validation},
+    state_transition::{
+        Context, Event, Observer, Result, SignatureSets, SignatureStrategy, Validation,
+    },
 };
 use crate::{
-    crypto::{eth_aggregate_public_keys, eth_fast_aggregate_verify, fast_aggregate_verify, hash},
+    crypto::{aggregate, eth_aggregate_public_keys, eth_fast_aggregate_verify, hash},
+    rewards::{
+        AttestationRewards, BlockRewards, IdealAttestationRewards, SyncCommitteeReward,
+        TotalAttestationRewards,
+    },
     ssz::prelude::*,
 };
 use integer_sqrt::IntegerSquareRoot;
-use std::{
-    cmp,
-    collections::{HashMap, HashSet},
-    iter::zip,
-    mem,
-};
+use std::{cmp, collections::HashSet, iter::zip, mem};
 pub fn process_attestation<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -98,6 +110,7 @@ pub fn process_attestation<
         MAX_EXTRA_DATA_BYTES,
     >,
     attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let data = &attestation.data;
@@ -157,6 +170,7 @@ pub fn process_attestation<
     is_valid_indexed_attestation(
         state,
         &mut get_indexed_attestation(state, attestation, context)?,
+        signatures,
         context,
     )?;
     let attesting_indices =
@@ -212,6 +226,7 @@ pub fn process_deposit<
         MAX_EXTRA_DATA_BYTES,
     >,
     deposit: &mut Deposit,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let branch = deposit
@@ -231,9 +246,15 @@ pub fn process_deposit<
     state.eth1_deposit_index += 1;
     let public_key = &deposit.data.public_key;
     let amount = deposit.data.amount;
-    let validator_public_keys: HashSet<&BlsPublicKey> =
-        HashSet::from_iter(state.validators.iter().map(|v| &v.public_key));
-    if !validator_public_keys.contains(public_key) {
+    if let Some(index) = context.pubkey_cache.get_index(public_key, &state.validators) {
+        increase_balance(state, index, amount);
+        observer.on_event(&Event::DepositApplied {
+            index,
+            public_key: public_key.clone(),
+            amount,
+            is_new_validator: false,
+        });
+    } else {
         let mut deposit_message = DepositMessage {
             public_key: public_key.clone(),
             withdrawal_credentials: deposit.data.withdrawal_credentials.clone(),
@@ -244,14 +265,18 @@ pub fn process_deposit<
         if verify_signature(public_key, signing_root.as_ref(), &deposit.data.signature).is_err() {
             return Ok(())
         }
+        let index = state.validators.len();
         state.validators.push(get_validator_from_deposit(deposit, context));
         state.balances.push(amount);
         state.previous_epoch_participation.push(ParticipationFlags::default());
         state.current_epoch_participation.push(ParticipationFlags::default());
-        state.inactivity_scores.push(0)
-    } else {
-        let index = state.validators.iter().position(|v| &v.public_key == public_key).unwrap();
-        increase_balance(state, index, amount);
+        state.inactivity_scores.push(0);
+        observer.on_event(&Event::DepositApplied {
+            index,
+            public_key: public_key.clone(),
+            amount,
+            is_new_validator: true,
+        });
     }
     Ok(())
 }
@@ -280,12 +305,21 @@ pub fn process_sync_aggregate<
         MAX_EXTRA_DATA_BYTES,
     >,
     sync_aggregate: &SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
-    let committee_public_keys = &state.current_sync_committee.public_keys;
-    let participant_public_keys =
-        zip(committee_public_keys.iter(), sync_aggregate.sync_committee_bits.iter())
-            .filter_map(|(public_key, bit)| if *bit { Some(public_key) } else { None })
+    let mut committee_indices: Vec<ValidatorIndex> = Vec::default();
+    for public_key in state.current_sync_committee.public_keys.iter() {
+        committee_indices.push(
+            context
+                .pubkey_cache
+                .get_index(public_key, &state.validators)
+                .expect("validator public_key should exist"),
+        );
+    }
+    let participant_indices =
+        zip(committee_indices.iter(), sync_aggregate.sync_committee_bits.iter())
+            .filter_map(|(index, bit)| if *bit { Some(*index) } else { None })
             .collect::<Vec<_>>();
     let previous_slot = u64::max(state.slot, 1) - 1;
     let domain = get_domain(
@@ -296,20 +330,20 @@ pub fn process_sync_aggregate<
     )?;
     let mut root_at_slot = *get_block_root_at_slot(state, previous_slot)?;
     let signing_root = compute_signing_root(&mut root_at_slot, domain)?;
-    if eth_fast_aggregate_verify(
-        participant_public_keys.as_slice(),
+    let signature_set = context.pubkey_cache.aggregate_signature_set(
+        &state.validators,
+        &participant_indices,
         signing_root.as_ref(),
         &sync_aggregate.sync_committee_signature,
-    )
-    .is_err()
-    {
-        return Err(invalid_operation_error(InvalidOperation::SyncAggregate(
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::SyncAggregate(
             InvalidSyncAggregate::InvalidSignature {
                 signature: sync_aggregate.sync_committee_signature.clone(),
                 root: signing_root,
             },
-        )))
-    }
+        ))
+    })?;
     let total_active_increments =
         get_total_active_balance(state, context)? / context.effective_balance_increment;
     let total_base_rewards =
@@ -319,17 +353,6 @@ pub fn process_sync_aggregate<
     let participant_reward = max_participant_rewards / context.sync_committee_size as u64;
     let proposer_reward =
         participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
-    let all_public_keys = state
-        .validators
-        .iter()
-        .enumerate()
-        .map(|(i, v)| (&v.public_key, i))
-        .collect::<HashMap<&BlsPublicKey, usize>>();
-    let mut committee_indices: Vec<ValidatorIndex> = Vec::default();
-    for public_key in state.current_sync_committee.public_keys.iter() {
-        committee_indices
-            .push(*all_public_keys.get(public_key).expect("validator public_key should exist"));
-    }
     for (participant_index, participation_bit) in
         zip(committee_indices.iter(), sync_aggregate.sync_committee_bits.iter())
     {
@@ -342,6 +365,75 @@ pub fn process_sync_aggregate<
     }
     Ok(())
 }
+pub fn get_sync_aggregate_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    sync_aggregate: &SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    rewards: &mut BlockRewards,
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<Vec<SyncCommitteeReward>> {
+    let proposer_index = rewards.proposer_index;
+    let mut committee_indices: Vec<ValidatorIndex> = Vec::default();
+    for public_key in state.current_sync_committee.public_keys.iter() {
+        let index = context
+            .pubkey_cache
+            .get_index(public_key, &state.validators)
+            .ok_or_else(|| Error::UnknownValidator(public_key.clone()))?;
+        committee_indices.push(index);
+    }
+    let total_active_increments =
+        get_total_active_balance(state, context)? / context.effective_balance_increment;
+    let total_base_rewards =
+        get_base_reward_per_increment(state, context)? * total_active_increments;
+    let max_participant_rewards =
+        total_base_rewards * SYNC_REWARD_WEIGHT / WEIGHT_DENOMINATOR / context.slots_per_epoch;
+    let participant_reward = max_participant_rewards / context.sync_committee_size as u64;
+    let proposer_reward =
+        participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
+    let participant_count =
+        sync_aggregate.sync_committee_bits.iter().filter(|bit| **bit).count() as u64;
+    rewards.sync_aggregate = proposer_reward * participant_count;
+    let mut members = Vec::new();
+    for index in committee_indices {
+        if !members.iter().any(|(member, _)| *member == index) {
+            members.push((index, state.balances[index]));
+        }
+    }
+    process_sync_aggregate(state, sync_aggregate, signatures, context)?;
+    let sync_committee_rewards = members
+        .into_iter()
+        .map(|(validator_index, balance)| {
+            let mut reward = state.balances[validator_index] as i64 - balance as i64;
+            if validator_index == proposer_index {
+                reward -= rewards.sync_aggregate as i64;
+            }
+            SyncCommitteeReward { validator_index, reward }
+        })
+        .collect();
+    Ok(sync_committee_rewards)
+}
 pub fn process_proposer_slashing<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -367,6 +459,8 @@ pub fn process_proposer_slashing<
         MAX_EXTRA_DATA_BYTES,
     >,
     proposer_slashing: &mut ProposerSlashing,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let header_1 = &proposer_slashing.signed_header_1.message;
@@ -406,14 +500,19 @@ pub fn process_proposer_slashing<
         [&mut proposer_slashing.signed_header_1, &mut proposer_slashing.signed_header_2]
     {
         let signing_root = compute_signing_root(&mut signed_header.message, domain)?;
-        let public_key = &proposer.public_key;
-        if verify_signature(public_key, signing_root.as_ref(), &signed_header.signature).is_err() {
-            return Err(invalid_operation_error(InvalidOperation::ProposerSlashing(
+        let signature_set = context.pubkey_cache.signature_set(
+            &state.validators,
+            proposer_index,
+            signing_root.as_ref(),
+            &signed_header.signature,
+        );
+        signatures.verify(signature_set, || {
+            invalid_operation_error(InvalidOperation::ProposerSlashing(
                 InvalidProposerSlashing::InvalidSignature(signed_header.signature.clone()),
-            )))
-        }
+            ))
+        })?;
     }
-    slash_validator(state, proposer_index, None, context)
+    slash_validator(state, proposer_index, None, observer, context)
 }
 pub fn process_attester_slashing<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -440,6 +539,8 @@ pub fn process_attester_slashing<
         MAX_EXTRA_DATA_BYTES,
     >,
     attester_slashing: &mut AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let attestation_1 = &mut attester_slashing.attestation_1;
@@ -452,8 +553,8 @@ pub fn process_attester_slashing<
             ),
         )))
     }
-    is_valid_indexed_attestation(state, attestation_1, context)?;
-    is_valid_indexed_attestation(state, attestation_2, context)?;
+    is_valid_indexed_attestation(state, attestation_1, signatures, context)?;
+    is_valid_indexed_attestation(state, attestation_2, signatures, context)?;
     let indices_1: HashSet<ValidatorIndex> =
         HashSet::from_iter(attestation_1.attesting_indices.iter().cloned());
     let indices_2 = HashSet::from_iter(attestation_2.attesting_indices.iter().cloned());
@@ -463,7 +564,7 @@ pub fn process_attester_slashing<
     let current_epoch = get_current_epoch(state, context);
     for &index in &indices {
         if is_slashable_validator(&state.validators[index], current_epoch) {
-            slash_validator(state, index, None, context)?;
+            slash_validator(state, index, None, observer, context)?;
             slashed_any = true;
         }
     }
@@ -517,6 +618,8 @@ pub fn process_voluntary_exit<
         MAX_EXTRA_DATA_BYTES,
     >,
     signed_voluntary_exit: &mut SignedVoluntaryExit,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let voluntary_exit = &mut signed_voluntary_exit.message;
@@ -555,14 +658,19 @@ pub fn process_voluntary_exit<
         )))
     }
     let domain = get_domain(state, DomainType::VoluntaryExit, Some(voluntary_exit.epoch), context)?;
-    let public_key = &validator.public_key;
-    verify_signed_data(voluntary_exit, &signed_voluntary_exit.signature, public_key, domain)
-        .map_err(|_| {
-            invalid_operation_error(InvalidOperation::VoluntaryExit(
-                InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
-            ))
-        })?;
-    initiate_validator_exit(state, voluntary_exit.validator_index, context);
+    let signing_root = compute_signing_root(voluntary_exit, domain)?;
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        voluntary_exit.validator_index,
+        signing_root.as_ref(),
+        &signed_voluntary_exit.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::VoluntaryExit(
+            InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
+        ))
+    })?;
+    initiate_validator_exit(state, voluntary_exit.validator_index, observer, context);
     Ok(())
 }
 pub fn process_block_header<
@@ -706,16 +814,22 @@ pub fn process_randao<
         MAX_WITHDRAWALS_PER_PAYLOAD,
         MAX_BLS_TO_EXECUTION_CHANGES,
     >,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let mut epoch = get_current_epoch(state, context);
     let proposer_index = get_beacon_proposer_index(state, context)?;
-    let proposer = &state.validators[proposer_index];
     let domain = get_domain(state, DomainType::Randao, Some(epoch), context)?;
     let signing_root = compute_signing_root(&mut epoch, domain)?;
-    if verify_signature(&proposer.public_key, signing_root.as_ref(), &body.randao_reveal).is_err() {
-        return Err(invalid_operation_error(InvalidOperation::Randao(body.randao_reveal.clone())))
-    }
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        proposer_index,
+        signing_root.as_ref(),
+        &body.randao_reveal,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::Randao(body.randao_reveal.clone()))
+    })?;
     let mix = xor(get_randao_mix(state, epoch), &hash(body.randao_reveal.as_ref()));
     let mix_index = epoch % context.epochs_per_historical_vector;
     state.randao_mixes[mix_index as usize] = mix;
@@ -778,6 +892,217 @@ pub fn process_eth1_data<
         state.eth1_data = body.eth1_data.clone();
     }
 }
+/// Selects up to `MAX_ATTESTATIONS` of the candidate `attestations` for a block on top of `state`,
+/// greedily taking the attestation that sets the participation flags worth the most base reward
+/// that are not yet set in `state` or by the attestations selected so far.
+///
+/// Each selected attestation is checked with `process_attestation` against a copy of `state`;
+/// invalid candidates are skipped.
+pub fn select_attestations<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    attestations: &[Attestation<MAX_VALIDATORS_PER_COMMITTEE>],
+    context: &Context,
+) -> Result<Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>> {
+    let current_epoch = get_current_epoch(state, context);
+    let base_reward_per_increment = get_base_reward_per_increment(state, context)?;
+    let mut candidates = vec![];
+    for attestation in attestations {
+        let data = &attestation.data;
+        if data.slot + context.min_attestation_inclusion_delay > state.slot {
+            continue
+        }
+        let inclusion_delay = state.slot - data.slot;
+        let participation_flag_indices =
+            match get_attestation_participation_flag_indices(state, data, inclusion_delay, context)
+            {
+                Ok(participation_flag_indices) => participation_flag_indices,
+                Err(_) => continue,
+            };
+        let attesting_indices =
+            match get_attesting_indices(state, data, &attestation.aggregation_bits, context) {
+                Ok(attesting_indices) => attesting_indices,
+                Err(_) => continue,
+            };
+        let epoch_participation = if data.target.epoch == current_epoch {
+            &state.current_epoch_participation
+        } else {
+            &state.previous_epoch_participation
+        };
+        let mut cover = vec![];
+        for index in attesting_indices {
+            for flag_index in &participation_flag_indices {
+                if !has_flag(epoch_participation[index], *flag_index) {
+                    let increments = state.validators[index].effective_balance /
+                        context.effective_balance_increment;
+                    let weight = increments *
+                        base_reward_per_increment *
+                        PARTICIPATION_FLAG_WEIGHTS[*flag_index];
+                    cover.push(((data.target.epoch, index, *flag_index), weight));
+                }
+            }
+        }
+        candidates.push((attestation, cover));
+    }
+    let mut state = state.clone();
+    let mut covered = HashSet::new();
+    let mut selected = vec![];
+    let signatures = &mut SignatureSets::default();
+    while selected.len() < context.max_attestations {
+        let mut best = None;
+        let mut best_weight = 0;
+        for (i, (_, cover)) in candidates.iter().enumerate() {
+            let weight = cover
+                .iter()
+                .filter(|(key, _)| !covered.contains(key))
+                .map(|(_, weight)| weight)
+                .sum::<u64>();
+            if weight > best_weight {
+                best = Some(i);
+                best_weight = weight;
+            }
+        }
+        let i = match best {
+            Some(i) => i,
+            None => break,
+        };
+        let (attestation, cover) = candidates.remove(i);
+        if process_attestation(&mut state, attestation, signatures, context).is_ok() {
+            covered.extend(cover.into_iter().map(|(key, _)| key));
+            selected.push(attestation.clone());
+        }
+    }
+    Ok(selected)
+}
+/// Aggregates the `contributions` for the block root at the slot before `state.slot`, taking the
+/// contribution with the most participants for each subcommittee.
+pub fn get_sync_aggregate<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    contributions: &[SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>],
+    context: &Context,
+) -> Result<SyncAggregate<SYNC_COMMITTEE_SIZE>> {
+    let previous_slot = u64::max(state.slot, 1) - 1;
+    let root = get_block_root_at_slot(state, previous_slot)?;
+    let subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+    let mut best_contributions: Vec<Option<&SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>> =
+        vec![None; SYNC_COMMITTEE_SUBNET_COUNT];
+    for contribution in contributions {
+        let subcommittee_index = contribution.subcommittee_index as usize;
+        if contribution.slot != previous_slot ||
+            &contribution.beacon_block_root != root ||
+            subcommittee_index >= SYNC_COMMITTEE_SUBNET_COUNT
+        {
+            continue
+        }
+        let participants = contribution.aggregation_bits.count_ones();
+        let is_better = match best_contributions[subcommittee_index] {
+            Some(best) => best.aggregation_bits.count_ones() < participants,
+            None => true,
+        };
+        if is_better {
+            best_contributions[subcommittee_index] = Some(contribution);
+        }
+    }
+    let mut sync_aggregate = SyncAggregate::<SYNC_COMMITTEE_SIZE>::default();
+    let mut signatures = vec![];
+    for (subcommittee_index, contribution) in best_contributions.into_iter().enumerate() {
+        if let Some(contribution) = contribution {
+            for (i, bit) in contribution.aggregation_bits.iter().enumerate() {
+                if *bit {
+                    sync_aggregate
+                        .sync_committee_bits
+                        .set(subcommittee_index * subcommittee_size + i, true);
+                }
+            }
+            signatures.push(contribution.signature.clone());
+        }
+    }
+    sync_aggregate.sync_committee_signature =
+        if signatures.is_empty() { BlsSignature::infinity() } else { aggregate(&signatures)? };
+    Ok(sync_aggregate)
+}
+/// Applies the aggregate of the `contributions` to `state` as in `process_sync_aggregate` and
+/// returns it. If the aggregate signature is invalid, an empty aggregate is applied instead.
+pub fn select_sync_aggregate<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    contributions: &[SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>],
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<SyncAggregate<SYNC_COMMITTEE_SIZE>> {
+    let sync_aggregate = get_sync_aggregate(state, contributions, context)?;
+    if process_sync_aggregate(state, &sync_aggregate, signatures, context).is_ok() {
+        return Ok(sync_aggregate)
+    }
+    let sync_aggregate =
+        SyncAggregate { sync_committee_signature: BlsSignature::infinity(), ..Default::default() };
+    process_sync_aggregate(state, &sync_aggregate, signatures, context)?;
+    Ok(sync_aggregate)
+}
 pub fn process_slashings<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -879,6 +1204,7 @@ pub fn process_justification_and_finalization<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let current_epoch = get_current_epoch(state, context);
@@ -905,6 +1231,7 @@ pub fn process_justification_and_finalization<
         total_active_balance,
         previous_target_balance,
         current_target_balance,
+        observer,
         context,
     )
 }
@@ -1004,6 +1331,95 @@ pub fn process_rewards_and_penalties<
     }
     Ok(())
 }
+pub fn get_attestation_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    context: &Context,
+) -> Result<AttestationRewards> {
+    let validator_count = state.validators.len();
+    if get_current_epoch(state, context) == GENESIS_EPOCH {
+        let total_rewards = (0..validator_count)
+            .map(|validator_index| TotalAttestationRewards {
+                validator_index,
+                ..Default::default()
+            })
+            .collect();
+        return Ok(AttestationRewards { ideal_rewards: vec![], total_rewards })
+    }
+    let previous_epoch = get_previous_epoch(state, context);
+    let increment = context.effective_balance_increment;
+    let base_reward_per_increment = get_base_reward_per_increment(state, context)?;
+    let active_increments = get_total_active_balance(state, context)? / increment;
+    let in_inactivity_leak = is_in_inactivity_leak(state, context);
+    let mut participating_increments = [0; PARTICIPATION_FLAG_WEIGHTS.len()];
+    let mut deltas = Vec::with_capacity(PARTICIPATION_FLAG_WEIGHTS.len());
+    for (flag_index, increments) in participating_increments.iter_mut().enumerate() {
+        let participating_indices =
+            get_unslashed_participating_indices(state, flag_index, previous_epoch, context)?;
+        *increments = get_total_balance(state, &participating_indices, context)? / increment;
+        deltas.push(get_flag_index_deltas(state, flag_index, context)?);
+    }
+    let (_, inactivity_penalties) = get_inactivity_penalty_deltas(state, context)?;
+    let ideal_rewards = (1..=context.max_effective_balance / increment)
+        .map(|increments| {
+            let base_reward = increments * base_reward_per_increment;
+            let flag_reward = |flag_index: usize| {
+                if in_inactivity_leak {
+                    0
+                } else {
+                    let reward_numerator = base_reward *
+                        PARTICIPATION_FLAG_WEIGHTS[flag_index] *
+                        participating_increments[flag_index];
+                    reward_numerator / (active_increments * WEIGHT_DENOMINATOR)
+                }
+            };
+            IdealAttestationRewards {
+                effective_balance: increments * increment,
+                head: flag_reward(TIMELY_HEAD_FLAG_INDEX),
+                target: flag_reward(TIMELY_TARGET_FLAG_INDEX),
+                source: flag_reward(TIMELY_SOURCE_FLAG_INDEX),
+                inclusion_delay: None,
+                inactivity: 0,
+            }
+        })
+        .collect();
+    let flag_delta = |flag_index: usize, i: ValidatorIndex| {
+        let (rewards, penalties) = &deltas[flag_index];
+        rewards[i] as i64 - penalties[i] as i64
+    };
+    let total_rewards = (0..validator_count)
+        .map(|i| TotalAttestationRewards {
+            validator_index: i,
+            head: flag_delta(TIMELY_HEAD_FLAG_INDEX, i),
+            target: flag_delta(TIMELY_TARGET_FLAG_INDEX, i),
+            source: flag_delta(TIMELY_SOURCE_FLAG_INDEX, i),
+            inclusion_delay: None,
+            inactivity: -(inactivity_penalties[i] as i64),
+        })
+        .collect();
+    Ok(AttestationRewards { ideal_rewards, total_rewards })
+}
 pub fn process_participation_flag_updates<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -1060,6 +1476,7 @@ pub fn process_sync_committee_updates<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let next_epoch = get_current_epoch(state, context) + 1;
@@ -1068,10 +1485,11 @@ pub fn process_sync_committee_updates<
         let current_sync_committee =
             mem::replace(&mut state.next_sync_committee, next_sync_committee);
         state.current_sync_committee = current_sync_committee;
+        observer.on_event(&Event::SyncCommitteeRotated { epoch: next_epoch });
     }
     Ok(())
 }
-pub fn process_registry_updates<
+pub fn get_progressive_balances<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
     const ETH1_DATA_VOTES_BOUND: usize,
@@ -1083,7 +1501,7 @@ pub fn process_registry_updates<
     const BYTES_PER_LOGS_BLOOM: usize,
     const MAX_EXTRA_DATA_BYTES: usize,
 >(
-    state: &mut BeaconState<
+    state: &BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
         HISTORICAL_ROOTS_LIMIT,
         ETH1_DATA_VOTES_BOUND,
@@ -1096,19 +1514,187 @@ pub fn process_registry_updates<
         MAX_EXTRA_DATA_BYTES,
     >,
     context: &Context,
-) {
+) -> Result<ProgressiveBalances> {
+    let previous_epoch = get_previous_epoch(state, context);
     let current_epoch = get_current_epoch(state, context);
-    for i in 0..state.validators.len() {
-        let validator = &mut state.validators[i];
-        if is_eligible_for_activation_queue(validator, context) {
-            validator.activation_eligibility_epoch = current_epoch + 1;
-        }
-        if is_active_validator(validator, current_epoch) &&
-            validator.effective_balance <= context.ejection_balance
-        {
-            initiate_validator_exit(state, i, context);
+    let previous_epoch_participation = if previous_epoch == current_epoch {
+        &state.current_epoch_participation
+    } else {
+        &state.previous_epoch_participation
+    };
+    let mut balances = ProgressiveBalances::default();
+    for (i, validator) in state.validators.iter().enumerate() {
+        let effective_balance = validator.effective_balance;
+        let add = |balance: &mut Gwei| -> Result<()> {
+            *balance = balance.checked_add(effective_balance).ok_or(Error::Overflow)?;
+            Ok(())
+        };
+        if is_active_validator(validator, current_epoch) {
+            add(&mut balances.total_active_balance)?;
+            if !validator.slashed &&
+                has_flag(state.current_epoch_participation[i], TIMELY_TARGET_FLAG_INDEX)
+            {
+                add(&mut balances.current_epoch_target_balance)?;
+            }
         }
-    }
+        if !validator.slashed && is_active_validator(validator, previous_epoch) {
+            for (flag_index, balance) in
+                balances.previous_epoch_participating_balances.iter_mut().enumerate()
+            {
+                if has_flag(previous_epoch_participation[i], flag_index) {
+                    add(balance)?;
+                }
+            }
+        }
+    }
+    Ok(balances.with_minimum_balance(context))
+}
+pub fn process_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    balances: &ProgressiveBalances,
+    parameters: &SinglePassParameters,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let current_epoch = get_current_epoch(state, context);
+    let total_active_balance = balances.total_active_balance;
+    let adjusted_total_slashing_balance = Gwei::min(
+        state.slashings.iter().sum::<Gwei>() * parameters.proportional_slashing_multiplier,
+        total_active_balance,
+    );
+    let process_rewards = current_epoch != GENESIS_EPOCH;
+    let previous_epoch = get_previous_epoch(state, context);
+    let is_leaking = is_in_inactivity_leak(state, context);
+    let increment = context.effective_balance_increment;
+    let base_reward_per_increment =
+        increment * context.base_reward_factor / total_active_balance.integer_sqrt();
+    let active_increments = total_active_balance / increment;
+    let participating_increments =
+        balances.previous_epoch_participating_balances.map(|balance| balance / increment);
+    let inactivity_penalty_denominator =
+        context.inactivity_score_bias * parameters.inactivity_penalty_quotient;
+    let mut activation_queue = vec![];
+    for i in 0..state.validators.len() {
+        let validator = &state.validators[i];
+        let is_eligible = is_active_validator(validator, previous_epoch) ||
+            (validator.slashed && previous_epoch + 1 < validator.withdrawable_epoch);
+        if process_rewards && is_eligible {
+            let effective_balance = validator.effective_balance;
+            let is_participating =
+                !validator.slashed && is_active_validator(validator, previous_epoch);
+            let flags = state.previous_epoch_participation[i];
+            let has_participation_flag =
+                |flag_index| is_participating && has_flag(flags, flag_index);
+            let inactivity_score = &mut state.inactivity_scores[i];
+            if has_participation_flag(TIMELY_TARGET_FLAG_INDEX) {
+                *inactivity_score -= u64::min(1, *inactivity_score);
+            } else {
+                *inactivity_score += context.inactivity_score_bias;
+            }
+            if !is_leaking {
+                *inactivity_score -=
+                    u64::min(context.inactivity_score_recovery_rate, *inactivity_score);
+            }
+            let inactivity_score = *inactivity_score;
+            let base_reward = effective_balance / increment * base_reward_per_increment;
+            for (flag_index, weight) in PARTICIPATION_FLAG_WEIGHTS.into_iter().enumerate() {
+                if has_participation_flag(flag_index) {
+                    if !is_leaking {
+                        let reward_numerator =
+                            base_reward * weight * participating_increments[flag_index];
+                        let reward = reward_numerator / (active_increments * WEIGHT_DENOMINATOR);
+                        increase_balance(state, i, reward);
+                    }
+                } else if flag_index != TIMELY_HEAD_FLAG_INDEX {
+                    decrease_balance(state, i, base_reward * weight / WEIGHT_DENOMINATOR);
+                }
+            }
+            if !has_participation_flag(TIMELY_TARGET_FLAG_INDEX) {
+                let penalty_numerator = effective_balance * inactivity_score;
+                decrease_balance(state, i, penalty_numerator / inactivity_penalty_denominator);
+            }
+        }
+        if process_validator_updates(
+            state,
+            i,
+            total_active_balance,
+            adjusted_total_slashing_balance,
+            observer,
+            context,
+        ) {
+            activation_queue.push(i);
+        }
+    }
+    process_activation_queue(
+        state,
+        activation_queue,
+        parameters.activation_churn_limit,
+        observer,
+        context,
+    );
+    Ok(())
+}
+pub fn process_registry_updates<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    observer: &mut dyn Observer,
+    context: &Context,
+) {
+    let current_epoch = get_current_epoch(state, context);
+    for i in 0..state.validators.len() {
+        let validator = &mut state.validators[i];
+        if is_eligible_for_activation_queue(validator, context) {
+            validator.activation_eligibility_epoch = current_epoch + 1;
+        }
+        if is_active_validator(validator, current_epoch) &&
+            validator.effective_balance <= context.ejection_balance
+        {
+            initiate_validator_exit(state, i, observer, context);
+        }
+    }
     let mut activation_queue =
         state
             .validators
@@ -1131,6 +1717,10 @@ pub fn process_registry_updates<
     for i in activation_queue.into_iter().take(get_validator_churn_limit(state, context)) {
         let validator = &mut state.validators[i];
         validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
     }
 }
 pub fn process_eth1_data_reset<
@@ -1188,6 +1778,7 @@ pub fn process_effective_balance_updates<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     let hysteresis_increment = context.effective_balance_increment / context.hysteresis_quotient;
@@ -1199,10 +1790,18 @@ pub fn process_effective_balance_updates<
         if balance + downward_threshold < validator.effective_balance ||
             validator.effective_balance + upward_threshold < balance
         {
+            let previous = validator.effective_balance;
             validator.effective_balance = Gwei::min(
                 balance - balance % context.effective_balance_increment,
                 context.max_effective_balance,
             );
+            if validator.effective_balance != previous {
+                observer.on_event(&Event::EffectiveBalanceChanged {
+                    index: i,
+                    previous,
+                    current: validator.effective_balance,
+                });
+            }
         }
     }
 }
@@ -1331,6 +1930,7 @@ pub fn weigh_justification_and_finalization<
     total_active_balance: Gwei,
     previous_epoch_target_balance: Gwei,
     current_epoch_target_balance: Gwei,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let previous_epoch = get_previous_epoch(state, context);
@@ -1354,6 +1954,15 @@ pub fn weigh_justification_and_finalization<
         };
         state.justification_bits.set(0, true);
     }
+    if state.previous_justified_checkpoint != old_previous_justified_checkpoint ||
+        state.current_justified_checkpoint != old_current_justified_checkpoint
+    {
+        observer.on_event(&Event::JustificationChanged {
+            previous_justified: state.previous_justified_checkpoint.clone(),
+            current_justified: state.current_justified_checkpoint.clone(),
+        });
+    }
+    let old_finalized_checkpoint = state.finalized_checkpoint.clone();
     let bits = &state.justification_bits;
     if bits[1..4].all() && old_previous_justified_checkpoint.epoch + 3 == current_epoch {
         state.finalized_checkpoint = old_previous_justified_checkpoint.clone();
@@ -1367,6 +1976,11 @@ pub fn weigh_justification_and_finalization<
     if bits[0..2].all() && old_current_justified_checkpoint.epoch + 1 == current_epoch {
         state.finalized_checkpoint = old_current_justified_checkpoint;
     }
+    if state.finalized_checkpoint != old_finalized_checkpoint {
+        observer.on_event(&Event::FinalizationChanged {
+            finalized: state.finalized_checkpoint.clone(),
+        });
+    }
     Ok(())
 }
 pub fn get_proposer_reward<
@@ -1454,6 +2068,232 @@ pub fn is_in_inactivity_leak<
 ) -> bool {
     get_finality_delay(state, context) > context.min_epochs_to_inactivity_penalty
 }
+pub fn process_justification_and_finalization_with_balances<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    balances: &ProgressiveBalances,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    if get_current_epoch(state, context) <= GENESIS_EPOCH + 1 {
+        return Ok(())
+    }
+    weigh_justification_and_finalization(
+        state,
+        balances.total_active_balance,
+        balances.previous_epoch_target_balance(),
+        balances.current_epoch_target_balance,
+        observer,
+        context,
+    )
+}
+pub fn process_validator_updates<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    index: ValidatorIndex,
+    total_active_balance: Gwei,
+    adjusted_total_slashing_balance: Gwei,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> bool {
+    let current_epoch = get_current_epoch(state, context);
+    let validator = &mut state.validators[index];
+    if is_eligible_for_activation_queue(validator, context) {
+        validator.activation_eligibility_epoch = current_epoch + 1;
+    }
+    if is_active_validator(validator, current_epoch) &&
+        validator.effective_balance <= context.ejection_balance
+    {
+        initiate_validator_exit(state, index, observer, context);
+    }
+    let validator = &state.validators[index];
+    let is_eligible_for_activation = is_eligible_for_activation(state, validator);
+    if validator.slashed &&
+        (current_epoch + context.epochs_per_slashings_vector / 2) == validator.withdrawable_epoch
+    {
+        let increment = context.effective_balance_increment;
+        let penalty_numerator =
+            validator.effective_balance / increment * adjusted_total_slashing_balance;
+        let penalty = penalty_numerator / total_active_balance * increment;
+        decrease_balance(state, index, penalty);
+    }
+    let hysteresis_increment = context.effective_balance_increment / context.hysteresis_quotient;
+    let downward_threshold = hysteresis_increment * context.hysteresis_downward_multiplier;
+    let upward_threshold = hysteresis_increment * context.hysteresis_upward_multiplier;
+    let validator = &mut state.validators[index];
+    let balance = state.balances[index];
+    if balance + downward_threshold < validator.effective_balance ||
+        validator.effective_balance + upward_threshold < balance
+    {
+        let previous = validator.effective_balance;
+        validator.effective_balance = Gwei::min(
+            balance - balance % context.effective_balance_increment,
+            context.max_effective_balance,
+        );
+        if validator.effective_balance != previous {
+            observer.on_event(&Event::EffectiveBalanceChanged {
+                index,
+                previous,
+                current: validator.effective_balance,
+            });
+        }
+    }
+    is_eligible_for_activation
+}
+pub fn process_activation_queue<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    mut activation_queue: Vec<ValidatorIndex>,
+    activation_churn_limit: usize,
+    observer: &mut dyn Observer,
+    context: &Context,
+) {
+    activation_queue.sort_by(|&i, &j| {
+        let a = &state.validators[i];
+        let b = &state.validators[j];
+        (a.activation_eligibility_epoch, i).cmp(&(b.activation_eligibility_epoch, j))
+    });
+    let current_epoch = get_current_epoch(state, context);
+    let activation_exit_epoch = compute_activation_exit_epoch(current_epoch, context);
+    for i in activation_queue.into_iter().take(activation_churn_limit) {
+        let validator = &mut state.validators[i];
+        validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
+    }
+}
+pub fn is_valid_terminal_pow_block(block: &PowBlock, parent: &PowBlock, context: &Context) -> bool {
+    let is_total_difficulty_reached = block.total_difficulty >= context.terminal_total_difficulty;
+    let is_parent_total_difficulty_valid =
+        parent.total_difficulty < context.terminal_total_difficulty;
+    is_total_difficulty_reached && is_parent_total_difficulty_valid
+}
+pub fn validate_merge_block<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    F: Fn(&Hash32) -> Option<PowBlock>,
+>(
+    block: &BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+    get_pow_block: F,
+    context: &Context,
+) -> Result<()> {
+    let parent_hash = &block.body.execution_payload.parent_hash;
+    if context.terminal_block_hash != Hash32::default() {
+        let epoch = compute_epoch_at_slot(block.slot, context);
+        let activation_epoch = context.terminal_block_hash_activation_epoch;
+        if epoch < activation_epoch {
+            return Err(
+                ForkChoiceError::TerminalBlockHashNotActivated { epoch, activation_epoch }.into()
+            )
+        }
+        if parent_hash != &context.terminal_block_hash {
+            return Err(ForkChoiceError::InvalidTerminalBlockHash {
+                provided: parent_hash.clone(),
+                expected: context.terminal_block_hash.clone(),
+            }
+            .into())
+        }
+        return Ok(())
+    }
+    let pow_block = get_pow_block(parent_hash)
+        .ok_or_else(|| ForkChoiceError::UnknownPowBlock(parent_hash.clone()))?;
+    let pow_parent = get_pow_block(&pow_block.parent_hash)
+        .ok_or_else(|| ForkChoiceError::UnknownPowBlock(pow_block.parent_hash.clone()))?;
+    if !is_valid_terminal_pow_block(&pow_block, &pow_parent, context) {
+        return Err(ForkChoiceError::InvalidTerminalPowBlock(pow_block.block_hash).into())
+    }
+    Ok(())
+}
 pub fn is_valid_genesis_state<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -1611,10 +2451,11 @@ pub fn slash_validator<
     >,
     slashed_index: ValidatorIndex,
     whistleblower_index: Option<ValidatorIndex>,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let epoch = get_current_epoch(state, context);
-    initiate_validator_exit(state, slashed_index, context);
+    initiate_validator_exit(state, slashed_index, observer, context);
     state.validators[slashed_index].slashed = true;
     state.validators[slashed_index].withdrawable_epoch = u64::max(
         state.validators[slashed_index].withdrawable_epoch,
@@ -1630,6 +2471,7 @@ pub fn slash_validator<
     );
     let proposer_index = get_beacon_proposer_index(state, context)?;
     let whistleblower_index = whistleblower_index.unwrap_or(proposer_index);
+    observer.on_event(&Event::ValidatorSlashed { index: slashed_index, whistleblower_index });
     let whistleblower_reward =
         state.validators[slashed_index].effective_balance / context.whistleblower_reward_quotient;
     let proposer_reward_scaling_factor = PROPOSER_WEIGHT / WEIGHT_DENOMINATOR;
@@ -2164,6 +3006,7 @@ pub fn is_valid_indexed_attestation<
         MAX_EXTRA_DATA_BYTES,
     >,
     indexed_attestation: &mut IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let attesting_indices = &indexed_attestation.attesting_indices;
@@ -2190,14 +3033,12 @@ pub fn is_valid_indexed_attestation<
             InvalidIndexedAttestation::DuplicateIndices(Vec::from_iter(duplicates)),
         )))
     }
-    let mut public_keys = vec![];
     for &index in &attesting_indices[..] {
-        let public_key = state.validators.get(index).map(|v| &v.public_key).ok_or_else(|| {
-            invalid_operation_error(InvalidOperation::IndexedAttestation(
+        if index >= state.validators.len() {
+            return Err(invalid_operation_error(InvalidOperation::IndexedAttestation(
                 InvalidIndexedAttestation::InvalidIndex(index),
-            ))
-        })?;
-        public_keys.push(public_key);
+            )))
+        }
     }
     let domain = get_domain(
         state,
@@ -2206,8 +3047,17 @@ pub fn is_valid_indexed_attestation<
         context,
     )?;
     let signing_root = compute_signing_root(&mut indexed_attestation.data, domain)?;
-    fast_aggregate_verify(&public_keys, signing_root.as_ref(), &indexed_attestation.signature)
-        .map_err(Into::into)
+    let signature_set = context.pubkey_cache.aggregate_signature_set(
+        &state.validators,
+        attesting_indices,
+        signing_root.as_ref(),
+        &indexed_attestation.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::IndexedAttestation(
+            InvalidIndexedAttestation::InvalidSignature(indexed_attestation.signature.clone()),
+        ))
+    })
 }
 pub fn verify_block_signature<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -2257,17 +3107,19 @@ pub fn verify_block_signature<
         MAX_WITHDRAWALS_PER_PAYLOAD,
         MAX_BLS_TO_EXECUTION_CHANGES,
     >,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let proposer_index = signed_block.message.proposer_index;
-    let proposer = state
-        .validators
-        .get(proposer_index)
-        .ok_or(Error::OutOfBounds { requested: proposer_index, bound: state.validators.len() })?;
     let domain = get_domain(state, DomainType::BeaconProposer, None, context)?;
     let signing_root = compute_signing_root(&mut signed_block.message, domain)?;
-    let public_key = &proposer.public_key;
-    verify_signature(public_key, signing_root.as_ref(), &signed_block.signature).map_err(Into::into)
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        proposer_index,
+        signing_root.as_ref(),
+        &signed_block.signature,
+    );
+    signatures.verify(signature_set, || crate::crypto::Error::InvalidSignature.into())
 }
 pub fn get_domain<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -2362,9 +3214,54 @@ pub fn compute_shuffled_index(
     }
     Ok(index)
 }
-pub fn compute_proposer_index<
-    const SLOTS_PER_HISTORICAL_ROOT: usize,
-    const HISTORICAL_ROOTS_LIMIT: usize,
+/// Shuffles the entire list of `indices` with the "swap-or-not" network so that
+/// `compute_shuffled_list(indices, seed, context)[i] ==
+/// indices[compute_shuffled_index(i, indices.len(), seed, context)]`.
+pub fn compute_shuffled_list(
+    indices: &[ValidatorIndex],
+    seed: &Bytes32,
+    context: &Context,
+) -> Vec<ValidatorIndex> {
+    let mut list = indices.to_vec();
+    let index_count = list.len();
+    if index_count < 2 {
+        return list
+    }
+    let mut pivot_input = [0u8; 33];
+    pivot_input[..32].copy_from_slice(seed.as_ref());
+    let mut source_input = [0u8; 37];
+    source_input[..32].copy_from_slice(seed.as_ref());
+    for current_round in (0..context.shuffle_round_count).rev() {
+        pivot_input[32] = current_round as u8;
+        let pivot_bytes: [u8; 8] = hash(pivot_input).as_ref()[..8].try_into().unwrap();
+        let pivot = (u64::from_le_bytes(pivot_bytes) as usize) % index_count;
+        source_input[32] = current_round as u8;
+        let mut source_position = None;
+        let mut source = Bytes32::default();
+        for index in 0..index_count {
+            let flip = (pivot + index_count - index) % index_count;
+            if index >= flip {
+                continue
+            }
+            let position = flip;
+            if source_position != Some(position / 256) {
+                let position_bytes: [u8; 4] = ((position / 256) as u32).to_le_bytes();
+                source_input[33..].copy_from_slice(&position_bytes);
+                source = hash(source_input);
+                source_position = Some(position / 256);
+            }
+            let byte = source.as_ref()[(position % 256) / 8];
+            let bit = (byte >> (position % 8)) % 2;
+            if bit != 0 {
+                list.swap(index, flip);
+            }
+        }
+    }
+    list
+}
+pub fn compute_proposer_index<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
     const ETH1_DATA_VOTES_BOUND: usize,
     const VALIDATOR_REGISTRY_LIMIT: usize,
     const EPOCHS_PER_HISTORICAL_VECTOR: usize,
@@ -2762,12 +3659,26 @@ pub fn get_beacon_committee<
     context: &Context,
 ) -> Result<Vec<ValidatorIndex>> {
     let epoch = compute_epoch_at_slot(slot, context);
-    let committees_per_slot = get_committee_count_per_slot(state, epoch, context);
-    let indices = get_active_validator_indices(state, epoch);
     let seed = get_seed(state, epoch, DomainType::BeaconAttester, context);
-    let index = (slot % context.slots_per_epoch) * committees_per_slot as u64 + index as u64;
-    let count = committees_per_slot as u64 * context.slots_per_epoch;
-    compute_committee(&indices, &seed, index as usize, count as usize, context)
+    let shuffled_indices = context.shuffling_cache.get_or_compute_shuffling(
+        epoch,
+        &seed,
+        || get_active_validator_indices(state, epoch),
+        context,
+    );
+    let active_validator_count = shuffled_indices.len();
+    let committees_per_slot = u64::max(
+        1,
+        u64::min(
+            context.max_committees_per_slot,
+            active_validator_count as u64 / context.slots_per_epoch / context.target_committee_size,
+        ),
+    );
+    let index = (slot % context.slots_per_epoch) * committees_per_slot + index as u64;
+    let count = committees_per_slot * context.slots_per_epoch;
+    let start = (active_validator_count * index as usize) / count as usize;
+    let end = active_validator_count * (index as usize + 1) / count as usize;
+    Ok(shuffled_indices[start..end].to_vec())
 }
 pub fn get_beacon_proposer_index<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -2801,8 +3712,12 @@ pub fn get_beacon_proposer_index<
         .copy_from_slice(get_seed(state, epoch, DomainType::BeaconProposer, context).as_ref());
     input[32..40].copy_from_slice(&state.slot.to_le_bytes());
     let seed = hash(input);
-    let indices = get_active_validator_indices(state, epoch);
-    compute_proposer_index(state, &indices, &seed, context)
+    context.shuffling_cache.get_or_compute_proposer(
+        &seed,
+        || get_active_validator_indices(state, epoch),
+        |index| state.validators[index].effective_balance,
+        context,
+    )
 }
 pub fn get_total_balance<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
@@ -3034,6 +3949,7 @@ pub fn initiate_validator_exit<
         MAX_EXTRA_DATA_BYTES,
     >,
     index: ValidatorIndex,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     if state.validators[index].exit_epoch != FAR_FUTURE_EPOCH {
@@ -3055,6 +3971,11 @@ pub fn initiate_validator_exit<
     state.validators[index].exit_epoch = exit_queue_epoch;
     state.validators[index].withdrawable_epoch =
         state.validators[index].exit_epoch + context.min_validator_withdrawability_delay;
+    observer.on_event(&Event::ValidatorExited {
+        index,
+        exit_epoch: exit_queue_epoch,
+        withdrawable_epoch: state.validators[index].withdrawable_epoch,
+    });
 }
 pub fn get_eligible_validator_indices<
     'a,
@@ -3094,46 +4015,462 @@ pub fn get_eligible_validator_indices<
         }
     })
 }
-pub fn process_slots<
-    const SLOTS_PER_HISTORICAL_ROOT: usize,
-    const HISTORICAL_ROOTS_LIMIT: usize,
-    const ETH1_DATA_VOTES_BOUND: usize,
-    const VALIDATOR_REGISTRY_LIMIT: usize,
-    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
-    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
-    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+pub fn compute_sync_committee_period_at_slot(slot: Slot, context: &Context) -> u64 {
+    compute_epoch_at_slot(slot, context) / context.epochs_per_sync_committee_period
+}
+pub fn get_subtree_index(generalized_index: usize) -> usize {
+    generalized_index % 2usize.pow(generalized_index.ilog2())
+}
+pub fn is_sync_committee_update<
     const SYNC_COMMITTEE_SIZE: usize,
     const BYTES_PER_LOGS_BLOOM: usize,
     const MAX_EXTRA_DATA_BYTES: usize,
 >(
-    state: &mut BeaconState<
-        SLOTS_PER_HISTORICAL_ROOT,
-        HISTORICAL_ROOTS_LIMIT,
-        ETH1_DATA_VOTES_BOUND,
-        VALIDATOR_REGISTRY_LIMIT,
-        EPOCHS_PER_HISTORICAL_VECTOR,
-        EPOCHS_PER_SLASHINGS_VECTOR,
-        MAX_VALIDATORS_PER_COMMITTEE,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> bool {
+    update.next_sync_committee_branch.iter().any(|node| node != &Bytes32::default())
+}
+pub fn is_finality_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> bool {
+    update.finality_branch.iter().any(|node| node != &Bytes32::default())
+}
+pub fn is_better_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    new_update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    old_update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    context: &Context,
+) -> bool {
+    let max_active_participants = new_update.sync_aggregate.sync_committee_bits.len();
+    let new_num_active_participants = new_update.sync_aggregate.sync_committee_bits.count_ones();
+    let old_num_active_participants = old_update.sync_aggregate.sync_committee_bits.count_ones();
+    let new_has_supermajority = new_num_active_participants * 3 >= max_active_participants * 2;
+    let old_has_supermajority = old_num_active_participants * 3 >= max_active_participants * 2;
+    if new_has_supermajority != old_has_supermajority {
+        return new_has_supermajority
+    }
+    if !new_has_supermajority && new_num_active_participants != old_num_active_participants {
+        return new_num_active_participants > old_num_active_participants
+    }
+    let new_has_relevant_sync_committee = is_sync_committee_update(new_update) &&
+        compute_sync_committee_period_at_slot(new_update.attested_header.beacon.slot, context) ==
+            compute_sync_committee_period_at_slot(new_update.signature_slot, context);
+    let old_has_relevant_sync_committee = is_sync_committee_update(old_update) &&
+        compute_sync_committee_period_at_slot(old_update.attested_header.beacon.slot, context) ==
+            compute_sync_committee_period_at_slot(old_update.signature_slot, context);
+    if new_has_relevant_sync_committee != old_has_relevant_sync_committee {
+        return new_has_relevant_sync_committee
+    }
+    let new_has_finality = is_finality_update(new_update);
+    let old_has_finality = is_finality_update(old_update);
+    if new_has_finality != old_has_finality {
+        return new_has_finality
+    }
+    if new_has_finality {
+        let new_has_sync_committee_finality = compute_sync_committee_period_at_slot(
+            new_update.finalized_header.beacon.slot,
+            context,
+        ) == compute_sync_committee_period_at_slot(
+            new_update.attested_header.beacon.slot,
+            context,
+        );
+        let old_has_sync_committee_finality = compute_sync_committee_period_at_slot(
+            old_update.finalized_header.beacon.slot,
+            context,
+        ) == compute_sync_committee_period_at_slot(
+            old_update.attested_header.beacon.slot,
+            context,
+        );
+        if new_has_sync_committee_finality != old_has_sync_committee_finality {
+            return new_has_sync_committee_finality
+        }
+    }
+    if new_num_active_participants != old_num_active_participants {
+        return new_num_active_participants > old_num_active_participants
+    }
+    if new_update.attested_header.beacon.slot != old_update.attested_header.beacon.slot {
+        return new_update.attested_header.beacon.slot < old_update.attested_header.beacon.slot
+    }
+    new_update.signature_slot < old_update.signature_slot
+}
+pub fn is_next_sync_committee_known<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> bool {
+    store.next_sync_committee != SyncCommittee::default()
+}
+pub fn get_safety_threshold<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> u64 {
+    cmp::max(store.previous_max_active_participants, store.current_max_active_participants) / 2
+}
+pub fn initialize_light_client_store<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    trusted_block_root: Root,
+    bootstrap: &LightClientBootstrap<
         SYNC_COMMITTEE_SIZE,
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
-    slot: Slot,
+    context: &Context,
+) -> Result<LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>> {
+    if !is_valid_light_client_header(&bootstrap.header, context) {
+        return Err(LightClientError::InvalidHeader(bootstrap.header.beacon.slot).into())
+    }
+    let header_root = bootstrap.header.beacon.clone().hash_tree_root()?;
+    if header_root != trusted_block_root {
+        return Err(LightClientError::UntrustedBootstrap {
+            provided: header_root,
+            expected: trusted_block_root,
+        }
+        .into())
+    }
+    let leaf = bootstrap.current_sync_committee.clone().hash_tree_root()?;
+    let branch = bootstrap
+        .current_sync_committee_branch
+        .iter()
+        .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+        .collect::<Vec<_>>();
+    let depth = CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2;
+    let index = get_subtree_index(CURRENT_SYNC_COMMITTEE_INDEX);
+    let root = &bootstrap.header.beacon.state_root;
+    if !is_valid_merkle_branch(&leaf, branch.iter(), depth, index, root) {
+        return Err(LightClientError::InvalidCurrentSyncCommitteeProof(*root).into())
+    }
+    Ok(LightClientStore {
+        finalized_header: bootstrap.header.clone(),
+        current_sync_committee: bootstrap.current_sync_committee.clone(),
+        next_sync_committee: SyncCommittee::default(),
+        best_valid_update: None,
+        optimistic_header: bootstrap.header.clone(),
+        previous_max_active_participants: 0,
+        current_max_active_participants: 0,
+    })
+}
+pub fn validate_light_client_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    current_slot: Slot,
+    genesis_validators_root: Root,
     context: &Context,
 ) -> Result<()> {
-    if state.slot >= slot {
-        return Err(Error::TransitionToPreviousSlot { requested: slot, current: state.slot })
+    let sync_aggregate = &update.sync_aggregate;
+    let participants = sync_aggregate.sync_committee_bits.count_ones();
+    if participants < context.min_sync_committee_participants {
+        return Err(LightClientError::InsufficientParticipation {
+            participants,
+            minimum: context.min_sync_committee_participants,
+        }
+        .into())
     }
-    while state.slot < slot {
-        process_slot(state, context)?;
-        if (state.slot + 1) % context.slots_per_epoch == 0 {
-            process_epoch(state, context)?;
+    if !is_valid_light_client_header(&update.attested_header, context) {
+        return Err(LightClientError::InvalidHeader(update.attested_header.beacon.slot).into())
+    }
+    let update_attested_slot = update.attested_header.beacon.slot;
+    let update_finalized_slot = update.finalized_header.beacon.slot;
+    if !(current_slot >= update.signature_slot &&
+        update.signature_slot > update_attested_slot &&
+        update_attested_slot >= update_finalized_slot)
+    {
+        return Err(LightClientError::InvalidSlots {
+            current_slot,
+            signature_slot: update.signature_slot,
+            attested_slot: update_attested_slot,
+            finalized_slot: update_finalized_slot,
         }
-        state.slot += 1;
+        .into())
+    }
+    let store_period =
+        compute_sync_committee_period_at_slot(store.finalized_header.beacon.slot, context);
+    let update_signature_period =
+        compute_sync_committee_period_at_slot(update.signature_slot, context);
+    let is_valid_signature_period = if is_next_sync_committee_known(store) {
+        update_signature_period == store_period || update_signature_period == store_period + 1
+    } else {
+        update_signature_period == store_period
+    };
+    if !is_valid_signature_period {
+        return Err(LightClientError::InvalidSignaturePeriod {
+            signature_period: update_signature_period,
+            store_period,
+        }
+        .into())
+    }
+    let update_attested_period =
+        compute_sync_committee_period_at_slot(update_attested_slot, context);
+    let update_has_next_sync_committee = !is_next_sync_committee_known(store) &&
+        is_sync_committee_update(update) &&
+        update_attested_period == store_period;
+    if !(update_attested_slot > store.finalized_header.beacon.slot ||
+        update_has_next_sync_committee)
+    {
+        return Err(LightClientError::IrrelevantUpdate {
+            attested_slot: update_attested_slot,
+            finalized_slot: store.finalized_header.beacon.slot,
+        }
+        .into())
+    }
+    if !is_finality_update(update) {
+        if update.finalized_header != LightClientHeader::default() {
+            return Err(LightClientError::UnexpectedFinalizedHeader.into())
+        }
+    } else {
+        let finalized_root = if update_finalized_slot == GENESIS_SLOT {
+            if update.finalized_header != LightClientHeader::default() {
+                return Err(LightClientError::UnexpectedFinalizedHeader.into())
+            }
+            Node::default()
+        } else {
+            if !is_valid_light_client_header(&update.finalized_header, context) {
+                return Err(LightClientError::InvalidHeader(update_finalized_slot).into())
+            }
+            update.finalized_header.beacon.clone().hash_tree_root()?
+        };
+        let branch = update
+            .finality_branch
+            .iter()
+            .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        let depth = FINALIZED_ROOT_INDEX_FLOOR_LOG_2;
+        let index = get_subtree_index(FINALIZED_ROOT_INDEX);
+        let root = &update.attested_header.beacon.state_root;
+        if !is_valid_merkle_branch(&finalized_root, branch.iter(), depth, index, root) {
+            return Err(LightClientError::InvalidFinalityProof(finalized_root).into())
+        }
+    }
+    if !is_sync_committee_update(update) {
+        if update.next_sync_committee != SyncCommittee::default() {
+            return Err(LightClientError::UnexpectedNextSyncCommittee.into())
+        }
+    } else {
+        if update_attested_period == store_period &&
+            is_next_sync_committee_known(store) &&
+            update.next_sync_committee != store.next_sync_committee
+        {
+            return Err(LightClientError::InconsistentNextSyncCommittee.into())
+        }
+        let leaf = update.next_sync_committee.clone().hash_tree_root()?;
+        let branch = update
+            .next_sync_committee_branch
+            .iter()
+            .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        let depth = NEXT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2;
+        let index = get_subtree_index(NEXT_SYNC_COMMITTEE_INDEX);
+        let root = &update.attested_header.beacon.state_root;
+        if !is_valid_merkle_branch(&leaf, branch.iter(), depth, index, root) {
+            return Err(LightClientError::InvalidNextSyncCommitteeProof.into())
+        }
+    }
+    let sync_committee = if update_signature_period == store_period {
+        &store.current_sync_committee
+    } else {
+        &store.next_sync_committee
+    };
+    let participant_public_keys =
+        zip(sync_committee.public_keys.iter(), sync_aggregate.sync_committee_bits.iter())
+            .filter_map(|(public_key, bit)| if *bit { Some(public_key) } else { None })
+            .collect::<Vec<_>>();
+    let fork_version_slot = cmp::max(update.signature_slot, 1) - 1;
+    let fork_version = context.fork_version_for(context.fork_for(fork_version_slot));
+    let domain = compute_domain(
+        DomainType::SyncCommittee,
+        Some(fork_version),
+        Some(genesis_validators_root),
+        context,
+    )?;
+    let signing_root = compute_signing_root(&mut update.attested_header.beacon.clone(), domain)?;
+    if eth_fast_aggregate_verify(
+        participant_public_keys.as_slice(),
+        signing_root.as_ref(),
+        &sync_aggregate.sync_committee_signature,
+    )
+    .is_err()
+    {
+        return Err(LightClientError::InvalidSignature {
+            signature: sync_aggregate.sync_committee_signature.clone(),
+            root: signing_root,
+        }
+        .into())
     }
     Ok(())
 }
-pub fn process_slot<
+pub fn apply_light_client_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    context: &Context,
+) -> Result<()> {
+    let store_period =
+        compute_sync_committee_period_at_slot(store.finalized_header.beacon.slot, context);
+    let update_finalized_period =
+        compute_sync_committee_period_at_slot(update.finalized_header.beacon.slot, context);
+    if !is_next_sync_committee_known(store) {
+        if update_finalized_period != store_period {
+            return Err(LightClientError::InvalidFinalizedPeriod {
+                finalized_period: update_finalized_period,
+                store_period,
+            }
+            .into())
+        }
+        store.next_sync_committee = update.next_sync_committee.clone();
+    } else if update_finalized_period == store_period + 1 {
+        store.current_sync_committee =
+            std::mem::replace(&mut store.next_sync_committee, update.next_sync_committee.clone());
+        store.previous_max_active_participants = store.current_max_active_participants;
+        store.current_max_active_participants = 0;
+    }
+    if update.finalized_header.beacon.slot > store.finalized_header.beacon.slot {
+        store.finalized_header = update.finalized_header.clone();
+        if store.finalized_header.beacon.slot > store.optimistic_header.beacon.slot {
+            store.optimistic_header = store.finalized_header.clone();
+        }
+    }
+    Ok(())
+}
+pub fn process_light_client_store_force_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    current_slot: Slot,
+    context: &Context,
+) -> Result<()> {
+    if current_slot > store.finalized_header.beacon.slot + context.update_timeout as Slot {
+        if let Some(mut best_valid_update) = store.best_valid_update.clone() {
+            if best_valid_update.finalized_header.beacon.slot <= store.finalized_header.beacon.slot
+            {
+                best_valid_update.finalized_header = best_valid_update.attested_header.clone();
+            }
+            apply_light_client_update(store, &best_valid_update, context)?;
+            store.best_valid_update = None;
+        }
+    }
+    Ok(())
+}
+pub fn process_light_client_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    validate_light_client_update(store, update, current_slot, genesis_validators_root, context)?;
+    let sync_committee_bits = &update.sync_aggregate.sync_committee_bits;
+    let participants = sync_committee_bits.count_ones() as u64;
+    let is_better = match store.best_valid_update.as_ref() {
+        Some(best_valid_update) => is_better_update(update, best_valid_update, context),
+        None => true,
+    };
+    if is_better {
+        store.best_valid_update = Some(update.clone());
+    }
+    store.current_max_active_participants =
+        cmp::max(store.current_max_active_participants, participants);
+    if participants > get_safety_threshold(store) &&
+        update.attested_header.beacon.slot > store.optimistic_header.beacon.slot
+    {
+        store.optimistic_header = update.attested_header.clone();
+    }
+    let update_has_finalized_next_sync_committee = !is_next_sync_committee_known(store) &&
+        is_sync_committee_update(update) &&
+        is_finality_update(update) &&
+        compute_sync_committee_period_at_slot(update.finalized_header.beacon.slot, context) ==
+            compute_sync_committee_period_at_slot(
+                update.attested_header.beacon.slot,
+                context,
+            );
+    if participants * 3 >= sync_committee_bits.len() as u64 * 2 &&
+        (update.finalized_header.beacon.slot > store.finalized_header.beacon.slot ||
+            update_has_finalized_next_sync_committee)
+    {
+        apply_light_client_update(store, update, context)?;
+        store.best_valid_update = None;
+    }
+    Ok(())
+}
+pub fn process_light_client_finality_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    finality_update: &LightClientFinalityUpdate<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    let update = LightClientUpdate {
+        attested_header: finality_update.attested_header.clone(),
+        next_sync_committee: SyncCommittee::default(),
+        next_sync_committee_branch: Default::default(),
+        finalized_header: finality_update.finalized_header.clone(),
+        finality_branch: finality_update.finality_branch.clone(),
+        sync_aggregate: finality_update.sync_aggregate.clone(),
+        signature_slot: finality_update.signature_slot,
+    };
+    process_light_client_update(store, &update, current_slot, genesis_validators_root, context)
+}
+pub fn process_light_client_optimistic_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    store: &mut LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    optimistic_update: &LightClientOptimisticUpdate<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    current_slot: Slot,
+    genesis_validators_root: Root,
+    context: &Context,
+) -> Result<()> {
+    let update = LightClientUpdate {
+        attested_header: optimistic_update.attested_header.clone(),
+        next_sync_committee: SyncCommittee::default(),
+        next_sync_committee_branch: Default::default(),
+        finalized_header: LightClientHeader::default(),
+        finality_branch: Default::default(),
+        sync_aggregate: optimistic_update.sync_aggregate.clone(),
+        signature_slot: optimistic_update.signature_slot,
+    };
+    process_light_client_update(store, &update, current_slot, genesis_validators_root, context)
+}
+pub fn get_latest_block_root<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
     const ETH1_DATA_VOTES_BOUND: usize,
@@ -3158,19 +4495,22 @@ pub fn process_slot<
         MAX_EXTRA_DATA_BYTES,
     >,
     context: &Context,
-) -> Result<()> {
-    let previous_state_root = state.hash_tree_root()?;
-    let root_index = state.slot % context.slots_per_historical_root;
-    state.state_roots[root_index as usize] = previous_state_root;
-    if state.latest_block_header.state_root == Root::default() {
-        state.latest_block_header.state_root = previous_state_root;
+) -> Result<Root> {
+    if compute_epoch_at_slot(state.slot, context) < context.altair_fork_epoch {
+        return Err(LightClientError::UnsupportedSlot(state.slot).into())
     }
-    let previous_block_root = state.latest_block_header.hash_tree_root()?;
-    let root_index = state.slot % context.slots_per_historical_root;
-    state.block_roots[root_index as usize] = previous_block_root;
-    Ok(())
+    if state.slot != state.latest_block_header.slot {
+        return Err(LightClientError::UnexpectedStateSlot {
+            state_slot: state.slot,
+            header_slot: state.latest_block_header.slot,
+        }
+        .into())
+    }
+    let mut header = state.latest_block_header.clone();
+    header.state_root = state.hash_tree_root()?;
+    Ok(header.hash_tree_root()?)
 }
-pub fn state_transition_block_in_slot<
+pub fn create_light_client_bootstrap<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
     const ETH1_DATA_VOTES_BOUND: usize,
@@ -3190,13 +4530,6 @@ pub fn state_transition_block_in_slot<
     const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
     const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
     const MAX_BLS_TO_EXECUTION_CHANGES: usize,
-    E: ExecutionEngine<
-        BYTES_PER_LOGS_BLOOM,
-        MAX_EXTRA_DATA_BYTES,
-        MAX_BYTES_PER_TRANSACTION,
-        MAX_TRANSACTIONS_PER_PAYLOAD,
-        MAX_WITHDRAWALS_PER_PAYLOAD,
-    >,
 >(
     state: &mut BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
@@ -3210,7 +4543,7 @@ pub fn state_transition_block_in_slot<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
-    signed_block: &mut SignedBeaconBlock<
+    block: &SignedBeaconBlock<
         MAX_PROPOSER_SLASHINGS,
         MAX_VALIDATORS_PER_COMMITTEE,
         MAX_ATTESTER_SLASHINGS,
@@ -3225,21 +4558,477 @@ pub fn state_transition_block_in_slot<
         MAX_WITHDRAWALS_PER_PAYLOAD,
         MAX_BLS_TO_EXECUTION_CHANGES,
     >,
-    execution_engine: &E,
-    validation: Validation,
     context: &Context,
-) -> Result<()> {
-    let validate_result = match validation {
-        Validation::Enabled => true,
-        Validation::Disabled => false,
-    };
-    if validate_result {
-        verify_block_signature(state, signed_block, context)?;
+) -> Result<LightClientBootstrap<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>> {
+    let expected = get_latest_block_root(state, context)?;
+    let block_root = block.message.clone().hash_tree_root()?;
+    if block_root != expected {
+        return Err(LightClientError::InconsistentBlock { provided: block_root, expected }.into())
     }
-    let block = &mut signed_block.message;
-    process_block(state, block, execution_engine, context)?;
-    if validate_result && block.state_root != state.hash_tree_root()? {
-        Err(Error::InvalidStateRoot)
+    let current_sync_committee_branch = compute_merkle_proof(state, CURRENT_SYNC_COMMITTEE_INDEX)?
+        .iter()
+        .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+        .collect::<Vec<_>>();
+    Ok(LightClientBootstrap {
+        header: block_to_light_client_header(block, context)?,
+        current_sync_committee: state.current_sync_committee.clone(),
+        current_sync_committee_branch: Vector::try_from(current_sync_committee_branch)
+            .map_err(|(_, err)| err)?,
+    })
+}
+pub fn create_light_client_update<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+    attested_state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    attested_block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+    finalized_block: Option<
+        &SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+        >,
+    >,
+    context: &Context,
+) -> Result<LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>> {
+    let sync_aggregate = &block.message.body.sync_aggregate;
+    let participants = sync_aggregate.sync_committee_bits.count_ones();
+    if participants < context.min_sync_committee_participants {
+        return Err(LightClientError::InsufficientParticipation {
+            participants,
+            minimum: context.min_sync_committee_participants,
+        }
+        .into())
+    }
+    let expected = get_latest_block_root(state, context)?;
+    let block_root = block.message.clone().hash_tree_root()?;
+    if block_root != expected {
+        return Err(LightClientError::InconsistentBlock { provided: block_root, expected }.into())
+    }
+    let update_signature_period =
+        compute_sync_committee_period_at_slot(block.message.slot, context);
+    let expected = get_latest_block_root(attested_state, context)?;
+    let attested_block_root = attested_block.message.clone().hash_tree_root()?;
+    if attested_block_root != expected {
+        return Err(
+            LightClientError::InconsistentBlock { provided: attested_block_root, expected }.into()
+        )
+    }
+    if attested_block_root != block.message.parent_root {
+        return Err(LightClientError::InconsistentAttestedBlock {
+            provided: attested_block_root,
+            expected: block.message.parent_root,
+        }
+        .into())
+    }
+    let update_attested_period =
+        compute_sync_committee_period_at_slot(attested_block.message.slot, context);
+    let mut update = LightClientUpdate {
+        attested_header: block_to_light_client_header(attested_block, context)?,
+        sync_aggregate: sync_aggregate.clone(),
+        signature_slot: block.message.slot,
+        ..Default::default()
+    };
+    if update_attested_period == update_signature_period {
+        let next_sync_committee_branch =
+            compute_merkle_proof(attested_state, NEXT_SYNC_COMMITTEE_INDEX)?
+                .iter()
+                .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+                .collect::<Vec<_>>();
+        update.next_sync_committee = attested_state.next_sync_committee.clone();
+        update.next_sync_committee_branch =
+            Vector::try_from(next_sync_committee_branch).map_err(|(_, err)| err)?;
+    }
+    if let Some(finalized_block) = finalized_block {
+        let expected = attested_state.finalized_checkpoint.root;
+        if finalized_block.message.slot != GENESIS_SLOT {
+            update.finalized_header = block_to_light_client_header(finalized_block, context)?;
+            let finalized_root = update.finalized_header.beacon.clone().hash_tree_root()?;
+            if finalized_root != expected {
+                return Err(LightClientError::InconsistentFinalizedBlock {
+                    provided: finalized_root,
+                    expected,
+                }
+                .into())
+            }
+        } else if expected != Root::default() {
+            return Err(LightClientError::InconsistentFinalizedBlock {
+                provided: Root::default(),
+                expected,
+            }
+            .into())
+        }
+        let finality_branch = compute_merkle_proof(attested_state, FINALIZED_ROOT_INDEX)?
+            .iter()
+            .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        update.finality_branch = Vector::try_from(finality_branch).map_err(|(_, err)| err)?;
+    }
+    Ok(update)
+}
+pub fn create_light_client_finality_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientFinalityUpdate {
+        attested_header: update.attested_header.clone(),
+        finalized_header: update.finalized_header.clone(),
+        finality_branch: update.finality_branch.clone(),
+        sync_aggregate: update.sync_aggregate.clone(),
+        signature_slot: update.signature_slot,
+    }
+}
+pub fn create_light_client_optimistic_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    update: &LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientOptimisticUpdate {
+        attested_header: update.attested_header.clone(),
+        sync_aggregate: update.sync_aggregate.clone(),
+        signature_slot: update.signature_slot,
+    }
+}
+pub fn process_best_light_client_update<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    best_updates: &mut BestLightClientUpdates<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    update: LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    context: &Context,
+) -> bool {
+    let period = compute_sync_committee_period_at_slot(update.attested_header.beacon.slot, context);
+    let is_better = match best_updates.updates.get(&period) {
+        Some(best_update) => is_better_update(&update, best_update, context),
+        None => true,
+    };
+    if is_better {
+        best_updates.updates.insert(period, update);
+    }
+    is_better
+}
+pub fn process_slots<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    if state.slot >= slot {
+        return Err(Error::TransitionToPreviousSlot { requested: slot, current: state.slot })
+    }
+    while state.slot < slot {
+        process_slot(state, context)?;
+        if (state.slot + 1) % context.slots_per_epoch == 0 {
+            process_epoch_single_pass(state, observer, context)?;
+        }
+        state.slot += 1;
+    }
+    Ok(())
+}
+pub fn process_slot<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    context: &Context,
+) -> Result<()> {
+    let previous_state_root = state.hash_tree_root()?;
+    process_slot_with_state_root(state, previous_state_root, context)
+}
+/// Performs `process_slot` with the known root of `state` as `previous_state_root`, rather than
+/// computing it.
+pub fn process_slot_with_state_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    previous_state_root: Root,
+    context: &Context,
+) -> Result<()> {
+    let root_index = state.slot % context.slots_per_historical_root;
+    state.state_roots[root_index as usize] = previous_state_root;
+    if state.latest_block_header.state_root == Root::default() {
+        state.latest_block_header.state_root = previous_state_root;
+    }
+    let previous_block_root = state.latest_block_header.hash_tree_root()?;
+    let root_index = state.slot % context.slots_per_historical_root;
+    state.block_roots[root_index as usize] = previous_block_root;
+    Ok(())
+}
+/// Performs `process_slots` with the known root of `state` as `state_root`, which saves hashing
+/// the state for the first slot processed.
+pub fn process_slots_with_state_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    state_root: Root,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    if state.slot >= slot {
+        return Err(Error::TransitionToPreviousSlot { requested: slot, current: state.slot })
+    }
+    process_slot_with_state_root(state, state_root, context)?;
+    if (state.slot + 1) % context.slots_per_epoch == 0 {
+        process_epoch_single_pass(state, observer, context)?;
+    }
+    state.slot += 1;
+    if state.slot < slot {
+        process_slots(state, slot, observer, context)?;
+    }
+    Ok(())
+}
+pub fn state_transition_block_in_slot<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    E: ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    signed_block: &mut SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+    >,
+    execution_engine: &E,
+    validation: Validation,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let mut signatures = SignatureSets::for_validation(&validation);
+    let result = if validation.signatures {
+        verify_block_signature(state, signed_block, &mut signatures, context)
+    } else {
+        Ok(())
+    };
+    let result = result.and_then(|_| {
+        process_block(
+            state,
+            &mut signed_block.message,
+            execution_engine,
+            validation,
+            &mut signatures,
+            observer,
+            context,
+        )
+    });
+    signatures.finish(result)?;
+    let block = &signed_block.message;
+    if validation.state_root && block.state_root != state.hash_tree_root()? {
+        Err(Error::InvalidStateRoot)
     } else {
         Ok(())
     }
@@ -3301,9 +5090,150 @@ pub fn state_transition<
     >,
     execution_engine: &E,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_slots(state, signed_block.message.slot, context)?;
-    state_transition_block_in_slot(state, signed_block, execution_engine, validation, context)
+    process_slots(state, signed_block.message.slot, observer, context)?;
+    state_transition_block_in_slot(
+        state,
+        signed_block,
+        execution_engine,
+        validation,
+        observer,
+        context,
+    )
+}
+pub fn is_sync_committee_aggregator(signature: &BlsSignature, context: &Context) -> bool {
+    let modulo = u64::max(
+        1,
+        (context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT) as u64 /
+            context.target_aggregators_per_sync_subcommittee,
+    );
+    let signature_hash: [u8; 8] = hash(signature.as_ref()).as_ref()[..8].try_into().unwrap();
+    u64::from_le_bytes(signature_hash) % modulo == 0
+}
+pub fn get_sync_subcommittee_pubkeys<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    subcommittee_index: u64,
+    context: &Context,
+) -> Vec<BlsPublicKey> {
+    let current_period =
+        compute_epoch_at_slot(state.slot, context) / context.epochs_per_sync_committee_period;
+    let next_slot_period =
+        compute_epoch_at_slot(state.slot + 1, context) / context.epochs_per_sync_committee_period;
+    let sync_committee = if current_period == next_slot_period {
+        &state.current_sync_committee
+    } else {
+        &state.next_sync_committee
+    };
+    let sync_subcommittee_size = SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT;
+    let start = subcommittee_index as usize * sync_subcommittee_size;
+    sync_committee.public_keys[start..start + sync_subcommittee_size].to_vec()
+}
+pub fn compute_subnets_for_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<HashSet<u64>> {
+    let target_public_key = &state
+        .validators
+        .get(validator_index)
+        .ok_or(Error::OutOfBounds { requested: validator_index, bound: state.validators.len() })?
+        .public_key;
+    let current_period =
+        compute_epoch_at_slot(state.slot, context) / context.epochs_per_sync_committee_period;
+    let next_slot_period =
+        compute_epoch_at_slot(state.slot + 1, context) / context.epochs_per_sync_committee_period;
+    let sync_committee = if current_period == next_slot_period {
+        &state.current_sync_committee
+    } else {
+        &state.next_sync_committee
+    };
+    let sync_subcommittee_size = SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT;
+    Ok(sync_committee
+        .public_keys
+        .iter()
+        .enumerate()
+        .filter(|(_, public_key)| *public_key == target_public_key)
+        .map(|(index, _)| (index / sync_subcommittee_size) as u64)
+        .collect())
+}
+pub fn is_aggregator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    index: CommitteeIndex,
+    slot_signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool> {
+    let committee = get_beacon_committee(state, slot, index, context)?;
+    let modulo = u64::max(1, committee.len() as u64 / context.target_aggregators_per_committee);
+    let signature_hash: [u8; 8] = hash(slot_signature.as_ref()).as_ref()[..8].try_into().unwrap();
+    Ok(u64::from_le_bytes(signature_hash) % modulo == 0)
 }
 pub use crate::capella::execution_engine::ExecutionEngine;
@@ -2,6 +2,7 @@
 use crate::serde::{try_bytes_from_hex_str, HexError};
 use crate::{primitives::Bytes32, ssz::prelude::*};
 use blst::{min_pk as bls_impl, BLST_ERROR};
+use rand::Rng;
 use sha2::{Digest, Sha256};
 use std::{
     fmt,
@@ -66,9 +67,17 @@ pub fn verify_signature(
     msg: &[u8],
     signature: &Signature,
 ) -> Result<(), Error> {
-    let public_key: bls_impl::PublicKey = public_key.try_into()?;
+    let public_key = DecompressedPublicKey::try_from(public_key)?;
+    verify_signature_decompressed(&public_key, msg, signature)
+}
+
+pub fn verify_signature_decompressed(
+    public_key: &DecompressedPublicKey,
+    msg: &[u8],
+    signature: &Signature,
+) -> Result<(), Error> {
     let signature: bls_impl::Signature = signature.try_into()?;
-    let res = signature.verify(true, msg, BLS_DST, &[], &public_key, true);
+    let res = signature.verify(true, msg, BLS_DST, &[], &public_key.0, true);
     if res == BLST_ERROR::BLST_SUCCESS {
         Ok(())
     } else {
@@ -119,9 +128,18 @@ pub fn fast_aggregate_verify(
     let public_keys = public_keys
         .iter()
         .cloned()
-        .map(bls_impl::PublicKey::try_from)
-        .collect::<Result<Vec<bls_impl::PublicKey>, Error>>()?;
-    let public_keys: Vec<&bls_impl::PublicKey> = public_keys.iter().collect();
+        .map(DecompressedPublicKey::try_from)
+        .collect::<Result<Vec<DecompressedPublicKey>, Error>>()?;
+    let public_keys: Vec<&DecompressedPublicKey> = public_keys.iter().collect();
+    fast_aggregate_verify_decompressed(&public_keys, msg, signature)
+}
+
+pub fn fast_aggregate_verify_decompressed(
+    public_keys: &[&DecompressedPublicKey],
+    msg: &[u8],
+    signature: &Signature,
+) -> Result<(), Error> {
+    let public_keys: Vec<&bls_impl::PublicKey> = public_keys.iter().map(|pk| &pk.0).collect();
     let signature: bls_impl::Signature = signature.try_into()?;
     let res = signature.fast_aggregate_verify(true, msg, BLS_DST, &public_keys);
     if res == BLST_ERROR::BLST_SUCCESS {
@@ -159,6 +177,133 @@ pub fn eth_fast_aggregate_verify(
     }
 }
 
+pub fn eth_fast_aggregate_verify_decompressed(
+    public_keys: &[&DecompressedPublicKey],
+    message: &[u8],
+    signature: &Signature,
+) -> Result<(), Error> {
+    if public_keys.is_empty() && signature.is_infinity() {
+        Ok(())
+    } else {
+        fast_aggregate_verify_decompressed(public_keys, message, signature)
+    }
+}
+
+/// A signature over `message` by the aggregate of `public_keys`, to be verified along with
+/// other sets by `verify_signature_sets`.
+///
+/// An empty set of `public_keys` is only valid with the infinity signature, following
+/// `eth_fast_aggregate_verify`.
+#[derive(Debug, Clone)]
+pub struct SignatureSet {
+    pub public_keys: Vec<DecompressedPublicKey>,
+    pub message: Bytes32,
+    pub signature: Signature,
+}
+
+impl SignatureSet {
+    pub fn new(
+        public_keys: Vec<DecompressedPublicKey>,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<Self, Error> {
+        let message =
+            Bytes32::try_from(message).map_err(|err| -> SimpleSerializeError { err.into() })?;
+        Ok(Self { public_keys, message, signature: signature.clone() })
+    }
+
+    pub fn from_public_key(
+        public_key: &PublicKey,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<Self, Error> {
+        let public_key = DecompressedPublicKey::try_from(public_key)?;
+        Self::new(vec![public_key], message, signature)
+    }
+
+    pub fn verify(&self) -> Result<(), Error> {
+        let public_keys = self.public_keys.iter().collect::<Vec<_>>();
+        eth_fast_aggregate_verify_decompressed(&public_keys, self.message.as_ref(), &self.signature)
+    }
+
+    fn aggregate_public_key(&self) -> Result<bls_impl::PublicKey, Error> {
+        let public_keys = self.public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>();
+        bls_impl::AggregatePublicKey::aggregate(&public_keys, false)
+            .map(|agg_pk| agg_pk.to_public_key())
+            .map_err(|e| BLSTError::from(e).into())
+    }
+}
+
+// Verify `sets` with a single multi-pairing, weighting each set by a random scalar so that
+// invalid signatures cannot cancel each other out.
+fn verify_signature_sets_inner(sets: &[&SignatureSet]) -> Result<(), Error> {
+    let mut rng = rand::thread_rng();
+    let mut public_keys = Vec::with_capacity(sets.len());
+    let mut messages = Vec::with_capacity(sets.len());
+    let mut signatures = Vec::with_capacity(sets.len());
+    let mut scalars = Vec::with_capacity(sets.len());
+    for set in sets {
+        if set.public_keys.is_empty() {
+            if set.signature.is_infinity() {
+                continue
+            }
+            return Err(Error::InvalidSignature)
+        }
+        public_keys.push(set.aggregate_public_key()?);
+        messages.push(set.message.as_ref());
+        signatures.push(bls_impl::Signature::try_from(&set.signature)?);
+        let mut scalar = blst::blst_scalar::default();
+        let mut randomness = 0u64;
+        while randomness == 0 {
+            randomness = rng.gen();
+        }
+        scalar.b[..8].copy_from_slice(&randomness.to_le_bytes());
+        scalars.push(scalar);
+    }
+    if signatures.is_empty() {
+        return Ok(())
+    }
+
+    let public_keys = public_keys.iter().collect::<Vec<_>>();
+    let signatures = signatures.iter().collect::<Vec<_>>();
+    let res = bls_impl::Signature::verify_multiple_aggregate_signatures(
+        &messages,
+        BLS_DST,
+        &public_keys,
+        false,
+        &signatures,
+        true,
+        &scalars,
+        64,
+    );
+    if res == BLST_ERROR::BLST_SUCCESS {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+/// Verifies all of `sets` at once. An error does not indicate which set is invalid so callers
+/// wanting to know should check each set with `SignatureSet::verify`.
+#[cfg(not(feature = "rayon"))]
+pub fn verify_signature_sets(sets: &[SignatureSet]) -> Result<(), Error> {
+    let sets = sets.iter().collect::<Vec<_>>();
+    verify_signature_sets_inner(&sets)
+}
+
+/// Verifies all of `sets` at once. An error does not indicate which set is invalid so callers
+/// wanting to know should check each set with `SignatureSet::verify`.
+#[cfg(feature = "rayon")]
+pub fn verify_signature_sets(sets: &[SignatureSet]) -> Result<(), Error> {
+    use rayon::prelude::*;
+
+    let chunk_size = (sets.len() / rayon::current_num_threads()).max(1);
+    sets.par_chunks(chunk_size).try_for_each(|chunk| {
+        let chunk = chunk.iter().collect::<Vec<_>>();
+        verify_signature_sets_inner(&chunk)
+    })
+}
+
 #[derive(Clone, Default, serde::Deserialize)]
 #[serde(try_from = "String")]
 pub struct SecretKey(bls_impl::SecretKey);
@@ -225,7 +370,15 @@ impl SecretKey {
 }
 
 #[derive(
-    Clone, Default, Hash, PartialEq, Eq, SimpleSerialize, serde::Serialize, serde::Deserialize,
+    Clone,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    SimpleSerialize,
+    Prove,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 pub struct PublicKey(ByteVector<BLS_PUBLIC_KEY_BYTES_LEN>);
 
@@ -275,8 +428,43 @@ impl TryFrom<&PublicKey> for bls_impl::PublicKey {
     }
 }
 
+// A `PublicKey` that has already been decompressed and validated so that it can be reused
+// across signature verifications without repeating that work.
+#[derive(Clone)]
+pub struct DecompressedPublicKey(bls_impl::PublicKey);
+
+impl fmt::Debug for DecompressedPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = PublicKey::from(self);
+        write!(f, "{inner:?}")
+    }
+}
+
+impl TryFrom<&PublicKey> for DecompressedPublicKey {
+    type Error = Error;
+
+    fn try_from(public_key: &PublicKey) -> Result<Self, Error> {
+        let inner = bls_impl::PublicKey::try_from(public_key)?;
+        Ok(Self(inner))
+    }
+}
+
+impl From<&DecompressedPublicKey> for PublicKey {
+    fn from(public_key: &DecompressedPublicKey) -> Self {
+        PublicKey::try_from(public_key.0.to_bytes().as_ref()).unwrap()
+    }
+}
+
 #[derive(
-    Clone, Default, Hash, PartialEq, Eq, SimpleSerialize, serde::Serialize, serde::Deserialize,
+    Clone,
+    Default,
+    Hash,
+    PartialEq,
+    Eq,
+    SimpleSerialize,
+    Prove,
+    serde::Serialize,
+    serde::Deserialize,
 )]
 pub struct Signature(ByteVector<BLS_SIGNATURE_BYTES_LEN>);
 
@@ -334,6 +522,11 @@ const INFINITY_COMPRESSED_SIGNATURE: [u8; BLS_SIGNATURE_BYTES_LEN] = [
 ];
 
 impl Signature {
+    /// Returns the signature of an empty set of signers, i.e. the point at infinity.
+    pub fn infinity() -> Self {
+        Self::try_from(INFINITY_COMPRESSED_SIGNATURE.as_ref()).expect("is valid length")
+    }
+
     pub fn is_infinity(&self) -> bool {
         self.as_ref() == INFINITY_COMPRESSED_SIGNATURE
     }
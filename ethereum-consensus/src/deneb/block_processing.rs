@@ -17,9 +17,9 @@ use crate::{
         InvalidVoluntaryExit,
     },
     primitives::FAR_FUTURE_EPOCH,
-    signing::verify_signed_data,
+    signing::compute_signing_root,
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{Context, Observer, Result, SignatureSets, Validation},
 };
 
 pub fn process_attestation<
@@ -47,6 +47,7 @@ pub fn process_attestation<
         MAX_EXTRA_DATA_BYTES,
     >,
     attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let data = &attestation.data;
@@ -103,6 +104,7 @@ pub fn process_attestation<
     is_valid_indexed_attestation(
         state,
         &mut get_indexed_attestation(state, attestation, context)?,
+        signatures,
         context,
     )?;
     let attesting_indices =
@@ -192,6 +194,7 @@ pub fn process_execution_payload<
         MAX_BLOB_COMMITMENTS_PER_BLOCK,
     >,
     execution_engine: &E,
+    validation: Validation,
     context: &Context,
 ) -> Result<()> {
     let payload = &mut body.execution_payload;
@@ -231,7 +234,7 @@ pub fn process_execution_payload<
         ))
     }
 
-    if body.blob_kzg_commitments.len() > context.max_blobs_per_block {
+    if validation.kzg_commitments && body.blob_kzg_commitments.len() > context.max_blobs_per_block {
         return Err(invalid_operation_error(
             InvalidExecutionPayload::InvalidBlobCommitments {
                 provided: body.blob_kzg_commitments.len(),
@@ -241,15 +244,20 @@ pub fn process_execution_payload<
         ))
     }
 
-    let versioned_hashes =
-        body.blob_kzg_commitments.iter().map(kzg_commitment_to_versioned_hash).collect::<Vec<_>>();
+    if validation.execution_payload {
+        let versioned_hashes = body
+            .blob_kzg_commitments
+            .iter()
+            .map(kzg_commitment_to_versioned_hash)
+            .collect::<Vec<_>>();
 
-    let new_payload_request = NewPayloadRequest {
-        execution_payload: &*payload,
-        versioned_hashes: &versioned_hashes,
-        parent_beacon_block_root: state.latest_block_header.parent_root,
-    };
-    execution_engine.verify_and_notify_new_payload(&new_payload_request)?;
+        let new_payload_request = NewPayloadRequest {
+            execution_payload: &*payload,
+            versioned_hashes: &versioned_hashes,
+            parent_beacon_block_root: state.latest_block_header.parent_root,
+        };
+        execution_engine.verify_and_notify_new_payload(&new_payload_request)?;
+    }
 
     state.latest_execution_payload_header = ExecutionPayloadHeader {
         parent_hash: payload.parent_hash.clone(),
@@ -299,6 +307,8 @@ pub fn process_voluntary_exit<
         MAX_EXTRA_DATA_BYTES,
     >,
     signed_voluntary_exit: &mut SignedVoluntaryExit,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let voluntary_exit = &mut signed_voluntary_exit.message;
@@ -342,14 +352,19 @@ pub fn process_voluntary_exit<
         Some(state.genesis_validators_root),
         context,
     )?;
-    let public_key = &validator.public_key;
-    verify_signed_data(voluntary_exit, &signed_voluntary_exit.signature, public_key, domain)
-        .map_err(|_| {
-            invalid_operation_error(InvalidOperation::VoluntaryExit(
-                InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
-            ))
-        })?;
-    initiate_validator_exit(state, voluntary_exit.validator_index, context);
+    let signing_root = compute_signing_root(voluntary_exit, domain)?;
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        voluntary_exit.validator_index,
+        signing_root.as_ref(),
+        &signed_voluntary_exit.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::VoluntaryExit(
+            InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
+        ))
+    })?;
+    initiate_validator_exit(state, voluntary_exit.validator_index, observer, context);
     Ok(())
 }
 
@@ -411,14 +426,17 @@ pub fn process_block<
         MAX_BLOB_COMMITMENTS_PER_BLOCK,
     >,
     execution_engine: &E,
+    validation: Validation,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     process_block_header(state, block, context)?;
-    process_withdrawals(state, &block.body.execution_payload, context)?;
-    process_execution_payload(state, &mut block.body, execution_engine, context)?;
-    process_randao(state, &block.body, context)?;
+    process_withdrawals(state, &block.body.execution_payload, observer, context)?;
+    process_execution_payload(state, &mut block.body, execution_engine, validation, context)?;
+    process_randao(state, &block.body, signatures, context)?;
     process_eth1_data(state, &block.body, context);
-    process_operations(state, &mut block.body, context)?;
-    process_sync_aggregate(state, &block.body.sync_aggregate, context)?;
+    process_operations(state, &mut block.body, signatures, observer, context)?;
+    process_sync_aggregate(state, &block.body.sync_aggregate, signatures, context)?;
     Ok(())
 }
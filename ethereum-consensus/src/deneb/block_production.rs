@@ -0,0 +1,368 @@
+use crate::{
+    deneb::{
+        compute_timestamp_at_slot, get_beacon_proposer_index, get_current_epoch,
+        get_expected_withdrawals, get_randao_mix, polynomial_commitments::KzgCommitment,
+        process_block_header, process_eth1_data, process_execution_payload, process_randao,
+        process_slots, process_withdrawals, select_operations, select_sync_aggregate, BeaconBlock,
+        BeaconState, BlindedBeaconBlock, BlindedBeaconBlockBody, BlockOperations,
+        DefaultExecutionEngine, ExecutionPayload, ExecutionPayloadHeader,
+    },
+    error::{
+        invalid_operation_error, InvalidExecutionPayload, InvalidOperation, InvalidWithdrawals,
+    },
+    primitives::{BlsSignature, Bytes32, Root, Slot},
+    ssz::prelude::*,
+    state_transition::{Context, Result, SignatureSets, Validation},
+};
+
+/// Produces an unsigned block for `slot` on top of `state` with the given `execution_payload` and
+/// its `blob_kzg_commitments`, including as many of the candidate `operations` as are valid. The
+/// `eth1_data` of the block is the vote in `operations`, if any, or repeats the current vote of
+/// `state`.
+///
+/// The signature of each candidate is verified so that candidates with invalid signatures are
+/// skipped. The `execution_payload` is expected to come from the execution engine and is not sent
+/// back to it.
+#[allow(clippy::too_many_arguments)]
+pub fn produce_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    execution_payload: ExecutionPayload<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+    blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+> {
+    let mut state = state.clone();
+    if state.slot < slot {
+        process_slots(&mut state, slot, &mut (), context)?;
+    }
+
+    let mut block = BeaconBlock {
+        slot,
+        proposer_index: get_beacon_proposer_index(&state, context)?,
+        parent_root: state.latest_block_header.hash_tree_root()?,
+        ..Default::default()
+    };
+    block.body.randao_reveal = randao_reveal;
+    block.body.eth1_data = operations.eth1_data.clone().unwrap_or_else(|| state.eth1_data.clone());
+    block.body.graffiti = graffiti;
+    block.body.execution_payload = execution_payload;
+    block.body.blob_kzg_commitments = blob_kzg_commitments;
+
+    let signatures = &mut SignatureSets::default();
+    let validation = Validation { execution_payload: false, ..Validation::enabled() };
+    process_block_header(&mut state, &mut block, context)?;
+    process_withdrawals(&mut state, &block.body.execution_payload, &mut (), context)?;
+    process_execution_payload(
+        &mut state,
+        &mut block.body,
+        &DefaultExecutionEngine::default(),
+        validation,
+        context,
+    )?;
+    process_randao(&mut state, &block.body, signatures, context)?;
+    process_eth1_data(&mut state, &block.body, context);
+    select_operations(&mut state, &mut block.body, operations, signatures, context)?;
+    block.body.sync_aggregate = select_sync_aggregate(
+        &mut state,
+        &operations.sync_committee_contributions,
+        signatures,
+        context,
+    )?;
+
+    // NOTE: the header was processed before the operations were selected
+    state.latest_block_header.body_root = block.body.hash_tree_root()?;
+    block.state_root = state.hash_tree_root()?;
+    Ok(block)
+}
+
+fn process_execution_payload_header<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    header: &ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    withdrawals_root: Root,
+    blob_kzg_commitments_count: usize,
+    context: &Context,
+) -> Result<()> {
+    if header.withdrawals_root != withdrawals_root {
+        return Err(invalid_operation_error(InvalidOperation::Withdrawal(
+            InvalidWithdrawals::IncorrectWithdrawalsRoot {
+                provided: header.withdrawals_root,
+                expected: withdrawals_root,
+            },
+        )))
+    }
+
+    if header.parent_hash != state.latest_execution_payload_header.block_hash {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidParentHash {
+                provided: header.parent_hash.clone(),
+                expected: state.latest_execution_payload_header.block_hash.clone(),
+            }
+            .into(),
+        ))
+    }
+
+    let current_epoch = get_current_epoch(state, context);
+    let randao_mix = get_randao_mix(state, current_epoch);
+    if &header.prev_randao != randao_mix {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidPrevRandao {
+                provided: header.prev_randao.clone(),
+                expected: randao_mix.clone(),
+            }
+            .into(),
+        ))
+    }
+
+    let timestamp = compute_timestamp_at_slot(state, state.slot, context)?;
+    if header.timestamp != timestamp {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidTimestamp {
+                provided: header.timestamp,
+                expected: timestamp,
+            }
+            .into(),
+        ))
+    }
+
+    if blob_kzg_commitments_count > context.max_blobs_per_block {
+        return Err(invalid_operation_error(
+            InvalidExecutionPayload::InvalidBlobCommitments {
+                provided: blob_kzg_commitments_count,
+                limit: context.max_blobs_per_block,
+            }
+            .into(),
+        ))
+    }
+
+    state.latest_execution_payload_header = header.clone();
+    Ok(())
+}
+
+/// Produces an unsigned blinded block for `slot` on top of `state` with the given
+/// `execution_payload_header` and the `blob_kzg_commitments` of its payload, including as many of
+/// the candidate `operations` as are valid.
+///
+/// The header is checked against `state` as its payload would be in `produce_block`, and its
+/// `withdrawals_root` against the expected withdrawals. The bounds of the transactions and
+/// withdrawals of the payload are not part of the block and must be given explicitly.
+#[allow(clippy::too_many_arguments)]
+pub fn produce_blinded_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    execution_payload_header: ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    blob_kzg_commitments: List<KzgCommitment, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    context: &Context,
+) -> Result<
+    BlindedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+> {
+    let mut state = state.clone();
+    if state.slot < slot {
+        process_slots(&mut state, slot, &mut (), context)?;
+    }
+
+    // NOTE: operations are selected into a full block whose payload only has the withdrawals
+    let mut block = BeaconBlock::<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    > {
+        slot,
+        proposer_index: get_beacon_proposer_index(&state, context)?,
+        parent_root: state.latest_block_header.hash_tree_root()?,
+        ..Default::default()
+    };
+    block.body.randao_reveal = randao_reveal;
+    block.body.eth1_data = operations.eth1_data.clone().unwrap_or_else(|| state.eth1_data.clone());
+    block.body.graffiti = graffiti;
+    block.body.execution_payload.withdrawals =
+        List::try_from(get_expected_withdrawals(&state, context)).map_err(|(_, err)| err)?;
+    block.body.blob_kzg_commitments = blob_kzg_commitments;
+
+    let signatures = &mut SignatureSets::default();
+    process_block_header(&mut state, &mut block, context)?;
+    process_withdrawals(&mut state, &block.body.execution_payload, &mut (), context)?;
+    let withdrawals_root = block.body.execution_payload.withdrawals.hash_tree_root()?;
+    process_execution_payload_header(
+        &mut state,
+        &execution_payload_header,
+        withdrawals_root,
+        block.body.blob_kzg_commitments.len(),
+        context,
+    )?;
+    process_randao(&mut state, &block.body, signatures, context)?;
+    process_eth1_data(&mut state, &block.body, context);
+    select_operations(&mut state, &mut block.body, operations, signatures, context)?;
+    block.body.sync_aggregate = select_sync_aggregate(
+        &mut state,
+        &operations.sync_committee_contributions,
+        signatures,
+        context,
+    )?;
+
+    let body = block.body;
+    let mut block = BlindedBeaconBlock {
+        slot: block.slot,
+        proposer_index: block.proposer_index,
+        parent_root: block.parent_root,
+        state_root: Default::default(),
+        body: BlindedBeaconBlockBody {
+            randao_reveal: body.randao_reveal,
+            eth1_data: body.eth1_data,
+            graffiti: body.graffiti,
+            proposer_slashings: body.proposer_slashings,
+            attester_slashings: body.attester_slashings,
+            attestations: body.attestations,
+            deposits: body.deposits,
+            voluntary_exits: body.voluntary_exits,
+            sync_aggregate: body.sync_aggregate,
+            execution_payload_header,
+            bls_to_execution_changes: body.bls_to_execution_changes,
+            blob_kzg_commitments: body.blob_kzg_commitments,
+        },
+    };
+
+    // NOTE: the header was processed before the operations were selected
+    state.latest_block_header.body_root = block.body.hash_tree_root()?;
+    block.state_root = state.hash_tree_root()?;
+    Ok(block)
+}
@@ -1,11 +1,14 @@
 use crate::{
     deneb::{
-        compute_activation_exit_epoch, get_current_epoch, get_validator_activation_churn_limit,
-        initiate_validator_exit, is_active_validator, is_eligible_for_activation,
-        is_eligible_for_activation_queue, BeaconState,
+        compute_activation_exit_epoch, get_current_epoch, get_progressive_balances,
+        get_validator_activation_churn_limit, initiate_validator_exit, is_active_validator,
+        is_eligible_for_activation, is_eligible_for_activation_queue, process_eth1_data_reset,
+        process_historical_summaries_update, process_justification_and_finalization_with_balances,
+        process_participation_flag_updates, process_randao_mixes_reset, process_single_pass,
+        process_slashings_reset, process_sync_committee_updates, BeaconState, SinglePassParameters,
     },
     primitives::ValidatorIndex,
-    state_transition::Context,
+    state_transition::{Context, Event, Observer, Result},
 };
 
 pub fn process_registry_updates<
@@ -32,6 +35,7 @@ pub fn process_registry_updates<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     let current_epoch = get_current_epoch(state, context);
@@ -43,7 +47,7 @@ pub fn process_registry_updates<
         if is_active_validator(validator, current_epoch) &&
             validator.effective_balance <= context.ejection_balance
         {
-            initiate_validator_exit(state, i, context);
+            initiate_validator_exit(state, i, observer, context);
         }
     }
     let mut activation_queue =
@@ -69,5 +73,57 @@ pub fn process_registry_updates<
     {
         let validator = &mut state.validators[i];
         validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
     }
 }
+
+/// An optimized `process_epoch` which visits each validator once for the inactivity updates,
+/// rewards and penalties, registry updates, slashings and effective balance updates.
+///
+/// The result is identical to `process_epoch`, which is kept for differential testing.
+pub fn process_epoch_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let balances = get_progressive_balances(state, context)?;
+    process_justification_and_finalization_with_balances(state, &balances, observer, context)?;
+    let parameters = SinglePassParameters {
+        inactivity_penalty_quotient: context.inactivity_penalty_quotient_bellatrix,
+        proportional_slashing_multiplier: context.proportional_slashing_multiplier_bellatrix,
+        activation_churn_limit: get_validator_activation_churn_limit(state, context),
+    };
+    process_single_pass(state, &balances, &parameters, observer, context)?;
+    process_eth1_data_reset(state, context);
+    process_slashings_reset(state, context);
+    process_randao_mixes_reset(state, context);
+    process_historical_summaries_update(state, context)?;
+    process_participation_flag_updates(state)?;
+    process_sync_committee_updates(state, observer, context)?;
+    Ok(())
+}
@@ -1,6 +1,12 @@
 use crate::{
     capella,
-    deneb::{BeaconState, ExecutionPayloadHeader, Fork},
+    deneb::{
+        light_client::{
+            LightClientBootstrap, LightClientFinalityUpdate, LightClientHeader,
+            LightClientOptimisticUpdate, LightClientStore, LightClientUpdate,
+        },
+        BeaconState, ExecutionPayloadHeader, Fork,
+    },
     state_transition::Context,
 };
 
@@ -97,3 +103,234 @@ pub fn upgrade_to_deneb<
         historical_summaries: state.historical_summaries.clone(),
     }
 }
+
+pub fn upgrade_lc_header_to_deneb<
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &capella::light_client::LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+) -> LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    let execution = &pre.execution;
+    LightClientHeader {
+        beacon: pre.beacon.clone(),
+        execution: ExecutionPayloadHeader {
+            parent_hash: execution.parent_hash.clone(),
+            fee_recipient: execution.fee_recipient.clone(),
+            state_root: execution.state_root.clone(),
+            receipts_root: execution.receipts_root.clone(),
+            logs_bloom: execution.logs_bloom.clone(),
+            prev_randao: execution.prev_randao.clone(),
+            block_number: execution.block_number,
+            gas_limit: execution.gas_limit,
+            gas_used: execution.gas_used,
+            timestamp: execution.timestamp,
+            extra_data: execution.extra_data.clone(),
+            base_fee_per_gas: execution.base_fee_per_gas.clone(),
+            block_hash: execution.block_hash.clone(),
+            transactions_root: execution.transactions_root,
+            withdrawals_root: execution.withdrawals_root,
+            blob_gas_used: 0,
+            excess_blob_gas: 0,
+        },
+        execution_branch: pre.execution_branch.clone(),
+    }
+}
+
+pub fn upgrade_lc_bootstrap_to_deneb<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &capella::light_client::LightClientBootstrap<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> LightClientBootstrap<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientBootstrap {
+        header: upgrade_lc_header_to_deneb(&pre.header),
+        current_sync_committee: pre.current_sync_committee.clone(),
+        current_sync_committee_branch: pre.current_sync_committee_branch.clone(),
+    }
+}
+
+pub fn upgrade_lc_update_to_deneb<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &capella::light_client::LightClientUpdate<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientUpdate {
+        attested_header: upgrade_lc_header_to_deneb(&pre.attested_header),
+        next_sync_committee: pre.next_sync_committee.clone(),
+        next_sync_committee_branch: pre.next_sync_committee_branch.clone(),
+        finalized_header: upgrade_lc_header_to_deneb(&pre.finalized_header),
+        finality_branch: pre.finality_branch.clone(),
+        sync_aggregate: pre.sync_aggregate.clone(),
+        signature_slot: pre.signature_slot,
+    }
+}
+
+pub fn upgrade_lc_finality_update_to_deneb<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &capella::light_client::LightClientFinalityUpdate<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> LightClientFinalityUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientFinalityUpdate {
+        attested_header: upgrade_lc_header_to_deneb(&pre.attested_header),
+        finalized_header: upgrade_lc_header_to_deneb(&pre.finalized_header),
+        finality_branch: pre.finality_branch.clone(),
+        sync_aggregate: pre.sync_aggregate.clone(),
+        signature_slot: pre.signature_slot,
+    }
+}
+
+pub fn upgrade_lc_optimistic_update_to_deneb<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &capella::light_client::LightClientOptimisticUpdate<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> LightClientOptimisticUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientOptimisticUpdate {
+        attested_header: upgrade_lc_header_to_deneb(&pre.attested_header),
+        sync_aggregate: pre.sync_aggregate.clone(),
+        signature_slot: pre.signature_slot,
+    }
+}
+
+pub fn upgrade_lc_store_to_deneb<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    pre: &capella::light_client::LightClientStore<
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> LightClientStore<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES> {
+    LightClientStore {
+        finalized_header: upgrade_lc_header_to_deneb(&pre.finalized_header),
+        current_sync_committee: pre.current_sync_committee.clone(),
+        next_sync_committee: pre.next_sync_committee.clone(),
+        best_valid_update: pre.best_valid_update.as_ref().map(upgrade_lc_update_to_deneb),
+        optimistic_header: upgrade_lc_header_to_deneb(&pre.optimistic_header),
+        previous_max_active_participants: pre.previous_max_active_participants,
+        current_max_active_participants: pre.current_max_active_participants,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        deneb::{
+            initialize_light_client_store, is_valid_light_client_header,
+            process_light_client_finality_update, process_light_client_optimistic_update,
+            process_light_client_update,
+        },
+        harness::minimal::Harness,
+        primitives::Root,
+    };
+
+    #[test]
+    fn test_upgraded_light_client_data_is_processed() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        context.bellatrix_fork_epoch = 0;
+        context.capella_fork_epoch = 0;
+        let mut harness = Harness::new(64, context).unwrap();
+        let roots = harness.extend_chain(3).unwrap();
+        let context = harness.context();
+        let state = |root: &Root| harness.state(root).unwrap().capella().unwrap().clone();
+        let block = |root: &Root| harness.block(root).unwrap().capella().unwrap().clone();
+
+        let mut bootstrap_state = state(&roots[0]);
+        let genesis_validators_root = bootstrap_state.genesis_validators_root;
+        let bootstrap = capella::create_light_client_bootstrap(
+            &mut bootstrap_state,
+            &block(&roots[0]),
+            context,
+        )
+        .unwrap();
+        let update = capella::create_light_client_update(
+            &mut state(&roots[2]),
+            &block(&roots[2]),
+            &mut state(&roots[1]),
+            &block(&roots[1]),
+            Some(&capella::SignedBeaconBlock::default()),
+            context,
+        )
+        .unwrap();
+        let finality_update = capella::create_light_client_finality_update(&update);
+        let optimistic_update = capella::create_light_client_optimistic_update(&update);
+
+        let mut capella_store =
+            capella::initialize_light_client_store(roots[0], &bootstrap, context).unwrap();
+        capella::process_light_client_update(
+            &mut capella_store,
+            &update,
+            3,
+            genesis_validators_root,
+            context,
+        )
+        .unwrap();
+
+        // data produced before the fork is still verified after upgrading it, as the execution
+        // root of a pre-deneb header is computed over the capella execution payload header
+        let bootstrap = upgrade_lc_bootstrap_to_deneb(&bootstrap);
+        let update = upgrade_lc_update_to_deneb(&update);
+        assert!(is_valid_light_client_header(&update.attested_header, context));
+        assert_eq!(update.attested_header.beacon.clone().hash_tree_root().unwrap(), roots[1]);
+        let mut store = initialize_light_client_store(roots[0], &bootstrap, context).unwrap();
+        process_light_client_update(&mut store, &update, 3, genesis_validators_root, context)
+            .unwrap();
+        process_light_client_finality_update(
+            &mut store,
+            &upgrade_lc_finality_update_to_deneb(&finality_update),
+            3,
+            genesis_validators_root,
+            context,
+        )
+        .unwrap();
+        process_light_client_optimistic_update(
+            &mut store,
+            &upgrade_lc_optimistic_update_to_deneb(&optimistic_update),
+            3,
+            genesis_validators_root,
+            context,
+        )
+        .unwrap();
+
+        // a pre-deneb header must not carry blob gas
+        let mut invalid_header = update.attested_header.clone();
+        invalid_header.execution.blob_gas_used = 1;
+        assert!(!is_valid_light_client_header(&invalid_header, context));
+
+        let upgraded_store = upgrade_lc_store_to_deneb(&capella_store);
+        assert_eq!(upgraded_store.finalized_header, store.finalized_header);
+        assert_eq!(upgraded_store.optimistic_header, store.optimistic_header);
+        assert_eq!(upgraded_store.current_sync_committee, store.current_sync_committee);
+        assert_eq!(upgraded_store.next_sync_committee, store.next_sync_committee);
+        assert_eq!(
+            upgraded_store.current_max_active_participants,
+            store.current_max_active_participants
+        );
+    }
+}
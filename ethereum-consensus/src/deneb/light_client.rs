@@ -0,0 +1,309 @@
+use crate::{
+    altair::{
+        light_client::{
+            get_subtree_index, CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2,
+            FINALIZED_ROOT_INDEX_FLOOR_LOG_2, NEXT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2,
+        },
+        sync::{SyncAggregate, SyncCommittee},
+    },
+    capella::{
+        self,
+        light_client::{EXECUTION_PAYLOAD_INDEX, EXECUTION_PAYLOAD_INDEX_FLOOR_LOG_2},
+    },
+    deneb::{compute_epoch_at_slot, BeaconBlockHeader, ExecutionPayloadHeader, SignedBeaconBlock},
+    primitives::{Bytes32, Root, Slot},
+    ssz::prelude::*,
+    state_transition::{Context, Result},
+};
+use std::collections::HashMap;
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct LightClientHeader<const BYTES_PER_LOGS_BLOOM: usize, const MAX_EXTRA_DATA_BYTES: usize> {
+    pub beacon: BeaconBlockHeader,
+    pub execution: ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub execution_branch: Vector<Bytes32, EXECUTION_PAYLOAD_INDEX_FLOOR_LOG_2>,
+}
+
+#[derive(Default, Debug, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
+pub struct LightClientBootstrap<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> {
+    pub header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub current_sync_committee: SyncCommittee<SYNC_COMMITTEE_SIZE>,
+    pub current_sync_committee_branch: Vector<Bytes32, CURRENT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2>,
+}
+
+#[derive(Default, Debug, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
+pub struct LightClientUpdate<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> {
+    pub attested_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub next_sync_committee: SyncCommittee<SYNC_COMMITTEE_SIZE>,
+    pub next_sync_committee_branch: Vector<Bytes32, NEXT_SYNC_COMMITTEE_INDEX_FLOOR_LOG_2>,
+    pub finalized_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub finality_branch: Vector<Bytes32, FINALIZED_ROOT_INDEX_FLOOR_LOG_2>,
+    pub sync_aggregate: SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    pub signature_slot: Slot,
+}
+
+#[derive(Default, Debug, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
+pub struct LightClientFinalityUpdate<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> {
+    pub attested_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub finalized_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub finality_branch: Vector<Bytes32, FINALIZED_ROOT_INDEX_FLOOR_LOG_2>,
+    pub sync_aggregate: SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    pub signature_slot: Slot,
+}
+
+#[derive(Default, Debug, Clone, SimpleSerialize, serde::Serialize, serde::Deserialize)]
+pub struct LightClientOptimisticUpdate<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> {
+    pub attested_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub sync_aggregate: SyncAggregate<SYNC_COMMITTEE_SIZE>,
+    pub signature_slot: Slot,
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct LightClientStore<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> {
+    pub finalized_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub current_sync_committee: SyncCommittee<SYNC_COMMITTEE_SIZE>,
+    pub next_sync_committee: SyncCommittee<SYNC_COMMITTEE_SIZE>,
+    pub best_valid_update:
+        Option<LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>>,
+    pub optimistic_header: LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    pub previous_max_active_participants: u64,
+    pub current_max_active_participants: u64,
+}
+
+pub fn get_lc_execution_root<
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    header: &LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    context: &Context,
+) -> Result<Root> {
+    let epoch = compute_epoch_at_slot(header.beacon.slot, context);
+    if epoch >= context.deneb_fork_epoch {
+        return Ok(header.execution.clone().hash_tree_root()?)
+    }
+
+    if epoch >= context.capella_fork_epoch {
+        let execution = &header.execution;
+        let mut execution_header = capella::ExecutionPayloadHeader {
+            parent_hash: execution.parent_hash.clone(),
+            fee_recipient: execution.fee_recipient.clone(),
+            state_root: execution.state_root.clone(),
+            receipts_root: execution.receipts_root.clone(),
+            logs_bloom: execution.logs_bloom.clone(),
+            prev_randao: execution.prev_randao.clone(),
+            block_number: execution.block_number,
+            gas_limit: execution.gas_limit,
+            gas_used: execution.gas_used,
+            timestamp: execution.timestamp,
+            extra_data: execution.extra_data.clone(),
+            base_fee_per_gas: execution.base_fee_per_gas.clone(),
+            block_hash: execution.block_hash.clone(),
+            transactions_root: execution.transactions_root,
+            withdrawals_root: execution.withdrawals_root,
+        };
+        return Ok(execution_header.hash_tree_root()?)
+    }
+
+    Ok(Root::default())
+}
+
+pub fn is_valid_light_client_header<
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    header: &LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    context: &Context,
+) -> bool {
+    let epoch = compute_epoch_at_slot(header.beacon.slot, context);
+    if epoch < context.deneb_fork_epoch &&
+        (header.execution.blob_gas_used != 0 || header.execution.excess_blob_gas != 0)
+    {
+        return false
+    }
+
+    if epoch < context.capella_fork_epoch {
+        return header.execution == ExecutionPayloadHeader::default() &&
+            header.execution_branch.iter().all(|node| node == &Bytes32::default())
+    }
+
+    let Ok(leaf) = get_lc_execution_root(header, context) else { return false };
+    let branch = header
+        .execution_branch
+        .iter()
+        .map(|node| Node::try_from(node.as_ref()).expect("is valid instance"))
+        .collect::<Vec<_>>();
+    let depth = EXECUTION_PAYLOAD_INDEX_FLOOR_LOG_2;
+    let index = get_subtree_index(EXECUTION_PAYLOAD_INDEX);
+    let root = &header.beacon.body_root;
+    is_valid_merkle_branch(&leaf, branch.iter(), depth, index, root)
+}
+
+// Tracks the best `LightClientUpdate` for each sync committee period a server has seen,
+// ranked by `is_better_update`.
+#[derive(Default, Debug, Clone)]
+pub struct BestLightClientUpdates<
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+> {
+    pub updates: HashMap<
+        u64,
+        LightClientUpdate<SYNC_COMMITTEE_SIZE, BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    >,
+}
+
+pub fn block_to_light_client_header<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    context: &Context,
+) -> Result<LightClientHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>> {
+    let block = &block.message;
+    let mut body = block.body.clone();
+    let epoch = compute_epoch_at_slot(block.slot, context);
+    let (execution, execution_branch) = if epoch >= context.capella_fork_epoch {
+        let payload = &mut body.execution_payload;
+        let mut execution_header = ExecutionPayloadHeader {
+            parent_hash: payload.parent_hash.clone(),
+            fee_recipient: payload.fee_recipient.clone(),
+            state_root: payload.state_root.clone(),
+            receipts_root: payload.receipts_root.clone(),
+            logs_bloom: payload.logs_bloom.clone(),
+            prev_randao: payload.prev_randao.clone(),
+            block_number: payload.block_number,
+            gas_limit: payload.gas_limit,
+            gas_used: payload.gas_used,
+            timestamp: payload.timestamp,
+            extra_data: payload.extra_data.clone(),
+            base_fee_per_gas: payload.base_fee_per_gas.clone(),
+            block_hash: payload.block_hash.clone(),
+            transactions_root: payload.transactions.hash_tree_root()?,
+            withdrawals_root: payload.withdrawals.hash_tree_root()?,
+            blob_gas_used: 0,
+            excess_blob_gas: 0,
+        };
+        if epoch >= context.deneb_fork_epoch {
+            execution_header.blob_gas_used = payload.blob_gas_used;
+            execution_header.excess_blob_gas = payload.excess_blob_gas;
+        }
+        let execution_branch = compute_merkle_proof(&mut body, EXECUTION_PAYLOAD_INDEX)?
+            .iter()
+            .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        (execution_header, Vector::try_from(execution_branch).map_err(|(_, err)| err)?)
+    } else {
+        (ExecutionPayloadHeader::default(), Vector::default())
+    };
+
+    Ok(LightClientHeader {
+        beacon: BeaconBlockHeader {
+            slot: block.slot,
+            proposer_index: block.proposer_index,
+            parent_root: block.parent_root,
+            state_root: block.state_root,
+            body_root: body.hash_tree_root()?,
+        },
+        execution,
+        execution_branch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::minimal::Harness;
+
+    fn tamper<const N: usize>(branch: &Vector<Bytes32, N>) -> Vector<Bytes32, N> {
+        let mut nodes = branch.iter().cloned().collect::<Vec<_>>();
+        nodes[0] = Bytes32::try_from([1u8; 32].as_ref()).unwrap();
+        Vector::try_from(nodes).unwrap()
+    }
+
+    #[test]
+    fn test_light_client_header_validity() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        context.bellatrix_fork_epoch = 0;
+        context.capella_fork_epoch = 0;
+        context.deneb_fork_epoch = 0;
+        let mut harness = Harness::new(64, context).unwrap();
+        let roots = harness.extend_chain(1).unwrap();
+        let context = harness.context();
+        let block = harness.block(&roots[0]).unwrap().deneb().unwrap();
+
+        let header = block_to_light_client_header(block, context).unwrap();
+        assert_eq!(header.beacon.clone().hash_tree_root().unwrap(), roots[0]);
+        assert!(is_valid_light_client_header(&header, context));
+
+        let mut invalid_header = header.clone();
+        invalid_header.execution.excess_blob_gas += 1;
+        assert!(!is_valid_light_client_header(&invalid_header, context));
+        let mut invalid_header = header.clone();
+        invalid_header.execution_branch = tamper(&header.execution_branch);
+        assert!(!is_valid_light_client_header(&invalid_header, context));
+
+        // before the deneb fork, the execution root is the root of the capella execution header
+        let mut pre_deneb_context = context.clone();
+        pre_deneb_context.deneb_fork_epoch = 1;
+        assert_ne!(
+            get_lc_execution_root(&header, &pre_deneb_context).unwrap(),
+            get_lc_execution_root(&header, context).unwrap()
+        );
+        assert!(!is_valid_light_client_header(&header, &pre_deneb_context));
+        let mut pre_capella_context = pre_deneb_context.clone();
+        pre_capella_context.capella_fork_epoch = 1;
+        assert_eq!(get_lc_execution_root(&header, &pre_capella_context).unwrap(), Root::default());
+        assert!(!is_valid_light_client_header(&header, &pre_capella_context));
+        assert!(is_valid_light_client_header(&LightClientHeader::default(), &pre_capella_context));
+    }
+}
@@ -7,12 +7,14 @@ pub mod blinded_beacon_block;
 pub mod blinded_blob_sidecar;
 pub mod blob_sidecar;
 pub mod block_processing;
+pub mod block_production;
 pub mod epoch_processing;
 pub mod execution_engine;
 pub mod execution_payload;
 pub mod fork;
 pub mod genesis;
 pub mod helpers;
+pub mod light_client;
 pub mod networking;
 pub mod polynomial_commitments;
 pub mod presets;
@@ -1,4 +1,27 @@
+use crate::{
+    deneb::blob_sidecar::BlobIdentifier,
+    primitives::{BlobIndex, Slot},
+    ssz::prelude::*,
+};
+
 pub const MAX_REQUEST_BLOCKS_DENEB: usize = 2usize.pow(7);
 pub const MAX_REQUEST_BLOB_SIDECARS: usize = 768;
 pub const MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS: usize = 2usize.pow(12);
 pub const BLOB_SIDECAR_SUBNET_COUNT: usize = 6;
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct BlobSidecarsByRangeRequest {
+    #[serde(with = "crate::serde::as_str")]
+    pub start_slot: Slot,
+    #[serde(with = "crate::serde::as_str")]
+    pub count: u64,
+}
+
+pub type BlobSidecarsByRootRequest = List<BlobIdentifier, MAX_REQUEST_BLOB_SIDECARS>;
+
+// Return the subnet the blob sidecar with `blob_index` is gossiped on.
+pub fn compute_subnet_for_blob_sidecar(blob_index: BlobIndex) -> u64 {
+    (blob_index % BLOB_SIDECAR_SUBNET_COUNT) as u64
+}
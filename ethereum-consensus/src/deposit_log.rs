@@ -0,0 +1,223 @@
+//! Decoding of the `DepositEvent` logs the deposit contract emits for each deposit, following the
+//! ABI of `DepositEvent(bytes pubkey, bytes withdrawal_credentials, bytes amount, bytes signature,
+//! bytes index)`.
+use crate::{
+    error::DepositLogError,
+    phase0::DepositData,
+    primitives::{BlsPublicKey, BlsSignature, Bytes32, ExecutionAddress, Gwei},
+    state_transition::Context,
+    Error,
+};
+
+/// `keccak256("DepositEvent(bytes,bytes,bytes,bytes,bytes)")`, the first topic of every
+/// `DepositEvent` log.
+pub const DEPOSIT_EVENT_TOPIC: [u8; 32] = [
+    0x64, 0x9b, 0xbc, 0x62, 0xd0, 0xe3, 0x13, 0x42, 0xaf, 0xea, 0x4e, 0x5c, 0xd8, 0x2d, 0x40, 0x49,
+    0xe7, 0xe1, 0xee, 0x91, 0x2f, 0xc0, 0x88, 0x9a, 0xa7, 0x90, 0x80, 0x3b, 0xe3, 0x90, 0x38, 0xc5,
+];
+
+const ABI_WORD_LEN: usize = 32;
+const PUBLIC_KEY_LEN: usize = 48;
+const WITHDRAWAL_CREDENTIALS_LEN: usize = 32;
+const AMOUNT_LEN: usize = 8;
+const SIGNATURE_LEN: usize = 96;
+const INDEX_LEN: usize = 8;
+
+/// A log emitted by an execution layer contract, as returned from `eth_getLogs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Log {
+    pub address: ExecutionAddress,
+    pub topics: Vec<Bytes32>,
+    pub data: Vec<u8>,
+}
+
+/// The fields of a `DepositEvent`: the `DepositData` of a deposit and its index among all deposits
+/// made to the deposit contract.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepositLog {
+    pub public_key: BlsPublicKey,
+    pub withdrawal_credentials: Bytes32,
+    pub amount: Gwei,
+    pub signature: BlsSignature,
+    pub index: u64,
+}
+
+impl DepositLog {
+    /// Decodes the `DepositEvent` in `log`, which must be emitted by the deposit contract
+    /// of `context`.
+    pub fn decode(log: &Log, context: &Context) -> Result<Self, Error> {
+        if log.address != context.deposit_contract_address {
+            return Err(DepositLogError::UnexpectedAddress {
+                provided: log.address.clone(),
+                expected: context.deposit_contract_address.clone(),
+            }
+            .into())
+        }
+        match log.topics.first() {
+            Some(topic) if topic.as_slice() == DEPOSIT_EVENT_TOPIC.as_slice() => {}
+            _ => return Err(DepositLogError::NotDepositEvent.into()),
+        }
+
+        let data = &log.data;
+        let public_key = read_bytes(data, 0, "pubkey", PUBLIC_KEY_LEN)?;
+        let withdrawal_credentials =
+            read_bytes(data, 1, "withdrawal_credentials", WITHDRAWAL_CREDENTIALS_LEN)?;
+        let amount = read_bytes(data, 2, "amount", AMOUNT_LEN)?;
+        let signature = read_bytes(data, 3, "signature", SIGNATURE_LEN)?;
+        let index = read_bytes(data, 4, "index", INDEX_LEN)?;
+
+        Ok(Self {
+            public_key: BlsPublicKey::try_from(public_key)?,
+            withdrawal_credentials: Bytes32::try_from(withdrawal_credentials)
+                .expect("is valid instance"),
+            amount: u64::from_le_bytes(amount.try_into().expect("is valid instance")),
+            signature: BlsSignature::try_from(signature)?,
+            index: u64::from_le_bytes(index.try_into().expect("is valid instance")),
+        })
+    }
+}
+
+impl From<DepositLog> for DepositData {
+    fn from(log: DepositLog) -> Self {
+        Self {
+            public_key: log.public_key,
+            withdrawal_credentials: log.withdrawal_credentials,
+            amount: log.amount,
+            signature: log.signature,
+        }
+    }
+}
+
+// Reads the ABI word at `offset` in `data` as an integer that fits in a `usize`.
+fn read_usize(data: &[u8], offset: usize) -> Option<usize> {
+    let word = data.get(offset..offset.checked_add(ABI_WORD_LEN)?)?;
+    let (high, low) = word.split_at(ABI_WORD_LEN - 8);
+    if high.iter().any(|&byte| byte != 0) {
+        return None
+    }
+    let value = u64::from_be_bytes(low.try_into().expect("is valid instance"));
+    usize::try_from(value).ok()
+}
+
+// Reads the `bytes` parameter at position `position` of the ABI-encoded `data`, which must have
+// length `expected`.
+fn read_bytes<'a>(
+    data: &'a [u8],
+    position: usize,
+    field: &'static str,
+    expected: usize,
+) -> Result<&'a [u8], DepositLogError> {
+    let offset = read_usize(data, position * ABI_WORD_LEN)
+        .ok_or(DepositLogError::InvalidOffset { field })?;
+    let length = read_usize(data, offset).ok_or(DepositLogError::InvalidOffset { field })?;
+    if length != expected {
+        return Err(DepositLogError::InvalidLength { field, provided: length, expected })
+    }
+    let start = offset + ABI_WORD_LEN;
+    data.get(start..start + length).ok_or(DepositLogError::InvalidOffset { field })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ABI-encodes `fields` as the `bytes` parameters of an event.
+    fn encode(fields: &[&[u8]]) -> Vec<u8> {
+        let mut head = vec![];
+        let mut tail = vec![];
+        for field in fields {
+            let offset = fields.len() * ABI_WORD_LEN + tail.len();
+            head.extend_from_slice(&[0u8; ABI_WORD_LEN - 8]);
+            head.extend_from_slice(&(offset as u64).to_be_bytes());
+            tail.extend_from_slice(&[0u8; ABI_WORD_LEN - 8]);
+            tail.extend_from_slice(&(field.len() as u64).to_be_bytes());
+            tail.extend_from_slice(field);
+            let padding = (ABI_WORD_LEN - field.len() % ABI_WORD_LEN) % ABI_WORD_LEN;
+            tail.extend(std::iter::repeat(0u8).take(padding));
+        }
+        head.extend(tail);
+        head
+    }
+
+    fn deposit_log_for(deposit: &DepositLog, context: &Context) -> Log {
+        let amount = deposit.amount.to_le_bytes();
+        let index = deposit.index.to_le_bytes();
+        let data = encode(&[
+            deposit.public_key.as_slice(),
+            deposit.withdrawal_credentials.as_slice(),
+            &amount,
+            deposit.signature.as_slice(),
+            &index,
+        ]);
+        Log {
+            address: context.deposit_contract_address.clone(),
+            topics: vec![Bytes32::try_from(DEPOSIT_EVENT_TOPIC.as_ref()).unwrap()],
+            data,
+        }
+    }
+
+    fn sample_deposit() -> DepositLog {
+        DepositLog {
+            public_key: BlsPublicKey::try_from([0xaa; PUBLIC_KEY_LEN].as_ref()).unwrap(),
+            withdrawal_credentials: Bytes32::try_from([0x01; 32].as_ref()).unwrap(),
+            amount: 32_000_000_000,
+            signature: BlsSignature::try_from([0xbb; SIGNATURE_LEN].as_ref()).unwrap(),
+            index: 17,
+        }
+    }
+
+    #[test]
+    fn test_decode_deposit_log() {
+        let context = Context::for_minimal();
+        let deposit = sample_deposit();
+        let log = deposit_log_for(&deposit, &context);
+        // the deposit contract always emits 576 bytes of data
+        assert_eq!(log.data.len(), 576);
+
+        let decoded = DepositLog::decode(&log, &context).unwrap();
+        assert_eq!(decoded, deposit);
+
+        let deposit_data = DepositData::from(decoded);
+        assert_eq!(deposit_data.public_key, deposit.public_key);
+        assert_eq!(deposit_data.amount, deposit.amount);
+    }
+
+    #[test]
+    fn test_reject_invalid_logs() {
+        let context = Context::for_minimal();
+        let log = deposit_log_for(&sample_deposit(), &context);
+
+        let mut wrong_address = log.clone();
+        wrong_address.address = ExecutionAddress::default();
+        assert!(matches!(
+            DepositLog::decode(&wrong_address, &context),
+            Err(Error::DepositLog(DepositLogError::UnexpectedAddress { .. }))
+        ));
+
+        let mut wrong_topic = log.clone();
+        wrong_topic.topics = vec![Bytes32::default()];
+        assert!(matches!(
+            DepositLog::decode(&wrong_topic, &context),
+            Err(Error::DepositLog(DepositLogError::NotDepositEvent))
+        ));
+
+        let mut truncated = log.clone();
+        truncated.data.truncate(500);
+        assert!(matches!(
+            DepositLog::decode(&truncated, &context),
+            Err(Error::DepositLog(DepositLogError::InvalidOffset { field: "index" }))
+        ));
+
+        let mut wrong_length = log;
+        // shorten the length prefix of the public key
+        wrong_length.data[5 * ABI_WORD_LEN + ABI_WORD_LEN - 1] = 47;
+        assert!(matches!(
+            DepositLog::decode(&wrong_length, &context),
+            Err(Error::DepositLog(DepositLogError::InvalidLength {
+                field: "pubkey",
+                provided: 47,
+                expected: PUBLIC_KEY_LEN
+            }))
+        ));
+    }
+}
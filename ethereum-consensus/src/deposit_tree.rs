@@ -0,0 +1,502 @@
+//! The incremental Merkle tree of the deposit contract, with the snapshots of finalized deposits
+//! defined in EIP-4881.
+use crate::{
+    crypto::hash,
+    error::DepositTreeError,
+    phase0::{DepositData, Eth1Data, DEPOSIT_CONTRACT_TREE_DEPTH},
+    primitives::{Bytes32, Hash32, Root},
+    ssz::prelude::*,
+    Error,
+};
+
+const DEPOSIT_PROOF_LENGTH: usize = DEPOSIT_CONTRACT_TREE_DEPTH + 1;
+
+fn hash_pair(left: &Root, right: &Root) -> Root {
+    let mut data = [0u8; 64];
+    data[..32].copy_from_slice(left.as_ref());
+    data[32..].copy_from_slice(right.as_ref());
+    Root::try_from(hash(data).as_ref()).expect("is valid instance")
+}
+
+fn length_root(length: u64) -> Root {
+    let mut data = [0u8; 32];
+    data[..8].copy_from_slice(&length.to_le_bytes());
+    Root::try_from(data.as_ref()).expect("is valid instance")
+}
+
+// Returns the roots of empty subtrees of each height up to and including the height of the tree.
+fn zero_hashes() -> Vec<Root> {
+    let mut hashes = vec![Root::default()];
+    for height in 0..DEPOSIT_CONTRACT_TREE_DEPTH {
+        hashes.push(hash_pair(&hashes[height], &hashes[height]));
+    }
+    hashes
+}
+
+fn to_hash32(root: &Root) -> Hash32 {
+    Hash32::try_from(root.as_ref()).expect("is valid instance")
+}
+
+fn to_root(hash: &Hash32) -> Root {
+    Root::try_from(hash.as_ref()).expect("is valid instance")
+}
+
+/// The finalized part of a `DepositTree`, from which the tree can be restored to keep adding
+/// deposits after the finalized ones.
+#[derive(
+    Default, Debug, SimpleSerialize, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct DepositTreeSnapshot {
+    /// The roots of the finalized subtrees, from the left of the tree.
+    pub finalized: List<Hash32, DEPOSIT_CONTRACT_TREE_DEPTH>,
+    /// The deposit root over the finalized deposits.
+    pub deposit_root: Hash32,
+    #[serde(with = "crate::serde::as_str")]
+    pub deposit_count: u64,
+    pub execution_block_hash: Hash32,
+    #[serde(with = "crate::serde::as_str")]
+    pub execution_block_height: u64,
+}
+
+// A subtree of the deposit tree. Its height is implied by its position in the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MerkleTree {
+    // A subtree without any deposits
+    Zero(usize),
+    Leaf(Root),
+    Node(Root, Box<MerkleTree>, Box<MerkleTree>),
+    // A full subtree whose nodes were pruned on finalization
+    Finalized(Root),
+}
+
+impl MerkleTree {
+    fn node(left: Self, right: Self, zero_hashes: &[Root]) -> Self {
+        let root = hash_pair(&left.root(zero_hashes), &right.root(zero_hashes));
+        Self::Node(root, Box::new(left), Box::new(right))
+    }
+
+    fn root(&self, zero_hashes: &[Root]) -> Root {
+        match self {
+            Self::Zero(height) => zero_hashes[*height],
+            Self::Leaf(root) | Self::Node(root, ..) | Self::Finalized(root) => *root,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        match self {
+            Self::Zero(_) => false,
+            Self::Leaf(_) | Self::Finalized(_) => true,
+            Self::Node(_, _, right) => right.is_full(),
+        }
+    }
+
+    // Adds `leaf` after the last leaf of this subtree, which must not be full.
+    fn push_leaf(self, leaf: Root, height: usize, zero_hashes: &[Root]) -> Self {
+        match self {
+            Self::Zero(0) => Self::Leaf(leaf),
+            Self::Zero(height) => {
+                let left = Self::Zero(height - 1).push_leaf(leaf, height - 1, zero_hashes);
+                Self::node(left, Self::Zero(height - 1), zero_hashes)
+            }
+            Self::Node(_, left, right) => {
+                if left.is_full() {
+                    let right = right.push_leaf(leaf, height - 1, zero_hashes);
+                    Self::node(*left, right, zero_hashes)
+                } else {
+                    let left = left.push_leaf(leaf, height - 1, zero_hashes);
+                    Self::node(left, *right, zero_hashes)
+                }
+            }
+            Self::Leaf(_) | Self::Finalized(_) => {
+                unreachable!("leaves are not pushed to full subtrees")
+            }
+        }
+    }
+
+    // Prunes the first `deposit_count` leaves of this subtree, keeping only the roots of the full
+    // subtrees covering them.
+    fn finalize(self, deposit_count: u64, height: usize) -> Self {
+        if deposit_count == 0 {
+            return self
+        }
+        match self {
+            Self::Node(root, left, right) => {
+                let size = 1u64 << height;
+                if deposit_count >= size {
+                    return Self::Finalized(root)
+                }
+                let half = size / 2;
+                let left = left.finalize(deposit_count, height - 1);
+                let right = if deposit_count > half {
+                    right.finalize(deposit_count - half, height - 1)
+                } else {
+                    *right
+                };
+                Self::Node(root, Box::new(left), Box::new(right))
+            }
+            Self::Leaf(root) => Self::Finalized(root),
+            tree => tree,
+        }
+    }
+
+    // Appends the roots of the finalized subtrees to `finalized` from the left, returning the
+    // number of deposits under them.
+    fn get_finalized(&self, height: usize, finalized: &mut Vec<Root>) -> u64 {
+        match self {
+            Self::Finalized(root) => {
+                finalized.push(*root);
+                1 << height
+            }
+            Self::Node(_, left, right) => {
+                left.get_finalized(height - 1, finalized) +
+                    right.get_finalized(height - 1, finalized)
+            }
+            Self::Zero(_) | Self::Leaf(_) => 0,
+        }
+    }
+
+    fn from_snapshot_parts(
+        finalized: &[Root],
+        deposit_count: u64,
+        height: usize,
+        zero_hashes: &[Root],
+    ) -> Self {
+        if finalized.is_empty() || deposit_count == 0 {
+            return Self::Zero(height)
+        }
+        if deposit_count == 1 << height {
+            return Self::Finalized(finalized[0])
+        }
+        let half = 1 << (height - 1);
+        let (left, right) = if deposit_count <= half {
+            let left = Self::from_snapshot_parts(finalized, deposit_count, height - 1, zero_hashes);
+            (left, Self::Zero(height - 1))
+        } else {
+            let right = Self::from_snapshot_parts(
+                &finalized[1..],
+                deposit_count - half,
+                height - 1,
+                zero_hashes,
+            );
+            (Self::Finalized(finalized[0]), right)
+        };
+        Self::node(left, right, zero_hashes)
+    }
+
+    // Returns the root of this subtree over only its first `deposit_count` leaves, or `None` if
+    // that needs the nodes of a finalized subtree.
+    fn root_at(&self, deposit_count: u64, height: usize, zero_hashes: &[Root]) -> Option<Root> {
+        if deposit_count == 0 {
+            return Some(zero_hashes[height])
+        }
+        if deposit_count >= 1 << height {
+            return Some(self.root(zero_hashes))
+        }
+        match self {
+            Self::Node(_, left, right) => {
+                let half = 1 << (height - 1);
+                let left = left.root_at(deposit_count.min(half), height - 1, zero_hashes)?;
+                let right =
+                    right.root_at(deposit_count.saturating_sub(half), height - 1, zero_hashes)?;
+                Some(hash_pair(&left, &right))
+            }
+            Self::Finalized(_) => None,
+            Self::Zero(_) | Self::Leaf(_) => Some(self.root(zero_hashes)),
+        }
+    }
+
+    // Returns the branch of the leaf at `index` against the root of the tree over only its first
+    // `deposit_count` leaves, ordered from the leaf to the root, or `None` if the branch needs the
+    // nodes of a finalized subtree.
+    fn generate_proof(
+        &self,
+        index: u64,
+        mut deposit_count: u64,
+        zero_hashes: &[Root],
+    ) -> Option<Vec<Root>> {
+        let mut node = self;
+        let mut branch = Vec::with_capacity(DEPOSIT_CONTRACT_TREE_DEPTH);
+        for height in (0..DEPOSIT_CONTRACT_TREE_DEPTH).rev() {
+            let (left, right) = match node {
+                Self::Node(_, left, right) => (left, right),
+                _ => return None,
+            };
+            let half = 1 << height;
+            if (index >> height) & 1 == 1 {
+                branch.push(left.root_at(half, height, zero_hashes)?);
+                deposit_count -= half;
+                node = right;
+            } else {
+                branch.push(right.root_at(
+                    deposit_count.saturating_sub(half),
+                    height,
+                    zero_hashes,
+                )?);
+                deposit_count = deposit_count.min(half);
+                node = left;
+            }
+        }
+        branch.reverse();
+        Some(branch)
+    }
+}
+
+/// The Merkle tree of the deposit contract, which proves deposits for blocks against the deposit
+/// root of any `Eth1Data` the tree has reached.
+///
+/// Deposits that are finalized with `finalize` are pruned from the tree, keeping just enough to
+/// add new deposits and prove them. The finalized part can be exported as a
+/// `DepositTreeSnapshot` and the tree restored from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositTree {
+    tree: MerkleTree,
+    deposit_count: u64,
+    finalized_deposit_count: u64,
+    // The hash and height of the execution block of the last finalization
+    finalized_execution_block: Option<(Hash32, u64)>,
+}
+
+impl Default for DepositTree {
+    fn default() -> Self {
+        Self {
+            tree: MerkleTree::Zero(DEPOSIT_CONTRACT_TREE_DEPTH),
+            deposit_count: 0,
+            finalized_deposit_count: 0,
+            finalized_execution_block: None,
+        }
+    }
+}
+
+impl DepositTree {
+    /// Restores the tree from `snapshot`, which is checked against its deposit root.
+    pub fn from_snapshot(snapshot: &DepositTreeSnapshot) -> Result<Self, Error> {
+        let bound = 1u64 << DEPOSIT_CONTRACT_TREE_DEPTH;
+        if snapshot.deposit_count > bound {
+            return Err(DepositTreeError::DepositCountOutOfRange {
+                requested: snapshot.deposit_count,
+                deposit_count: bound,
+            }
+            .into())
+        }
+        let zero_hashes = zero_hashes();
+        let finalized = snapshot.finalized.iter().map(to_root).collect::<Vec<_>>();
+        let tree = MerkleTree::from_snapshot_parts(
+            &finalized,
+            snapshot.deposit_count,
+            DEPOSIT_CONTRACT_TREE_DEPTH,
+            &zero_hashes,
+        );
+        let tree = Self {
+            tree,
+            deposit_count: snapshot.deposit_count,
+            finalized_deposit_count: snapshot.deposit_count,
+            finalized_execution_block: Some((
+                snapshot.execution_block_hash.clone(),
+                snapshot.execution_block_height,
+            )),
+        };
+        let expected = tree.root();
+        let provided = to_root(&snapshot.deposit_root);
+        if provided != expected {
+            return Err(DepositTreeError::InvalidSnapshot { provided, expected }.into())
+        }
+        Ok(tree)
+    }
+
+    /// Returns the snapshot of the deposits finalized by the last call to `finalize`.
+    pub fn snapshot(&self) -> Result<DepositTreeSnapshot, Error> {
+        let (execution_block_hash, execution_block_height) =
+            self.finalized_execution_block.clone().ok_or(DepositTreeError::NotFinalized)?;
+        let mut finalized = vec![];
+        let deposit_count = self.tree.get_finalized(DEPOSIT_CONTRACT_TREE_DEPTH, &mut finalized);
+        let finalized = finalized.iter().map(to_hash32).collect::<Vec<_>>();
+        Ok(DepositTreeSnapshot {
+            finalized: List::try_from(finalized).map_err(|(_, err)| err)?,
+            deposit_root: to_hash32(&self.root_at(deposit_count)?),
+            deposit_count,
+            execution_block_hash,
+            execution_block_height,
+        })
+    }
+
+    pub fn deposit_count(&self) -> u64 {
+        self.deposit_count
+    }
+
+    /// Adds the deposit with the `hash_tree_root` of its `DepositData` as `leaf`.
+    pub fn push_leaf(&mut self, leaf: Root) -> Result<(), Error> {
+        if self.tree.is_full() {
+            return Err(DepositTreeError::Full.into())
+        }
+        let tree = std::mem::replace(&mut self.tree, MerkleTree::Zero(0));
+        self.tree = tree.push_leaf(leaf, DEPOSIT_CONTRACT_TREE_DEPTH, &zero_hashes());
+        self.deposit_count += 1;
+        Ok(())
+    }
+
+    pub fn push_deposit_data(&mut self, deposit_data: &mut DepositData) -> Result<(), Error> {
+        let leaf = deposit_data.hash_tree_root()?;
+        self.push_leaf(leaf)
+    }
+
+    /// Returns the deposit root over all deposits in the tree.
+    pub fn root(&self) -> Root {
+        let root = self.tree.root(&zero_hashes());
+        hash_pair(&root, &length_root(self.deposit_count))
+    }
+
+    /// Returns the deposit root over the first `deposit_count` deposits, as in an `Eth1Data` with
+    /// that `deposit_count`.
+    pub fn root_at(&self, deposit_count: u64) -> Result<Root, Error> {
+        self.check_deposit_count(deposit_count)?;
+        let root = self
+            .tree
+            .root_at(deposit_count, DEPOSIT_CONTRACT_TREE_DEPTH, &zero_hashes())
+            .ok_or(DepositTreeError::Pruned(self.finalized_deposit_count))?;
+        Ok(hash_pair(&root, &length_root(deposit_count)))
+    }
+
+    /// Returns the proof of the deposit at `index` against `root_at(deposit_count)`, as expected
+    /// in a `Deposit` included in a block for an `Eth1Data` with that `deposit_count`.
+    pub fn get_proof(
+        &self,
+        index: u64,
+        deposit_count: u64,
+    ) -> Result<Vector<Bytes32, DEPOSIT_PROOF_LENGTH>, Error> {
+        self.check_deposit_count(deposit_count)?;
+        if index >= deposit_count {
+            return Err(DepositTreeError::DepositIndexOutOfRange { index, deposit_count }.into())
+        }
+        let mut branch = self
+            .tree
+            .generate_proof(index, deposit_count, &zero_hashes())
+            .ok_or(DepositTreeError::Pruned(self.finalized_deposit_count))?;
+        branch.push(length_root(deposit_count));
+        let proof = branch
+            .iter()
+            .map(|node| Bytes32::try_from(node.as_ref()).expect("is valid instance"))
+            .collect::<Vec<_>>();
+        let proof = Vector::try_from(proof).map_err(|(_, err)| err)?;
+        Ok(proof)
+    }
+
+    /// Prunes the deposits up to the `deposit_count` of `eth1_data`, which is finalized at the
+    /// execution block with `execution_block_height`. Their proofs are no longer available.
+    pub fn finalize(
+        &mut self,
+        eth1_data: &Eth1Data,
+        execution_block_height: u64,
+    ) -> Result<(), Error> {
+        self.check_deposit_count(eth1_data.deposit_count)?;
+        let tree = std::mem::replace(&mut self.tree, MerkleTree::Zero(0));
+        self.tree = tree.finalize(eth1_data.deposit_count, DEPOSIT_CONTRACT_TREE_DEPTH);
+        self.finalized_deposit_count = self.finalized_deposit_count.max(eth1_data.deposit_count);
+        self.finalized_execution_block =
+            Some((eth1_data.block_hash.clone(), execution_block_height));
+        Ok(())
+    }
+
+    fn check_deposit_count(&self, deposit_count: u64) -> Result<(), Error> {
+        if deposit_count > self.deposit_count {
+            Err(DepositTreeError::DepositCountOutOfRange {
+                requested: deposit_count,
+                deposit_count: self.deposit_count,
+            }
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase0::DEPOSIT_DATA_LIST_BOUND;
+
+    fn leaves(count: u8) -> Vec<Root> {
+        (0..count).map(|i| Root::try_from([i + 1; 32].as_ref()).unwrap()).collect()
+    }
+
+    fn list_root(leaves: &[Root]) -> Root {
+        let mut list = List::<Root, DEPOSIT_DATA_LIST_BOUND>::try_from(leaves.to_vec()).unwrap();
+        list.hash_tree_root().unwrap()
+    }
+
+    fn is_valid_proof(tree: &DepositTree, leaf: &Root, index: u64, deposit_count: u64) -> bool {
+        let proof = tree.get_proof(index, deposit_count).unwrap();
+        let branch =
+            proof.iter().map(|node| Root::try_from(node.as_ref()).unwrap()).collect::<Vec<_>>();
+        let root = tree.root_at(deposit_count).unwrap();
+        is_valid_merkle_branch(leaf, branch.iter(), DEPOSIT_PROOF_LENGTH, index as usize, &root)
+    }
+
+    #[test]
+    fn test_proofs_at_each_deposit_count() {
+        let leaves = leaves(13);
+        let mut tree = DepositTree::default();
+        assert_eq!(tree.root(), list_root(&[]));
+        for leaf in &leaves {
+            tree.push_leaf(*leaf).unwrap();
+        }
+        assert_eq!(tree.root(), list_root(&leaves));
+
+        for deposit_count in 1..=leaves.len() {
+            assert_eq!(
+                tree.root_at(deposit_count as u64).unwrap(),
+                list_root(&leaves[..deposit_count])
+            );
+            for (index, leaf) in leaves.iter().enumerate().take(deposit_count) {
+                assert!(is_valid_proof(&tree, leaf, index as u64, deposit_count as u64));
+            }
+        }
+        assert!(tree.get_proof(13, 13).is_err());
+        assert!(tree.root_at(14).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let leaves = leaves(21);
+        let mut tree = DepositTree::default();
+        assert!(tree.snapshot().is_err());
+        for leaf in &leaves[..16] {
+            tree.push_leaf(*leaf).unwrap();
+        }
+        let eth1_data = Eth1Data {
+            deposit_root: tree.root_at(11).unwrap(),
+            deposit_count: 11,
+            block_hash: Hash32::try_from([7u8; 32].as_ref()).unwrap(),
+        };
+        tree.finalize(&eth1_data, 100).unwrap();
+        assert!(matches!(
+            tree.get_proof(7, 16),
+            Err(Error::DepositTree(DepositTreeError::Pruned(11)))
+        ));
+        assert!(is_valid_proof(&tree, &leaves[11], 11, 16));
+
+        let snapshot = tree.snapshot().unwrap();
+        assert_eq!(snapshot.deposit_count, 11);
+        assert_eq!(to_root(&snapshot.deposit_root), eth1_data.deposit_root);
+        assert_eq!(snapshot.execution_block_height, 100);
+
+        let mut restored = DepositTree::from_snapshot(&snapshot).unwrap();
+        for leaf in &leaves[11..] {
+            restored.push_leaf(*leaf).unwrap();
+        }
+        for leaf in &leaves[16..] {
+            tree.push_leaf(*leaf).unwrap();
+        }
+        assert_eq!(restored.root(), list_root(&leaves));
+        assert_eq!(restored.root(), tree.root());
+        for index in 11..leaves.len() {
+            assert_eq!(
+                restored.get_proof(index as u64, 21).unwrap(),
+                tree.get_proof(index as u64, 21).unwrap()
+            );
+        }
+
+        let mut invalid = snapshot;
+        invalid.deposit_count = 12;
+        assert!(DepositTree::from_snapshot(&invalid).is_err());
+    }
+}
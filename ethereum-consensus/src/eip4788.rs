@@ -0,0 +1,422 @@
+//! Proofs against the beacon block roots exposed to the execution layer by EIP-4788.
+//!
+//! Each `ProofBundle` proves a leaf against the root of a `BeaconBlock` (which is also the root of
+//! its `BeaconBlockHeader`) so that a contract given a `parent_beacon_block_root` can verify it.
+use crate::{
+    primitives::{Root, ValidatorIndex, WithdrawalIndex},
+    ssz::{
+        prelude::*,
+        proofs::{calculate_merkle_root, compute_node_and_branch, concat_generalized_indices},
+    },
+    types::{BeaconBlock, BeaconState},
+    Error,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ProofBundle {
+    // The beacon block root the proof is against
+    pub root: Root,
+    pub leaf: Root,
+    // Ordered from the leaf to the root
+    pub branch: Vec<Root>,
+    #[serde(with = "crate::serde::as_str")]
+    pub gindex: GeneralizedIndex,
+}
+
+impl ProofBundle {
+    pub fn verify(&self) -> bool {
+        verify_merkle_proof(&self.leaf, &self.branch, self.gindex, &self.root)
+    }
+}
+
+fn block_as_prove<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+) -> &mut dyn Prove {
+    match block {
+        BeaconBlock::Phase0(inner) => inner,
+        BeaconBlock::Altair(inner) => inner,
+        BeaconBlock::Bellatrix(inner) => inner,
+        BeaconBlock::Capella(inner) => inner,
+        BeaconBlock::Deneb(inner) => inner,
+    }
+}
+
+fn state_as_prove<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+) -> &mut dyn Prove {
+    match state {
+        BeaconState::Phase0(inner) => inner,
+        BeaconState::Altair(inner) => inner,
+        BeaconState::Bellatrix(inner) => inner,
+        BeaconState::Capella(inner) => inner,
+        BeaconState::Deneb(inner) => inner,
+    }
+}
+
+// Proves the node at `path` in `object` against the root of `object`, which follows from the
+// node and its branch.
+fn prove(object: &mut dyn Prove, path: &[PathElement]) -> Result<ProofBundle, Error> {
+    let gindex = get_generalized_index(object, path)?;
+    let (leaf, branch) = compute_node_and_branch(object, gindex)?;
+    let root = calculate_merkle_root(&leaf, &branch, gindex)?;
+    Ok(ProofBundle { root, leaf, branch, gindex })
+}
+
+// Proves the node at `path` in `state` against the root of `block`, which must commit to `state`.
+fn prove_in_state<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    path: &[PathElement],
+) -> Result<ProofBundle, Error> {
+    let mut proof = prove(state_as_prove(state), path)?;
+    if proof.root != block.state_root() {
+        return Err(Error::InvalidStateRoot)
+    }
+
+    let state_root_proof = prove(block_as_prove(block), &["state_root".into()])?;
+    proof.root = state_root_proof.root;
+    proof.branch.extend(state_root_proof.branch);
+    proof.gindex = concat_generalized_indices(&[state_root_proof.gindex, proof.gindex]);
+    Ok(proof)
+}
+
+// Proves the `Validator` at `index` in `state.validators` against the root of `block`.
+pub fn prove_validator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    index: ValidatorIndex,
+) -> Result<ProofBundle, Error> {
+    let bound = state.validators().len();
+    if index >= bound {
+        return Err(Error::OutOfBounds { requested: index, bound })
+    }
+    prove_in_state(block, state, &["validators".into(), index.into()])
+}
+
+// Proves the balance of the validator at `index` against the root of `block`.
+// Balances are packed four to a leaf, so the balance is the `index % 4`-th little-endian `u64`
+// of the leaf.
+pub fn prove_balance<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    index: ValidatorIndex,
+) -> Result<ProofBundle, Error> {
+    let bound = state.balances().len();
+    if index >= bound {
+        return Err(Error::OutOfBounds { requested: index, bound })
+    }
+    prove_in_state(block, state, &["balances".into(), index.into()])
+}
+
+// Proves the `Withdrawal` at position `index` in the execution payload of `block`
+// against the root of `block`.
+pub fn prove_withdrawal<
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    index: WithdrawalIndex,
+) -> Result<ProofBundle, Error> {
+    let bound = block
+        .body()
+        .execution_payload()
+        .and_then(|payload| payload.withdrawals().map(|withdrawals| withdrawals.len()))
+        .unwrap_or_default();
+    if index >= bound {
+        return Err(Error::OutOfBounds { requested: index, bound })
+    }
+    let path = ["body".into(), "execution_payload".into(), "withdrawals".into(), index.into()];
+    prove(block_as_prove(block), &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        capella::Withdrawal,
+        deneb::minimal as deneb,
+        phase0::Validator,
+        types::minimal::{BeaconBlock, BeaconState},
+    };
+
+    #[test]
+    fn test_validator_and_balance_proofs() {
+        let mut inner = deneb::BeaconState::default();
+        for i in 0..5 {
+            inner.validators.push(Validator { effective_balance: i, ..Default::default() });
+            inner.balances.push(i);
+        }
+        let mut state = BeaconState::Deneb(inner);
+        let mut block = deneb::BeaconBlock::default();
+        block.state_root = state.hash_tree_root().unwrap();
+        let mut block = BeaconBlock::Deneb(block);
+        let block_root = block.hash_tree_root().unwrap();
+
+        let proof = prove_validator(&mut block, &mut state, 3).unwrap();
+        assert_eq!(proof.root, block_root);
+        assert_eq!(proof.leaf, state.validators()[3].clone().hash_tree_root().unwrap());
+        assert!(proof.verify());
+
+        let proof = prove_balance(&mut block, &mut state, 4).unwrap();
+        assert_eq!(proof.root, block_root);
+        assert_eq!(&proof.leaf.as_ref()[..8], 4u64.to_le_bytes());
+        assert!(proof.verify());
+
+        assert!(prove_validator(&mut block, &mut state, 5).is_err());
+    }
+
+    #[test]
+    fn test_withdrawal_proof() {
+        let mut inner = deneb::BeaconBlock::default();
+        for index in 0..3 {
+            inner
+                .body
+                .execution_payload
+                .withdrawals
+                .push(Withdrawal { index, ..Default::default() });
+        }
+        let mut block = BeaconBlock::Deneb(inner);
+        let block_root = block.hash_tree_root().unwrap();
+
+        let proof = prove_withdrawal(&mut block, 1).unwrap();
+        assert_eq!(proof.root, block_root);
+        assert!(proof.verify());
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&proof).unwrap();
+            assert_eq!(json["gindex"], proof.gindex.to_string());
+            assert_eq!(json["branch"].as_array().unwrap().len(), proof.branch.len());
+            let recovered: ProofBundle = serde_json::from_value(json).unwrap();
+            assert_eq!(recovered, proof);
+        }
+    }
+}
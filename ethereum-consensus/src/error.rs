@@ -3,7 +3,10 @@ use crate::{
     crypto::Error as CryptoError,
     deneb::polynomial_commitments::Error as PolynomialCommitmentError,
     phase0::{AttestationData, BeaconBlockHeader, Checkpoint},
-    primitives::{BlsPublicKey, BlsSignature, Bytes32, Epoch, Hash32, Root, Slot, ValidatorIndex},
+    primitives::{
+        BlsPublicKey, BlsSignature, Bytes32, Epoch, ExecutionAddress, ForkDigest, Hash32, Root,
+        Slot, ValidatorIndex,
+    },
     ssz::prelude::*,
     Fork,
 };
@@ -54,10 +57,40 @@ pub enum Error {
     #[cfg(feature = "serde")]
     #[error("an unknown preset {0} was supplied when constructing context")]
     UnknownPreset(String),
+    #[error("generalized index {0} is not valid for the given type")]
+    InvalidGeneralizedIndex(usize),
+    #[error("path element {0} is not valid for the given type")]
+    InvalidPathElement(String),
+    #[error("merkle proof does not match the generalized indices it proves")]
+    InvalidMerkleProof,
+    #[error("no block is known with root {0}")]
+    UnknownBlockRoot(Root),
+    #[error("a block of fork {0:?} requires an execution payload")]
+    MissingExecutionPayload(Fork),
+    #[error("no validator is known with public key {0}")]
+    UnknownValidator(BlsPublicKey),
+    #[error("deposit {index} of new validator {public_key} has an invalid signature")]
+    InvalidDepositSignature { index: usize, public_key: BlsPublicKey },
+    #[error("validator with public key {0} is given more than once")]
+    DuplicateValidator(BlsPublicKey),
     #[error(transparent)]
     ExecutionEngine(#[from] ExecutionEngineError),
     #[error(transparent)]
     PolynomialCommitment(#[from] PolynomialCommitmentError),
+    #[error(transparent)]
+    ForkChoice(#[from] ForkChoiceError),
+    #[error(transparent)]
+    LightClient(#[from] LightClientError),
+    #[error(transparent)]
+    DepositTree(#[from] DepositTreeError),
+    #[error(transparent)]
+    Eth1(#[from] Eth1Error),
+    #[error(transparent)]
+    DepositLog(#[from] DepositLogError),
+    #[error(transparent)]
+    GossipTopic(#[from] GossipTopicError),
+    #[error(transparent)]
+    ReqResp(#[from] ReqRespError),
 }
 
 #[derive(Debug, Error)]
@@ -136,6 +169,8 @@ pub enum InvalidIndexedAttestation {
     AttestingIndicesNotSorted,
     #[error("index in attesting set is invalid for this state")]
     InvalidIndex(ValidatorIndex),
+    #[error("invalid signature")]
+    InvalidSignature(BlsSignature),
 }
 
 #[derive(Debug, Error)]
@@ -192,6 +227,10 @@ pub enum InvalidVoluntaryExit {
 pub enum InvalidWithdrawals {
     #[error("expected withdrawals {expected:#?} do not match provided withdrawals {provided:#?}")]
     IncorrectWithdrawals { provided: Vec<Withdrawal>, expected: Vec<Withdrawal> },
+    #[error(
+        "expected withdrawals root {expected} does not match provided withdrawals root {provided}"
+    )]
+    IncorrectWithdrawalsRoot { provided: Root, expected: Root },
 }
 
 #[derive(Debug, Error)]
@@ -239,3 +278,166 @@ pub enum ExecutionEngineError {
     #[error("invalid versioned hashes in payload")]
     InvalidVersionedHashes,
 }
+
+#[derive(Debug, Error)]
+pub enum ForkChoiceError {
+    #[error("anchor block has state root {provided} but the anchor state has root {expected}")]
+    InvalidAnchor { provided: Root, expected: Root },
+    #[error("block with root {0} is not known to the store")]
+    UnknownBlock(Root),
+    #[error("block at slot {block_slot} is from the future (current slot is {current_slot})")]
+    FutureBlock { block_slot: Slot, current_slot: Slot },
+    #[error("block at slot {block_slot} is not later than the finalized slot {finalized_slot}")]
+    BlockBeforeFinalized { block_slot: Slot, finalized_slot: Slot },
+    #[error("block with parent root {parent_root} does not descend from the finalized checkpoint {finalized:?}")]
+    NotDescendantOfFinalized { parent_root: Root, finalized: Checkpoint },
+    #[error("attestation target epoch {target} is not the current epoch {current} or the previous epoch")]
+    InvalidAttestationTargetEpoch { target: Epoch, current: Epoch },
+    #[error("attestation target epoch {target} does not match the epoch of its slot {slot}")]
+    AttestationTargetSlotMismatch { target: Epoch, slot: Slot },
+    #[error(
+        "attestation at slot {attestation_slot} votes for a block from a later slot {block_slot}"
+    )]
+    AttestationForFutureBlock { attestation_slot: Slot, block_slot: Slot },
+    #[error("attestation target root {target} is not the checkpoint block of its head vote {beacon_block_root}")]
+    InconsistentAttestationTarget { target: Root, beacon_block_root: Root },
+    #[error("attestation at slot {attestation_slot} can only be considered after its slot (current slot is {current_slot})")]
+    FutureAttestation { attestation_slot: Slot, current_slot: Slot },
+    #[error("proof-of-work block with hash {0} is not known")]
+    UnknownPowBlock(Hash32),
+    #[error("proof-of-work block with hash {0} is not a valid terminal block")]
+    InvalidTerminalPowBlock(Hash32),
+    #[error("merge transition block in epoch {epoch} is before the terminal block hash activation epoch {activation_epoch}")]
+    TerminalBlockHashNotActivated { epoch: Epoch, activation_epoch: Epoch },
+    #[error("merge transition block has parent hash {provided} but the terminal block hash is {expected}")]
+    InvalidTerminalBlockHash { provided: Hash32, expected: Hash32 },
+    #[error("blobs committed to by block with root {0} are not available")]
+    DataUnavailable(Root),
+}
+
+#[derive(Debug, Error)]
+pub enum LightClientError {
+    #[error("light client header for block at slot {0} is not valid")]
+    InvalidHeader(Slot),
+    #[error("bootstrap header has root {provided} but the trusted block root is {expected}")]
+    UntrustedBootstrap { provided: Root, expected: Root },
+    #[error("invalid proof of the current sync committee against state root {0}")]
+    InvalidCurrentSyncCommitteeProof(Root),
+    #[error("update has {participants} participants but at least {minimum} are required")]
+    InsufficientParticipation { participants: usize, minimum: usize },
+    #[error("update slots are not ordered: current slot {current_slot} >= signature slot {signature_slot} > attested slot {attested_slot} >= finalized slot {finalized_slot}")]
+    InvalidSlots {
+        current_slot: Slot,
+        signature_slot: Slot,
+        attested_slot: Slot,
+        finalized_slot: Slot,
+    },
+    #[error("update signed in sync committee period {signature_period} cannot be verified from store period {store_period}")]
+    InvalidSignaturePeriod { signature_period: u64, store_period: u64 },
+    #[error("update attested at slot {attested_slot} is not relevant for a store finalized at slot {finalized_slot}")]
+    IrrelevantUpdate { attested_slot: Slot, finalized_slot: Slot },
+    #[error("update without a finality branch must have an empty finalized header")]
+    UnexpectedFinalizedHeader,
+    #[error("invalid proof of the finalized root {0} against the attested state")]
+    InvalidFinalityProof(Root),
+    #[error("update without a next sync committee branch must have an empty next sync committee")]
+    UnexpectedNextSyncCommittee,
+    #[error(
+        "update next sync committee does not match the next sync committee known to the store"
+    )]
+    InconsistentNextSyncCommittee,
+    #[error("invalid proof of the next sync committee against the attested state")]
+    InvalidNextSyncCommitteeProof,
+    #[error("invalid sync committee aggregate signature {signature} over signing root {root}")]
+    InvalidSignature { signature: BlsSignature, root: Root },
+    #[error("update finalized in sync committee period {finalized_period} cannot be applied to store period {store_period}")]
+    InvalidFinalizedPeriod { finalized_period: u64, store_period: u64 },
+    #[error("light client data is not available for slot {0} before the altair fork")]
+    UnsupportedSlot(Slot),
+    #[error("state at slot {state_slot} must not advance past its latest block header at slot {header_slot}")]
+    UnexpectedStateSlot { state_slot: Slot, header_slot: Slot },
+    #[error(
+        "block has root {provided} but the latest block header of the state has root {expected}"
+    )]
+    InconsistentBlock { provided: Root, expected: Root },
+    #[error(
+        "attested block has root {provided} but the signature block has parent root {expected}"
+    )]
+    InconsistentAttestedBlock { provided: Root, expected: Root },
+    #[error(
+        "finalized block has root {provided} but the attested state has finalized root {expected}"
+    )]
+    InconsistentFinalizedBlock { provided: Root, expected: Root },
+}
+
+#[derive(Debug, Error)]
+pub enum DepositTreeError {
+    #[error("the deposit tree is full")]
+    Full,
+    #[error("deposit count {requested} is larger than the {deposit_count} deposits in the tree")]
+    DepositCountOutOfRange { requested: u64, deposit_count: u64 },
+    #[error("deposit {index} is not among the first {deposit_count} deposits")]
+    DepositIndexOutOfRange { index: u64, deposit_count: u64 },
+    #[error("the first {0} deposits are finalized and pruned from the tree")]
+    Pruned(u64),
+    #[error("the deposit tree has not been finalized")]
+    NotFinalized,
+    #[error("snapshot has deposit root {provided} but its finalized nodes have root {expected}")]
+    InvalidSnapshot { provided: Root, expected: Root },
+}
+
+#[derive(Debug, Error)]
+pub enum Eth1Error {
+    #[error("eth1 block with timestamp {timestamp} is older than the latest known block with timestamp {latest_timestamp}")]
+    OutOfOrderBlock { timestamp: u64, latest_timestamp: u64 },
+    #[error("deposit with index {index} does not follow the {deposit_count} known deposits")]
+    OutOfOrderDeposit { index: u64, deposit_count: u64 },
+    #[error("deposit with index {index} is not among the {deposit_count} known deposits")]
+    UnknownDeposit { index: u64, deposit_count: u64 },
+}
+
+#[derive(Debug, Error)]
+pub enum DepositLogError {
+    #[error("log was emitted by {provided} and not the deposit contract at {expected}")]
+    UnexpectedAddress { provided: ExecutionAddress, expected: ExecutionAddress },
+    #[error("log is not a `DepositEvent`")]
+    NotDepositEvent,
+    #[error("`DepositEvent` field `{field}` has an invalid offset")]
+    InvalidOffset { field: &'static str },
+    #[error("`DepositEvent` field `{field}` has length {provided} but expected length {expected}")]
+    InvalidLength { field: &'static str, provided: usize, expected: usize },
+}
+
+#[derive(Debug, Error)]
+pub enum GossipTopicError {
+    #[error(
+        "gossip topic `{0}` does not have the form `/eth2/{{fork_digest}}/{{name}}/ssz_snappy`"
+    )]
+    InvalidFormat(String),
+    #[error("gossip topic has invalid fork digest `{0}`")]
+    InvalidForkDigest(String),
+    #[error("gossip topic has unknown name `{0}`")]
+    UnknownName(String),
+    #[error("gossip topic has unsupported encoding `{0}`")]
+    UnsupportedEncoding(String),
+}
+
+#[derive(Debug, Error)]
+pub enum ReqRespError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("length prefix is not a valid varint")]
+    InvalidLengthPrefix,
+    #[error("chunk has length {length} but the maximum chunk size is {limit}")]
+    ChunkTooLarge { length: usize, limit: usize },
+    #[error("response chunk is missing its context bytes")]
+    MissingContextBytes,
+    #[error("context bytes {0:?} do not match the fork digest of any known fork")]
+    UnknownForkDigest(ForkDigest),
+    #[error("request for {count} items exceeds the limit of {limit} items")]
+    RequestTooLarge { count: u64, limit: u64 },
+    #[error("request has invalid step {0}")]
+    InvalidStep(u64),
+    #[error("peer responded with error code {code}: {message}")]
+    ErrorResponse { code: u8, message: String },
+}
@@ -0,0 +1,423 @@
+//! Honest validator voting on the `Eth1Data` of blocks, from a local cache of the eth1 chain and
+//! the deposits made to the deposit contract.
+use crate::{
+    deposit_tree::DepositTree,
+    error::Eth1Error,
+    phase0::{Deposit, DepositData, Eth1Block, Eth1Data},
+    primitives::{Hash32, Slot},
+    state_transition::Context,
+    types::BeaconState,
+    Error,
+};
+
+/// The eth1 blocks and deposits a node has seen, which a proposer takes the `eth1_data` vote and
+/// the deposits of a block from.
+///
+/// Blocks are inserted in order of block number and deposits in order of deposit index, which is
+/// the order the deposit contract logs them in.
+#[derive(Debug, Clone, Default)]
+pub struct Eth1Cache {
+    blocks: Vec<(Hash32, Eth1Block)>,
+    deposits: Vec<DepositData>,
+    deposit_tree: DepositTree,
+}
+
+impl Eth1Cache {
+    /// Adds the eth1 block with `block_hash` after the blocks already in the cache.
+    pub fn insert_block(&mut self, block_hash: Hash32, block: Eth1Block) -> Result<(), Error> {
+        if let Some((_, latest)) = self.blocks.last() {
+            if block.timestamp < latest.timestamp {
+                return Err(Eth1Error::OutOfOrderBlock {
+                    timestamp: block.timestamp,
+                    latest_timestamp: latest.timestamp,
+                }
+                .into())
+            }
+        }
+        self.blocks.push((block_hash, block));
+        Ok(())
+    }
+
+    /// Adds the deposit with `index`, which must be the next deposit after those in the cache.
+    pub fn insert_deposit(
+        &mut self,
+        index: u64,
+        mut deposit_data: DepositData,
+    ) -> Result<(), Error> {
+        let deposit_count = self.deposits.len() as u64;
+        if index != deposit_count {
+            return Err(Eth1Error::OutOfOrderDeposit { index, deposit_count }.into())
+        }
+        self.deposit_tree.push_deposit_data(&mut deposit_data)?;
+        self.deposits.push(deposit_data);
+        Ok(())
+    }
+
+    /// Drops the blocks older than `timestamp`, which can no longer be voted for once every
+    /// voting period starts after `timestamp` plus twice the follow distance.
+    pub fn prune_blocks(&mut self, timestamp: u64) {
+        self.blocks.retain(|(_, block)| block.timestamp >= timestamp);
+    }
+
+    pub fn deposit_tree(&self) -> &DepositTree {
+        &self.deposit_tree
+    }
+
+    /// Returns the deposits a block on top of `state` must include, with proofs against
+    /// `eth1_data`, which is the `eth1_data` of the state once the block's vote is processed.
+    pub fn get_deposits<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+    >(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        eth1_data: &Eth1Data,
+        context: &Context,
+    ) -> Result<Vec<Deposit>, Error> {
+        let start = state.eth1_deposit_index();
+        let end = eth1_data.deposit_count.min(start + context.max_deposits as u64);
+        (start..end)
+            .map(|index| {
+                let data = self.deposits.get(index as usize).ok_or(Eth1Error::UnknownDeposit {
+                    index,
+                    deposit_count: self.deposits.len() as u64,
+                })?;
+                let proof = self.deposit_tree.get_proof(index, eth1_data.deposit_count)?;
+                Ok(Deposit { proof, data: data.clone() })
+            })
+            .collect()
+    }
+}
+
+pub fn compute_time_at_slot<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    context: &Context,
+) -> u64 {
+    state.genesis_time() + slot * context.seconds_per_slot
+}
+
+pub fn voting_period_start_time<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    context: &Context,
+) -> u64 {
+    let slots_per_voting_period = context.epochs_per_eth1_voting_period * context.slots_per_epoch;
+    let eth1_voting_period_start_slot = state.slot() - state.slot() % slots_per_voting_period;
+    compute_time_at_slot(state, eth1_voting_period_start_slot, context)
+}
+
+pub fn is_candidate_block(block: &Eth1Block, period_start: u64, context: &Context) -> bool {
+    let follow_time = context.seconds_per_eth1_block * context.eth1_follow_distance;
+    block.timestamp + follow_time <= period_start &&
+        block.timestamp + follow_time * 2 >= period_start
+}
+
+/// Returns the `Eth1Data` of `block`, whose hash is `block_hash`.
+///
+/// NOTE: the spec stubs this function, taking the `hash_tree_root` of `block` as its hash.
+pub fn get_eth1_data(block_hash: &Hash32, block: &Eth1Block) -> Eth1Data {
+    Eth1Data {
+        deposit_root: block.deposit_root,
+        deposit_count: block.deposit_count,
+        block_hash: block_hash.clone(),
+    }
+}
+
+pub fn get_eth1_vote<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    eth1_chain: &Eth1Cache,
+    context: &Context,
+) -> Eth1Data {
+    let period_start = voting_period_start_time(state, context);
+    let votes_to_consider = eth1_chain
+        .blocks
+        .iter()
+        .filter(|(_, block)| {
+            is_candidate_block(block, period_start, context) &&
+                // Ensure cannot move back to earlier deposit contract states
+                block.deposit_count >= state.eth1_data().deposit_count
+        })
+        .map(|(block_hash, block)| get_eth1_data(block_hash, block))
+        .collect::<Vec<_>>();
+
+    // Tally the valid votes already cast during this period, in order of their first vote
+    let mut tallies: Vec<(&Eth1Data, usize)> = vec![];
+    for vote in state.eth1_data_votes().iter().filter(|vote| votes_to_consider.contains(*vote)) {
+        match tallies.iter_mut().find(|(tallied, _)| *tallied == vote) {
+            Some((_, count)) => *count += 1,
+            None => tallies.push((vote, 1)),
+        }
+    }
+
+    // Default vote on latest eth1 block data in the period range unless eth1 chain is not live
+    let default_vote = votes_to_consider.last().unwrap_or(state.eth1_data());
+    // NOTE: `max_by_key` returns the last maximum, so iterate in reverse to tiebreak by the
+    // earliest vote
+    tallies
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(vote, _)| *vote)
+        .unwrap_or(default_vote)
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        phase0::{self, DEPOSIT_CONTRACT_TREE_DEPTH},
+        primitives::Root,
+        ssz::prelude::*,
+        types::minimal,
+    };
+
+    const GENESIS_TIME: u64 = 1_000_000;
+
+    fn hash(seed: u64) -> Hash32 {
+        let mut data = [0u8; 32];
+        data[..8].copy_from_slice(&seed.to_le_bytes());
+        Hash32::try_from(data.as_ref()).unwrap()
+    }
+
+    fn state(slot: Slot, eth1_data: Eth1Data, votes: Vec<Eth1Data>) -> minimal::BeaconState {
+        minimal::BeaconState::Phase0(phase0::minimal::BeaconState {
+            genesis_time: GENESIS_TIME,
+            slot,
+            eth1_data,
+            eth1_data_votes: List::try_from(votes).unwrap(),
+            ..Default::default()
+        })
+    }
+
+    // Returns a cache with an eth1 block every `seconds_per_eth1_block` up to genesis, with one
+    // more deposit in each block.
+    fn eth1_chain(context: &Context) -> Eth1Cache {
+        let mut cache = Eth1Cache::default();
+        let block_count = 4 * context.eth1_follow_distance;
+        let start = GENESIS_TIME - block_count * context.seconds_per_eth1_block;
+        for number in 0..block_count {
+            let block = Eth1Block {
+                timestamp: start + number * context.seconds_per_eth1_block,
+                deposit_root: Root::try_from(hash(number).as_ref()).unwrap(),
+                deposit_count: number,
+            };
+            cache.insert_block(hash(number), block).unwrap();
+        }
+        cache
+    }
+
+    #[test]
+    fn test_is_candidate_block() {
+        let context = Context::for_minimal();
+        let follow_time = context.seconds_per_eth1_block * context.eth1_follow_distance;
+        let period_start = GENESIS_TIME;
+        let block = |timestamp| Eth1Block { timestamp, ..Default::default() };
+        assert!(is_candidate_block(&block(period_start - follow_time), period_start, &context));
+        assert!(is_candidate_block(&block(period_start - 2 * follow_time), period_start, &context));
+        assert!(!is_candidate_block(
+            &block(period_start - follow_time + 1),
+            period_start,
+            &context
+        ));
+        assert!(!is_candidate_block(
+            &block(period_start - 2 * follow_time - 1),
+            period_start,
+            &context
+        ));
+    }
+
+    #[test]
+    fn test_get_eth1_vote() {
+        let context = Context::for_minimal();
+        let cache = eth1_chain(&context);
+        let slots_per_voting_period =
+            context.epochs_per_eth1_voting_period * context.slots_per_epoch;
+        let slot = slots_per_voting_period + 3;
+        let period_start = GENESIS_TIME + slots_per_voting_period * context.seconds_per_slot;
+        assert_eq!(
+            voting_period_start_time(&state(slot, Default::default(), vec![]), &context),
+            period_start
+        );
+
+        let candidates = cache
+            .blocks
+            .iter()
+            .filter(|(_, block)| is_candidate_block(block, period_start, &context))
+            .map(|(block_hash, block)| get_eth1_data(block_hash, block))
+            .collect::<Vec<_>>();
+        assert!(candidates.len() > 5);
+        let latest = candidates.last().unwrap().clone();
+        let (first, second) = (candidates[2].clone(), candidates[5].clone());
+        let non_candidate = get_eth1_data(&cache.blocks[0].0, &cache.blocks[0].1);
+
+        // without votes, the latest candidate
+        let vote = get_eth1_vote(&state(slot, Default::default(), vec![]), &cache, &context);
+        assert_eq!(vote, latest);
+
+        // the candidate with the most votes, ignoring votes for other blocks
+        let votes = vec![
+            second.clone(),
+            non_candidate.clone(),
+            first.clone(),
+            non_candidate.clone(),
+            first.clone(),
+            non_candidate,
+        ];
+        let vote = get_eth1_vote(&state(slot, Default::default(), votes), &cache, &context);
+        assert_eq!(vote, first);
+
+        // ties go to the candidate voted for first
+        let votes = vec![second.clone(), first.clone(), first, second.clone()];
+        let vote = get_eth1_vote(&state(slot, Default::default(), votes), &cache, &context);
+        assert_eq!(vote, second);
+
+        // candidates with fewer deposits than the state are ignored
+        let eth1_data = Eth1Data { deposit_count: latest.deposit_count + 1, ..Default::default() };
+        let vote = get_eth1_vote(&state(slot, eth1_data.clone(), vec![second]), &cache, &context);
+        assert_eq!(vote, eth1_data);
+    }
+
+    #[test]
+    fn test_get_deposits() {
+        let context = Context::for_minimal();
+        let mut cache = Eth1Cache::default();
+        for index in 0..5 {
+            let deposit_data = DepositData { amount: index + 1, ..Default::default() };
+            cache.insert_deposit(index, deposit_data).unwrap();
+        }
+        assert!(cache.insert_deposit(6, Default::default()).is_err());
+
+        let eth1_data = Eth1Data {
+            deposit_root: cache.deposit_tree().root_at(4).unwrap(),
+            deposit_count: 4,
+            ..Default::default()
+        };
+        let mut state = state(0, eth1_data.clone(), vec![]);
+        if let minimal::BeaconState::Phase0(state) = &mut state {
+            state.eth1_deposit_index = 1;
+        }
+        let deposits = cache.get_deposits(&state, &eth1_data, &context).unwrap();
+        assert_eq!(deposits.len(), 3);
+        for (index, mut deposit) in (1..).zip(deposits) {
+            assert_eq!(deposit.data.amount, index as u64 + 1);
+            let leaf = deposit.data.hash_tree_root().unwrap();
+            let branch = deposit
+                .proof
+                .iter()
+                .map(|node| Root::try_from(node.as_ref()).unwrap())
+                .collect::<Vec<_>>();
+            assert!(is_valid_merkle_branch(
+                &leaf,
+                branch.iter(),
+                DEPOSIT_CONTRACT_TREE_DEPTH + 1,
+                index,
+                &eth1_data.deposit_root,
+            ));
+        }
+
+        // the vote is for deposits the cache has not seen yet
+        let eth1_data = Eth1Data { deposit_count: 7, ..eth1_data };
+        if let minimal::BeaconState::Phase0(state) = &mut state {
+            state.eth1_deposit_index = 5;
+        }
+        let result = cache.get_deposits(&state, &eth1_data, &context);
+        assert!(matches!(
+            result,
+            Err(Error::Eth1(Eth1Error::UnknownDeposit { index: 5, deposit_count: 5 }))
+        ));
+    }
+}
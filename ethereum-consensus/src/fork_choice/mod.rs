@@ -0,0 +1,14 @@
+mod presets;
+mod proto_array;
+mod store;
+
+pub use proto_array::*;
+pub use store::*;
+
+pub mod mainnet {
+    pub use super::presets::mainnet::*;
+}
+
+pub mod minimal {
+    pub use super::presets::minimal::*;
+}
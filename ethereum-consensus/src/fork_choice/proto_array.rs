@@ -0,0 +1,164 @@
+//! A "proto-array" representation of the block tree.
+//!
+//! Blocks are kept in a flat list in insertion order so that every parent precedes its children.
+//! This allows weights to be propagated and heads to be found with a single backwards sweep
+//! rather than the repeated tree walks of the spec's `get_weight` and `get_head`.
+use crate::{
+    error::ForkChoiceError,
+    phase0::Checkpoint,
+    primitives::{Gwei, Root, Slot},
+    state_transition::Result,
+    Error,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct ProtoNode {
+    pub root: Root,
+    pub parent: Option<usize>,
+    pub slot: Slot,
+    // `current_justified_checkpoint` of the block's post-state
+    pub justified_checkpoint: Checkpoint,
+    // `finalized_checkpoint` of the block's post-state
+    pub finalized_checkpoint: Checkpoint,
+    pub weight: Gwei,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ProtoArray {
+    nodes: Vec<ProtoNode>,
+    indices: HashMap<Root, usize>,
+}
+
+impl ProtoArray {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, root: &Root) -> bool {
+        self.indices.contains_key(root)
+    }
+
+    pub fn index_of(&self, root: &Root) -> Option<usize> {
+        self.indices.get(root).copied()
+    }
+
+    pub fn get(&self, root: &Root) -> Option<&ProtoNode> {
+        self.index_of(root).map(|index| &self.nodes[index])
+    }
+
+    pub fn nodes(&self) -> &[ProtoNode] {
+        &self.nodes
+    }
+
+    // Adds a block to the tree. The first block inserted is the anchor of the tree,
+    // every later block must have its parent in the tree already.
+    pub fn insert(
+        &mut self,
+        root: Root,
+        parent_root: Root,
+        slot: Slot,
+        justified_checkpoint: Checkpoint,
+        finalized_checkpoint: Checkpoint,
+    ) -> Result<()> {
+        if self.contains(&root) {
+            return Ok(())
+        }
+        let parent = self.index_of(&parent_root);
+        if parent.is_none() && !self.is_empty() {
+            return Err(ForkChoiceError::UnknownBlock(parent_root).into())
+        }
+        let node =
+            ProtoNode { root, parent, slot, justified_checkpoint, finalized_checkpoint, weight: 0 };
+        self.indices.insert(root, self.nodes.len());
+        self.nodes.push(node);
+        Ok(())
+    }
+
+    // Applies the per-node `deltas` to the weight of each node and all of its ancestors.
+    pub fn apply_score_changes(&mut self, mut deltas: Vec<i64>) -> Result<()> {
+        if deltas.len() != self.nodes.len() {
+            return Err(Error::OutOfBounds { requested: deltas.len(), bound: self.nodes.len() })
+        }
+        for index in (0..self.nodes.len()).rev() {
+            let delta = deltas[index];
+            let node = &mut self.nodes[index];
+            node.weight = if delta < 0 {
+                node.weight.checked_sub(delta.unsigned_abs()).ok_or(Error::Underflow)?
+            } else {
+                node.weight.checked_add(delta as u64).ok_or(Error::Overflow)?
+            };
+            if let Some(parent) = node.parent {
+                deltas[parent] = deltas[parent].checked_add(delta).ok_or(Error::Overflow)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Returns the root of the ancestor of `root` at (or most recently before) `slot`,
+    // following the spec's `get_ancestor`. Returns `None` if the walk leaves the tree.
+    pub fn ancestor(&self, root: &Root, slot: Slot) -> Option<Root> {
+        let mut index = self.index_of(root)?;
+        loop {
+            let node = &self.nodes[index];
+            if node.slot <= slot {
+                return Some(node.root)
+            }
+            index = node.parent?;
+        }
+    }
+
+    // Finds the head of the chain starting from `justified_root`.
+    //
+    // Mirrors the spec's `get_filtered_block_tree`: a leaf is kept if `is_viable` accepts it
+    // and an inner node is kept if any of its children are kept. The head is found by
+    // following the kept child with the greatest `(weight, root)` from the justified block.
+    pub fn find_head<F: Fn(&ProtoNode) -> bool>(
+        &self,
+        justified_root: &Root,
+        is_viable: F,
+    ) -> Result<Root> {
+        let justified_index =
+            self.index_of(justified_root).ok_or(ForkChoiceError::UnknownBlock(*justified_root))?;
+
+        let count = self.nodes.len();
+        let mut has_children = vec![false; count];
+        let mut is_kept = vec![false; count];
+        let mut best_child: Vec<Option<usize>> = vec![None; count];
+        for index in (justified_index..count).rev() {
+            let node = &self.nodes[index];
+            if !has_children[index] {
+                is_kept[index] = is_viable(node);
+            }
+            if index == justified_index {
+                break
+            }
+            let Some(parent) = node.parent else { continue };
+            has_children[parent] = true;
+            if !is_kept[index] {
+                continue
+            }
+            is_kept[parent] = true;
+            let is_better = match best_child[parent] {
+                Some(best) => {
+                    let best = &self.nodes[best];
+                    (node.weight, node.root.as_ref()) > (best.weight, best.root.as_ref())
+                }
+                None => true,
+            };
+            if is_better {
+                best_child[parent] = Some(index);
+            }
+        }
+
+        let mut head = justified_index;
+        while let Some(child) = best_child[head] {
+            head = child;
+        }
+        Ok(self.nodes[head].root)
+    }
+}
@@ -0,0 +1,941 @@
+//! The fork choice `Store` combining LMD-GHOST with Casper FFG as specified in
+//! the consensus specs' `fork-choice.md` (including the Bellatrix merge transition checks).
+//!
+//! The fork choice follows the version of the specs pinned for the spec tests (see
+//! `test-gen/spec-test-version`), so justification is updated through `best_justified_checkpoint`
+//! and `SAFE_SLOTS_TO_UPDATE_JUSTIFIED` rather than by pulling up unrealized justification.
+//! `on_attester_slashing` and the Deneb data availability check follow later versions.
+//!
+//! The block tree is tracked with a `ProtoArray` so that `get_head` does not need to
+//! walk the tree for every candidate; weights are updated incrementally from the
+//! changes in latest messages, justified balances and proposer boost since the last call.
+use crate::{
+    altair, bellatrix,
+    bellatrix::PowBlock,
+    capella, deneb,
+    error::{invalid_operation_error, ForkChoiceError, InvalidAttesterSlashing, InvalidOperation},
+    fork_choice::proto_array::{ProtoArray, ProtoNode},
+    phase0::{
+        self, compute_epoch_at_slot, compute_start_slot_at_epoch, is_active_validator,
+        is_slashable_attestation_data, Attestation, AttesterSlashing, BeaconBlockHeader,
+        Checkpoint, IndexedAttestation,
+    },
+    primitives::{Epoch, Gwei, Hash32, Root, Slot, ValidatorIndex, GENESIS_EPOCH, GENESIS_SLOT},
+    ssz::prelude::*,
+    state_transition::{
+        BeaconState, Context, ExecutionEngine, Executor, Result, SignatureSets, SignedBeaconBlock,
+    },
+};
+use std::collections::{HashMap, HashSet};
+
+pub const INTERVALS_PER_SLOT: u64 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatestMessage {
+    pub epoch: Epoch,
+    pub root: Root,
+}
+
+#[derive(Debug)]
+pub struct Store<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    B: bellatrix::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+    >,
+    C: capella::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+    D: deneb::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+> {
+    pub time: u64,
+    pub genesis_time: u64,
+    pub justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+    pub best_justified_checkpoint: Checkpoint,
+    pub proposer_boost_root: Root,
+    pub equivocating_indices: HashSet<ValidatorIndex>,
+    pub block_states: HashMap<
+        Root,
+        BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >,
+    pub checkpoint_states: HashMap<
+        Checkpoint,
+        BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >,
+    pub latest_messages: HashMap<ValidatorIndex, LatestMessage>,
+    // Proof-of-work blocks consulted when validating the merge transition block,
+    // standing in for the spec's `get_pow_block`.
+    pub pow_blocks: HashMap<Hash32, PowBlock>,
+    // Roots of the Deneb blocks whose blobs the caller has retrieved and verified, standing in
+    // for the spec's `is_data_available`. Blocks committing to blobs are rejected until added.
+    pub available_blobs: HashSet<Root>,
+    proto_array: ProtoArray,
+    // The votes and proposer boost currently reflected in the weights of `proto_array`.
+    applied_votes: HashMap<ValidatorIndex, (Root, Gwei)>,
+    applied_proposer_boost: Option<(Root, Gwei)>,
+    // Drives the state transition for new blocks; its `state` is only used as scratch space.
+    executor: Executor<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        B,
+        C,
+        D,
+    >,
+}
+
+impl<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+        B: bellatrix::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+        >,
+        C: capella::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
+        D: deneb::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
+    >
+    Store<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        B,
+        C,
+        D,
+    >
+{
+    // Follows `get_forkchoice_store` from the spec.
+    pub fn new(
+        mut anchor_state: BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        anchor_block: &BeaconBlockHeader,
+        execution_engine: ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            B,
+            C,
+            D,
+        >,
+        context: Context,
+    ) -> Result<Self> {
+        let state_root = Self::state_root(&mut anchor_state)?;
+        if anchor_block.state_root != state_root {
+            return Err(ForkChoiceError::InvalidAnchor {
+                provided: anchor_block.state_root,
+                expected: state_root,
+            }
+            .into())
+        }
+        let anchor_root = anchor_block.clone().hash_tree_root()?;
+        let anchor_epoch = compute_epoch_at_slot(anchor_state.slot(), &context);
+        let justified_checkpoint = Checkpoint { epoch: anchor_epoch, root: anchor_root };
+        let finalized_checkpoint = justified_checkpoint.clone();
+        let genesis_time = anchor_state.genesis_time();
+        let time = genesis_time + context.seconds_per_slot * anchor_state.slot();
+
+        let mut proto_array = ProtoArray::default();
+        proto_array.insert(
+            anchor_root,
+            anchor_block.parent_root,
+            anchor_block.slot,
+            anchor_state.current_justified_checkpoint().clone(),
+            anchor_state.finalized_checkpoint().clone(),
+        )?;
+
+        Ok(Self {
+            time,
+            genesis_time,
+            justified_checkpoint: justified_checkpoint.clone(),
+            finalized_checkpoint,
+            best_justified_checkpoint: justified_checkpoint.clone(),
+            proposer_boost_root: Root::default(),
+            equivocating_indices: Default::default(),
+            block_states: HashMap::from_iter([(anchor_root, anchor_state.clone())]),
+            checkpoint_states: HashMap::from_iter([(
+                justified_checkpoint.clone(),
+                anchor_state.clone(),
+            )]),
+            latest_messages: Default::default(),
+            pow_blocks: Default::default(),
+            available_blobs: Default::default(),
+            proto_array,
+            applied_votes: Default::default(),
+            applied_proposer_boost: None,
+            executor: Executor::new(anchor_state, execution_engine, context),
+        })
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.executor.context
+    }
+
+    pub fn get_current_slot(&self) -> Slot {
+        GENESIS_SLOT + self.time.saturating_sub(self.genesis_time) / self.context().seconds_per_slot
+    }
+
+    pub fn get_current_store_epoch(&self) -> Epoch {
+        compute_epoch_at_slot(self.get_current_slot(), self.context())
+    }
+
+    pub fn get_ancestor(&self, root: &Root, slot: Slot) -> Option<Root> {
+        self.proto_array.ancestor(root, slot)
+    }
+
+    pub fn get_checkpoint_block(&self, root: &Root, epoch: Epoch) -> Option<Root> {
+        let epoch_first_slot = compute_start_slot_at_epoch(epoch, self.context());
+        self.get_ancestor(root, epoch_first_slot)
+    }
+
+    pub fn contains_block(&self, root: &Root) -> bool {
+        self.proto_array.contains(root)
+    }
+
+    pub fn proto_array(&self) -> &ProtoArray {
+        &self.proto_array
+    }
+
+    pub fn get_head(&mut self) -> Result<Root> {
+        let justified_checkpoint = self.justified_checkpoint.clone();
+        self.store_target_checkpoint_state(&justified_checkpoint)?;
+        let context = &self.executor.context;
+        let justified_state = &self.checkpoint_states[&justified_checkpoint];
+        let balances = get_justified_balances(justified_state, context);
+
+        let mut deltas = vec![0i64; self.proto_array.len()];
+        for (index, message) in &self.latest_messages {
+            let vote = if self.equivocating_indices.contains(index) {
+                None
+            } else {
+                Some((message.root, balances.get(*index).copied().unwrap_or_default()))
+            };
+            let applied_vote = self.applied_votes.get(index).copied();
+            if vote == applied_vote {
+                continue
+            }
+            if let Some((root, balance)) = applied_vote {
+                if let Some(node) = self.proto_array.index_of(&root) {
+                    deltas[node] -= balance as i64;
+                }
+            }
+            match vote {
+                Some((root, balance)) => {
+                    if let Some(node) = self.proto_array.index_of(&root) {
+                        deltas[node] += balance as i64;
+                    }
+                    self.applied_votes.insert(*index, (root, balance));
+                }
+                None => {
+                    self.applied_votes.remove(index);
+                }
+            }
+        }
+
+        let proposer_boost = if self.proposer_boost_root == Root::default() {
+            None
+        } else {
+            let committee_weight = get_committee_weight(justified_state, context);
+            let proposer_score = committee_weight * context.proposer_score_boost / 100;
+            Some((self.proposer_boost_root, proposer_score))
+        };
+        if proposer_boost != self.applied_proposer_boost {
+            if let Some((root, score)) = self.applied_proposer_boost {
+                if let Some(node) = self.proto_array.index_of(&root) {
+                    deltas[node] -= score as i64;
+                }
+            }
+            if let Some((root, score)) = proposer_boost {
+                if let Some(node) = self.proto_array.index_of(&root) {
+                    deltas[node] += score as i64;
+                }
+            }
+            self.applied_proposer_boost = proposer_boost;
+        }
+        self.proto_array.apply_score_changes(deltas)?;
+
+        let justified_checkpoint = &self.justified_checkpoint;
+        let finalized_checkpoint = &self.finalized_checkpoint;
+        // Follows `filter_block_tree` from the spec for each leaf of the block tree.
+        let is_viable_for_head = |node: &ProtoNode| {
+            let is_correct_justified = justified_checkpoint.epoch == GENESIS_EPOCH ||
+                &node.justified_checkpoint == justified_checkpoint;
+            let is_correct_finalized = finalized_checkpoint.epoch == GENESIS_EPOCH ||
+                &node.finalized_checkpoint == finalized_checkpoint;
+            is_correct_justified && is_correct_finalized
+        };
+        self.proto_array.find_head(&justified_checkpoint.root, is_viable_for_head)
+    }
+
+    // Follows `should_update_justified_checkpoint` from the spec: late in an epoch, the justified
+    // checkpoint only moves to a descendant of the current one so that it cannot be used to
+    // bounce the head between conflicting chains.
+    fn should_update_justified_checkpoint(&self, new_justified_checkpoint: &Checkpoint) -> bool {
+        let context = self.context();
+        let slots_since_epoch_start = self.get_current_slot() % context.slots_per_epoch;
+        if slots_since_epoch_start < context.safe_slots_to_update_justified {
+            return true
+        }
+        let justified_slot = compute_start_slot_at_epoch(self.justified_checkpoint.epoch, context);
+        self.get_ancestor(&new_justified_checkpoint.root, justified_slot) ==
+            Some(self.justified_checkpoint.root)
+    }
+
+    pub fn on_tick(&mut self, time: u64) {
+        let previous_slot = self.get_current_slot();
+
+        self.time = time;
+
+        let current_slot = self.get_current_slot();
+        if current_slot > previous_slot {
+            self.proposer_boost_root = Root::default();
+        }
+
+        // Update the justified checkpoint on the first tick of an epoch
+        if current_slot <= previous_slot || current_slot % self.context().slots_per_epoch != 0 {
+            return
+        }
+        if self.best_justified_checkpoint.epoch > self.justified_checkpoint.epoch {
+            let finalized_slot =
+                compute_start_slot_at_epoch(self.finalized_checkpoint.epoch, self.context());
+            let ancestor_at_finalized_slot =
+                self.get_ancestor(&self.best_justified_checkpoint.root, finalized_slot);
+            if ancestor_at_finalized_slot == Some(self.finalized_checkpoint.root) {
+                self.justified_checkpoint = self.best_justified_checkpoint.clone();
+            }
+        }
+    }
+
+    // NOTE: following the spec, the attestations and attester slashings included in the block
+    // are not applied here; callers should forward them to `on_attestation` (with
+    // `is_from_block` set) and `on_attester_slashing` once the block has been accepted.
+    pub fn on_block(
+        &mut self,
+        signed_block: &mut SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+    ) -> Result<()> {
+        let block_slot = signed_block.slot();
+        let parent_root = signed_block.parent_root();
+        let pre_state = self
+            .block_states
+            .get(&parent_root)
+            .ok_or(ForkChoiceError::UnknownBlock(parent_root))?;
+
+        let current_slot = self.get_current_slot();
+        if current_slot < block_slot {
+            return Err(ForkChoiceError::FutureBlock { block_slot, current_slot }.into())
+        }
+
+        let finalized_slot =
+            compute_start_slot_at_epoch(self.finalized_checkpoint.epoch, self.context());
+        if block_slot <= finalized_slot {
+            return Err(ForkChoiceError::BlockBeforeFinalized { block_slot, finalized_slot }.into())
+        }
+
+        let finalized_checkpoint_block =
+            self.get_checkpoint_block(&parent_root, self.finalized_checkpoint.epoch);
+        if finalized_checkpoint_block != Some(self.finalized_checkpoint.root) {
+            return Err(ForkChoiceError::NotDescendantOfFinalized {
+                parent_root,
+                finalized: self.finalized_checkpoint.clone(),
+            }
+            .into())
+        }
+
+        let block_root = signed_block.message_root()?;
+        let mut state = pre_state.clone();
+        std::mem::swap(&mut self.executor.state, &mut state);
+        let result = self.executor.apply_block(signed_block);
+        std::mem::swap(&mut self.executor.state, &mut state);
+        result?;
+
+        if let SignedBeaconBlock::Deneb(signed_block) = signed_block {
+            let blob_kzg_commitments = &signed_block.message.body.blob_kzg_commitments;
+            if !blob_kzg_commitments.is_empty() && !self.available_blobs.contains(&block_root) {
+                return Err(ForkChoiceError::DataUnavailable(block_root).into())
+            }
+        }
+
+        if let SignedBeaconBlock::Bellatrix(signed_block) = signed_block {
+            let block = &signed_block.message;
+            let is_merge_transition_block = match pre_state {
+                BeaconState::Bellatrix(pre_state) => {
+                    bellatrix::is_merge_transition_block(pre_state, &block.body)
+                }
+                // the block upgrades its parent's state so the transition cannot be complete yet
+                _ => block.body.execution_payload != bellatrix::ExecutionPayload::default(),
+            };
+            if is_merge_transition_block {
+                let get_pow_block = |block_hash: &Hash32| self.pow_blocks.get(block_hash).cloned();
+                bellatrix::validate_merge_block(block, get_pow_block, &self.executor.context)?;
+            }
+        }
+
+        let seconds_per_slot = self.context().seconds_per_slot;
+        let time_into_slot = (self.time - self.genesis_time) % seconds_per_slot;
+        let is_before_attesting_interval = time_into_slot < seconds_per_slot / INTERVALS_PER_SLOT;
+        if current_slot == block_slot && is_before_attesting_interval {
+            self.proposer_boost_root = block_root;
+        }
+
+        let justified_checkpoint = state.current_justified_checkpoint().clone();
+        let finalized_checkpoint = state.finalized_checkpoint().clone();
+        self.proto_array.insert(
+            block_root,
+            parent_root,
+            block_slot,
+            justified_checkpoint.clone(),
+            finalized_checkpoint.clone(),
+        )?;
+        self.block_states.insert(block_root, state);
+
+        if justified_checkpoint.epoch > self.justified_checkpoint.epoch {
+            if justified_checkpoint.epoch > self.best_justified_checkpoint.epoch {
+                self.best_justified_checkpoint = justified_checkpoint.clone();
+            }
+            if self.should_update_justified_checkpoint(&justified_checkpoint) {
+                self.justified_checkpoint = justified_checkpoint.clone();
+            }
+        }
+        if finalized_checkpoint.epoch > self.finalized_checkpoint.epoch {
+            self.finalized_checkpoint = finalized_checkpoint;
+            self.justified_checkpoint = justified_checkpoint;
+        }
+        Ok(())
+    }
+
+    fn store_target_checkpoint_state(&mut self, target: &Checkpoint) -> Result<()> {
+        if self.checkpoint_states.contains_key(target) {
+            return Ok(())
+        }
+        let mut base_state = self
+            .block_states
+            .get(&target.root)
+            .ok_or(ForkChoiceError::UnknownBlock(target.root))?
+            .clone();
+        let target_slot = compute_start_slot_at_epoch(target.epoch, self.context());
+        if base_state.slot() < target_slot {
+            Self::process_slots(&mut base_state, target_slot, self.context())?;
+        }
+        self.checkpoint_states.insert(target.clone(), base_state);
+        Ok(())
+    }
+
+    fn validate_on_attestation(
+        &self,
+        attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+        is_from_block: bool,
+    ) -> Result<()> {
+        let data = &attestation.data;
+        let target = &data.target;
+
+        // Attestations received via a block may be from past epochs.
+        if !is_from_block {
+            let current_epoch = self.get_current_store_epoch();
+            let previous_epoch = current_epoch.saturating_sub(1).max(GENESIS_EPOCH);
+            if target.epoch != current_epoch && target.epoch != previous_epoch {
+                return Err(ForkChoiceError::InvalidAttestationTargetEpoch {
+                    target: target.epoch,
+                    current: current_epoch,
+                }
+                .into())
+            }
+        }
+
+        if target.epoch != compute_epoch_at_slot(data.slot, self.context()) {
+            return Err(ForkChoiceError::AttestationTargetSlotMismatch {
+                target: target.epoch,
+                slot: data.slot,
+            }
+            .into())
+        }
+
+        if !self.contains_block(&target.root) {
+            return Err(ForkChoiceError::UnknownBlock(target.root).into())
+        }
+
+        let block = self
+            .proto_array
+            .get(&data.beacon_block_root)
+            .ok_or(ForkChoiceError::UnknownBlock(data.beacon_block_root))?;
+        if block.slot > data.slot {
+            return Err(ForkChoiceError::AttestationForFutureBlock {
+                attestation_slot: data.slot,
+                block_slot: block.slot,
+            }
+            .into())
+        }
+
+        if self.get_checkpoint_block(&data.beacon_block_root, target.epoch) != Some(target.root) {
+            return Err(ForkChoiceError::InconsistentAttestationTarget {
+                target: target.root,
+                beacon_block_root: data.beacon_block_root,
+            }
+            .into())
+        }
+
+        // Attestations can only affect the fork choice of subsequent slots.
+        let current_slot = self.get_current_slot();
+        if current_slot < data.slot + 1 {
+            return Err(ForkChoiceError::FutureAttestation {
+                attestation_slot: data.slot,
+                current_slot,
+            }
+            .into())
+        }
+        Ok(())
+    }
+
+    fn update_latest_messages(
+        &mut self,
+        attesting_indices: &[ValidatorIndex],
+        attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    ) {
+        let target = &attestation.data.target;
+        let beacon_block_root = attestation.data.beacon_block_root;
+        for index in attesting_indices {
+            if self.equivocating_indices.contains(index) {
+                continue
+            }
+            let is_newer = match self.latest_messages.get(index) {
+                Some(message) => target.epoch > message.epoch,
+                None => true,
+            };
+            if is_newer {
+                let message = LatestMessage { epoch: target.epoch, root: beacon_block_root };
+                self.latest_messages.insert(*index, message);
+            }
+        }
+    }
+
+    pub fn on_attestation(
+        &mut self,
+        attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+        is_from_block: bool,
+    ) -> Result<()> {
+        self.validate_on_attestation(attestation, is_from_block)?;
+
+        let target = &attestation.data.target;
+        self.store_target_checkpoint_state(target)?;
+
+        let target_state = &self.checkpoint_states[target];
+        let context = &self.executor.context;
+        let mut indexed_attestation =
+            Self::get_indexed_attestation(target_state, attestation, context)?;
+        Self::is_valid_indexed_attestation(target_state, &mut indexed_attestation, context)?;
+
+        self.update_latest_messages(&indexed_attestation.attesting_indices, attestation);
+        Ok(())
+    }
+
+    pub fn on_attester_slashing(
+        &mut self,
+        attester_slashing: &AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>,
+    ) -> Result<()> {
+        let attestation_1 = &attester_slashing.attestation_1;
+        let attestation_2 = &attester_slashing.attestation_2;
+        if !is_slashable_attestation_data(&attestation_1.data, &attestation_2.data) {
+            return Err(invalid_operation_error(InvalidOperation::AttesterSlashing(
+                InvalidAttesterSlashing::NotSlashable(
+                    Box::new(attestation_1.data.clone()),
+                    Box::new(attestation_2.data.clone()),
+                ),
+            )))
+        }
+
+        let root = self.justified_checkpoint.root;
+        let state = self.block_states.get(&root).ok_or(ForkChoiceError::UnknownBlock(root))?;
+        let context = &self.executor.context;
+        Self::is_valid_indexed_attestation(state, &mut attestation_1.clone(), context)?;
+        Self::is_valid_indexed_attestation(state, &mut attestation_2.clone(), context)?;
+
+        let indices = HashSet::<_>::from_iter(attestation_1.attesting_indices.iter().copied());
+        for index in attestation_2.attesting_indices.iter() {
+            if indices.contains(index) {
+                self.equivocating_indices.insert(*index);
+            }
+        }
+        Ok(())
+    }
+
+    fn state_root(
+        state: &mut BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> Result<Root> {
+        let root = match state {
+            BeaconState::Phase0(state) => state.hash_tree_root(),
+            BeaconState::Altair(state) => state.hash_tree_root(),
+            BeaconState::Bellatrix(state) => state.hash_tree_root(),
+            BeaconState::Capella(state) => state.hash_tree_root(),
+            BeaconState::Deneb(state) => state.hash_tree_root(),
+        }?;
+        Ok(root)
+    }
+
+    fn process_slots(
+        state: &mut BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        slot: Slot,
+        context: &Context,
+    ) -> Result<()> {
+        match state {
+            BeaconState::Phase0(state) => phase0::process_slots(state, slot, &mut (), context),
+            BeaconState::Altair(state) => altair::process_slots(state, slot, &mut (), context),
+            BeaconState::Bellatrix(state) => {
+                bellatrix::process_slots(state, slot, &mut (), context)
+            }
+            BeaconState::Capella(state) => capella::process_slots(state, slot, &mut (), context),
+            BeaconState::Deneb(state) => deneb::process_slots(state, slot, &mut (), context),
+        }
+    }
+
+    fn get_indexed_attestation(
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+        context: &Context,
+    ) -> Result<IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>> {
+        match state {
+            BeaconState::Phase0(state) => {
+                phase0::get_indexed_attestation(state, attestation, context)
+            }
+            BeaconState::Altair(state) => {
+                altair::get_indexed_attestation(state, attestation, context)
+            }
+            BeaconState::Bellatrix(state) => {
+                bellatrix::get_indexed_attestation(state, attestation, context)
+            }
+            BeaconState::Capella(state) => {
+                capella::get_indexed_attestation(state, attestation, context)
+            }
+            BeaconState::Deneb(state) => {
+                deneb::get_indexed_attestation(state, attestation, context)
+            }
+        }
+    }
+
+    fn is_valid_indexed_attestation(
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        indexed_attestation: &mut IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>,
+        context: &Context,
+    ) -> Result<()> {
+        let signatures = &mut SignatureSets::default();
+        match state {
+            BeaconState::Phase0(state) => phase0::is_valid_indexed_attestation(
+                state,
+                indexed_attestation,
+                signatures,
+                context,
+            ),
+            BeaconState::Altair(state) => altair::is_valid_indexed_attestation(
+                state,
+                indexed_attestation,
+                signatures,
+                context,
+            ),
+            BeaconState::Bellatrix(state) => bellatrix::is_valid_indexed_attestation(
+                state,
+                indexed_attestation,
+                signatures,
+                context,
+            ),
+            BeaconState::Capella(state) => capella::is_valid_indexed_attestation(
+                state,
+                indexed_attestation,
+                signatures,
+                context,
+            ),
+            BeaconState::Deneb(state) => {
+                deneb::is_valid_indexed_attestation(state, indexed_attestation, signatures, context)
+            }
+        }
+    }
+}
+
+// Returns the effective balance of each active validator in the justified state (zero for all
+// others).
+fn get_justified_balances<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    context: &Context,
+) -> Vec<Gwei> {
+    let current_epoch = compute_epoch_at_slot(state.slot(), context);
+    state
+        .validators()
+        .iter()
+        .map(|validator| {
+            if is_active_validator(validator, current_epoch) {
+                validator.effective_balance
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+// Returns the weight of the average committee of a slot in the justified state, which the proposer
+// boost is a fraction of.
+fn get_committee_weight<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    context: &Context,
+) -> Gwei {
+    let current_epoch = compute_epoch_at_slot(state.slot(), context);
+    let mut validator_count = 0;
+    let mut total_active_balance = 0;
+    for validator in state.validators().iter() {
+        if is_active_validator(validator, current_epoch) {
+            validator_count += 1;
+            total_active_balance += validator.effective_balance;
+        }
+    }
+    if validator_count == 0 {
+        return 0
+    }
+    let total_active_balance = Gwei::max(total_active_balance, context.effective_balance_increment);
+    let average_balance = total_active_balance / validator_count;
+    let committee_size = validator_count / context.slots_per_epoch;
+    committee_size * average_balance
+}
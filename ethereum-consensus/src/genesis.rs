@@ -0,0 +1,874 @@
+//! Genesis states for local networks, built directly from deposit data instead of processing
+//! each deposit with its proof as `initialize_beacon_state_from_eth1` does.
+use crate::{
+    altair, bellatrix, capella,
+    crypto::{hash, verify_signature_sets, SecretKey, SignatureSet},
+    deneb,
+    domains::DomainType,
+    phase0::{
+        self, BeaconBlockHeader, DepositData, DepositMessage, Eth1Data, Validator,
+        DEPOSIT_DATA_LIST_BOUND,
+    },
+    primitives::{
+        BlsPublicKey, Bytes32, Gwei, Hash32, ParticipationFlags, BLS_WITHDRAWAL_PREFIX,
+        FAR_FUTURE_EPOCH, GENESIS_EPOCH, GENESIS_SLOT,
+    },
+    signing::{compute_signing_root, sign_with_domain},
+    ssz::prelude::*,
+    state_transition::Context,
+    types::{BeaconState, ExecutionPayloadHeader},
+    Error, Fork,
+};
+use std::collections::{HashMap, HashSet};
+
+// The order of the BLS12-381 scalar field, big-endian
+const CURVE_ORDER: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// The eth1 block hash of genesis states without an execution payload header.
+pub const GENESIS_ETH1_BLOCK_HASH: [u8; 32] = [0x42; 32];
+
+/// Returns the secret key of the interop validator at `index`, as used by clients to start local
+/// networks: the SHA-256 hash of the 32-byte little-endian `index`, read as a little-endian
+/// integer modulo the curve order.
+pub fn interop_secret_key(index: usize) -> Result<SecretKey, Error> {
+    let mut preimage = [0u8; 32];
+    preimage[..8].copy_from_slice(&(index as u64).to_le_bytes());
+    let mut key = [0u8; 32];
+    key.copy_from_slice(hash(preimage).as_ref());
+    key.reverse();
+    while key >= CURVE_ORDER {
+        let mut borrow = 0;
+        for (byte, order_byte) in key.iter_mut().zip(CURVE_ORDER).rev() {
+            let difference = *byte as i16 - order_byte as i16 - borrow;
+            borrow = (difference < 0) as i16;
+            *byte = (difference + 256 * borrow) as u8;
+        }
+    }
+    Ok(SecretKey::try_from(key.as_ref())?)
+}
+
+/// Returns the secret keys of the first `count` interop validators.
+pub fn interop_secret_keys(count: usize) -> Result<Vec<SecretKey>, Error> {
+    (0..count).map(interop_secret_key).collect()
+}
+
+/// Returns signed deposit data for `amount` to the validator with `secret_key`, withdrawing to
+/// BLS credentials derived from its own public key.
+pub fn make_deposit_data(
+    secret_key: &SecretKey,
+    amount: Gwei,
+    context: &Context,
+) -> Result<DepositData, Error> {
+    let public_key = secret_key.public_key();
+    let mut withdrawal_credentials = hash(public_key.as_ref());
+    withdrawal_credentials[0] = BLS_WITHDRAWAL_PREFIX;
+    let mut message = DepositMessage { public_key, withdrawal_credentials, amount };
+    let domain = phase0::compute_domain(DomainType::Deposit, None, None, context)?;
+    let signature = sign_with_domain(&mut message, secret_key, domain)?;
+    Ok(DepositData {
+        public_key: message.public_key,
+        withdrawal_credentials: message.withdrawal_credentials,
+        amount,
+        signature,
+    })
+}
+
+/// Returns the deposit data of the first `validator_count` interop validators, each depositing
+/// the maximum effective balance.
+pub fn interop_deposit_data(
+    validator_count: usize,
+    context: &Context,
+) -> Result<Vec<DepositData>, Error> {
+    interop_secret_keys(validator_count)?
+        .iter()
+        .map(|key| make_deposit_data(key, context.max_effective_balance, context))
+        .collect()
+}
+
+// Returns whether the signature of each of `deposit_data` is valid. The signatures are verified
+// all at once and only one by one if any of them is invalid.
+fn verify_deposit_signatures(
+    deposit_data: &[DepositData],
+    context: &Context,
+) -> Result<Vec<bool>, Error> {
+    let domain = phase0::compute_domain(DomainType::Deposit, None, None, context)?;
+    let signature_sets = deposit_data
+        .iter()
+        .map(|data| {
+            let mut message = DepositMessage {
+                public_key: data.public_key.clone(),
+                withdrawal_credentials: data.withdrawal_credentials.clone(),
+                amount: data.amount,
+            };
+            let signing_root = compute_signing_root(&mut message, domain)?;
+            // NOTE: the signature of a deposit with an invalid public key is invalid
+            let signature_set = SignatureSet::from_public_key(
+                &data.public_key,
+                signing_root.as_ref(),
+                &data.signature,
+            );
+            Ok(signature_set.ok())
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let candidates = signature_sets.iter().flatten().cloned().collect::<Vec<_>>();
+    if verify_signature_sets(&candidates).is_ok() {
+        return Ok(signature_sets.iter().map(Option::is_some).collect())
+    }
+    Ok(signature_sets
+        .iter()
+        .map(|signature_set| matches!(signature_set, Some(set) if set.verify().is_ok()))
+        .collect())
+}
+
+/// A validator of the genesis state of a network, with its balance at genesis.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GenesisValidator {
+    #[serde(rename = "pubkey")]
+    pub public_key: BlsPublicKey,
+    pub withdrawal_credentials: Bytes32,
+    #[serde(with = "crate::serde::as_str")]
+    pub balance: Gwei,
+}
+
+/// Returns the genesis state of a network whose validators are those deposited by
+/// `deposit_data`, in order, starting at `genesis_time`.
+///
+/// The state is the one `initialize_beacon_state_from_eth1` would return for these deposits at the
+/// fork of `GENESIS_SLOT` in `context`, except that its `genesis_time` is given directly. Unlike
+/// there, a deposit for a new validator with an invalid signature is an error instead of being
+/// skipped, so that no validator is silently missing from the network. As there, top ups of a
+/// validator deposited before need no valid signature. The signatures are verified as a batch.
+///
+/// From Bellatrix, the state has `execution_payload_header` as its latest execution payload
+/// header, or a default one if `None`, and the block hash of the header is the eth1 block hash of
+/// the state. Before Bellatrix, the eth1 block hash is `GENESIS_ETH1_BLOCK_HASH`.
+pub fn initialize_genesis_state<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    deposit_data: &[DepositData],
+    execution_payload_header: Option<
+        ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    >,
+    genesis_time: u64,
+    context: &Context,
+) -> Result<
+    BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    Error,
+> {
+    let is_valid_signature = verify_deposit_signatures(deposit_data, context)?;
+    let mut validators = Vec::<GenesisValidator>::new();
+    let mut validator_indices = HashMap::<BlsPublicKey, usize>::new();
+    for (index, (data, is_valid_signature)) in
+        deposit_data.iter().zip(is_valid_signature).enumerate()
+    {
+        if let Some(&validator_index) = validator_indices.get(&data.public_key) {
+            validators[validator_index].balance += data.amount;
+            continue
+        }
+        if !is_valid_signature {
+            return Err(Error::InvalidDepositSignature {
+                index,
+                public_key: data.public_key.clone(),
+            })
+        }
+        validator_indices.insert(data.public_key.clone(), validators.len());
+        validators.push(GenesisValidator {
+            public_key: data.public_key.clone(),
+            withdrawal_credentials: data.withdrawal_credentials.clone(),
+            balance: data.amount,
+        });
+    }
+    build_genesis_state::<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >(&validators, deposit_data, execution_payload_header, genesis_time, context)
+}
+
+/// Returns the genesis state of a network whose validators are `validators`, in order, starting
+/// at `genesis_time`.
+///
+/// The state is the one `initialize_genesis_state` returns, except that no deposits are made for
+/// the validators: the state has the eth1 data of an empty deposit contract. It is an error if a
+/// public key is given for more than one validator.
+pub fn initialize_genesis_state_from_validators<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    validators: &[GenesisValidator],
+    execution_payload_header: Option<
+        ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    >,
+    genesis_time: u64,
+    context: &Context,
+) -> Result<
+    BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    Error,
+> {
+    let mut public_keys = HashSet::new();
+    for validator in validators {
+        if !public_keys.insert(&validator.public_key) {
+            return Err(Error::DuplicateValidator(validator.public_key.clone()))
+        }
+    }
+    build_genesis_state::<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >(validators, &[], execution_payload_header, genesis_time, context)
+}
+
+// Returns the genesis state with `validators`, whose eth1 data is that of a deposit contract
+// that received `deposit_data`.
+fn build_genesis_state<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    validators: &[GenesisValidator],
+    deposit_data: &[DepositData],
+    execution_payload_header: Option<
+        ExecutionPayloadHeader<BYTES_PER_LOGS_BLOOM, MAX_EXTRA_DATA_BYTES>,
+    >,
+    genesis_time: u64,
+    context: &Context,
+) -> Result<
+    BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    Error,
+> {
+    let fork = context.fork_for(GENESIS_SLOT);
+    if let Some(header) = execution_payload_header.as_ref() {
+        if header.version() != fork {
+            return Err(Error::IncompatibleFork {
+                source_fork: header.version(),
+                destination_fork: fork,
+            })
+        }
+    }
+    let eth1_block_hash = match execution_payload_header.as_ref() {
+        Some(header) => header.block_hash().clone(),
+        None if fork >= Fork::Bellatrix => Hash32::default(),
+        None => Hash32::try_from(GENESIS_ETH1_BLOCK_HASH.as_ref()).expect("is valid instance"),
+    };
+
+    let balances = validators.iter().map(|validator| validator.balance).collect::<Vec<_>>();
+    let mut validators = validators
+        .iter()
+        .map(|validator| Validator {
+            public_key: validator.public_key.clone(),
+            withdrawal_credentials: validator.withdrawal_credentials.clone(),
+            effective_balance: 0,
+            slashed: false,
+            activation_eligibility_epoch: FAR_FUTURE_EPOCH,
+            activation_epoch: FAR_FUTURE_EPOCH,
+            exit_epoch: FAR_FUTURE_EPOCH,
+            withdrawable_epoch: FAR_FUTURE_EPOCH,
+        })
+        .collect::<Vec<_>>();
+    for (validator, &balance) in validators.iter_mut().zip(balances.iter()) {
+        validator.effective_balance = Gwei::min(
+            balance - balance % context.effective_balance_increment,
+            context.max_effective_balance,
+        );
+        if validator.effective_balance == context.max_effective_balance {
+            validator.activation_eligibility_epoch = GENESIS_EPOCH;
+            validator.activation_epoch = GENESIS_EPOCH;
+        }
+    }
+
+    let validator_count = validators.len();
+    let mut validators = List::<Validator, VALIDATOR_REGISTRY_LIMIT>::try_from(validators)
+        .map_err(|(_, err)| err)?;
+    let balances = List::try_from(balances).map_err(|(_, err)| err)?;
+    let genesis_validators_root = validators.hash_tree_root()?;
+    let mut deposit_tree =
+        List::<DepositData, DEPOSIT_DATA_LIST_BOUND>::try_from(deposit_data.to_vec())
+            .map_err(|(_, err)| err)?;
+    let eth1_data = Eth1Data {
+        deposit_root: deposit_tree.hash_tree_root()?,
+        deposit_count: deposit_data.len() as u64,
+        block_hash: eth1_block_hash.clone(),
+    };
+    let randao_mixes = Vector::try_from(vec![eth1_block_hash; EPOCHS_PER_HISTORICAL_VECTOR])
+        .map_err(|(_, err)| err)?;
+    let fork_data = phase0::Fork {
+        previous_version: context.fork_version_for(fork),
+        current_version: context.fork_version_for(fork),
+        epoch: GENESIS_EPOCH,
+    };
+    let participation =
+        List::<ParticipationFlags, VALIDATOR_REGISTRY_LIMIT>::try_from(vec![0; validator_count])
+            .map_err(|(_, err)| err)?;
+    let inactivity_scores =
+        List::<u64, VALIDATOR_REGISTRY_LIMIT>::try_from(vec![0; validator_count])
+            .map_err(|(_, err)| err)?;
+
+    let state = match fork {
+        Fork::Phase0 => {
+            let mut body = phase0::BeaconBlockBody::<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+            >::default();
+            let latest_block_header =
+                BeaconBlockHeader { body_root: body.hash_tree_root()?, ..Default::default() };
+            BeaconState::Phase0(phase0::BeaconState {
+                genesis_time,
+                genesis_validators_root,
+                fork: fork_data,
+                latest_block_header,
+                eth1_data,
+                eth1_deposit_index: deposit_data.len() as u64,
+                validators,
+                balances,
+                randao_mixes,
+                ..Default::default()
+            })
+        }
+        Fork::Altair => {
+            let mut body = altair::BeaconBlockBody::<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+            >::default();
+            let latest_block_header =
+                BeaconBlockHeader { body_root: body.hash_tree_root()?, ..Default::default() };
+            let mut state = altair::BeaconState {
+                genesis_time,
+                genesis_validators_root,
+                fork: fork_data,
+                latest_block_header,
+                eth1_data,
+                eth1_deposit_index: deposit_data.len() as u64,
+                validators,
+                balances,
+                randao_mixes,
+                previous_epoch_participation: participation.clone(),
+                current_epoch_participation: participation,
+                inactivity_scores,
+                ..Default::default()
+            };
+            let sync_committee = altair::get_next_sync_committee(&state, context)?;
+            state.current_sync_committee = sync_committee.clone();
+            state.next_sync_committee = sync_committee;
+            BeaconState::Altair(state)
+        }
+        Fork::Bellatrix => {
+            let mut body = bellatrix::BeaconBlockBody::<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+            >::default();
+            let latest_block_header =
+                BeaconBlockHeader { body_root: body.hash_tree_root()?, ..Default::default() };
+            let latest_execution_payload_header = match execution_payload_header {
+                Some(ExecutionPayloadHeader::Bellatrix(header)) => header,
+                _ => Default::default(),
+            };
+            let mut state = bellatrix::BeaconState {
+                genesis_time,
+                genesis_validators_root,
+                fork: fork_data,
+                latest_block_header,
+                eth1_data,
+                eth1_deposit_index: deposit_data.len() as u64,
+                validators,
+                balances,
+                randao_mixes,
+                previous_epoch_participation: participation.clone(),
+                current_epoch_participation: participation,
+                inactivity_scores,
+                latest_execution_payload_header,
+                ..Default::default()
+            };
+            let sync_committee = bellatrix::get_next_sync_committee(&state, context)?;
+            state.current_sync_committee = sync_committee.clone();
+            state.next_sync_committee = sync_committee;
+            BeaconState::Bellatrix(state)
+        }
+        Fork::Capella => {
+            let mut body = capella::BeaconBlockBody::<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+            >::default();
+            let latest_block_header =
+                BeaconBlockHeader { body_root: body.hash_tree_root()?, ..Default::default() };
+            let latest_execution_payload_header = match execution_payload_header {
+                Some(ExecutionPayloadHeader::Capella(header)) => header,
+                _ => Default::default(),
+            };
+            let mut state = capella::BeaconState {
+                genesis_time,
+                genesis_validators_root,
+                fork: fork_data,
+                latest_block_header,
+                eth1_data,
+                eth1_deposit_index: deposit_data.len() as u64,
+                validators,
+                balances,
+                randao_mixes,
+                previous_epoch_participation: participation.clone(),
+                current_epoch_participation: participation,
+                inactivity_scores,
+                latest_execution_payload_header,
+                ..Default::default()
+            };
+            let sync_committee = capella::get_next_sync_committee(&state, context)?;
+            state.current_sync_committee = sync_committee.clone();
+            state.next_sync_committee = sync_committee;
+            BeaconState::Capella(state)
+        }
+        Fork::Deneb => {
+            let mut body = deneb::BeaconBlockBody::<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >::default();
+            let latest_block_header =
+                BeaconBlockHeader { body_root: body.hash_tree_root()?, ..Default::default() };
+            let latest_execution_payload_header = match execution_payload_header {
+                Some(ExecutionPayloadHeader::Deneb(header)) => header,
+                _ => Default::default(),
+            };
+            let mut state = deneb::BeaconState {
+                genesis_time,
+                genesis_validators_root,
+                fork: fork_data,
+                latest_block_header,
+                eth1_data,
+                eth1_deposit_index: deposit_data.len() as u64,
+                validators,
+                balances,
+                randao_mixes,
+                previous_epoch_participation: participation.clone(),
+                current_epoch_participation: participation,
+                inactivity_scores,
+                latest_execution_payload_header,
+                ..Default::default()
+            };
+            let sync_committee = deneb::get_next_sync_committee(&state, context)?;
+            state.current_sync_committee = sync_committee.clone();
+            state.next_sync_committee = sync_committee;
+            BeaconState::Deneb(state)
+        }
+    };
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::minimal::SYNC_COMMITTEE_SIZE,
+        bellatrix::minimal::{
+            BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+        },
+        capella::minimal::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD},
+        deneb::minimal::MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        phase0::{
+            initialize_beacon_state_from_eth1,
+            minimal::{
+                EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
+                HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS,
+                MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+                PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT, VALIDATOR_REGISTRY_LIMIT,
+            },
+            Deposit,
+        },
+        primitives::Root,
+    };
+
+    #[test]
+    fn test_interop_secret_keys() {
+        let expected = [
+            "25295f0d1d592a90b333e26e85149708208e9f8e8bc18f6c77bd62f8ad7a6866",
+            "51d0b65185db6989ab0b560d6deed19c7ead0e24b9b6372cbecb1f26bdfad000",
+        ];
+        let keys = interop_secret_keys(expected.len()).unwrap();
+        for (key, expected) in keys.into_iter().zip(expected) {
+            let expected = (0..expected.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&expected[i..i + 2], 16).unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(key.to_bytes().as_ref(), expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_matches_initialize_beacon_state_from_eth1() {
+        let context = Context::for_minimal();
+        let mut deposit_data = interop_deposit_data(8, &context).unwrap();
+        // a top up of the first validator, which needs no valid signature
+        let mut top_up = deposit_data[0].clone();
+        top_up.signature = Default::default();
+        deposit_data.push(top_up);
+
+        let mut leaves = List::<Root, DEPOSIT_DATA_LIST_BOUND>::default();
+        let mut deposits = vec![];
+        for data in &deposit_data {
+            leaves.push(data.clone().hash_tree_root().unwrap());
+            let index = leaves.len() - 1;
+            let generalized_index =
+                get_generalized_index(&mut leaves, &[PathElement::Index(index)]).unwrap();
+            let proof = compute_merkle_proof(&mut leaves, generalized_index).unwrap();
+            let proof = proof
+                .iter()
+                .map(|node| Bytes32::try_from(node.as_ref()).unwrap())
+                .collect::<Vec<_>>();
+            deposits.push(Deposit { proof: Vector::try_from(proof).unwrap(), data: data.clone() });
+        }
+        let eth1_block_hash = Hash32::try_from(GENESIS_ETH1_BLOCK_HASH.as_ref()).unwrap();
+        let mut expected = initialize_beacon_state_from_eth1::<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            MAX_PROPOSER_SLASHINGS,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+        >(eth1_block_hash, 0, &mut deposits, &context)
+        .unwrap();
+
+        let state = initialize_genesis_state::<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_PROPOSER_SLASHINGS,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >(&deposit_data, None, expected.genesis_time, &context)
+        .unwrap();
+        let mut state = match state {
+            BeaconState::Phase0(state) => state,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(state.validators.len(), 8);
+        assert_eq!(state.balances[0], 2 * context.max_effective_balance);
+        assert_eq!(state.hash_tree_root().unwrap(), expected.hash_tree_root().unwrap());
+    }
+
+    #[test]
+    fn test_invalid_deposit_signature() {
+        let context = Context::for_minimal();
+        let mut deposit_data = interop_deposit_data(4, &context).unwrap();
+        deposit_data[2].signature = deposit_data[0].signature.clone();
+
+        let result = initialize_genesis_state::<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_PROPOSER_SLASHINGS,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >(&deposit_data, None, 0, &context);
+        assert!(matches!(result, Err(Error::InvalidDepositSignature { index: 2, .. })));
+    }
+
+    #[test]
+    fn test_initialize_genesis_state_from_validators() {
+        let context = Context::for_minimal();
+        let deposit_data = interop_deposit_data(8, &context).unwrap();
+        let mut validators = deposit_data
+            .iter()
+            .map(|data| GenesisValidator {
+                public_key: data.public_key.clone(),
+                withdrawal_credentials: data.withdrawal_credentials.clone(),
+                balance: data.amount,
+            })
+            .collect::<Vec<_>>();
+
+        let from_deposits = initialize_genesis_state::<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_PROPOSER_SLASHINGS,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >(&deposit_data, None, 0, &context)
+        .unwrap();
+        let from_validators = initialize_genesis_state_from_validators::<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_PROPOSER_SLASHINGS,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >(&validators, None, 0, &context)
+        .unwrap();
+        let (from_deposits, from_validators) = match (from_deposits, from_validators) {
+            (BeaconState::Phase0(from_deposits), BeaconState::Phase0(from_validators)) => {
+                (from_deposits, from_validators)
+            }
+            _ => unreachable!(),
+        };
+        assert_eq!(from_validators.validators, from_deposits.validators);
+        assert_eq!(from_validators.balances, from_deposits.balances);
+        assert_eq!(from_validators.genesis_validators_root, from_deposits.genesis_validators_root);
+        assert_eq!(from_validators.eth1_data.deposit_count, 0);
+        assert_eq!(from_validators.eth1_deposit_index, 0);
+
+        validators.push(validators[0].clone());
+        let result = initialize_genesis_state_from_validators::<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_PROPOSER_SLASHINGS,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >(&validators, None, 0, &context);
+        assert!(matches!(result, Err(Error::DuplicateValidator(_))));
+    }
+}
@@ -0,0 +1,7 @@
+//! Support for the gossip domain of the consensus p2p interface as specified in the consensus
+//! specs' `p2p-interface.md`.
+mod topics;
+mod validation;
+
+pub use topics::*;
+pub use validation::*;
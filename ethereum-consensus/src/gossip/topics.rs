@@ -0,0 +1,372 @@
+//! The topics of the gossip domain, with the form `/eth2/{fork_digest}/{name}/{encoding}`.
+use crate::{
+    altair::constants::SYNC_COMMITTEE_SUBNET_COUNT,
+    deneb::networking::BLOB_SIDECAR_SUBNET_COUNT,
+    error::GossipTopicError,
+    phase0::{compute_fork_digest, networking::ATTESTATION_SUBNET_COUNT},
+    primitives::{ForkDigest, Root, Slot},
+    state_transition::Context,
+    Error, Fork,
+};
+use std::{fmt, str::FromStr};
+
+pub const TOPIC_PREFIX: &str = "eth2";
+pub const SSZ_SNAPPY_ENCODING: &str = "ssz_snappy";
+
+const BEACON_BLOCK: &str = "beacon_block";
+const BEACON_AGGREGATE_AND_PROOF: &str = "beacon_aggregate_and_proof";
+const BEACON_ATTESTATION_PREFIX: &str = "beacon_attestation_";
+const VOLUNTARY_EXIT: &str = "voluntary_exit";
+const PROPOSER_SLASHING: &str = "proposer_slashing";
+const ATTESTER_SLASHING: &str = "attester_slashing";
+const SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF: &str = "sync_committee_contribution_and_proof";
+const SYNC_COMMITTEE_PREFIX: &str = "sync_committee_";
+const BLS_TO_EXECUTION_CHANGE: &str = "bls_to_execution_change";
+const BLOB_SIDECAR_PREFIX: &str = "blob_sidecar_";
+
+/// The kind of message gossiped on a topic, with the subnet for the topics of a subnet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GossipKind {
+    BeaconBlock,
+    BeaconAggregateAndProof,
+    BeaconAttestation(u64),
+    VoluntaryExit,
+    ProposerSlashing,
+    AttesterSlashing,
+    SyncCommitteeContributionAndProof,
+    SyncCommittee(u64),
+    BlsToExecutionChange,
+    BlobSidecar(u64),
+}
+
+impl GossipKind {
+    /// The fork the topic is introduced in.
+    pub fn fork(&self) -> Fork {
+        match self {
+            Self::SyncCommitteeContributionAndProof | Self::SyncCommittee(_) => Fork::Altair,
+            Self::BlsToExecutionChange => Fork::Capella,
+            Self::BlobSidecar(_) => Fork::Deneb,
+            _ => Fork::Phase0,
+        }
+    }
+
+    /// The kinds of the topics a node gossips on during `fork` regardless of its subnet
+    /// subscriptions.
+    pub fn global_kinds(fork: Fork) -> Vec<Self> {
+        let mut kinds = vec![
+            Self::BeaconBlock,
+            Self::BeaconAggregateAndProof,
+            Self::VoluntaryExit,
+            Self::ProposerSlashing,
+            Self::AttesterSlashing,
+            Self::SyncCommitteeContributionAndProof,
+            Self::BlsToExecutionChange,
+        ];
+        kinds.extend((0..BLOB_SIDECAR_SUBNET_COUNT as u64).map(Self::BlobSidecar));
+        kinds.retain(|kind| kind.fork() <= fork);
+        kinds
+    }
+}
+
+impl fmt::Display for GossipKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BeaconBlock => write!(f, "{BEACON_BLOCK}"),
+            Self::BeaconAggregateAndProof => write!(f, "{BEACON_AGGREGATE_AND_PROOF}"),
+            Self::BeaconAttestation(subnet_id) => {
+                write!(f, "{BEACON_ATTESTATION_PREFIX}{subnet_id}")
+            }
+            Self::VoluntaryExit => write!(f, "{VOLUNTARY_EXIT}"),
+            Self::ProposerSlashing => write!(f, "{PROPOSER_SLASHING}"),
+            Self::AttesterSlashing => write!(f, "{ATTESTER_SLASHING}"),
+            Self::SyncCommitteeContributionAndProof => {
+                write!(f, "{SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF}")
+            }
+            Self::SyncCommittee(subnet_id) => write!(f, "{SYNC_COMMITTEE_PREFIX}{subnet_id}"),
+            Self::BlsToExecutionChange => write!(f, "{BLS_TO_EXECUTION_CHANGE}"),
+            Self::BlobSidecar(subnet_id) => write!(f, "{BLOB_SIDECAR_PREFIX}{subnet_id}"),
+        }
+    }
+}
+
+impl FromStr for GossipKind {
+    type Err = GossipTopicError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let kind = match name {
+            BEACON_BLOCK => Self::BeaconBlock,
+            BEACON_AGGREGATE_AND_PROOF => Self::BeaconAggregateAndProof,
+            VOLUNTARY_EXIT => Self::VoluntaryExit,
+            PROPOSER_SLASHING => Self::ProposerSlashing,
+            ATTESTER_SLASHING => Self::AttesterSlashing,
+            SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF => Self::SyncCommitteeContributionAndProof,
+            BLS_TO_EXECUTION_CHANGE => Self::BlsToExecutionChange,
+            name => {
+                let parse_subnet = |prefix: &str, subnet_count: usize| {
+                    name.strip_prefix(prefix)
+                        .and_then(|subnet_id| parse_subnet_id(subnet_id, subnet_count))
+                };
+                if let Some(subnet_id) =
+                    parse_subnet(BEACON_ATTESTATION_PREFIX, ATTESTATION_SUBNET_COUNT)
+                {
+                    Self::BeaconAttestation(subnet_id)
+                } else if let Some(subnet_id) =
+                    parse_subnet(SYNC_COMMITTEE_PREFIX, SYNC_COMMITTEE_SUBNET_COUNT)
+                {
+                    Self::SyncCommittee(subnet_id)
+                } else if let Some(subnet_id) =
+                    parse_subnet(BLOB_SIDECAR_PREFIX, BLOB_SIDECAR_SUBNET_COUNT)
+                {
+                    Self::BlobSidecar(subnet_id)
+                } else {
+                    return Err(GossipTopicError::UnknownName(name.to_string()))
+                }
+            }
+        };
+        Ok(kind)
+    }
+}
+
+/// A gossip topic, with the `ssz_snappy` encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GossipTopic {
+    pub fork_digest: ForkDigest,
+    pub kind: GossipKind,
+}
+
+impl GossipTopic {
+    pub fn new(kind: GossipKind, fork_digest: ForkDigest) -> Self {
+        Self { fork_digest, kind }
+    }
+
+    /// The topic for `kind` during `fork` of the chain with `genesis_validators_root`.
+    pub fn for_fork(
+        kind: GossipKind,
+        fork: Fork,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<Self, Error> {
+        let fork_digest =
+            compute_fork_digest(context.fork_version_for(fork), genesis_validators_root)?;
+        Ok(Self::new(kind, fork_digest))
+    }
+
+    /// The topic for `kind` at `slot` following the fork schedule of `context`.
+    pub fn at_slot(
+        kind: GossipKind,
+        slot: Slot,
+        genesis_validators_root: Root,
+        context: &Context,
+    ) -> Result<Self, Error> {
+        Self::for_fork(kind, context.fork_for(slot), genesis_validators_root, context)
+    }
+}
+
+impl fmt::Display for GossipTopic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "/{TOPIC_PREFIX}/")?;
+        for byte in self.fork_digest {
+            write!(f, "{byte:02x}")?;
+        }
+        write!(f, "/{}/{SSZ_SNAPPY_ENCODING}", self.kind)
+    }
+}
+
+impl FromStr for GossipTopic {
+    type Err = GossipTopicError;
+
+    fn from_str(topic: &str) -> Result<Self, Self::Err> {
+        let parts = topic.split('/').collect::<Vec<_>>();
+        let [empty, prefix, fork_digest, name, encoding] = parts[..] else {
+            return Err(GossipTopicError::InvalidFormat(topic.to_string()))
+        };
+        if !empty.is_empty() || prefix != TOPIC_PREFIX {
+            return Err(GossipTopicError::InvalidFormat(topic.to_string()))
+        }
+        if encoding != SSZ_SNAPPY_ENCODING {
+            return Err(GossipTopicError::UnsupportedEncoding(encoding.to_string()))
+        }
+        let fork_digest = parse_fork_digest(fork_digest)
+            .ok_or_else(|| GossipTopicError::InvalidForkDigest(fork_digest.to_string()))?;
+        Ok(Self::new(name.parse()?, fork_digest))
+    }
+}
+
+// Parses the canonical decimal form of a subnet id below `subnet_count`, without a sign or
+// leading zeros
+fn parse_subnet_id(subnet_id: &str, subnet_count: usize) -> Option<u64> {
+    if !subnet_id.bytes().all(|byte| byte.is_ascii_digit()) ||
+        (subnet_id.len() > 1 && subnet_id.starts_with('0'))
+    {
+        return None
+    }
+    subnet_id.parse().ok().filter(|&subnet_id| subnet_id < subnet_count as u64)
+}
+
+// Parses the lowercase hex `fork_digest` of a topic, without a `0x` prefix.
+fn parse_fork_digest(fork_digest: &str) -> Option<ForkDigest> {
+    let mut digest = ForkDigest::default();
+    if fork_digest.len() != 2 * digest.len() || !fork_digest.is_ascii() {
+        return None
+    }
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&fork_digest[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::networking::compute_message_id as compute_message_id_altair,
+        phase0::networking::{compute_message_id, compute_subscribed_subnets},
+    };
+
+    #[test]
+    fn test_topic_round_trip() {
+        let context = Context::for_mainnet();
+        let genesis_validators_root = Root::default();
+        let kinds = [
+            GossipKind::BeaconBlock,
+            GossipKind::BeaconAttestation(17),
+            GossipKind::SyncCommittee(3),
+            GossipKind::BlobSidecar(5),
+            GossipKind::BlsToExecutionChange,
+        ];
+        for kind in kinds {
+            let topic = GossipTopic::at_slot(kind, 0, genesis_validators_root, &context).unwrap();
+            let expected_digest =
+                compute_fork_digest(context.genesis_fork_version, genesis_validators_root).unwrap();
+            assert_eq!(topic.fork_digest, expected_digest);
+            let encoded = topic.to_string();
+            assert!(encoded.starts_with("/eth2/"));
+            assert!(encoded.ends_with(&format!("/{kind}/ssz_snappy")));
+            assert_eq!(encoded.parse::<GossipTopic>().unwrap(), topic);
+        }
+
+        let topic = "/eth2/bba4da96/beacon_attestation_3/ssz_snappy".parse::<GossipTopic>();
+        assert_eq!(
+            topic.unwrap(),
+            GossipTopic::new(GossipKind::BeaconAttestation(3), [0xbb, 0xa4, 0xda, 0x96])
+        );
+        for invalid in [
+            "/eth2/bba4da96/beacon_attestation_3/ssz",
+            "/eth2/bba4da/beacon_block/ssz_snappy",
+            "/eth2/bba4da96/beacon_attestation_/ssz_snappy",
+            "/eth1/bba4da96/beacon_block/ssz_snappy",
+            "eth2/bba4da96/beacon_block",
+        ] {
+            assert!(invalid.parse::<GossipTopic>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_subnet_ids() {
+        let subnets = [
+            ("beacon_attestation_0", GossipKind::BeaconAttestation(0)),
+            ("beacon_attestation_63", GossipKind::BeaconAttestation(63)),
+            ("sync_committee_3", GossipKind::SyncCommittee(3)),
+            ("blob_sidecar_5", GossipKind::BlobSidecar(5)),
+        ];
+        for (name, kind) in subnets {
+            assert_eq!(name.parse::<GossipKind>().unwrap(), kind);
+        }
+
+        let out_of_range = [
+            "beacon_attestation_64",
+            "beacon_attestation_99",
+            "sync_committee_4",
+            "sync_committee_9",
+            "blob_sidecar_6",
+        ];
+        let non_canonical = [
+            "beacon_attestation_03",
+            "beacon_attestation_00",
+            "sync_committee_+1",
+            "blob_sidecar_ 1",
+        ];
+        for invalid in out_of_range.into_iter().chain(non_canonical) {
+            assert!(invalid.parse::<GossipKind>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_global_kinds() {
+        let phase0_kinds = GossipKind::global_kinds(Fork::Phase0);
+        assert_eq!(phase0_kinds.len(), 5);
+        let deneb_kinds = GossipKind::global_kinds(Fork::Deneb);
+        assert_eq!(deneb_kinds.len(), 7 + BLOB_SIDECAR_SUBNET_COUNT);
+        assert!(deneb_kinds.contains(&GossipKind::BlobSidecar(0)));
+    }
+
+    #[test]
+    fn test_message_id() {
+        let data = b"some gossip message".to_vec();
+        let compressed = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+
+        // the message id of a valid snappy message commits to the decompressed data
+        let message_id = compute_message_id(&compressed);
+        let expected = crate::crypto::hash([[1u8, 0, 0, 0].as_slice(), &data].concat());
+        assert_eq!(message_id.as_slice(), &expected[..20]);
+
+        let invalid = [0xffu8; 8];
+        let message_id = compute_message_id(&invalid);
+        let expected = crate::crypto::hash([[0u8; 4].as_slice(), &invalid].concat());
+        assert_eq!(message_id.as_slice(), &expected[..20]);
+
+        // the altair message id also commits to the topic
+        let topic = "/eth2/bba4da96/beacon_block/ssz_snappy";
+        let message_id = compute_message_id_altair(topic, &compressed, Fork::Altair);
+        let expected = crate::crypto::hash(
+            [
+                [1u8, 0, 0, 0].as_slice(),
+                &(topic.len() as u64).to_le_bytes(),
+                topic.as_bytes(),
+                &data,
+            ]
+            .concat(),
+        );
+        assert_eq!(message_id.as_slice(), &expected[..20]);
+        assert_ne!(
+            message_id,
+            compute_message_id_altair("/eth2/00000000/x/ssz_snappy", &compressed, Fork::Altair)
+        );
+    }
+
+    #[test]
+    fn test_message_id_size_limit() {
+        // a message larger than `GOSSIP_MAX_SIZE` is only valid from the bellatrix fork onwards
+        let data = vec![0x42u8; 3 * 2usize.pow(20) / 2];
+        let compressed = snap::raw::Encoder::new().compress_vec(&data).unwrap();
+        let topic = "/eth2/4a26c58b/beacon_block/ssz_snappy";
+        let topic_len = (topic.len() as u64).to_le_bytes();
+
+        let message_id = compute_message_id_altair(topic, &compressed, Fork::Bellatrix);
+        let expected = crate::crypto::hash(
+            [[1u8, 0, 0, 0].as_slice(), &topic_len, topic.as_bytes(), &data].concat(),
+        );
+        assert_eq!(message_id.as_slice(), &expected[..20]);
+
+        let message_id = compute_message_id_altair(topic, &compressed, Fork::Altair);
+        let expected = crate::crypto::hash(
+            [[0u8; 4].as_slice(), &topic_len, topic.as_bytes(), &compressed].concat(),
+        );
+        assert_eq!(message_id.as_slice(), &expected[..20]);
+    }
+
+    #[test]
+    fn test_subscribed_subnets() {
+        let context = Context::for_mainnet();
+        let node_id = enr::NodeId::new(&[0x5a; 32]);
+        let subnets = compute_subscribed_subnets(&node_id, 0, &context).unwrap();
+        assert_eq!(subnets.len(), 2);
+        assert_eq!(subnets[1], (subnets[0] + 1) % 64);
+        // subscriptions only rotate at the end of a subscription period, offset by the node id
+        let period_end = 256 - 0x5a;
+        assert_eq!(
+            compute_subscribed_subnets(&node_id, period_end - 1, &context).unwrap(),
+            subnets
+        );
+    }
+}
@@ -0,0 +1,2433 @@
+//! The validation conditions of the messages on the global and subnet gossip topics.
+//!
+//! Each `validate_*` function checks a message from one topic against a `BeaconState` and
+//! returns the `ValidationResult` to report to the gossip router: `Accept` to forward the message,
+//! `Ignore` to drop it without penalizing the peer and `Reject` to drop it and penalize the peer.
+//! The conditions that depend on messages seen before are checked against a `GossipCache`, which
+//! records each accepted message.
+//!
+//! The `state` given to each function is expected to be the head state of the node advanced to
+//! the current slot, so that the shufflings of its previous, current and next epochs and its
+//! sync committees cover the messages currently being gossiped. Its finalized checkpoint stands
+//! in for the finalized checkpoint of the fork choice store. Messages from epochs outside of that
+//! range are ignored, except for blocks and blob sidecars from later epochs, whose proposers are
+//! computed from a copy of the state advanced to their epoch. As the proposers of the previous
+//! epoch depend on effective balances the state may no longer have, blocks and blob sidecars from
+//! the previous epoch with an unexpected proposer are ignored rather than rejected.
+//!
+//! NOTE: the data availability of blocks is not checked; blob sidecars are validated on their
+//! own topics with `validate_blob_sidecar` and the KZG proofs of blobs are not verified here.
+use crate::{
+    altair::{
+        self, SignedContributionAndProof, SyncAggregatorSelectionData, SyncCommitteeMessage,
+        SYNC_COMMITTEE_SUBNET_COUNT,
+    },
+    bellatrix, capella,
+    capella::SignedBlsToExecutionChange,
+    crypto::{fast_aggregate_verify, hash},
+    deneb::{self, networking::compute_subnet_for_blob_sidecar, SignedBlobSidecar},
+    domains::DomainType,
+    fork_choice::ProtoArray,
+    phase0::{
+        self, compute_domain, compute_epoch_at_slot, compute_start_slot_at_epoch,
+        networking::{
+            compute_subnet_for_attestation, ATTESTATION_PROPAGATION_SLOT_RANGE,
+            MAXIMUM_GOSSIP_CLOCK_DISPARITY,
+        },
+        Attestation, AttesterSlashing, Checkpoint, ProposerSlashing, SignedAggregateAndProof,
+        SignedVoluntaryExit, Validator,
+    },
+    primitives::{
+        BlobIndex, BlsPublicKey, BlsSignature, CommitteeIndex, Domain, Epoch, Hash32, Root, Slot,
+        ValidatorIndex, GENESIS_EPOCH,
+    },
+    signing::{compute_signing_root, SigningData},
+    ssz::prelude::*,
+    state_transition::{Context, SignatureSets},
+    types::{BeaconState, SignedBeaconBlock},
+    Error, Fork,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+/// The outcome of validating a gossip message.
+#[derive(Debug)]
+pub enum ValidationResult {
+    Accept,
+    Ignore(IgnoreReason),
+    Reject(RejectReason),
+}
+
+impl From<IgnoreReason> for ValidationResult {
+    fn from(reason: IgnoreReason) -> Self {
+        Self::Ignore(reason)
+    }
+}
+
+impl From<RejectReason> for ValidationResult {
+    fn from(reason: RejectReason) -> Self {
+        Self::Reject(reason)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IgnoreReason {
+    #[error("message from slot {slot} is from after the current slot {current_slot}")]
+    FutureSlot { slot: Slot, current_slot: Slot },
+    #[error("message from slot {slot} is from before the earliest accepted slot {earliest_slot}")]
+    PastSlot { slot: Slot, earliest_slot: Slot },
+    #[error("message from slot {slot} is not from after the finalized slot {finalized_slot}")]
+    FinalizedSlot { slot: Slot, finalized_slot: Slot },
+    #[error("an equivalent message has already been seen")]
+    AlreadySeen,
+    #[error("block {0} has not been seen")]
+    UnknownBlock(Root),
+    #[error("block {0} does not descend from the finalized block")]
+    NotDescendantOfFinalized(Root),
+    #[error("the shuffling of epoch {epoch} is not available from the state")]
+    ShufflingUnavailable { epoch: Epoch },
+    #[error("validator {proposer_index} is not the proposer of slot {slot} for the state")]
+    UnverifiedProposer { slot: Slot, proposer_index: ValidatorIndex },
+    #[error("the topic is only active from the {0:?} fork")]
+    BeforeFork(Fork),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RejectReason {
+    #[error("block {0} has failed validation")]
+    InvalidBlock(Root),
+    #[error("message from slot {slot} is not from after the slot {parent_slot} of its parent")]
+    NotLaterThanParent { slot: Slot, parent_slot: Slot },
+    #[error("block {0} does not descend from the finalized block")]
+    NotDescendantOfFinalized(Root),
+    #[error("proposer {provided} is not the expected proposer {expected}")]
+    IncorrectProposer { provided: ValidatorIndex, expected: ValidatorIndex },
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("execution payload has timestamp {provided} instead of {expected}")]
+    IncorrectTimestamp { provided: u64, expected: u64 },
+    #[error("block has {count} blob commitments, more than the limit of {limit}")]
+    TooManyBlobCommitments { count: usize, limit: usize },
+    #[error("committee index {index} is not less than the committee count {count}")]
+    InvalidCommitteeIndex { index: CommitteeIndex, count: usize },
+    #[error("message was sent on the incorrect subnet {0}")]
+    IncorrectSubnet(u64),
+    #[error("target epoch {provided} is not the epoch {expected} of the attestation slot")]
+    IncorrectTargetEpoch { provided: Epoch, expected: Epoch },
+    #[error("attestation does not have exactly one participant")]
+    NotSingleParticipant,
+    #[error("aggregate does not have any participants")]
+    NoParticipants,
+    #[error("aggregation bits have length {provided} instead of the committee size {expected}")]
+    InvalidAggregationBitsLength { provided: usize, expected: usize },
+    #[error("selection proof does not select an aggregator")]
+    NotAggregator,
+    #[error("aggregator is not a member of the committee")]
+    AggregatorNotInCommittee,
+    #[error("target {0} is not the checkpoint block of the attested block")]
+    IncorrectTarget(Root),
+    #[error("invalid sync subcommittee index {0}")]
+    InvalidSubcommitteeIndex(u64),
+    #[error("invalid blob index {0}")]
+    InvalidBlobIndex(BlobIndex),
+    #[error("unknown validator {0}")]
+    UnknownValidator(ValidatorIndex),
+    #[error("{0}")]
+    InvalidOperation(Box<Error>),
+}
+
+/// The messages seen on the gossip topics that later messages are checked against.
+///
+/// Messages are recorded when accepted; blocks that fail validation after being accepted on
+/// gossip, e.g. in the state transition, are recorded with `insert_invalid_block`. Entries that
+/// can no longer affect validation are removed with `prune`.
+#[derive(Debug, Default, Clone)]
+pub struct GossipCache {
+    block_proposers: HashSet<(Slot, ValidatorIndex)>,
+    invalid_blocks: HashMap<Root, Slot>,
+    // The aggregation bits of each aggregate, by target epoch and root of the attestation data
+    aggregates: HashMap<(Epoch, Root), Vec<Vec<bool>>>,
+    aggregators: HashSet<(Epoch, ValidatorIndex)>,
+    attesters: HashSet<(Epoch, ValidatorIndex)>,
+    // The aggregation bits of each contribution, by slot, block root and subcommittee index
+    sync_contributions: HashMap<(Slot, Root, u64), Vec<Vec<bool>>>,
+    sync_contributors: HashSet<(Slot, u64, ValidatorIndex)>,
+    sync_committee_messages: HashSet<(Slot, u64, ValidatorIndex)>,
+    voluntary_exits: HashSet<ValidatorIndex>,
+    proposer_slashings: HashSet<ValidatorIndex>,
+    attester_slashed_indices: HashSet<ValidatorIndex>,
+    bls_to_execution_changes: HashSet<ValidatorIndex>,
+    blob_sidecars: HashSet<(Slot, Root, BlobIndex)>,
+}
+
+impl GossipCache {
+    /// Records that the block with `root` at `slot` is invalid, so that messages referring to it
+    /// are rejected.
+    pub fn insert_invalid_block(&mut self, root: Root, slot: Slot) {
+        self.invalid_blocks.insert(root, slot);
+    }
+
+    pub fn is_invalid_block(&self, root: &Root) -> bool {
+        self.invalid_blocks.contains_key(root)
+    }
+
+    /// Removes the blocks and blob sidecars that are not from after the start of
+    /// `finalized_epoch`, the attestations from before the epoch preceding `current_slot` and the
+    /// sync committee messages from before the slot preceding `current_slot`.
+    pub fn prune(&mut self, current_slot: Slot, finalized_epoch: Epoch, context: &Context) {
+        let finalized_slot = compute_start_slot_at_epoch(finalized_epoch, context);
+        let previous_epoch = compute_epoch_at_slot(current_slot, context).saturating_sub(1);
+        let previous_slot = current_slot.saturating_sub(1);
+
+        self.block_proposers.retain(|(slot, _)| *slot > finalized_slot);
+        self.invalid_blocks.retain(|_, slot| *slot > finalized_slot);
+        self.aggregates.retain(|(epoch, _), _| *epoch >= previous_epoch);
+        self.aggregators.retain(|(epoch, _)| *epoch >= previous_epoch);
+        self.attesters.retain(|(epoch, _)| *epoch >= previous_epoch);
+        self.sync_contributions.retain(|(slot, _, _), _| *slot >= previous_slot);
+        self.sync_contributors.retain(|(slot, _, _)| *slot >= previous_slot);
+        self.sync_committee_messages.retain(|(slot, _, _)| *slot >= previous_slot);
+        self.blob_sidecars.retain(|(slot, _, _)| *slot > finalized_slot);
+    }
+}
+
+/// Validates `signed_block` from the `beacon_block` topic. `proto_array` holds the blocks that
+/// have been seen and passed validation, e.g. the tree of the fork choice store.
+///
+/// A block whose parent is invalid is recorded as invalid itself.
+pub fn validate_beacon_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    signed_block: &mut SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    proto_array: &ProtoArray,
+    cache: &mut GossipCache,
+    now: Duration,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let message = signed_block.message();
+    let (slot, proposer_index, parent_root) =
+        (message.slot(), message.proposer_index(), message.parent_root());
+
+    let (_, current_slot) = current_slot_range(state.genesis_time(), now, context);
+    if slot > current_slot {
+        return Ok(IgnoreReason::FutureSlot { slot, current_slot }.into())
+    }
+    let finalized_checkpoint = state.finalized_checkpoint();
+    let finalized_slot = compute_start_slot_at_epoch(finalized_checkpoint.epoch, context);
+    if slot <= finalized_slot {
+        return Ok(IgnoreReason::FinalizedSlot { slot, finalized_slot }.into())
+    }
+    if cache.block_proposers.contains(&(slot, proposer_index)) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let block_root = match signed_block {
+        SignedBeaconBlock::Phase0(signed_block) => signed_block.message.hash_tree_root(),
+        SignedBeaconBlock::Altair(signed_block) => signed_block.message.hash_tree_root(),
+        SignedBeaconBlock::Bellatrix(signed_block) => signed_block.message.hash_tree_root(),
+        SignedBeaconBlock::Capella(signed_block) => signed_block.message.hash_tree_root(),
+        SignedBeaconBlock::Deneb(signed_block) => signed_block.message.hash_tree_root(),
+    }?;
+    if cache.is_invalid_block(&parent_root) {
+        cache.insert_invalid_block(block_root, slot);
+        return Ok(RejectReason::InvalidBlock(parent_root).into())
+    }
+    let parent_slot = match proto_array.get(&parent_root) {
+        Some(parent) => parent.slot,
+        None => return Ok(IgnoreReason::UnknownBlock(parent_root).into()),
+    };
+    if slot <= parent_slot {
+        return Ok(RejectReason::NotLaterThanParent { slot, parent_slot }.into())
+    }
+    if !descends_from_finalized(proto_array, &parent_root, finalized_checkpoint, context) {
+        return Ok(RejectReason::NotDescendantOfFinalized(parent_root).into())
+    }
+
+    let epoch = compute_epoch_at_slot(slot, context);
+    let state_epoch = compute_epoch_at_slot(state.slot(), context);
+    if epoch + 1 < state_epoch {
+        return Ok(IgnoreReason::ShufflingUnavailable { epoch }.into())
+    }
+    let validators = state.validators();
+    if proposer_index >= validators.len() {
+        return Ok(RejectReason::UnknownValidator(proposer_index).into())
+    }
+    let domain = get_domain(state, DomainType::BeaconProposer, epoch, context)?;
+    let mut signing_data = SigningData { object_root: block_root, domain };
+    let signing_root = signing_data.hash_tree_root()?;
+    if !is_valid_signature(
+        validators,
+        proposer_index,
+        signing_root,
+        signed_block.signature(),
+        context,
+    )? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+    let expected = get_proposer_index_at_slot(state, slot, context)?;
+    if proposer_index != expected {
+        // NOTE: the proposers of the previous epoch are computed with the effective balances of
+        // `state`, which may have changed at the start of its epoch
+        if epoch < state_epoch {
+            return Ok(IgnoreReason::UnverifiedProposer { slot, proposer_index }.into())
+        }
+        return Ok(RejectReason::IncorrectProposer { provided: proposer_index, expected }.into())
+    }
+
+    let message = signed_block.message();
+    let body = message.body();
+    if let Some(execution_payload) = body.execution_payload() {
+        // NOTE: stands in for `is_execution_enabled`, as the payload of a block before the merge
+        // transition is the default payload
+        if execution_payload.block_hash() != &Hash32::default() {
+            let expected = state.genesis_time() + slot * context.seconds_per_slot;
+            let provided = execution_payload.timestamp();
+            if provided != expected {
+                return Ok(RejectReason::IncorrectTimestamp { provided, expected }.into())
+            }
+        }
+    }
+    if let SignedBeaconBlock::Deneb(signed_block) = signed_block {
+        let count = signed_block.message.body.blob_kzg_commitments.len();
+        if count > context.max_blobs_per_block {
+            return Ok(RejectReason::TooManyBlobCommitments {
+                count,
+                limit: context.max_blobs_per_block,
+            }
+            .into())
+        }
+    }
+
+    cache.block_proposers.insert((slot, proposer_index));
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `signed_aggregate_and_proof` from the `beacon_aggregate_and_proof` topic.
+/// `proto_array` holds the blocks that have been seen and passed validation.
+pub fn validate_aggregate_and_proof<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    signed_aggregate_and_proof: &mut SignedAggregateAndProof<MAX_VALIDATORS_PER_COMMITTEE>,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    proto_array: &ProtoArray,
+    cache: &mut GossipCache,
+    now: Duration,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let aggregate_and_proof = &signed_aggregate_and_proof.message;
+    let aggregator_index = aggregate_and_proof.aggregator_index;
+    let selection_proof = aggregate_and_proof.selection_proof.clone();
+    let aggregate = &aggregate_and_proof.aggregate;
+    let mut data = aggregate.data.clone();
+    let aggregation_bits = aggregate.aggregation_bits.iter().map(|bit| *bit).collect::<Vec<_>>();
+    let signature = aggregate.signature.clone();
+
+    if let Some(reason) = check_attestation_slot(data.slot, state.genesis_time(), now, context) {
+        return Ok(reason.into())
+    }
+    let epoch = compute_epoch_at_slot(data.slot, context);
+    if data.target.epoch != epoch {
+        return Ok(RejectReason::IncorrectTargetEpoch {
+            provided: data.target.epoch,
+            expected: epoch,
+        }
+        .into())
+    }
+    if !is_shuffling_available(state, epoch, context) {
+        return Ok(IgnoreReason::ShufflingUnavailable { epoch }.into())
+    }
+    let count = get_committee_count_per_slot(state, epoch, context);
+    if data.index >= count {
+        return Ok(RejectReason::InvalidCommitteeIndex { index: data.index, count }.into())
+    }
+    if !aggregation_bits.iter().any(|bit| *bit) {
+        return Ok(RejectReason::NoParticipants.into())
+    }
+
+    let data_root = data.hash_tree_root()?;
+    let is_covered = cache.aggregates.get(&(epoch, data_root)).map(|seen| {
+        seen.iter().any(|seen_bits| {
+            seen_bits.len() == aggregation_bits.len() &&
+                aggregation_bits.iter().zip(seen_bits).all(|(bit, seen_bit)| !bit || *seen_bit)
+        })
+    });
+    if is_covered.unwrap_or_default() || cache.aggregators.contains(&(epoch, aggregator_index)) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+    if let Some(reason) = check_attested_block(&data.beacon_block_root, proto_array, cache) {
+        return Ok(reason)
+    }
+
+    let committee = get_beacon_committee(state, data.slot, data.index, context)?;
+    if aggregation_bits.len() != committee.len() {
+        return Ok(RejectReason::InvalidAggregationBitsLength {
+            provided: aggregation_bits.len(),
+            expected: committee.len(),
+        }
+        .into())
+    }
+    if !committee.contains(&aggregator_index) {
+        return Ok(RejectReason::AggregatorNotInCommittee.into())
+    }
+    if !is_aggregator(state, data.slot, data.index, &selection_proof, context)? {
+        return Ok(RejectReason::NotAggregator.into())
+    }
+
+    let validators = state.validators();
+    let domain = get_domain(state, DomainType::SelectionProof, epoch, context)?;
+    let mut slot = data.slot;
+    let signing_root = compute_signing_root(&mut slot, domain)?;
+    if !is_valid_signature(validators, aggregator_index, signing_root, &selection_proof, context)? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+    let domain = get_domain(state, DomainType::AggregateAndProof, epoch, context)?;
+    let signing_root = compute_signing_root(&mut signed_aggregate_and_proof.message, domain)?;
+    if !is_valid_signature(
+        validators,
+        aggregator_index,
+        signing_root,
+        &signed_aggregate_and_proof.signature,
+        context,
+    )? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+    let attesting_indices = committee
+        .iter()
+        .zip(aggregation_bits.iter())
+        .filter_map(|(index, bit)| bit.then_some(*index))
+        .collect::<Vec<_>>();
+    let domain = get_domain(state, DomainType::BeaconAttester, data.target.epoch, context)?;
+    let signing_root = compute_signing_root(&mut data, domain)?;
+    let signature_set = context.pubkey_cache.aggregate_signature_set(
+        validators,
+        &attesting_indices,
+        signing_root.as_ref(),
+        &signature,
+    )?;
+    if signature_set.verify().is_err() {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+
+    if let Some(reason) =
+        check_attestation_target(&data.beacon_block_root, &data.target, proto_array, context)
+    {
+        return Ok(reason.into())
+    }
+    let finalized_checkpoint = state.finalized_checkpoint();
+    if !descends_from_finalized(proto_array, &data.beacon_block_root, finalized_checkpoint, context)
+    {
+        return Ok(IgnoreReason::NotDescendantOfFinalized(data.beacon_block_root).into())
+    }
+
+    cache.aggregates.entry((epoch, data_root)).or_default().push(aggregation_bits);
+    cache.aggregators.insert((epoch, aggregator_index));
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `attestation` from the `beacon_attestation_{subnet_id}` topic. `proto_array` holds
+/// the blocks that have been seen and passed validation.
+pub fn validate_attestation<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    subnet_id: u64,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    proto_array: &ProtoArray,
+    cache: &mut GossipCache,
+    now: Duration,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let mut data = attestation.data.clone();
+
+    if let Some(reason) = check_attestation_slot(data.slot, state.genesis_time(), now, context) {
+        return Ok(reason.into())
+    }
+    let epoch = compute_epoch_at_slot(data.slot, context);
+    if data.target.epoch != epoch {
+        return Ok(RejectReason::IncorrectTargetEpoch {
+            provided: data.target.epoch,
+            expected: epoch,
+        }
+        .into())
+    }
+    if !is_shuffling_available(state, epoch, context) {
+        return Ok(IgnoreReason::ShufflingUnavailable { epoch }.into())
+    }
+    let count = get_committee_count_per_slot(state, epoch, context);
+    if data.index >= count {
+        return Ok(RejectReason::InvalidCommitteeIndex { index: data.index, count }.into())
+    }
+    if compute_subnet_for_attestation(count as u64, data.slot, data.index, context) != subnet_id {
+        return Ok(RejectReason::IncorrectSubnet(subnet_id).into())
+    }
+    if attestation.aggregation_bits.count_ones() != 1 {
+        return Ok(RejectReason::NotSingleParticipant.into())
+    }
+
+    let committee = get_beacon_committee(state, data.slot, data.index, context)?;
+    let aggregation_bits = &attestation.aggregation_bits;
+    if aggregation_bits.len() != committee.len() {
+        return Ok(RejectReason::InvalidAggregationBitsLength {
+            provided: aggregation_bits.len(),
+            expected: committee.len(),
+        }
+        .into())
+    }
+    let position = aggregation_bits.iter().position(|bit| *bit).expect("has one participant");
+    let attester_index = committee[position];
+    if cache.attesters.contains(&(epoch, attester_index)) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let domain = get_domain(state, DomainType::BeaconAttester, epoch, context)?;
+    let signing_root = compute_signing_root(&mut data, domain)?;
+    if !is_valid_signature(
+        state.validators(),
+        attester_index,
+        signing_root,
+        &attestation.signature,
+        context,
+    )? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+
+    if let Some(reason) = check_attested_block(&data.beacon_block_root, proto_array, cache) {
+        return Ok(reason)
+    }
+    if let Some(reason) =
+        check_attestation_target(&data.beacon_block_root, &data.target, proto_array, context)
+    {
+        return Ok(reason.into())
+    }
+    let finalized_checkpoint = state.finalized_checkpoint();
+    if !descends_from_finalized(proto_array, &data.beacon_block_root, finalized_checkpoint, context)
+    {
+        return Ok(IgnoreReason::NotDescendantOfFinalized(data.beacon_block_root).into())
+    }
+
+    cache.attesters.insert((epoch, attester_index));
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `signed_contribution_and_proof` from the `sync_committee_contribution_and_proof`
+/// topic.
+pub fn validate_sync_committee_contribution<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+>(
+    signed_contribution_and_proof: &mut SignedContributionAndProof<SYNC_SUBCOMMITTEE_SIZE>,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    cache: &mut GossipCache,
+    now: Duration,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let contribution_and_proof = &signed_contribution_and_proof.message;
+    let aggregator_index = contribution_and_proof.aggregator_index;
+    let selection_proof = contribution_and_proof.selection_proof.clone();
+    let contribution = &contribution_and_proof.contribution;
+    let slot = contribution.slot;
+    let mut beacon_block_root = contribution.beacon_block_root;
+    let subcommittee_index = contribution.subcommittee_index;
+    let aggregation_bits = contribution.aggregation_bits.iter().map(|bit| *bit).collect::<Vec<_>>();
+    let signature = contribution.signature.clone();
+
+    if let Some(reason) = check_current_slot(slot, state.genesis_time(), now, context) {
+        return Ok(reason.into())
+    }
+    if subcommittee_index >= SYNC_COMMITTEE_SUBNET_COUNT as u64 {
+        return Ok(RejectReason::InvalidSubcommitteeIndex(subcommittee_index).into())
+    }
+    if !aggregation_bits.iter().any(|bit| *bit) {
+        return Ok(RejectReason::NoParticipants.into())
+    }
+    if !altair::is_sync_committee_aggregator(&selection_proof, context) {
+        return Ok(RejectReason::NotAggregator.into())
+    }
+    let validators = state.validators();
+    let aggregator = match validators.get(aggregator_index) {
+        Some(validator) => validator,
+        None => return Ok(RejectReason::UnknownValidator(aggregator_index).into()),
+    };
+    let public_keys = match get_sync_subcommittee_pubkeys(state, subcommittee_index, context) {
+        Some(public_keys) => public_keys,
+        None => return Ok(IgnoreReason::BeforeFork(Fork::Altair).into()),
+    };
+    if !public_keys.contains(&aggregator.public_key) {
+        return Ok(RejectReason::AggregatorNotInCommittee.into())
+    }
+
+    let key = (slot, beacon_block_root, subcommittee_index);
+    let is_covered = cache.sync_contributions.get(&key).map(|seen| {
+        seen.iter().any(|seen_bits| {
+            aggregation_bits.iter().zip(seen_bits).all(|(bit, seen_bit)| !bit || *seen_bit)
+        })
+    });
+    if is_covered.unwrap_or_default() ||
+        cache.sync_contributors.contains(&(slot, subcommittee_index, aggregator_index))
+    {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let epoch = compute_epoch_at_slot(slot, context);
+    let mut selection_data = SyncAggregatorSelectionData { slot, subcommittee_index };
+    let domain = get_domain(state, DomainType::SyncCommitteeSelectionProof, epoch, context)?;
+    let signing_root = compute_signing_root(&mut selection_data, domain)?;
+    if !is_valid_signature(validators, aggregator_index, signing_root, &selection_proof, context)? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+    let domain = get_domain(state, DomainType::ContributionAndProof, epoch, context)?;
+    let signing_root = compute_signing_root(&mut signed_contribution_and_proof.message, domain)?;
+    if !is_valid_signature(
+        validators,
+        aggregator_index,
+        signing_root,
+        &signed_contribution_and_proof.signature,
+        context,
+    )? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+    let participant_public_keys = public_keys
+        .iter()
+        .zip(aggregation_bits.iter())
+        .filter_map(|(public_key, bit)| bit.then_some(public_key))
+        .collect::<Vec<_>>();
+    let domain = get_domain(state, DomainType::SyncCommittee, epoch, context)?;
+    let signing_root = compute_signing_root(&mut beacon_block_root, domain)?;
+    if fast_aggregate_verify(&participant_public_keys, signing_root.as_ref(), &signature).is_err() {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+
+    cache.sync_contributions.entry(key).or_default().push(aggregation_bits);
+    cache.sync_contributors.insert((slot, subcommittee_index, aggregator_index));
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `message` from the `sync_committee_{subnet_id}` topic.
+pub fn validate_sync_committee_message<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    message: &SyncCommitteeMessage,
+    subnet_id: u64,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    cache: &mut GossipCache,
+    now: Duration,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let slot = message.slot;
+    let validator_index = message.validator_index;
+
+    if let Some(reason) = check_current_slot(slot, state.genesis_time(), now, context) {
+        return Ok(reason.into())
+    }
+    if validator_index >= state.validators().len() {
+        return Ok(RejectReason::UnknownValidator(validator_index).into())
+    }
+    let subnets = match compute_subnets_for_sync_committee(state, validator_index, context)? {
+        Some(subnets) => subnets,
+        None => return Ok(IgnoreReason::BeforeFork(Fork::Altair).into()),
+    };
+    if !subnets.contains(&subnet_id) {
+        return Ok(RejectReason::IncorrectSubnet(subnet_id).into())
+    }
+    if cache.sync_committee_messages.contains(&(slot, subnet_id, validator_index)) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let epoch = compute_epoch_at_slot(slot, context);
+    let domain = get_domain(state, DomainType::SyncCommittee, epoch, context)?;
+    let mut beacon_block_root = message.beacon_block_root;
+    let signing_root = compute_signing_root(&mut beacon_block_root, domain)?;
+    if !is_valid_signature(
+        state.validators(),
+        validator_index,
+        signing_root,
+        &message.signature,
+        context,
+    )? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+
+    cache.sync_committee_messages.insert((slot, subnet_id, validator_index));
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `signed_voluntary_exit` from the `voluntary_exit` topic by processing it against a
+/// copy of `state`.
+pub fn validate_voluntary_exit<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    signed_voluntary_exit: &mut SignedVoluntaryExit,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    cache: &mut GossipCache,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let validator_index = signed_voluntary_exit.message.validator_index;
+    if cache.voluntary_exits.contains(&validator_index) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let mut state = state.clone();
+    let signatures = &mut SignatureSets::default();
+    let result = match &mut state {
+        BeaconState::Phase0(state) => phase0::process_voluntary_exit(
+            state,
+            signed_voluntary_exit,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Altair(state) => altair::process_voluntary_exit(
+            state,
+            signed_voluntary_exit,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Bellatrix(state) => bellatrix::process_voluntary_exit(
+            state,
+            signed_voluntary_exit,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Capella(state) => capella::process_voluntary_exit(
+            state,
+            signed_voluntary_exit,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Deneb(state) => deneb::process_voluntary_exit(
+            state,
+            signed_voluntary_exit,
+            signatures,
+            &mut (),
+            context,
+        ),
+    };
+    if let Err(err) = result {
+        return Ok(RejectReason::InvalidOperation(Box::new(err)).into())
+    }
+
+    cache.voluntary_exits.insert(validator_index);
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `proposer_slashing` from the `proposer_slashing` topic by processing it against a
+/// copy of `state`.
+pub fn validate_proposer_slashing<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    proposer_slashing: &mut ProposerSlashing,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    cache: &mut GossipCache,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let proposer_index = proposer_slashing.signed_header_1.message.proposer_index;
+    if cache.proposer_slashings.contains(&proposer_index) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let mut state = state.clone();
+    let signatures = &mut SignatureSets::default();
+    let result = match &mut state {
+        BeaconState::Phase0(state) => phase0::process_proposer_slashing(
+            state,
+            proposer_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Altair(state) => altair::process_proposer_slashing(
+            state,
+            proposer_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Bellatrix(state) => bellatrix::process_proposer_slashing(
+            state,
+            proposer_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Capella(state) => capella::process_proposer_slashing(
+            state,
+            proposer_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Deneb(state) => {
+            deneb::process_proposer_slashing(state, proposer_slashing, signatures, &mut (), context)
+        }
+    };
+    if let Err(err) = result {
+        return Ok(RejectReason::InvalidOperation(Box::new(err)).into())
+    }
+
+    cache.proposer_slashings.insert(proposer_index);
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `attester_slashing` from the `attester_slashing` topic by processing it against a
+/// copy of `state`. A slashing is ignored unless it slashes a validator that is not slashed by
+/// any slashing accepted before.
+pub fn validate_attester_slashing<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    attester_slashing: &mut AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    cache: &mut GossipCache,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let indices_1 =
+        attester_slashing.attestation_1.attesting_indices.iter().collect::<HashSet<_>>();
+    let slashed_indices = attester_slashing
+        .attestation_2
+        .attesting_indices
+        .iter()
+        .filter(|index| indices_1.contains(index))
+        .copied()
+        .collect::<Vec<_>>();
+    if slashed_indices.iter().all(|index| cache.attester_slashed_indices.contains(index)) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let mut state = state.clone();
+    let signatures = &mut SignatureSets::default();
+    let result = match &mut state {
+        BeaconState::Phase0(state) => phase0::process_attester_slashing(
+            state,
+            attester_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Altair(state) => altair::process_attester_slashing(
+            state,
+            attester_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Bellatrix(state) => bellatrix::process_attester_slashing(
+            state,
+            attester_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Capella(state) => capella::process_attester_slashing(
+            state,
+            attester_slashing,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Deneb(state) => {
+            deneb::process_attester_slashing(state, attester_slashing, signatures, &mut (), context)
+        }
+    };
+    if let Err(err) = result {
+        return Ok(RejectReason::InvalidOperation(Box::new(err)).into())
+    }
+
+    cache.attester_slashed_indices.extend(slashed_indices);
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `signed_bls_to_execution_change` from the `bls_to_execution_change` topic by
+/// processing it against a copy of `state`.
+pub fn validate_bls_to_execution_change<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    signed_bls_to_execution_change: &mut SignedBlsToExecutionChange,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    cache: &mut GossipCache,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    if state.version() < Fork::Capella {
+        return Ok(IgnoreReason::BeforeFork(Fork::Capella).into())
+    }
+    let validator_index = signed_bls_to_execution_change.message.validator_index;
+    if cache.bls_to_execution_changes.contains(&validator_index) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+
+    let mut state = state.clone();
+    let signatures = &mut SignatureSets::default();
+    let result = match &mut state {
+        BeaconState::Phase0(_) | BeaconState::Altair(_) | BeaconState::Bellatrix(_) => {
+            unreachable!("checked the fork of the state above")
+        }
+        BeaconState::Capella(state) => capella::process_bls_to_execution_change(
+            state,
+            signed_bls_to_execution_change,
+            signatures,
+            &mut (),
+            context,
+        ),
+        BeaconState::Deneb(state) => deneb::process_bls_to_execution_change(
+            state,
+            signed_bls_to_execution_change,
+            signatures,
+            &mut (),
+            context,
+        ),
+    };
+    if let Err(err) = result {
+        return Ok(RejectReason::InvalidOperation(Box::new(err)).into())
+    }
+
+    cache.bls_to_execution_changes.insert(validator_index);
+    Ok(ValidationResult::Accept)
+}
+
+/// Validates `signed_blob_sidecar` from the `blob_sidecar_{subnet_id}` topic. `proto_array` holds
+/// the blocks that have been seen and passed validation.
+pub fn validate_blob_sidecar<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const BYTES_PER_BLOB: usize,
+>(
+    signed_blob_sidecar: &mut SignedBlobSidecar<BYTES_PER_BLOB>,
+    subnet_id: u64,
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    proto_array: &ProtoArray,
+    cache: &mut GossipCache,
+    now: Duration,
+    context: &Context,
+) -> Result<ValidationResult, Error> {
+    let sidecar = &signed_blob_sidecar.message;
+    let (slot, index, block_root, parent_root, proposer_index) = (
+        sidecar.slot,
+        sidecar.index,
+        sidecar.block_root,
+        sidecar.block_parent_root,
+        sidecar.proposer_index,
+    );
+
+    if state.version() < Fork::Deneb {
+        return Ok(IgnoreReason::BeforeFork(Fork::Deneb).into())
+    }
+    if index >= context.max_blobs_per_block {
+        return Ok(RejectReason::InvalidBlobIndex(index).into())
+    }
+    if compute_subnet_for_blob_sidecar(index) != subnet_id {
+        return Ok(RejectReason::IncorrectSubnet(subnet_id).into())
+    }
+    let (_, current_slot) = current_slot_range(state.genesis_time(), now, context);
+    if slot > current_slot {
+        return Ok(IgnoreReason::FutureSlot { slot, current_slot }.into())
+    }
+    let finalized_checkpoint = state.finalized_checkpoint();
+    let finalized_slot = compute_start_slot_at_epoch(finalized_checkpoint.epoch, context);
+    if slot <= finalized_slot {
+        return Ok(IgnoreReason::FinalizedSlot { slot, finalized_slot }.into())
+    }
+    if cache.is_invalid_block(&parent_root) {
+        return Ok(RejectReason::InvalidBlock(parent_root).into())
+    }
+    let parent_slot = match proto_array.get(&parent_root) {
+        Some(parent) => parent.slot,
+        None => return Ok(IgnoreReason::UnknownBlock(parent_root).into()),
+    };
+    if slot <= parent_slot {
+        return Ok(RejectReason::NotLaterThanParent { slot, parent_slot }.into())
+    }
+    if !descends_from_finalized(proto_array, &parent_root, finalized_checkpoint, context) {
+        return Ok(RejectReason::NotDescendantOfFinalized(parent_root).into())
+    }
+
+    let epoch = compute_epoch_at_slot(slot, context);
+    let state_epoch = compute_epoch_at_slot(state.slot(), context);
+    if epoch + 1 < state_epoch {
+        return Ok(IgnoreReason::ShufflingUnavailable { epoch }.into())
+    }
+    let validators = state.validators();
+    if proposer_index >= validators.len() {
+        return Ok(RejectReason::UnknownValidator(proposer_index).into())
+    }
+    let domain = get_domain(state, DomainType::BlobSidecar, epoch, context)?;
+    let signing_root = compute_signing_root(&mut signed_blob_sidecar.message, domain)?;
+    if !is_valid_signature(
+        validators,
+        proposer_index,
+        signing_root,
+        &signed_blob_sidecar.signature,
+        context,
+    )? {
+        return Ok(RejectReason::InvalidSignature.into())
+    }
+    if cache.blob_sidecars.contains(&(slot, block_root, index)) {
+        return Ok(IgnoreReason::AlreadySeen.into())
+    }
+    let expected = get_proposer_index_at_slot(state, slot, context)?;
+    if proposer_index != expected {
+        // NOTE: the proposers of the previous epoch are computed with the effective balances of
+        // `state`, which may have changed at the start of its epoch
+        if epoch < state_epoch {
+            return Ok(IgnoreReason::UnverifiedProposer { slot, proposer_index }.into())
+        }
+        return Ok(RejectReason::IncorrectProposer { provided: proposer_index, expected }.into())
+    }
+
+    cache.blob_sidecars.insert((slot, block_root, index));
+    Ok(ValidationResult::Accept)
+}
+
+// Returns the earliest and latest slot the current time may be in, allowing for
+// `MAXIMUM_GOSSIP_CLOCK_DISPARITY` in either direction.
+fn current_slot_range(genesis_time: u64, now: Duration, context: &Context) -> (Slot, Slot) {
+    let genesis_time = Duration::from_secs(genesis_time);
+    let slot_at =
+        |time: Duration| time.saturating_sub(genesis_time).as_secs() / context.seconds_per_slot;
+    (
+        slot_at(now.saturating_sub(MAXIMUM_GOSSIP_CLOCK_DISPARITY)),
+        slot_at(now + MAXIMUM_GOSSIP_CLOCK_DISPARITY),
+    )
+}
+
+// Checks that `slot` is the current slot.
+fn check_current_slot(
+    slot: Slot,
+    genesis_time: u64,
+    now: Duration,
+    context: &Context,
+) -> Option<IgnoreReason> {
+    let (earliest_slot, current_slot) = current_slot_range(genesis_time, now, context);
+    if slot > current_slot {
+        Some(IgnoreReason::FutureSlot { slot, current_slot })
+    } else if slot < earliest_slot {
+        Some(IgnoreReason::PastSlot { slot, earliest_slot })
+    } else {
+        None
+    }
+}
+
+// Checks that an attestation from `slot` is within `ATTESTATION_PROPAGATION_SLOT_RANGE` of the
+// current slot.
+fn check_attestation_slot(
+    slot: Slot,
+    genesis_time: u64,
+    now: Duration,
+    context: &Context,
+) -> Option<IgnoreReason> {
+    let (earliest_slot, current_slot) = current_slot_range(genesis_time, now, context);
+    let earliest_slot = earliest_slot.saturating_sub(ATTESTATION_PROPAGATION_SLOT_RANGE as Slot);
+    if slot > current_slot {
+        Some(IgnoreReason::FutureSlot { slot, current_slot })
+    } else if slot < earliest_slot {
+        Some(IgnoreReason::PastSlot { slot, earliest_slot })
+    } else {
+        None
+    }
+}
+
+// Checks that the block with `root` an attestation votes for has been seen and is valid.
+fn check_attested_block(
+    root: &Root,
+    proto_array: &ProtoArray,
+    cache: &GossipCache,
+) -> Option<ValidationResult> {
+    if cache.is_invalid_block(root) {
+        Some(RejectReason::InvalidBlock(*root).into())
+    } else if !proto_array.contains(root) {
+        Some(IgnoreReason::UnknownBlock(*root).into())
+    } else {
+        None
+    }
+}
+
+// Checks that `target` is the checkpoint block of its epoch on the chain of the block with `root`.
+fn check_attestation_target(
+    root: &Root,
+    target: &Checkpoint,
+    proto_array: &ProtoArray,
+    context: &Context,
+) -> Option<RejectReason> {
+    let target_slot = compute_start_slot_at_epoch(target.epoch, context);
+    if proto_array.ancestor(root, target_slot) == Some(target.root) {
+        None
+    } else {
+        Some(RejectReason::IncorrectTarget(target.root))
+    }
+}
+
+// Whether the block with `root` descends from the block of `finalized_checkpoint`. Every block
+// descends from the genesis block, whose root is not recorded in the genesis checkpoint.
+fn descends_from_finalized(
+    proto_array: &ProtoArray,
+    root: &Root,
+    finalized_checkpoint: &Checkpoint,
+    context: &Context,
+) -> bool {
+    if finalized_checkpoint.epoch == GENESIS_EPOCH {
+        return true
+    }
+    let finalized_slot = compute_start_slot_at_epoch(finalized_checkpoint.epoch, context);
+    proto_array.ancestor(root, finalized_slot) == Some(finalized_checkpoint.root)
+}
+
+// Whether `signature` is a valid signature of `signing_root` by the validator at `index`.
+fn is_valid_signature(
+    validators: &[Validator],
+    index: ValidatorIndex,
+    signing_root: Root,
+    signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool, Error> {
+    let signature_set =
+        context.pubkey_cache.signature_set(validators, index, signing_root.as_ref(), signature)?;
+    Ok(signature_set.verify().is_ok())
+}
+
+// Whether the committees of `epoch` can be computed from `state`.
+fn is_shuffling_available<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    context: &Context,
+) -> bool {
+    let state_epoch = compute_epoch_at_slot(state.slot(), context);
+    epoch + 1 >= state_epoch && epoch <= state_epoch + 1
+}
+
+// Returns the domain of `domain_type` for messages from `epoch` on the chain of `state`.
+fn get_domain<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    domain_type: DomainType,
+    epoch: Epoch,
+    context: &Context,
+) -> Result<Domain, Error> {
+    let fork = state.fork();
+    // a state that has not been advanced to `epoch` has not been upgraded to the fork of `epoch`
+    let scheduled_fork = context.fork_for(compute_start_slot_at_epoch(epoch, context));
+    let fork_version = if epoch < fork.epoch {
+        fork.previous_version
+    } else if scheduled_fork > state.version() {
+        context.fork_version_for(scheduled_fork)
+    } else {
+        fork.current_version
+    };
+    compute_domain(domain_type, Some(fork_version), Some(state.genesis_validators_root()), context)
+}
+
+// Returns the proposer of `slot`, which must not be from before the previous epoch of `state`.
+// The proposers of a later epoch are computed from a copy of `state` advanced to that epoch, as
+// its effective balances and seed are only final once the preceding epoch has been processed.
+// The proposers of the previous epoch are computed from the seed and active validators of that
+// epoch with the effective balances of `state`.
+fn get_proposer_index_at_slot<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    context: &Context,
+) -> Result<ValidatorIndex, Error> {
+    let epoch = compute_epoch_at_slot(slot, context);
+    if epoch > compute_epoch_at_slot(state.slot(), context) {
+        // NOTE: fork upgrades on the way to `epoch` do not change the proposers, so the state is
+        // advanced within its own fork
+        let start_slot = compute_start_slot_at_epoch(epoch, context);
+        let mut state = state.clone();
+        match &mut state {
+            BeaconState::Phase0(state) => {
+                phase0::process_slots(state, start_slot, &mut (), context)?
+            }
+            BeaconState::Altair(state) => {
+                altair::process_slots(state, start_slot, &mut (), context)?
+            }
+            BeaconState::Bellatrix(state) => {
+                bellatrix::process_slots(state, start_slot, &mut (), context)?
+            }
+            BeaconState::Capella(state) => {
+                capella::process_slots(state, start_slot, &mut (), context)?
+            }
+            BeaconState::Deneb(state) => deneb::process_slots(state, start_slot, &mut (), context)?,
+        }
+        return get_proposer_index_at_slot(&state, slot, context)
+    }
+    let domain_type = DomainType::BeaconProposer;
+    let epoch_seed = match state {
+        BeaconState::Phase0(state) => phase0::get_seed(state, epoch, domain_type, context),
+        BeaconState::Altair(state) => altair::get_seed(state, epoch, domain_type, context),
+        BeaconState::Bellatrix(state) => bellatrix::get_seed(state, epoch, domain_type, context),
+        BeaconState::Capella(state) => capella::get_seed(state, epoch, domain_type, context),
+        BeaconState::Deneb(state) => deneb::get_seed(state, epoch, domain_type, context),
+    };
+    let active_validator_indices = || match state {
+        BeaconState::Phase0(state) => phase0::get_active_validator_indices(state, epoch),
+        BeaconState::Altair(state) => altair::get_active_validator_indices(state, epoch),
+        BeaconState::Bellatrix(state) => bellatrix::get_active_validator_indices(state, epoch),
+        BeaconState::Capella(state) => capella::get_active_validator_indices(state, epoch),
+        BeaconState::Deneb(state) => deneb::get_active_validator_indices(state, epoch),
+    };
+    // NOTE: follows `get_beacon_proposer_index` for a state at `slot`
+    let mut input = [0u8; 40];
+    input[..32].copy_from_slice(epoch_seed.as_ref());
+    input[32..40].copy_from_slice(&slot.to_le_bytes());
+    let seed = hash(input);
+    let validators = state.validators();
+    context.shuffling_cache.get_or_compute_proposer(
+        &seed,
+        active_validator_indices,
+        |index| validators[index].effective_balance,
+        context,
+    )
+}
+
+fn get_committee_count_per_slot<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    epoch: Epoch,
+    context: &Context,
+) -> usize {
+    match state {
+        BeaconState::Phase0(state) => phase0::get_committee_count_per_slot(state, epoch, context),
+        BeaconState::Altair(state) => altair::get_committee_count_per_slot(state, epoch, context),
+        BeaconState::Bellatrix(state) => {
+            bellatrix::get_committee_count_per_slot(state, epoch, context)
+        }
+        BeaconState::Capella(state) => capella::get_committee_count_per_slot(state, epoch, context),
+        BeaconState::Deneb(state) => deneb::get_committee_count_per_slot(state, epoch, context),
+    }
+}
+
+fn get_beacon_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    index: CommitteeIndex,
+    context: &Context,
+) -> Result<Vec<ValidatorIndex>, Error> {
+    match state {
+        BeaconState::Phase0(state) => phase0::get_beacon_committee(state, slot, index, context),
+        BeaconState::Altair(state) => altair::get_beacon_committee(state, slot, index, context),
+        BeaconState::Bellatrix(state) => {
+            bellatrix::get_beacon_committee(state, slot, index, context)
+        }
+        BeaconState::Capella(state) => capella::get_beacon_committee(state, slot, index, context),
+        BeaconState::Deneb(state) => deneb::get_beacon_committee(state, slot, index, context),
+    }
+}
+
+fn is_aggregator<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    slot: Slot,
+    index: CommitteeIndex,
+    slot_signature: &BlsSignature,
+    context: &Context,
+) -> Result<bool, Error> {
+    match state {
+        BeaconState::Phase0(state) => {
+            phase0::is_aggregator(state, slot, index, slot_signature, context)
+        }
+        BeaconState::Altair(state) => {
+            altair::is_aggregator(state, slot, index, slot_signature, context)
+        }
+        BeaconState::Bellatrix(state) => {
+            bellatrix::is_aggregator(state, slot, index, slot_signature, context)
+        }
+        BeaconState::Capella(state) => {
+            capella::is_aggregator(state, slot, index, slot_signature, context)
+        }
+        BeaconState::Deneb(state) => {
+            deneb::is_aggregator(state, slot, index, slot_signature, context)
+        }
+    }
+}
+
+// Returns `None` if `state` is from before Altair.
+fn get_sync_subcommittee_pubkeys<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    subcommittee_index: u64,
+    context: &Context,
+) -> Option<Vec<BlsPublicKey>> {
+    match state {
+        BeaconState::Phase0(_) => None,
+        BeaconState::Altair(state) => {
+            Some(altair::get_sync_subcommittee_pubkeys(state, subcommittee_index, context))
+        }
+        BeaconState::Bellatrix(state) => {
+            Some(bellatrix::get_sync_subcommittee_pubkeys(state, subcommittee_index, context))
+        }
+        BeaconState::Capella(state) => {
+            Some(capella::get_sync_subcommittee_pubkeys(state, subcommittee_index, context))
+        }
+        BeaconState::Deneb(state) => {
+            Some(deneb::get_sync_subcommittee_pubkeys(state, subcommittee_index, context))
+        }
+    }
+}
+
+// Returns `None` if `state` is from before Altair.
+fn compute_subnets_for_sync_committee<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    validator_index: ValidatorIndex,
+    context: &Context,
+) -> Result<Option<HashSet<u64>>, Error> {
+    let subnets = match state {
+        BeaconState::Phase0(_) => return Ok(None),
+        BeaconState::Altair(state) => {
+            altair::compute_subnets_for_sync_committee(state, validator_index, context)
+        }
+        BeaconState::Bellatrix(state) => {
+            bellatrix::compute_subnets_for_sync_committee(state, validator_index, context)
+        }
+        BeaconState::Capella(state) => {
+            capella::compute_subnets_for_sync_committee(state, validator_index, context)
+        }
+        BeaconState::Deneb(state) => {
+            deneb::compute_subnets_for_sync_committee(state, validator_index, context)
+        }
+    }?;
+    Ok(Some(subnets))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::{
+            minimal::SYNC_SUBCOMMITTEE_SIZE, ContributionAndProof, SyncCommitteeContribution,
+        },
+        harness::minimal::Harness,
+        phase0::AggregateAndProof,
+    };
+
+    // Returns a tree of the genesis block of `harness` and the blocks with `roots` on top of it.
+    fn proto_array_for(harness: &Harness, roots: &[Root]) -> ProtoArray {
+        let mut proto_array = ProtoArray::default();
+        proto_array
+            .insert(
+                harness.genesis_root(),
+                Root::default(),
+                0,
+                Checkpoint::default(),
+                Checkpoint::default(),
+            )
+            .unwrap();
+        for root in roots {
+            let block = harness.block(root).unwrap().message();
+            proto_array
+                .insert(
+                    *root,
+                    block.parent_root(),
+                    block.slot(),
+                    Checkpoint::default(),
+                    Checkpoint::default(),
+                )
+                .unwrap();
+        }
+        proto_array
+    }
+
+    fn start_of_slot(harness: &Harness, slot: Slot) -> Duration {
+        let genesis_time = harness.head_state().genesis_time();
+        Duration::from_secs(genesis_time + slot * harness.context().seconds_per_slot)
+    }
+
+    #[test]
+    fn test_validate_beacon_block() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        let roots = harness.extend_chain(2).unwrap();
+        let proto_array = proto_array_for(&harness, &roots);
+        let head_root = harness.head_root();
+        let mut block = harness.produce_block(head_root, 3).unwrap();
+        let state = harness.advance(head_root, 3).unwrap();
+        let context = harness.context();
+        let mut cache = GossipCache::default();
+
+        let now = start_of_slot(&harness, 1);
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Ignore(IgnoreReason::FutureSlot { slot: 3, current_slot: 1 }))
+        ));
+
+        let now = start_of_slot(&harness, 3);
+        let mut unsigned_block = block.clone();
+        *unsigned_block.signature_mut() = harness.keys[0].sign(b"block");
+        let result = validate_beacon_block(
+            &mut unsigned_block,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::InvalidSignature))));
+
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+    }
+
+    #[test]
+    fn test_validate_beacon_block_from_another_epoch() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        let roots = harness.extend_chain(2).unwrap();
+        let proto_array = proto_array_for(&harness, &roots);
+        let head_root = harness.head_root();
+        let state = harness.head_state().clone();
+        let slots_per_epoch = harness.context().slots_per_epoch;
+        let mut cache = GossipCache::default();
+
+        // the proposers of the next epoch are computed by advancing the state
+        let slot = slots_per_epoch;
+        let mut block = harness.produce_block(head_root, slot).unwrap();
+        let now = start_of_slot(&harness, slot);
+        let context = harness.context();
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+
+        let slot = slots_per_epoch + 1;
+        let mut block = harness.produce_block(head_root, slot).unwrap();
+        let expected = block.message().proposer_index();
+        let provided = (expected + 1) % harness.keys.len();
+        let domain = harness.get_domain(&state, DomainType::BeaconProposer, 1).unwrap();
+        let signed_block = block.phase0_mut().unwrap();
+        signed_block.message.proposer_index = provided;
+        signed_block.signature = harness.sign(provided, &mut signed_block.message, domain).unwrap();
+        let now = start_of_slot(&harness, slot);
+        let context = harness.context();
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Reject(RejectReason::IncorrectProposer { provided: p, expected: e }))
+                if p == provided && e == expected
+        ));
+
+        // the proposers of the previous epoch are computed from a later state
+        let mut block = harness.produce_block(head_root, 3).unwrap();
+        let mut late_block = block.clone();
+        let state = harness.advance(head_root, slots_per_epoch).unwrap();
+        let now = start_of_slot(&harness, slots_per_epoch);
+        let context = harness.context();
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+
+        let mut block = harness.produce_block(head_root, 4).unwrap();
+        let expected = block.message().proposer_index();
+        let provided = (expected + 1) % harness.keys.len();
+        let domain = harness.get_domain(&state, DomainType::BeaconProposer, 0).unwrap();
+        let signed_block = block.phase0_mut().unwrap();
+        signed_block.message.proposer_index = provided;
+        signed_block.signature = harness.sign(provided, &mut signed_block.message, domain).unwrap();
+        let result =
+            validate_beacon_block(&mut block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Ignore(IgnoreReason::UnverifiedProposer { slot: 4, proposer_index }))
+                if proposer_index == provided
+        ));
+
+        // but not from a state more than one epoch later
+        let state = harness.advance(head_root, 2 * slots_per_epoch).unwrap();
+        let now = start_of_slot(&harness, 2 * slots_per_epoch);
+        let context = harness.context();
+        let mut cache = GossipCache::default();
+        let result =
+            validate_beacon_block(&mut late_block, &state, &proto_array, &mut cache, now, context);
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Ignore(IgnoreReason::ShufflingUnavailable { epoch: 0 }))
+        ));
+    }
+
+    #[test]
+    fn test_validate_aggregate_and_proof() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        let roots = harness.extend_chain(2).unwrap();
+        let proto_array = proto_array_for(&harness, &roots);
+        let slot = 2;
+        let aggregate = harness.make_attestations(harness.head_root(), slot).unwrap().remove(0);
+        let state = harness.head_state();
+        let context = harness.context();
+        let committee = get_beacon_committee(state, slot, aggregate.data.index, context).unwrap();
+        let sign_aggregate = |aggregator_index: ValidatorIndex| {
+            let domain = harness.get_domain(state, DomainType::SelectionProof, 0).unwrap();
+            let selection_proof =
+                harness.sign(aggregator_index, &mut slot.clone(), domain).unwrap();
+            let mut message = AggregateAndProof {
+                aggregator_index,
+                aggregate: aggregate.clone(),
+                selection_proof,
+            };
+            let domain = harness.get_domain(state, DomainType::AggregateAndProof, 0).unwrap();
+            let signature = harness.sign(aggregator_index, &mut message, domain).unwrap();
+            SignedAggregateAndProof { message, signature }
+        };
+        let now = start_of_slot(&harness, slot);
+        let mut cache = GossipCache::default();
+
+        let outsider = (0..harness.keys.len()).find(|index| !committee.contains(index)).unwrap();
+        let mut aggregate_and_proof = sign_aggregate(outsider);
+        let result = validate_aggregate_and_proof(
+            &mut aggregate_and_proof,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Reject(RejectReason::AggregatorNotInCommittee))
+        ));
+
+        let mut aggregate_and_proof = sign_aggregate(committee[0]);
+        let mut unsigned_aggregate_and_proof = aggregate_and_proof.clone();
+        unsigned_aggregate_and_proof.signature = harness.keys[0].sign(b"aggregate");
+        let result = validate_aggregate_and_proof(
+            &mut unsigned_aggregate_and_proof,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::InvalidSignature))));
+
+        // the attested block has not been seen
+        let result = validate_aggregate_and_proof(
+            &mut aggregate_and_proof,
+            state,
+            &proto_array_for(&harness, &roots[..1]),
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::UnknownBlock(_)))));
+
+        let result = validate_aggregate_and_proof(
+            &mut aggregate_and_proof,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        // an aggregate covered by one seen before is ignored, whoever aggregated it
+        let mut aggregate_and_proof = sign_aggregate(committee[1]);
+        let result = validate_aggregate_and_proof(
+            &mut aggregate_and_proof,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+    }
+
+    #[test]
+    fn test_validate_sync_committee_messages() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        let mut harness = Harness::new(64, context).unwrap();
+        harness.extend_chain(2).unwrap();
+        let slot = 2;
+        let head_root = harness.head_root();
+        let state = harness.head_state();
+        let context = harness.context();
+        let public_keys = &state.altair().unwrap().current_sync_committee.public_keys;
+        let validator_index_of = |position: usize| {
+            state
+                .validators()
+                .iter()
+                .position(|validator| validator.public_key == public_keys[position])
+                .unwrap()
+        };
+        let domain = harness.get_domain(state, DomainType::SyncCommittee, 0).unwrap();
+        let now = start_of_slot(&harness, slot);
+        let mut cache = GossipCache::default();
+
+        let validator_index = validator_index_of(0);
+        let subnets =
+            compute_subnets_for_sync_committee(state, validator_index, context).unwrap().unwrap();
+        let subnet_id = *subnets.iter().next().unwrap();
+        let message = SyncCommitteeMessage {
+            slot,
+            beacon_block_root: head_root,
+            validator_index,
+            signature: harness.sign(validator_index, &mut head_root.clone(), domain).unwrap(),
+        };
+        let result = validate_sync_committee_message(
+            &message,
+            SYNC_COMMITTEE_SUBNET_COUNT as u64,
+            state,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::IncorrectSubnet(_)))));
+        let mut invalid_message = message.clone();
+        invalid_message.beacon_block_root = Root::default();
+        let result = validate_sync_committee_message(
+            &invalid_message,
+            subnet_id,
+            state,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::InvalidSignature))));
+        let early = start_of_slot(&harness, slot - 1);
+        let result =
+            validate_sync_committee_message(&message, subnet_id, state, &mut cache, early, context);
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::FutureSlot { .. }))));
+        let result =
+            validate_sync_committee_message(&message, subnet_id, state, &mut cache, now, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result =
+            validate_sync_committee_message(&message, subnet_id, state, &mut cache, now, context);
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+
+        // a contribution of the first member of subcommittee 0, aggregated by that member
+        let aggregator_index = validator_index_of(0);
+        let mut aggregation_bits = Bitvector::<SYNC_SUBCOMMITTEE_SIZE>::default();
+        aggregation_bits.set(0, true);
+        let contribution = SyncCommitteeContribution {
+            slot,
+            beacon_block_root: head_root,
+            subcommittee_index: 0,
+            aggregation_bits,
+            signature: message.signature.clone(),
+        };
+        let sign_contribution = |contribution: SyncCommitteeContribution<
+            SYNC_SUBCOMMITTEE_SIZE,
+        >| {
+            let mut selection_data = SyncAggregatorSelectionData {
+                slot,
+                subcommittee_index: contribution.subcommittee_index,
+            };
+            let domain =
+                harness.get_domain(state, DomainType::SyncCommitteeSelectionProof, 0).unwrap();
+            let selection_proof =
+                harness.sign(aggregator_index, &mut selection_data, domain).unwrap();
+            let mut message =
+                ContributionAndProof { aggregator_index, contribution, selection_proof };
+            let domain = harness.get_domain(state, DomainType::ContributionAndProof, 0).unwrap();
+            let signature = harness.sign(aggregator_index, &mut message, domain).unwrap();
+            SignedContributionAndProof { message, signature }
+        };
+
+        let mut invalid_contribution = contribution.clone();
+        invalid_contribution.subcommittee_index = SYNC_COMMITTEE_SUBNET_COUNT as u64;
+        let mut contribution_and_proof = sign_contribution(invalid_contribution);
+        let result = validate_sync_committee_contribution(
+            &mut contribution_and_proof,
+            state,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Reject(RejectReason::InvalidSubcommitteeIndex(_)))
+        ));
+        let mut invalid_contribution = contribution.clone();
+        invalid_contribution.aggregation_bits = Default::default();
+        let mut contribution_and_proof = sign_contribution(invalid_contribution);
+        let result = validate_sync_committee_contribution(
+            &mut contribution_and_proof,
+            state,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::NoParticipants))));
+
+        let mut contribution_and_proof = sign_contribution(contribution);
+        let result = validate_sync_committee_contribution(
+            &mut contribution_and_proof,
+            state,
+            &mut cache,
+            early,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::FutureSlot { .. }))));
+        let result = validate_sync_committee_contribution(
+            &mut contribution_and_proof,
+            state,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result = validate_sync_committee_contribution(
+            &mut contribution_and_proof,
+            state,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+    }
+
+    #[test]
+    fn test_validate_attester_slashing() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        harness.extend_chain(1).unwrap();
+        let mut cache = GossipCache::default();
+
+        // attestations with the same data are not slashable
+        let mut attester_slashing = harness.make_attester_slashing(&[3], 0).unwrap();
+        attester_slashing.attestation_2 = attester_slashing.attestation_1.clone();
+        let state = harness.head_state();
+        let context = harness.context();
+        let result = validate_attester_slashing(&mut attester_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::InvalidOperation(_)))));
+
+        let mut attester_slashing = harness.make_attester_slashing(&[1, 2], 0).unwrap();
+        let state = harness.head_state();
+        let context = harness.context();
+        let result = validate_attester_slashing(&mut attester_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result = validate_attester_slashing(&mut attester_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+
+        // a slashing is only ignored once all of the validators it slashes have been slashed
+        let mut attester_slashing = harness.make_attester_slashing(&[2], 0).unwrap();
+        let state = harness.head_state();
+        let context = harness.context();
+        let result = validate_attester_slashing(&mut attester_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+        let mut attester_slashing = harness.make_attester_slashing(&[2, 4], 0).unwrap();
+        let state = harness.head_state();
+        let context = harness.context();
+        let result = validate_attester_slashing(&mut attester_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+    }
+
+    #[test]
+    fn test_validate_blob_sidecar() {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        context.bellatrix_fork_epoch = 0;
+        context.capella_fork_epoch = 0;
+        context.deneb_fork_epoch = 0;
+        let mut harness = Harness::new(64, context).unwrap();
+        let roots = harness.extend_chain(2).unwrap();
+        let proto_array = proto_array_for(&harness, &roots);
+        let head_root = harness.head_root();
+        let state = harness.head_state().clone();
+        let slots_per_epoch = harness.context().slots_per_epoch;
+        let mut sidecars = [3, slots_per_epoch]
+            .into_iter()
+            .map(|slot| {
+                let block = harness.produce_block(head_root, slot).unwrap();
+                let mut block = block.deneb().unwrap().message.clone();
+                deneb::minimal::BlobSidecar {
+                    block_root: block.hash_tree_root().unwrap(),
+                    index: 0,
+                    slot,
+                    block_parent_root: head_root,
+                    proposer_index: block.proposer_index,
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>();
+        let sign_sidecar = |mut message: deneb::minimal::BlobSidecar| {
+            let epoch = message.slot / slots_per_epoch;
+            let domain = harness.get_domain(&state, DomainType::BlobSidecar, epoch).unwrap();
+            let signature = harness.sign(message.proposer_index, &mut message, domain).unwrap();
+            SignedBlobSidecar { message, signature }
+        };
+        let context = harness.context();
+        let subnet_id = compute_subnet_for_blob_sidecar(0);
+        let mut cache = GossipCache::default();
+
+        let sidecar = sidecars.remove(0);
+        let now = start_of_slot(&harness, sidecar.slot);
+        let mut invalid_sidecar = sidecar.clone();
+        invalid_sidecar.index = context.max_blobs_per_block;
+        let result = validate_blob_sidecar(
+            &mut sign_sidecar(invalid_sidecar),
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::InvalidBlobIndex(_)))));
+        let result = validate_blob_sidecar(
+            &mut sign_sidecar(sidecar.clone()),
+            subnet_id + 1,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::IncorrectSubnet(_)))));
+        let mut invalid_sidecar = sidecar.clone();
+        invalid_sidecar.proposer_index = (sidecar.proposer_index + 1) % harness.keys.len();
+        let result = validate_blob_sidecar(
+            &mut sign_sidecar(invalid_sidecar),
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Reject(RejectReason::IncorrectProposer { .. }))
+        ));
+        let mut orphan_sidecar = sidecar.clone();
+        orphan_sidecar.block_parent_root = Root::try_from([1u8; 32].as_ref()).unwrap();
+        let result = validate_blob_sidecar(
+            &mut sign_sidecar(orphan_sidecar),
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::UnknownBlock(_)))));
+
+        let mut signed_sidecar = sign_sidecar(sidecar);
+        let result = validate_blob_sidecar(
+            &mut signed_sidecar,
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result = validate_blob_sidecar(
+            &mut signed_sidecar,
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+
+        // the proposer of a sidecar from the next epoch is computed by advancing the state
+        let sidecar = sidecars.remove(0);
+        let now = start_of_slot(&harness, sidecar.slot);
+        let result = validate_blob_sidecar(
+            &mut sign_sidecar(sidecar),
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+    }
+
+    #[test]
+    fn test_validate_attestation() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        let roots = harness.extend_chain(2).unwrap();
+        let proto_array = proto_array_for(&harness, &roots);
+        let slot = 2;
+        let aggregate = harness.make_attestations(harness.head_root(), slot).unwrap().remove(0);
+        let state = harness.head_state();
+        let context = harness.context();
+        let mut cache = GossipCache::default();
+
+        let mut data = aggregate.data.clone();
+        let committee = get_beacon_committee(state, slot, data.index, context).unwrap();
+        let domain = harness.get_domain(state, DomainType::BeaconAttester, 0).unwrap();
+        let signature = harness.sign(committee[0], &mut data, domain).unwrap();
+        let mut aggregation_bits = vec![false; committee.len()];
+        aggregation_bits[0] = true;
+        let attestation = Attestation {
+            aggregation_bits: Bitlist::try_from(aggregation_bits.as_slice()).unwrap(),
+            data,
+            signature,
+        };
+        let count = get_committee_count_per_slot(state, 0, context);
+        let subnet_id =
+            compute_subnet_for_attestation(count as u64, slot, attestation.data.index, context);
+        let now = start_of_slot(&harness, slot);
+
+        let result = validate_attestation(
+            &attestation,
+            subnet_id + 1,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::IncorrectSubnet(_)))));
+
+        let result = validate_attestation(
+            &aggregate,
+            subnet_id,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::NotSingleParticipant))));
+
+        let result = validate_attestation(
+            &attestation,
+            subnet_id,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result = validate_attestation(
+            &attestation,
+            subnet_id,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+
+        // more than `ATTESTATION_PROPAGATION_SLOT_RANGE` slots in the past, allowing for clock
+        // disparity
+        let now = start_of_slot(&harness, slot + ATTESTATION_PROPAGATION_SLOT_RANGE as Slot + 2);
+        let result = validate_attestation(
+            &attestation,
+            subnet_id,
+            state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::PastSlot { .. }))));
+    }
+
+    #[test]
+    fn test_validate_attestation_from_previous_epoch() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        let slots_per_epoch = harness.context().slots_per_epoch;
+        let roots = harness.extend_chain(slots_per_epoch as usize - 1).unwrap();
+        let proto_array = proto_array_for(&harness, &roots);
+        let slot = slots_per_epoch - 1;
+        let aggregate = harness.make_attestations(harness.head_root(), slot).unwrap().remove(0);
+        // the head state advanced to the first slot of the next epoch
+        let state = harness.advance(harness.head_root(), slots_per_epoch).unwrap();
+        let context = harness.context();
+        let mut cache = GossipCache::default();
+
+        let mut data = aggregate.data.clone();
+        let committee = get_beacon_committee(&state, slot, data.index, context).unwrap();
+        let domain = harness.get_domain(&state, DomainType::BeaconAttester, 0).unwrap();
+        let signature = harness.sign(committee[0], &mut data, domain).unwrap();
+        let mut aggregation_bits = vec![false; committee.len()];
+        aggregation_bits[0] = true;
+        let attestation = Attestation {
+            aggregation_bits: Bitlist::try_from(aggregation_bits.as_slice()).unwrap(),
+            data,
+            signature,
+        };
+        let count = get_committee_count_per_slot(&state, 0, context);
+        let subnet_id =
+            compute_subnet_for_attestation(count as u64, slot, attestation.data.index, context);
+        let now = start_of_slot(&harness, slots_per_epoch);
+
+        let result = validate_attestation(
+            &attestation,
+            subnet_id,
+            &state,
+            &proto_array,
+            &mut cache,
+            now,
+            context,
+        );
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+    }
+
+    #[test]
+    fn test_validate_operations() {
+        let mut harness = Harness::new(64, Context::for_minimal()).unwrap();
+        harness.extend_chain(1).unwrap();
+        let state = harness.head_state();
+        let context = harness.context();
+        let mut cache = GossipCache::default();
+
+        // validators can only exit after `SHARD_COMMITTEE_PERIOD` epochs
+        let mut voluntary_exit = harness.make_voluntary_exit(1, 0).unwrap();
+        let result = validate_voluntary_exit(&mut voluntary_exit, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Reject(RejectReason::InvalidOperation(_)))));
+
+        let mut proposer_slashing = harness.make_proposer_slashing(2, 1).unwrap();
+        let result = validate_proposer_slashing(&mut proposer_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Accept)));
+        let result = validate_proposer_slashing(&mut proposer_slashing, state, &mut cache, context);
+        assert!(matches!(result, Ok(ValidationResult::Ignore(IgnoreReason::AlreadySeen))));
+
+        let mut bls_to_execution_change = SignedBlsToExecutionChange::default();
+        let result = validate_bls_to_execution_change(
+            &mut bls_to_execution_change,
+            state,
+            &mut cache,
+            context,
+        );
+        assert!(matches!(
+            result,
+            Ok(ValidationResult::Ignore(IgnoreReason::BeforeFork(Fork::Capella)))
+        ));
+    }
+}
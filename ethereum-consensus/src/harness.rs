@@ -0,0 +1,1198 @@
+//! A deterministic chain of fully signed blocks for tests, built from the interop validator keys.
+use crate::{
+    altair::{self, SyncCommitteeContribution, SYNC_COMMITTEE_SUBNET_COUNT},
+    bellatrix, block_production, capella,
+    crypto::{aggregate, hash, SecretKey},
+    deneb,
+    deposit_tree::DepositTree,
+    domains::DomainType,
+    genesis::{
+        self, interop_secret_key, interop_secret_keys, make_deposit_data, GENESIS_ETH1_BLOCK_HASH,
+    },
+    operation_pool::OperationPool,
+    phase0::{
+        self, Attestation, AttestationData, AttesterSlashing, BeaconBlockHeader, Checkpoint,
+        Deposit, DepositData, Eth1Data, IndexedAttestation, ProposerSlashing,
+        SignedBeaconBlockHeader, SignedVoluntaryExit, VoluntaryExit,
+    },
+    primitives::{
+        BlsPublicKey, BlsSignature, Bytes32, Domain, Epoch, Gwei, Hash32, Root, Slot,
+        ValidatorIndex, GENESIS_SLOT,
+    },
+    signing::{compute_signing_root, sign_with_domain},
+    ssz::prelude::*,
+    state_transition::{self, Context, ExecutionEngine, Executor},
+    types::{
+        BeaconBlock, BeaconState, ExecutionPayload, ExecutionPayloadHeader, SignedBeaconBlock,
+    },
+    Error, Fork,
+};
+use std::collections::HashMap;
+
+// Returns a block hash for the execution payload at `slot` following the block with `parent_hash`.
+fn compute_block_hash(parent_hash: &Hash32, slot: Slot) -> Hash32 {
+    let mut data = parent_hash.to_vec();
+    data.extend_from_slice(&slot.to_le_bytes());
+    hash(data)
+}
+
+/// Builds a chain from a genesis state whose validators are the first interop validators and
+/// signs everything on the chain with their `keys`.
+///
+/// Blocks are produced with `block_production::produce_block` and applied with an `Executor`, so
+/// the chain upgrades at the fork epochs of the `Context` it is created with. Every block and its
+/// post-state are kept by root: blocks can be built on any of them to make forks, and the chain
+/// can skip slots by building on a parent from an earlier slot. Execution payloads are made up so
+/// that they pass `process_execution_payload` against the default execution engines.
+///
+/// Blocks include the attestations of every committee to the blocks before them, a full sync
+/// aggregate, any operations added to the `pool` and the deposits made with `add_deposit`, which
+/// the chain votes into its `eth1_data` first.
+pub struct Harness<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+> {
+    /// The secret key of each validator, by validator index.
+    pub keys: Vec<SecretKey>,
+    /// The operations to include in the blocks of the chain.
+    pub pool: OperationPool<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>,
+    executor: Executor<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        bellatrix::DefaultExecutionEngine,
+        capella::DefaultExecutionEngine,
+        deneb::DefaultExecutionEngine,
+    >,
+    validator_indices: HashMap<BlsPublicKey, ValidatorIndex>,
+    // The data of every deposit made so far and the deposit tree of their roots
+    deposit_data: Vec<DepositData>,
+    deposit_tree: DepositTree,
+    blocks: HashMap<
+        Root,
+        SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+    >,
+    // The post-state of each block, including the genesis block
+    states: HashMap<
+        Root,
+        BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >,
+    genesis_root: Root,
+    head: Root,
+}
+
+impl<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const SYNC_SUBCOMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    >
+    Harness<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >
+{
+    /// Creates a chain at genesis with `validator_count` interop validators, each deposited with
+    /// the maximum effective balance. The genesis state is of the fork of `GENESIS_SLOT` in
+    /// `context`; from Bellatrix, it has a non-empty execution payload header so that the merge
+    /// is complete at genesis.
+    pub fn new(validator_count: usize, context: Context) -> Result<Self, Error> {
+        let keys = interop_secret_keys(validator_count)?;
+        let deposit_data = keys
+            .iter()
+            .map(|key| make_deposit_data(key, context.max_effective_balance, &context))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let mut deposit_tree = DepositTree::default();
+        for data in deposit_data.iter() {
+            deposit_tree.push_deposit_data(&mut data.clone())?;
+        }
+
+        let block_hash =
+            Hash32::try_from(GENESIS_ETH1_BLOCK_HASH.as_ref()).expect("is valid instance");
+        let execution_payload_header = match context.fork_for(GENESIS_SLOT) {
+            Fork::Phase0 | Fork::Altair => None,
+            Fork::Bellatrix => {
+                Some(ExecutionPayloadHeader::Bellatrix(bellatrix::ExecutionPayloadHeader {
+                    block_hash,
+                    ..Default::default()
+                }))
+            }
+            Fork::Capella => {
+                Some(ExecutionPayloadHeader::Capella(capella::ExecutionPayloadHeader {
+                    block_hash,
+                    ..Default::default()
+                }))
+            }
+            Fork::Deneb => Some(ExecutionPayloadHeader::Deneb(deneb::ExecutionPayloadHeader {
+                block_hash,
+                ..Default::default()
+            })),
+        };
+        let mut genesis_state =
+            genesis::initialize_genesis_state::<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >(
+                &deposit_data, execution_payload_header, context.min_genesis_time, &context
+            )?;
+        let mut genesis_header = genesis_state.latest_block_header().clone();
+        genesis_header.state_root = genesis_state.hash_tree_root()?;
+        let genesis_root = genesis_header.hash_tree_root()?;
+
+        let validator_indices =
+            keys.iter().enumerate().map(|(index, key)| (key.public_key(), index)).collect();
+        let execution_engine = ExecutionEngine::new(
+            bellatrix::DefaultExecutionEngine::default(),
+            capella::DefaultExecutionEngine::default(),
+            deneb::DefaultExecutionEngine::default(),
+        );
+        let executor = Executor::new(
+            Self::to_executor_state(genesis_state.clone()),
+            execution_engine,
+            context,
+        );
+        Ok(Self {
+            keys,
+            pool: Default::default(),
+            executor,
+            validator_indices,
+            deposit_data,
+            deposit_tree,
+            blocks: Default::default(),
+            states: HashMap::from_iter([(genesis_root, genesis_state)]),
+            genesis_root,
+            head: genesis_root,
+        })
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.executor.context
+    }
+
+    pub fn genesis_root(&self) -> Root {
+        self.genesis_root
+    }
+
+    /// Returns the root of the block the next block of `extend_chain` is built on, which is the
+    /// last block applied unless changed with `set_head`.
+    pub fn head_root(&self) -> Root {
+        self.head
+    }
+
+    pub fn head_state(
+        &self,
+    ) -> &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    > {
+        &self.states[&self.head]
+    }
+
+    pub fn set_head(&mut self, root: Root) -> Result<(), Error> {
+        if !self.states.contains_key(&root) {
+            return Err(Error::UnknownBlockRoot(root))
+        }
+        self.head = root;
+        Ok(())
+    }
+
+    /// Returns the post-state of the block with `root`.
+    pub fn state(
+        &self,
+        root: &Root,
+    ) -> Option<
+        &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    > {
+        self.states.get(root)
+    }
+
+    /// Returns the block with `root`, which is `None` for the genesis block as only its header is
+    /// known.
+    pub fn block(
+        &self,
+        root: &Root,
+    ) -> Option<
+        &SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+    > {
+        self.blocks.get(root)
+    }
+
+    /// Returns the domain of `domain_type` for messages from `epoch` on the chain of `state`.
+    /// Voluntary exits are signed with the Capella fork version from Deneb, as in EIP-7044.
+    pub fn get_domain(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        domain_type: DomainType,
+        epoch: Epoch,
+    ) -> Result<Domain, Error> {
+        let fork = state.fork();
+        let fork_version =
+            if matches!(domain_type, DomainType::VoluntaryExit) && state.version() >= Fork::Deneb {
+                self.context().capella_fork_version
+            } else if epoch < fork.epoch {
+                fork.previous_version
+            } else {
+                fork.current_version
+            };
+        phase0::compute_domain(
+            domain_type,
+            Some(fork_version),
+            Some(state.genesis_validators_root()),
+            self.context(),
+        )
+    }
+
+    /// Signs `message` with the key of the validator at `validator_index` in `domain`.
+    pub fn sign<T: Merkleized>(
+        &self,
+        validator_index: ValidatorIndex,
+        message: &mut T,
+        domain: Domain,
+    ) -> Result<BlsSignature, Error> {
+        sign_with_domain(message, &self.keys[validator_index], domain)
+    }
+
+    /// Returns the post-state of the block with `root` advanced to `slot` without further blocks,
+    /// upgraded to the fork of `slot`.
+    pub fn advance(
+        &mut self,
+        root: Root,
+        slot: Slot,
+    ) -> Result<
+        BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        Error,
+    > {
+        let state = self.states.get(&root).ok_or(Error::UnknownBlockRoot(root))?.clone();
+        self.executor.state = Self::to_executor_state(state);
+        self.executor.process_slots(slot, &mut ())?;
+        Ok(Self::from_executor_state(self.executor.state.clone()))
+    }
+
+    /// Produces a signed block for `slot` on top of the block with `parent_root` without applying
+    /// it, so that it can be modified and signed again before `apply_block`.
+    pub fn produce_block(
+        &mut self,
+        parent_root: Root,
+        slot: Slot,
+    ) -> Result<
+        SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+        Error,
+    > {
+        let state = self.advance(parent_root, slot)?;
+        let context = self.context();
+        let mut epoch = slot / context.slots_per_epoch;
+        let proposer_index = self.get_beacon_proposer_index(&state)?;
+        let domain = self.get_domain(&state, DomainType::Randao, epoch)?;
+        let randao_reveal = self.sign(proposer_index, &mut epoch, domain)?;
+
+        let mut operations = self.pool.get_block_operations(&state, slot, context)?;
+        let (eth1_data, deposits) = self.get_eth1_vote_and_deposits(&state)?;
+        operations.eth1_data = eth1_data;
+        operations.deposits = deposits;
+        operations
+            .sync_committee_contributions
+            .extend(self.make_sync_committee_contributions(&state)?);
+
+        let mut block = block_production::produce_block(
+            &state,
+            slot,
+            randao_reveal,
+            Bytes32::default(),
+            self.make_execution_payload(&state, slot)?,
+            List::default(),
+            &operations,
+            context,
+        )?;
+        let domain = self.get_domain(&state, DomainType::BeaconProposer, epoch)?;
+        let signature = self.sign(proposer_index, &mut block, domain)?;
+        let signed_block = match block {
+            BeaconBlock::Phase0(message) => {
+                SignedBeaconBlock::Phase0(phase0::SignedBeaconBlock { message, signature })
+            }
+            BeaconBlock::Altair(message) => {
+                SignedBeaconBlock::Altair(altair::SignedBeaconBlock { message, signature })
+            }
+            BeaconBlock::Bellatrix(message) => {
+                SignedBeaconBlock::Bellatrix(bellatrix::SignedBeaconBlock { message, signature })
+            }
+            BeaconBlock::Capella(message) => {
+                SignedBeaconBlock::Capella(capella::SignedBeaconBlock { message, signature })
+            }
+            BeaconBlock::Deneb(message) => {
+                SignedBeaconBlock::Deneb(deneb::SignedBeaconBlock { message, signature })
+            }
+        };
+        Ok(signed_block)
+    }
+
+    /// Applies `signed_block` to the post-state of its parent with full validation and makes it
+    /// the head, returning its root. Operations made obsolete by the block are pruned from the
+    /// `pool`.
+    pub fn apply_block(
+        &mut self,
+        signed_block: SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+    ) -> Result<Root, Error> {
+        let parent_root = signed_block.message().parent_root();
+        let state =
+            self.states.get(&parent_root).ok_or(Error::UnknownBlockRoot(parent_root))?.clone();
+        self.executor.state = Self::to_executor_state(state);
+        let mut executor_block: state_transition::SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        > = match signed_block.clone() {
+            SignedBeaconBlock::Phase0(signed_block) => signed_block.into(),
+            SignedBeaconBlock::Altair(signed_block) => signed_block.into(),
+            SignedBeaconBlock::Bellatrix(signed_block) => signed_block.into(),
+            SignedBeaconBlock::Capella(signed_block) => signed_block.into(),
+            SignedBeaconBlock::Deneb(signed_block) => signed_block.into(),
+        };
+        self.executor.apply_block(&mut executor_block)?;
+        let root = executor_block.message_root()?;
+        let state = Self::from_executor_state(self.executor.state.clone());
+
+        self.pool.prune(&state, &self.executor.context);
+        self.blocks.insert(root, signed_block);
+        self.states.insert(root, state);
+        self.head = root;
+        Ok(root)
+    }
+
+    /// Produces and applies a block for `slot` on top of the block with `parent_root`, skipping
+    /// any slots in between, then adds the attestations of every committee of `slot` to the new
+    /// block to the `pool`. Returns the root of the new block, which becomes the head.
+    pub fn add_block(&mut self, parent_root: Root, slot: Slot) -> Result<Root, Error> {
+        let signed_block = self.produce_block(parent_root, slot)?;
+        let root = self.apply_block(signed_block)?;
+        for attestation in self.make_attestations(root, slot)? {
+            self.pool.insert_attestation(attestation)?;
+        }
+        Ok(root)
+    }
+
+    /// Adds a block for each of the `count` slots after the head, returning their roots.
+    pub fn extend_chain(&mut self, count: usize) -> Result<Vec<Root>, Error> {
+        (0..count)
+            .map(|_| {
+                let slot = self.head_state().slot() + 1;
+                self.add_block(self.head, slot)
+            })
+            .collect()
+    }
+
+    /// Returns an attestation from every validator of each committee of `slot` for the block with
+    /// `block_root`, aggregated by committee.
+    pub fn make_attestations(
+        &mut self,
+        block_root: Root,
+        slot: Slot,
+    ) -> Result<Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>, Error> {
+        let state = self.advance(block_root, slot)?;
+        let epoch = slot / self.context().slots_per_epoch;
+        let epoch_start_slot = epoch * self.context().slots_per_epoch;
+        // NOTE: the root of the block at the start of the epoch is only in `block_roots` once the
+        // state is past that slot
+        let target_root = if epoch_start_slot == slot {
+            block_root
+        } else {
+            state.block_roots()[epoch_start_slot as usize % SLOTS_PER_HISTORICAL_ROOT]
+        };
+        let source = state.current_justified_checkpoint().clone();
+        let domain = self.get_domain(&state, DomainType::BeaconAttester, epoch)?;
+
+        let mut attestations = vec![];
+        for (index, committee) in self.get_beacon_committees(&state, slot)?.into_iter().enumerate()
+        {
+            if committee.is_empty() {
+                continue
+            }
+            let data = AttestationData {
+                slot,
+                index,
+                beacon_block_root: block_root,
+                source: source.clone(),
+                target: Checkpoint { epoch, root: target_root },
+            };
+            let indexed_attestation = self.make_indexed_attestation(&committee, data, domain)?;
+            attestations.push(Attestation {
+                aggregation_bits: Bitlist::try_from(vec![true; committee.len()].as_slice())?,
+                data: indexed_attestation.data,
+                signature: indexed_attestation.signature,
+            });
+        }
+        Ok(attestations)
+    }
+
+    /// Returns a deposit of `amount` from the next interop validator, whose key is added to `keys`
+    /// at the index it gets once its deposit is processed. The deposit is included in the blocks
+    /// of the chain after the chain votes for it with its `eth1_data`.
+    pub fn add_deposit(&mut self, amount: Gwei) -> Result<ValidatorIndex, Error> {
+        let index = self.keys.len();
+        let key = interop_secret_key(index)?;
+        let mut deposit_data = make_deposit_data(&key, amount, self.context())?;
+        self.deposit_tree.push_deposit_data(&mut deposit_data)?;
+        self.deposit_data.push(deposit_data);
+        self.validator_indices.insert(key.public_key(), index);
+        self.keys.push(key);
+        Ok(index)
+    }
+
+    /// Returns an exit of the validator at `validator_index` from `epoch`, signed for the chain of
+    /// the head.
+    pub fn make_voluntary_exit(
+        &self,
+        validator_index: ValidatorIndex,
+        epoch: Epoch,
+    ) -> Result<SignedVoluntaryExit, Error> {
+        let mut message = VoluntaryExit { epoch, validator_index };
+        let domain = self.get_domain(self.head_state(), DomainType::VoluntaryExit, epoch)?;
+        let signature = self.sign(validator_index, &mut message, domain)?;
+        Ok(SignedVoluntaryExit { message, signature })
+    }
+
+    /// Returns a slashing of the validator at `validator_index` for proposing two different
+    /// blocks at `slot`.
+    pub fn make_proposer_slashing(
+        &self,
+        validator_index: ValidatorIndex,
+        slot: Slot,
+    ) -> Result<ProposerSlashing, Error> {
+        let epoch = slot / self.context().slots_per_epoch;
+        let domain = self.get_domain(self.head_state(), DomainType::BeaconProposer, epoch)?;
+        let mut message_1 =
+            BeaconBlockHeader { slot, proposer_index: validator_index, ..Default::default() };
+        let mut message_2 = BeaconBlockHeader {
+            body_root: Root::try_from([1u8; 32].as_ref()).expect("is valid instance"),
+            ..message_1.clone()
+        };
+        let signature_1 = self.sign(validator_index, &mut message_1, domain)?;
+        let signature_2 = self.sign(validator_index, &mut message_2, domain)?;
+        Ok(ProposerSlashing {
+            signed_header_1: SignedBeaconBlockHeader { message: message_1, signature: signature_1 },
+            signed_header_2: SignedBeaconBlockHeader { message: message_2, signature: signature_2 },
+        })
+    }
+
+    /// Returns a slashing of the validators at `validator_indices` for attesting to two different
+    /// blocks as the target of `epoch`.
+    pub fn make_attester_slashing(
+        &self,
+        validator_indices: &[ValidatorIndex],
+        epoch: Epoch,
+    ) -> Result<AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>, Error> {
+        let mut attesting_indices = validator_indices.to_vec();
+        attesting_indices.sort_unstable();
+        attesting_indices.dedup();
+        let domain = self.get_domain(self.head_state(), DomainType::BeaconAttester, epoch)?;
+        let data_1 = AttestationData {
+            slot: epoch * self.context().slots_per_epoch,
+            target: Checkpoint { epoch, ..Default::default() },
+            ..Default::default()
+        };
+        let data_2 = AttestationData {
+            target: Checkpoint {
+                epoch,
+                root: Root::try_from([1u8; 32].as_ref()).expect("is valid instance"),
+            },
+            ..data_1.clone()
+        };
+        Ok(AttesterSlashing {
+            attestation_1: self.make_indexed_attestation(&attesting_indices, data_1, domain)?,
+            attestation_2: self.make_indexed_attestation(&attesting_indices, data_2, domain)?,
+        })
+    }
+
+    fn make_indexed_attestation(
+        &self,
+        attesting_indices: &[ValidatorIndex],
+        mut data: AttestationData,
+        domain: Domain,
+    ) -> Result<IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>, Error> {
+        let signing_root = compute_signing_root(&mut data, domain)?;
+        let signatures = attesting_indices
+            .iter()
+            .map(|&index| self.keys[index].sign(signing_root.as_ref()))
+            .collect::<Vec<_>>();
+        Ok(IndexedAttestation {
+            attesting_indices: List::try_from(attesting_indices.to_vec())
+                .map_err(|(_, err)| err)?,
+            data,
+            signature: aggregate(&signatures)?,
+        })
+    }
+
+    // Returns a contribution from every member of each sync subcommittee of `state` for the block
+    // at the slot before it.
+    fn make_sync_committee_contributions(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> Result<Vec<SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>, Error> {
+        let sync_committee = match state.current_sync_committee() {
+            Some(sync_committee) => sync_committee,
+            None => return Ok(vec![]),
+        };
+        let context = self.context();
+        let previous_slot = state.slot().saturating_sub(1);
+        let mut beacon_block_root =
+            state.block_roots()[previous_slot as usize % SLOTS_PER_HISTORICAL_ROOT];
+        let domain = self.get_domain(
+            state,
+            DomainType::SyncCommittee,
+            previous_slot / context.slots_per_epoch,
+        )?;
+        let signing_root = compute_signing_root(&mut beacon_block_root, domain)?;
+
+        let subcommittee_size = context.sync_committee_size / SYNC_COMMITTEE_SUBNET_COUNT;
+        let mut contributions = vec![];
+        for (subcommittee_index, public_keys) in
+            sync_committee.public_keys.chunks(subcommittee_size).enumerate()
+        {
+            let mut aggregation_bits = Bitvector::<SYNC_SUBCOMMITTEE_SIZE>::default();
+            let mut signatures = vec![];
+            for (i, public_key) in public_keys.iter().enumerate() {
+                if let Some(&index) = self.validator_indices.get(public_key) {
+                    aggregation_bits.set(i, true);
+                    signatures.push(self.keys[index].sign(signing_root.as_ref()));
+                }
+            }
+            if signatures.is_empty() {
+                continue
+            }
+            contributions.push(SyncCommitteeContribution {
+                slot: previous_slot,
+                beacon_block_root,
+                subcommittee_index: subcommittee_index as u64,
+                aggregation_bits,
+                signature: aggregate(&signatures)?,
+            });
+        }
+        Ok(contributions)
+    }
+
+    // Returns a payload for `slot` on top of the latest execution payload header of `state`, or
+    // `None` before Bellatrix.
+    fn make_execution_payload(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        slot: Slot,
+    ) -> Result<
+        Option<
+            ExecutionPayload<
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+            >,
+        >,
+        Error,
+    > {
+        let context = self.context();
+        let epoch = slot / context.slots_per_epoch;
+        let prev_randao =
+            state.randao_mixes()[epoch as usize % EPOCHS_PER_HISTORICAL_VECTOR].clone();
+        let timestamp = state.genesis_time() + slot * context.seconds_per_slot;
+        let execution_payload = match state {
+            BeaconState::Phase0(_) | BeaconState::Altair(_) => return Ok(None),
+            BeaconState::Bellatrix(state) => {
+                let parent = &state.latest_execution_payload_header;
+                ExecutionPayload::Bellatrix(bellatrix::ExecutionPayload {
+                    parent_hash: parent.block_hash.clone(),
+                    prev_randao,
+                    block_number: parent.block_number + 1,
+                    gas_limit: parent.gas_limit,
+                    timestamp,
+                    block_hash: compute_block_hash(&parent.block_hash, slot),
+                    ..Default::default()
+                })
+            }
+            BeaconState::Capella(state) => {
+                let parent = &state.latest_execution_payload_header;
+                let withdrawals = capella::get_expected_withdrawals(state, context);
+                ExecutionPayload::Capella(capella::ExecutionPayload {
+                    parent_hash: parent.block_hash.clone(),
+                    prev_randao,
+                    block_number: parent.block_number + 1,
+                    gas_limit: parent.gas_limit,
+                    timestamp,
+                    block_hash: compute_block_hash(&parent.block_hash, slot),
+                    withdrawals: List::try_from(withdrawals).map_err(|(_, err)| err)?,
+                    ..Default::default()
+                })
+            }
+            BeaconState::Deneb(state) => {
+                let parent = &state.latest_execution_payload_header;
+                let withdrawals = deneb::get_expected_withdrawals(state, context);
+                ExecutionPayload::Deneb(deneb::ExecutionPayload {
+                    parent_hash: parent.block_hash.clone(),
+                    prev_randao,
+                    block_number: parent.block_number + 1,
+                    gas_limit: parent.gas_limit,
+                    timestamp,
+                    block_hash: compute_block_hash(&parent.block_hash, slot),
+                    withdrawals: List::try_from(withdrawals).map_err(|(_, err)| err)?,
+                    ..Default::default()
+                })
+            }
+        };
+        Ok(Some(execution_payload))
+    }
+
+    // Returns the eth1 vote of a block on top of `state` and the deposits it must include.
+    //
+    // The chain votes for the deposit tree of all deposits made so far while `state` has not
+    // processed all of them. If the vote reaches a majority with the block, the block must include
+    // deposits up to the voted deposit count.
+    fn get_eth1_vote_and_deposits(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> Result<(Option<Eth1Data>, Vec<Deposit>), Error> {
+        let context = self.context();
+        let mut eth1_data = state.eth1_data().clone();
+        let mut vote = None;
+        let deposit_count = self.deposit_tree.deposit_count();
+        if deposit_count > eth1_data.deposit_count {
+            let deposit_root = self.deposit_tree.root();
+            let candidate = Eth1Data {
+                deposit_root,
+                deposit_count,
+                block_hash: Hash32::try_from(deposit_root.as_ref()).expect("is valid instance"),
+            };
+            let votes = state.eth1_data_votes().iter().filter(|v| **v == candidate).count() as u64;
+            if (votes + 1) * 2 > context.epochs_per_eth1_voting_period * context.slots_per_epoch {
+                eth1_data = candidate.clone();
+            }
+            vote = Some(candidate);
+        }
+
+        let start = state.eth1_deposit_index();
+        let end = u64::min(eth1_data.deposit_count, start + context.max_deposits as u64);
+        let deposits = (start..end)
+            .map(|index| {
+                Ok(Deposit {
+                    proof: self.deposit_tree.get_proof(index, eth1_data.deposit_count)?,
+                    data: self.deposit_data[index as usize].clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok((vote, deposits))
+    }
+
+    fn get_beacon_proposer_index(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> Result<ValidatorIndex, Error> {
+        let context = self.context();
+        match state {
+            BeaconState::Phase0(state) => phase0::get_beacon_proposer_index(state, context),
+            BeaconState::Altair(state) => altair::get_beacon_proposer_index(state, context),
+            BeaconState::Bellatrix(state) => bellatrix::get_beacon_proposer_index(state, context),
+            BeaconState::Capella(state) => capella::get_beacon_proposer_index(state, context),
+            BeaconState::Deneb(state) => deneb::get_beacon_proposer_index(state, context),
+        }
+    }
+
+    // Returns the committees of `slot` in order of committee index.
+    fn get_beacon_committees(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        slot: Slot,
+    ) -> Result<Vec<Vec<ValidatorIndex>>, Error> {
+        let context = self.context();
+        let epoch = slot / context.slots_per_epoch;
+        match state {
+            BeaconState::Phase0(state) => {
+                (0..phase0::get_committee_count_per_slot(state, epoch, context))
+                    .map(|index| phase0::get_beacon_committee(state, slot, index, context))
+                    .collect()
+            }
+            BeaconState::Altair(state) => {
+                (0..altair::get_committee_count_per_slot(state, epoch, context))
+                    .map(|index| altair::get_beacon_committee(state, slot, index, context))
+                    .collect()
+            }
+            BeaconState::Bellatrix(state) => {
+                (0..bellatrix::get_committee_count_per_slot(state, epoch, context))
+                    .map(|index| bellatrix::get_beacon_committee(state, slot, index, context))
+                    .collect()
+            }
+            BeaconState::Capella(state) => {
+                (0..capella::get_committee_count_per_slot(state, epoch, context))
+                    .map(|index| capella::get_beacon_committee(state, slot, index, context))
+                    .collect()
+            }
+            BeaconState::Deneb(state) => {
+                (0..deneb::get_committee_count_per_slot(state, epoch, context))
+                    .map(|index| deneb::get_beacon_committee(state, slot, index, context))
+                    .collect()
+            }
+        }
+    }
+
+    fn to_executor_state(
+        state: BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> state_transition::BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    > {
+        match state {
+            BeaconState::Phase0(state) => state.into(),
+            BeaconState::Altair(state) => state.into(),
+            BeaconState::Bellatrix(state) => state.into(),
+            BeaconState::Capella(state) => state.into(),
+            BeaconState::Deneb(state) => state.into(),
+        }
+    }
+
+    fn from_executor_state(
+        state: state_transition::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    > {
+        match state {
+            state_transition::BeaconState::Phase0(state) => BeaconState::Phase0(*state),
+            state_transition::BeaconState::Altair(state) => BeaconState::Altair(*state),
+            state_transition::BeaconState::Bellatrix(state) => BeaconState::Bellatrix(*state),
+            state_transition::BeaconState::Capella(state) => BeaconState::Capella(*state),
+            state_transition::BeaconState::Deneb(state) => BeaconState::Deneb(*state),
+        }
+    }
+}
+
+pub mod mainnet {
+    use crate::{
+        altair::mainnet::{SYNC_COMMITTEE_SIZE, SYNC_SUBCOMMITTEE_SIZE},
+        bellatrix::mainnet::{
+            BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+        },
+        capella::mainnet::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD},
+        deneb::mainnet::MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        phase0::mainnet::{
+            EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
+            HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS,
+            MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+            PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT, VALIDATOR_REGISTRY_LIMIT,
+        },
+    };
+
+    pub type Harness = super::Harness<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >;
+}
+
+pub mod minimal {
+    use crate::{
+        altair::minimal::{SYNC_COMMITTEE_SIZE, SYNC_SUBCOMMITTEE_SIZE},
+        bellatrix::minimal::{
+            BYTES_PER_LOGS_BLOOM, MAX_BYTES_PER_TRANSACTION, MAX_EXTRA_DATA_BYTES,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+        },
+        capella::minimal::{MAX_BLS_TO_EXECUTION_CHANGES, MAX_WITHDRAWALS_PER_PAYLOAD},
+        deneb::minimal::MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        phase0::minimal::{
+            EPOCHS_PER_HISTORICAL_VECTOR, EPOCHS_PER_SLASHINGS_VECTOR, ETH1_DATA_VOTES_BOUND,
+            HISTORICAL_ROOTS_LIMIT, MAX_ATTESTATIONS, MAX_ATTESTER_SLASHINGS, MAX_DEPOSITS,
+            MAX_PROPOSER_SLASHINGS, MAX_VALIDATORS_PER_COMMITTEE, MAX_VOLUNTARY_EXITS,
+            PENDING_ATTESTATIONS_BOUND, SLOTS_PER_HISTORICAL_ROOT, VALIDATOR_REGISTRY_LIMIT,
+        },
+    };
+
+    pub type Harness = super::Harness<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        SYNC_SUBCOMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_PROPOSER_SLASHINGS,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_with_skipped_slots_and_forks() {
+        let context = Context::for_minimal();
+        let mut harness = minimal::Harness::new(64, context).unwrap();
+        let genesis_root = harness.genesis_root();
+        let roots = harness.extend_chain(3).unwrap();
+        assert_eq!(harness.head_root(), roots[2]);
+        assert_eq!(harness.head_state().slot(), 3);
+
+        // a block on top of the first block of the chain, skipping two slots
+        let root = harness.add_block(roots[0], 4).unwrap();
+        assert_eq!(harness.head_root(), root);
+        assert_eq!(harness.block(&root).unwrap().message().parent_root(), roots[0]);
+        assert!(harness.state(&roots[2]).is_some());
+        assert!(harness.block(&genesis_root).is_none());
+
+        // the attestations of the first blocks are included in the next ones
+        let block = harness.block(&roots[1]).unwrap();
+        assert!(!block.message().body().attestations().is_empty());
+    }
+}
@@ -1,18 +1,31 @@
 pub mod altair;
 pub mod bellatrix;
+pub mod block_production;
+pub mod block_replayer;
 pub mod builder;
 pub mod capella;
 pub mod clock;
 pub mod configs;
 pub mod crypto;
 pub mod deneb;
+pub mod deposit_log;
+pub mod deposit_tree;
 pub mod domains;
+pub mod eip4788;
 pub mod error;
+pub mod eth1;
 mod fork;
+pub mod fork_choice;
+pub mod genesis;
+pub mod gossip;
+pub mod harness;
 pub mod networking;
 pub mod networks;
+pub mod operation_pool;
 pub mod phase0;
 pub mod primitives;
+pub mod req_resp;
+pub mod rewards;
 #[cfg(feature = "serde")]
 pub mod serde;
 pub mod signing;
@@ -0,0 +1,540 @@
+//! An in-memory pool of the operations a node has seen on the network, from which the candidate
+//! operations of a block are taken.
+use crate::{
+    altair,
+    altair::SyncCommitteeContribution,
+    bellatrix,
+    block_production::BlockOperations,
+    capella,
+    capella::SignedBlsToExecutionChange,
+    crypto::aggregate,
+    deneb, phase0,
+    phase0::{
+        is_slashable_validator, Attestation, AttesterSlashing, ProposerSlashing,
+        SignedVoluntaryExit,
+    },
+    primitives::{Root, Slot, ValidatorIndex, BLS_WITHDRAWAL_PREFIX, FAR_FUTURE_EPOCH},
+    ssz::prelude::*,
+    state_transition::Context,
+    types::BeaconState,
+    Error,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    iter::zip,
+    ops::Deref,
+};
+
+/// Holds attestations, sync committee contributions, slashings, voluntary exits and BLS to
+/// execution changes until they are included in a block or made obsolete by the chain.
+///
+/// Attestations with the same `AttestationData` and sync committee contributions for the same
+/// subcommittee, slot and block root are aggregated as they arrive. Operations are not validated
+/// when inserted; the candidates returned by `get_block_operations` are checked with the
+/// `process_*` functions of the fork of the block when it is produced.
+#[derive(Debug, Default, Clone)]
+pub struct OperationPool<
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const SYNC_SUBCOMMITTEE_SIZE: usize,
+> {
+    // Aggregates with pairwise overlapping participants, keyed by the root of their data
+    attestations: HashMap<Root, Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>>,
+    sync_committee_contributions:
+        HashMap<(Slot, Root, u64), Vec<SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>>>,
+    proposer_slashings: HashMap<ValidatorIndex, ProposerSlashing>,
+    attester_slashings: Vec<AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>>,
+    voluntary_exits: HashMap<ValidatorIndex, SignedVoluntaryExit>,
+    bls_to_execution_changes: HashMap<ValidatorIndex, SignedBlsToExecutionChange>,
+}
+
+impl<const MAX_VALIDATORS_PER_COMMITTEE: usize, const SYNC_SUBCOMMITTEE_SIZE: usize>
+    OperationPool<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>
+{
+    /// Adds `attestation` to the first aggregate for the same data that it does not overlap with.
+    /// An attestation whose participants are all in one of the aggregates is dropped, and the
+    /// aggregates whose participants are all in the resulting aggregate are removed.
+    pub fn insert_attestation(
+        &mut self,
+        attestation: Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    ) -> Result<(), Error> {
+        let root = attestation.data.hash_tree_root()?;
+        let aggregates = self.attestations.entry(root).or_default();
+        let bits = &attestation.aggregation_bits;
+        if aggregates.iter().any(|aggregate| {
+            aggregate.aggregation_bits.len() == bits.len() &&
+                is_subset(bits.iter(), aggregate.aggregation_bits.iter())
+        }) {
+            return Ok(())
+        }
+        let disjoint_aggregate = aggregates.iter().position(|aggregate| {
+            aggregate.aggregation_bits.len() == bits.len() &&
+                is_disjoint(bits.iter(), aggregate.aggregation_bits.iter())
+        });
+        let attestation = match disjoint_aggregate {
+            Some(position) => {
+                let mut aggregate_attestation = aggregates.remove(position);
+                for (i, bit) in attestation.aggregation_bits.iter().enumerate() {
+                    if *bit {
+                        aggregate_attestation.aggregation_bits.set(i, true);
+                    }
+                }
+                aggregate_attestation.signature =
+                    aggregate(&[aggregate_attestation.signature.clone(), attestation.signature])?;
+                aggregate_attestation
+            }
+            None => attestation,
+        };
+        let bits = &attestation.aggregation_bits;
+        aggregates.retain(|aggregate| {
+            aggregate.aggregation_bits.len() != bits.len() ||
+                !is_subset(aggregate.aggregation_bits.iter(), bits.iter())
+        });
+        aggregates.push(attestation);
+        Ok(())
+    }
+
+    /// Adds `contribution` to the first aggregate for the same slot, block root and subcommittee
+    /// that it does not overlap with. A contribution whose participants are all in one of the
+    /// aggregates is dropped, and the aggregates whose participants are all in the resulting
+    /// aggregate are removed.
+    pub fn insert_sync_committee_contribution(
+        &mut self,
+        contribution: SyncCommitteeContribution<SYNC_SUBCOMMITTEE_SIZE>,
+    ) -> Result<(), Error> {
+        let key =
+            (contribution.slot, contribution.beacon_block_root, contribution.subcommittee_index);
+        let aggregates = self.sync_committee_contributions.entry(key).or_default();
+        let bits = &contribution.aggregation_bits;
+        if aggregates
+            .iter()
+            .any(|aggregate| is_subset(bits.iter(), aggregate.aggregation_bits.iter()))
+        {
+            return Ok(())
+        }
+        let disjoint_aggregate = aggregates
+            .iter()
+            .position(|aggregate| is_disjoint(bits.iter(), aggregate.aggregation_bits.iter()));
+        let contribution = match disjoint_aggregate {
+            Some(position) => {
+                let mut aggregate_contribution = aggregates.remove(position);
+                for (i, bit) in contribution.aggregation_bits.iter().enumerate() {
+                    if *bit {
+                        aggregate_contribution.aggregation_bits.set(i, true);
+                    }
+                }
+                aggregate_contribution.signature =
+                    aggregate(&[aggregate_contribution.signature.clone(), contribution.signature])?;
+                aggregate_contribution
+            }
+            None => contribution,
+        };
+        let bits = &contribution.aggregation_bits;
+        aggregates.retain(|aggregate| !is_subset(aggregate.aggregation_bits.iter(), bits.iter()));
+        aggregates.push(contribution);
+        Ok(())
+    }
+
+    /// Adds `proposer_slashing`, replacing any other slashing of the same proposer.
+    pub fn insert_proposer_slashing(&mut self, proposer_slashing: ProposerSlashing) {
+        let proposer_index = proposer_slashing.signed_header_1.message.proposer_index;
+        self.proposer_slashings.insert(proposer_index, proposer_slashing);
+    }
+
+    pub fn insert_attester_slashing(
+        &mut self,
+        attester_slashing: AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>,
+    ) {
+        if !self.attester_slashings.contains(&attester_slashing) {
+            self.attester_slashings.push(attester_slashing);
+        }
+    }
+
+    /// Adds `voluntary_exit`, replacing any other exit of the same validator.
+    pub fn insert_voluntary_exit(&mut self, voluntary_exit: SignedVoluntaryExit) {
+        let validator_index = voluntary_exit.message.validator_index;
+        self.voluntary_exits.insert(validator_index, voluntary_exit);
+    }
+
+    /// Adds `bls_to_execution_change`, replacing any other change of the same validator.
+    pub fn insert_bls_to_execution_change(
+        &mut self,
+        bls_to_execution_change: SignedBlsToExecutionChange,
+    ) {
+        let validator_index = bls_to_execution_change.message.validator_index;
+        self.bls_to_execution_changes.insert(validator_index, bls_to_execution_change);
+    }
+
+    /// Removes the operations that can no longer be included in a block descending from `state`:
+    /// attestations from before the previous epoch, sync committee contributions from before the
+    /// previous slot, slashings of validators that are not slashable and exits or BLS to execution
+    /// changes of validators that have already made them.
+    pub fn prune<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+    >(
+        &mut self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        context: &Context,
+    ) {
+        let slot = state.slot();
+        let current_epoch = slot / context.slots_per_epoch;
+        let previous_epoch = current_epoch.saturating_sub(1);
+        let validators = state.validators();
+
+        self.attestations.retain(|_, aggregates| {
+            aggregates.iter().any(|aggregate| aggregate.data.target.epoch >= previous_epoch)
+        });
+        self.sync_committee_contributions
+            .retain(|(contribution_slot, _, _), _| contribution_slot + 1 >= slot);
+        self.proposer_slashings.retain(|&index, _| {
+            validators
+                .get(index)
+                .map(|validator| is_slashable_validator(validator, current_epoch))
+                .unwrap_or_default()
+        });
+        self.attester_slashings.retain(|attester_slashing| {
+            let indices_1 =
+                attester_slashing.attestation_1.attesting_indices.iter().collect::<HashSet<_>>();
+            attester_slashing.attestation_2.attesting_indices.iter().any(|index| {
+                indices_1.contains(index) &&
+                    validators
+                        .get(*index)
+                        .map(|validator| is_slashable_validator(validator, current_epoch))
+                        .unwrap_or_default()
+            })
+        });
+        self.voluntary_exits.retain(|&index, _| {
+            validators
+                .get(index)
+                .map(|validator| validator.exit_epoch == FAR_FUTURE_EPOCH)
+                .unwrap_or_default()
+        });
+        self.bls_to_execution_changes.retain(|&index, _| {
+            validators
+                .get(index)
+                .map(|validator| validator.withdrawal_credentials[0] == BLS_WITHDRAWAL_PREFIX)
+                .unwrap_or_default()
+        });
+    }
+
+    /// Returns the candidate operations of a block for `slot` on top of `state`, to be passed to
+    /// `block_production::produce_block`.
+    ///
+    /// The attestations are those selected by the `select_attestations` of the fork of `state`
+    /// after advancing it to `slot`. Deposits are not tracked by the pool and must be added by the
+    /// caller.
+    pub fn get_block_operations<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+    >(
+        &self,
+        state: &BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            PENDING_ATTESTATIONS_BOUND,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+        slot: Slot,
+        context: &Context,
+    ) -> Result<BlockOperations<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>, Error> {
+        let attestations = self.attestations.values().flatten().cloned().collect::<Vec<_>>();
+        let mut state = state.clone();
+        let attestations = match &mut state {
+            BeaconState::Phase0(state) => {
+                if state.slot < slot {
+                    phase0::process_slots(state, slot, &mut (), context)?;
+                }
+                phase0::select_attestations(state, &attestations, context)?
+            }
+            BeaconState::Altair(state) => {
+                if state.slot < slot {
+                    altair::process_slots(state, slot, &mut (), context)?;
+                }
+                altair::select_attestations(state, &attestations, context)?
+            }
+            BeaconState::Bellatrix(state) => {
+                if state.slot < slot {
+                    bellatrix::process_slots(state, slot, &mut (), context)?;
+                }
+                bellatrix::select_attestations(state, &attestations, context)?
+            }
+            BeaconState::Capella(state) => {
+                if state.slot < slot {
+                    capella::process_slots(state, slot, &mut (), context)?;
+                }
+                capella::select_attestations(state, &attestations, context)?
+            }
+            BeaconState::Deneb(state) => {
+                if state.slot < slot {
+                    deneb::process_slots(state, slot, &mut (), context)?;
+                }
+                deneb::select_attestations(state, &attestations, context)?
+            }
+        };
+
+        Ok(BlockOperations {
+            proposer_slashings: self.proposer_slashings.values().cloned().collect(),
+            attester_slashings: self.attester_slashings.clone(),
+            attestations,
+            deposits: vec![],
+            voluntary_exits: self.voluntary_exits.values().cloned().collect(),
+            sync_committee_contributions: self
+                .sync_committee_contributions
+                .values()
+                .flatten()
+                .cloned()
+                .collect(),
+            bls_to_execution_changes: self.bls_to_execution_changes.values().cloned().collect(),
+            eth1_data: None,
+        })
+    }
+}
+
+fn is_disjoint<A, B>(a: A, b: B) -> bool
+where
+    A: Iterator,
+    B: Iterator,
+    A::Item: Deref<Target = bool>,
+    B::Item: Deref<Target = bool>,
+{
+    zip(a, b).all(|(a, b)| !(*a && *b))
+}
+
+// Whether every bit set in `a` is also set in `b`
+fn is_subset<A, B>(a: A, b: B) -> bool
+where
+    A: Iterator,
+    B: Iterator,
+    A::Item: Deref<Target = bool>,
+    B::Item: Deref<Target = bool>,
+{
+    zip(a, b).all(|(a, b)| !*a || *b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::minimal::SYNC_SUBCOMMITTEE_SIZE, capella::BlsToExecutionChange, crypto::SecretKey,
+        harness::minimal::Harness, phase0::minimal::MAX_VALIDATORS_PER_COMMITTEE,
+        primitives::ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+    };
+
+    fn attestation(bits: &[bool], seed: u8) -> Attestation<4> {
+        let secret_key = SecretKey::key_gen(&[seed; 32]).unwrap();
+        Attestation {
+            aggregation_bits: Bitlist::try_from(bits).unwrap(),
+            signature: secret_key.sign(b"attestation"),
+            ..Default::default()
+        }
+    }
+
+    fn contribution(bits: &[bool], seed: u8) -> SyncCommitteeContribution<4> {
+        let secret_key = SecretKey::key_gen(&[seed; 32]).unwrap();
+        let mut aggregation_bits = Bitvector::default();
+        for (i, bit) in bits.iter().enumerate() {
+            aggregation_bits.set(i, *bit);
+        }
+        SyncCommitteeContribution {
+            aggregation_bits,
+            signature: secret_key.sign(b"contribution"),
+            ..Default::default()
+        }
+    }
+
+    fn altair_harness() -> Harness {
+        let mut context = Context::for_minimal();
+        context.altair_fork_epoch = 0;
+        Harness::new(64, context).unwrap()
+    }
+
+    #[test]
+    fn test_attestations_are_aggregated() {
+        let mut pool = OperationPool::<4, 4>::default();
+        let first = attestation(&[true, false, false, false], 1);
+        let second = attestation(&[false, true, false, false], 2);
+        pool.insert_attestation(first.clone()).unwrap();
+        pool.insert_attestation(second.clone()).unwrap();
+
+        let aggregates = pool.attestations.values().next().unwrap();
+        assert_eq!(aggregates.len(), 1);
+        let aggregate_attestation = &aggregates[0];
+        assert_eq!(
+            aggregate_attestation.aggregation_bits,
+            Bitlist::try_from([true, true, false, false].as_ref()).unwrap()
+        );
+        assert_eq!(
+            aggregate_attestation.signature,
+            aggregate(&[first.signature, second.signature]).unwrap()
+        );
+
+        // already covered by the aggregate
+        pool.insert_attestation(attestation(&[true, false, false, false], 1)).unwrap();
+        assert_eq!(pool.attestations.values().next().unwrap().len(), 1);
+
+        // overlaps the aggregate without being covered by it
+        pool.insert_attestation(attestation(&[false, true, true, false], 3)).unwrap();
+        assert_eq!(pool.attestations.values().next().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_merged_aggregates_replace_covered_aggregates() {
+        let mut pool = OperationPool::<4, 4>::default();
+        let first = attestation(&[true, true, false, false], 1);
+        let third = attestation(&[false, false, true, true], 3);
+        pool.insert_attestation(first.clone()).unwrap();
+        pool.insert_attestation(attestation(&[false, true, true, false], 2)).unwrap();
+        assert_eq!(pool.attestations.values().next().unwrap().len(), 2);
+        // merged into the first aggregate, which then covers the second one
+        pool.insert_attestation(third.clone()).unwrap();
+        let aggregates = pool.attestations.values().next().unwrap();
+        assert_eq!(aggregates.len(), 1);
+        assert_eq!(
+            aggregates[0].aggregation_bits,
+            Bitlist::try_from([true, true, true, true].as_ref()).unwrap()
+        );
+        assert_eq!(
+            aggregates[0].signature,
+            aggregate(&[first.signature, third.signature]).unwrap()
+        );
+
+        let first = contribution(&[true, true, false, false], 1);
+        let third = contribution(&[false, false, true, true], 3);
+        pool.insert_sync_committee_contribution(first.clone()).unwrap();
+        pool.insert_sync_committee_contribution(contribution(&[false, true, true, false], 2))
+            .unwrap();
+        assert_eq!(pool.sync_committee_contributions.values().next().unwrap().len(), 2);
+        pool.insert_sync_committee_contribution(third.clone()).unwrap();
+        let aggregates = pool.sync_committee_contributions.values().next().unwrap();
+        assert_eq!(aggregates.len(), 1);
+        assert!(aggregates[0].aggregation_bits.iter().all(|bit| *bit));
+        assert_eq!(
+            aggregates[0].signature,
+            aggregate(&[first.signature, third.signature]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut harness = altair_harness();
+        let roots = harness.extend_chain(17).unwrap();
+        let context = harness.context().clone();
+        let mut state = harness.head_state().clone();
+        let slot = state.slot();
+        let current_epoch = slot / context.slots_per_epoch;
+
+        let mut pool =
+            OperationPool::<MAX_VALIDATORS_PER_COMMITTEE, SYNC_SUBCOMMITTEE_SIZE>::default();
+        // attestations from before the previous epoch can no longer be included
+        let stale_attestations = harness.make_attestations(roots[0], 1).unwrap();
+        let attestations = harness.make_attestations(roots[16], slot).unwrap();
+        for attestation in stale_attestations.into_iter().chain(attestations) {
+            pool.insert_attestation(attestation).unwrap();
+        }
+        for contribution_slot in [slot - 2, slot] {
+            let contribution =
+                SyncCommitteeContribution { slot: contribution_slot, ..Default::default() };
+            pool.insert_sync_committee_contribution(contribution).unwrap();
+        }
+        for index in [3, 4] {
+            pool.insert_voluntary_exit(harness.make_voluntary_exit(index, 0).unwrap());
+        }
+        for index in [5, 6] {
+            pool.insert_proposer_slashing(harness.make_proposer_slashing(index, slot).unwrap());
+        }
+        for index in [7, 8] {
+            pool.insert_attester_slashing(harness.make_attester_slashing(&[index], 0).unwrap());
+        }
+        for index in [9, 10] {
+            pool.insert_bls_to_execution_change(SignedBlsToExecutionChange {
+                message: BlsToExecutionChange { validator_index: index, ..Default::default() },
+                ..Default::default()
+            });
+        }
+
+        let validators = state.validators_mut();
+        validators[4].exit_epoch = current_epoch + 1;
+        validators[6].slashed = true;
+        validators[8].slashed = true;
+        validators[10].withdrawal_credentials[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+        pool.prune(&state, &context);
+
+        let attestations = pool.attestations.values().flatten().collect::<Vec<_>>();
+        assert!(!attestations.is_empty());
+        assert!(attestations.iter().all(|attestation| attestation.data.slot == slot));
+        let contribution_slots =
+            pool.sync_committee_contributions.keys().map(|(slot, _, _)| *slot).collect::<Vec<_>>();
+        assert_eq!(contribution_slots, vec![slot]);
+        assert_eq!(pool.voluntary_exits.keys().copied().collect::<Vec<_>>(), vec![3]);
+        assert_eq!(pool.proposer_slashings.keys().copied().collect::<Vec<_>>(), vec![5]);
+        assert_eq!(pool.attester_slashings.len(), 1);
+        let indices = &pool.attester_slashings[0].attestation_1.attesting_indices;
+        assert_eq!(indices.iter().copied().collect::<Vec<_>>(), vec![7]);
+        assert_eq!(pool.bls_to_execution_changes.keys().copied().collect::<Vec<_>>(), vec![9]);
+    }
+
+    #[test]
+    fn test_get_block_operations() {
+        let mut harness = altair_harness();
+        harness.extend_chain(2).unwrap();
+        let context = harness.context().clone();
+        let slot = harness.head_state().slot() + 1;
+        let proposer_slashing = harness.make_proposer_slashing(5, slot).unwrap();
+        let attester_slashing = harness.make_attester_slashing(&[6], 0).unwrap();
+        harness.pool.insert_proposer_slashing(proposer_slashing.clone());
+        harness.pool.insert_attester_slashing(attester_slashing.clone());
+
+        let operations =
+            harness.pool.get_block_operations(harness.head_state(), slot, &context).unwrap();
+        assert_eq!(operations.proposer_slashings, vec![proposer_slashing.clone()]);
+        assert_eq!(operations.attester_slashings, vec![attester_slashing.clone()]);
+        assert!(!operations.attestations.is_empty());
+        assert!(operations.attestations.iter().all(|attestation| attestation.data.slot < slot));
+        assert!(operations.deposits.is_empty());
+        assert!(operations.eth1_data.is_none());
+
+        // the block produced with the operations is valid and makes the slashings obsolete
+        let root = harness.add_block(harness.head_root(), slot).unwrap();
+        let body = harness.block(&root).unwrap().message().body();
+        assert_eq!(body.proposer_slashings().as_ref(), &[proposer_slashing]);
+        assert_eq!(body.attester_slashings().as_ref(), &[attester_slashing]);
+        assert_eq!(body.attestations().len(), operations.attestations.len());
+        assert!(harness.pool.proposer_slashings.is_empty());
+        assert!(harness.pool.attester_slashings.is_empty());
+    }
+}
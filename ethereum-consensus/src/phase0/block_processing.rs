@@ -9,12 +9,13 @@ use crate::{
         beacon_block::{BeaconBlock, BeaconBlockBody, BeaconBlockHeader},
         beacon_state::BeaconState,
         constants::DEPOSIT_CONTRACT_TREE_DEPTH,
+        epoch_processing::get_proposer_reward,
         helpers::{
-            compute_domain, compute_epoch_at_slot, get_beacon_committee, get_beacon_proposer_index,
-            get_committee_count_per_slot, get_current_epoch, get_domain, get_indexed_attestation,
-            get_previous_epoch, get_randao_mix, increase_balance, initiate_validator_exit,
-            is_active_validator, is_slashable_attestation_data, is_slashable_validator,
-            is_valid_indexed_attestation, slash_validator,
+            compute_domain, compute_epoch_at_slot, get_attesting_indices, get_beacon_committee,
+            get_beacon_proposer_index, get_committee_count_per_slot, get_current_epoch, get_domain,
+            get_indexed_attestation, get_previous_epoch, get_randao_mix, increase_balance,
+            initiate_validator_exit, is_active_validator, is_slashable_attestation_data,
+            is_slashable_validator, is_valid_indexed_attestation, slash_validator,
         },
         operations::{
             Attestation, AttesterSlashing, Deposit, DepositMessage, PendingAttestation,
@@ -22,10 +23,11 @@ use crate::{
         },
         validator::Validator,
     },
-    primitives::{BlsPublicKey, Bytes32, DomainType, Gwei, ValidatorIndex, FAR_FUTURE_EPOCH},
-    signing::{compute_signing_root, verify_signed_data},
+    primitives::{Bytes32, DomainType, Gwei, ValidatorIndex, FAR_FUTURE_EPOCH},
+    rewards::{BlockRewards, SyncCommitteeReward},
+    signing::compute_signing_root,
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{Context, Event, Observer, Result, SignatureSets, SignatureStrategy},
 };
 use std::collections::HashSet;
 
@@ -50,6 +52,8 @@ pub fn process_proposer_slashing<
         PENDING_ATTESTATIONS_BOUND,
     >,
     proposer_slashing: &mut ProposerSlashing,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let header_1 = &proposer_slashing.signed_header_1.message;
@@ -94,15 +98,20 @@ pub fn process_proposer_slashing<
         [&mut proposer_slashing.signed_header_1, &mut proposer_slashing.signed_header_2]
     {
         let signing_root = compute_signing_root(&mut signed_header.message, domain)?;
-        let public_key = &proposer.public_key;
-        if verify_signature(public_key, signing_root.as_ref(), &signed_header.signature).is_err() {
-            return Err(invalid_operation_error(InvalidOperation::ProposerSlashing(
+        let signature_set = context.pubkey_cache.signature_set(
+            &state.validators,
+            proposer_index,
+            signing_root.as_ref(),
+            &signed_header.signature,
+        );
+        signatures.verify(signature_set, || {
+            invalid_operation_error(InvalidOperation::ProposerSlashing(
                 InvalidProposerSlashing::InvalidSignature(signed_header.signature.clone()),
-            )))
-        }
+            ))
+        })?;
     }
 
-    slash_validator(state, proposer_index, None, context)
+    slash_validator(state, proposer_index, None, observer, context)
 }
 
 pub fn process_attester_slashing<
@@ -126,6 +135,8 @@ pub fn process_attester_slashing<
         PENDING_ATTESTATIONS_BOUND,
     >,
     attester_slashing: &mut AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let attestation_1 = &mut attester_slashing.attestation_1;
@@ -140,9 +151,9 @@ pub fn process_attester_slashing<
         )))
     }
 
-    is_valid_indexed_attestation(state, attestation_1, context)?;
+    is_valid_indexed_attestation(state, attestation_1, signatures, context)?;
 
-    is_valid_indexed_attestation(state, attestation_2, context)?;
+    is_valid_indexed_attestation(state, attestation_2, signatures, context)?;
 
     let indices_1: HashSet<ValidatorIndex> =
         HashSet::from_iter(attestation_1.attesting_indices.iter().cloned());
@@ -154,7 +165,7 @@ pub fn process_attester_slashing<
     let current_epoch = get_current_epoch(state, context);
     for &index in &indices {
         if is_slashable_validator(&state.validators[index], current_epoch) {
-            slash_validator(state, index, None, context)?;
+            slash_validator(state, index, None, observer, context)?;
             slashed_any = true;
         }
     }
@@ -189,6 +200,7 @@ pub fn process_attestation<
         PENDING_ATTESTATIONS_BOUND,
     >,
     attestation: &Attestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let data = &attestation.data;
@@ -254,6 +266,7 @@ pub fn process_attestation<
     is_valid_indexed_attestation(
         state,
         &mut get_indexed_attestation(state, attestation, context)?,
+        signatures,
         context,
     )?;
 
@@ -330,6 +343,7 @@ pub fn process_deposit<
         PENDING_ATTESTATIONS_BOUND,
     >,
     deposit: &mut Deposit,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let branch = deposit
@@ -351,9 +365,15 @@ pub fn process_deposit<
 
     let public_key = &deposit.data.public_key;
     let amount = deposit.data.amount;
-    let validator_public_keys: HashSet<&BlsPublicKey> =
-        HashSet::from_iter(state.validators.iter().map(|v| &v.public_key));
-    if !validator_public_keys.contains(public_key) {
+    if let Some(index) = context.pubkey_cache.get_index(public_key, &state.validators) {
+        increase_balance(state, index, amount);
+        observer.on_event(&Event::DepositApplied {
+            index,
+            public_key: public_key.clone(),
+            amount,
+            is_new_validator: false,
+        });
+    } else {
         let mut deposit_message = DepositMessage {
             public_key: public_key.clone(),
             withdrawal_credentials: deposit.data.withdrawal_credentials.clone(),
@@ -367,12 +387,15 @@ pub fn process_deposit<
             return Ok(())
         }
 
+        let index = state.validators.len();
         state.validators.push(get_validator_from_deposit(deposit, context));
         state.balances.push(amount);
-    } else {
-        let index = state.validators.iter().position(|v| &v.public_key == public_key).unwrap();
-
-        increase_balance(state, index, amount);
+        observer.on_event(&Event::DepositApplied {
+            index,
+            public_key: public_key.clone(),
+            amount,
+            is_new_validator: true,
+        });
     }
 
     Ok(())
@@ -399,6 +422,8 @@ pub fn process_voluntary_exit<
         PENDING_ATTESTATIONS_BOUND,
     >,
     signed_voluntary_exit: &mut SignedVoluntaryExit,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let voluntary_exit = &mut signed_voluntary_exit.message;
@@ -442,14 +467,19 @@ pub fn process_voluntary_exit<
     }
 
     let domain = get_domain(state, DomainType::VoluntaryExit, Some(voluntary_exit.epoch), context)?;
-    let public_key = &validator.public_key;
-    verify_signed_data(voluntary_exit, &signed_voluntary_exit.signature, public_key, domain)
-        .map_err(|_| {
-            invalid_operation_error(InvalidOperation::VoluntaryExit(
-                InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
-            ))
-        })?;
-    initiate_validator_exit(state, voluntary_exit.validator_index, context);
+    let signing_root = compute_signing_root(voluntary_exit, domain)?;
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        voluntary_exit.validator_index,
+        signing_root.as_ref(),
+        &signed_voluntary_exit.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::VoluntaryExit(
+            InvalidVoluntaryExit::InvalidSignature(signed_voluntary_exit.signature.clone()),
+        ))
+    })?;
+    initiate_validator_exit(state, voluntary_exit.validator_index, observer, context);
     Ok(())
 }
 
@@ -572,19 +602,25 @@ pub fn process_randao<
         MAX_DEPOSITS,
         MAX_VOLUNTARY_EXITS,
     >,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let mut epoch = get_current_epoch(state, context);
 
     let proposer_index = get_beacon_proposer_index(state, context)?;
-    let proposer = &state.validators[proposer_index];
 
     let domain = get_domain(state, DomainType::Randao, Some(epoch), context)?;
     let signing_root = compute_signing_root(&mut epoch, domain)?;
 
-    if verify_signature(&proposer.public_key, signing_root.as_ref(), &body.randao_reveal).is_err() {
-        return Err(invalid_operation_error(InvalidOperation::Randao(body.randao_reveal.clone())))
-    }
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        proposer_index,
+        signing_root.as_ref(),
+        &body.randao_reveal,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::Randao(body.randao_reveal.clone()))
+    })?;
 
     let mix = xor(get_randao_mix(state, epoch), &hash(body.randao_reveal.as_ref()));
     let mix_index = epoch % context.epochs_per_historical_vector;
@@ -670,6 +706,8 @@ pub fn process_operations<
         MAX_DEPOSITS,
         MAX_VOLUNTARY_EXITS,
     >,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let expected_deposit_count = usize::min(
@@ -688,15 +726,17 @@ pub fn process_operations<
 
     body.proposer_slashings
         .iter_mut()
-        .try_for_each(|op| process_proposer_slashing(state, op, context))?;
+        .try_for_each(|op| process_proposer_slashing(state, op, signatures, observer, context))?;
     body.attester_slashings
         .iter_mut()
-        .try_for_each(|op| process_attester_slashing(state, op, context))?;
-    body.attestations.iter().try_for_each(|op| process_attestation(state, op, context))?;
-    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, context))?;
+        .try_for_each(|op| process_attester_slashing(state, op, signatures, observer, context))?;
+    body.attestations
+        .iter()
+        .try_for_each(|op| process_attestation(state, op, signatures, context))?;
+    body.deposits.iter_mut().try_for_each(|op| process_deposit(state, op, observer, context))?;
     body.voluntary_exits
         .iter_mut()
-        .try_for_each(|op| process_voluntary_exit(state, op, context))?;
+        .try_for_each(|op| process_voluntary_exit(state, op, signatures, observer, context))?;
     Ok(())
 }
 
@@ -733,11 +773,92 @@ pub fn process_block<
         MAX_DEPOSITS,
         MAX_VOLUNTARY_EXITS,
     >,
+    signatures: &mut SignatureSets,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     process_block_header(state, block, context)?;
-    process_randao(state, &block.body, context)?;
+    process_randao(state, &block.body, signatures, context)?;
     process_eth1_data(state, &block.body, context);
-    process_operations(state, &mut block.body, context)?;
+    process_operations(state, &mut block.body, signatures, observer, context)?;
     Ok(())
 }
+
+// Return the rewards paid to the proposer of `block` split by the operations that pay them. Each
+// operation is applied to `state`, which is expected to be at the slot of `block`, measuring the
+// balance of the proposer around it. Proposers are paid for including attestations at the end of
+// the epoch, so this component is computed from `state`.
+pub fn get_block_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    block: &mut BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+    >,
+    context: &Context,
+) -> Result<(BlockRewards, Vec<SyncCommitteeReward>)> {
+    let proposer_index = block.proposer_index;
+    let body = &mut block.body;
+    let signatures = &mut SignatureSets::new(SignatureStrategy::Skip);
+    let mut rewards = BlockRewards { proposer_index, ..Default::default() };
+    for op in body.proposer_slashings.iter_mut() {
+        let balance = state.balances[proposer_index];
+        process_proposer_slashing(state, op, signatures, &mut (), context)?;
+        rewards.proposer_slashings += state.balances[proposer_index].saturating_sub(balance);
+    }
+    for op in body.attester_slashings.iter_mut() {
+        let balance = state.balances[proposer_index];
+        process_attester_slashing(state, op, signatures, &mut (), context)?;
+        rewards.attester_slashings += state.balances[proposer_index].saturating_sub(balance);
+    }
+
+    // Only the earliest inclusion of each attester pays its proposer
+    let mut included = HashSet::new();
+    for pending_attestation in
+        state.previous_epoch_attestations.iter().chain(state.current_epoch_attestations.iter())
+    {
+        let data = &pending_attestation.data;
+        for index in
+            get_attesting_indices(state, data, &pending_attestation.aggregation_bits, context)?
+        {
+            included.insert((data.target.epoch, index));
+        }
+    }
+    for attestation in body.attestations.iter() {
+        let data = &attestation.data;
+        for index in get_attesting_indices(state, data, &attestation.aggregation_bits, context)? {
+            if included.insert((data.target.epoch, index)) && !state.validators[index].slashed {
+                rewards.attestations += get_proposer_reward(state, index, context)?;
+            }
+        }
+    }
+
+    rewards.total = rewards.attestations + rewards.proposer_slashings + rewards.attester_slashings;
+    Ok((rewards, vec![]))
+}
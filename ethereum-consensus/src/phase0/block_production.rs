@@ -0,0 +1,289 @@
+use crate::{
+    error::{invalid_operation_error, InvalidDeposit, InvalidOperation},
+    phase0::{
+        beacon_block::{BeaconBlock, BeaconBlockBody},
+        beacon_state::BeaconState,
+        block_processing::{
+            process_attestation, process_attester_slashing, process_block_header, process_deposit,
+            process_eth1_data, process_proposer_slashing, process_randao, process_voluntary_exit,
+        },
+        helpers::{get_attesting_indices, get_beacon_proposer_index},
+        operations::{
+            Attestation, AttesterSlashing, Deposit, Eth1Data, ProposerSlashing, SignedVoluntaryExit,
+        },
+        slot_processing::process_slots,
+    },
+    primitives::{BlsSignature, Bytes32, Slot},
+    ssz::prelude::*,
+    state_transition::{Context, Result, SignatureSets},
+};
+use std::collections::HashSet;
+
+/// The candidate operations to include in a block, in order of preference.
+#[derive(Default, Debug, Clone)]
+pub struct BlockOperations<const MAX_VALIDATORS_PER_COMMITTEE: usize> {
+    pub proposer_slashings: Vec<ProposerSlashing>,
+    pub attester_slashings: Vec<AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub attestations: Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>,
+    pub deposits: Vec<Deposit>,
+    pub voluntary_exits: Vec<SignedVoluntaryExit>,
+    // The vote to make instead of repeating the current vote of the state
+    pub eth1_data: Option<Eth1Data>,
+}
+
+/// Applies the candidate `operations` to `state` in the order of `process_operations`, adding
+/// each one that is valid to `body` until the limit for its kind is reached.
+///
+/// Invalid candidates are skipped and leave `state` unchanged. Returns an error if there are not
+/// enough valid deposits to include all of those pending up to `MAX_DEPOSITS`.
+pub fn select_operations<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    body: &mut BeaconBlockBody<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+    >,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
+    context: &Context,
+) -> Result<()> {
+    for operation in &operations.proposer_slashings {
+        if body.proposer_slashings.len() == context.max_proposer_slashings {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_proposer_slashing(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.proposer_slashings.push(operation);
+        }
+    }
+    for operation in &operations.attester_slashings {
+        if body.attester_slashings.len() == context.max_attester_slashings {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_attester_slashing(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.attester_slashings.push(operation);
+        }
+    }
+    for operation in &operations.attestations {
+        if body.attestations.len() == context.max_attestations {
+            break
+        }
+        if process_attestation(state, operation, signatures, context).is_ok() {
+            body.attestations.push(operation.clone());
+        }
+    }
+    // NOTE: a block must include every pending deposit up to the limit, so only deposits at the
+    // next deposit index are accepted
+    let expected_deposit_count = usize::min(
+        context.max_deposits,
+        (state.eth1_data.deposit_count - state.eth1_deposit_index) as usize,
+    );
+    for operation in &operations.deposits {
+        if body.deposits.len() == expected_deposit_count {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_deposit(state, &mut operation, &mut (), context).is_ok() {
+            body.deposits.push(operation);
+        }
+    }
+    if body.deposits.len() != expected_deposit_count {
+        return Err(invalid_operation_error(InvalidOperation::Deposit(
+            InvalidDeposit::IncorrectCount {
+                expected: expected_deposit_count,
+                count: body.deposits.len(),
+            },
+        )))
+    }
+    for operation in &operations.voluntary_exits {
+        if body.voluntary_exits.len() == context.max_voluntary_exits {
+            break
+        }
+        let mut operation = operation.clone();
+        if process_voluntary_exit(state, &mut operation, signatures, &mut (), context).is_ok() {
+            body.voluntary_exits.push(operation);
+        }
+    }
+    Ok(())
+}
+
+/// Selects up to `MAX_ATTESTATIONS` of the candidate `attestations` for a block on top of `state`,
+/// greedily taking the attestation with the most attesters not yet included in `state` or in the
+/// attestations selected so far.
+///
+/// Each selected attestation is checked with `process_attestation` against a copy of `state`;
+/// invalid candidates are skipped.
+pub fn select_attestations<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    attestations: &[Attestation<MAX_VALIDATORS_PER_COMMITTEE>],
+    context: &Context,
+) -> Result<Vec<Attestation<MAX_VALIDATORS_PER_COMMITTEE>>> {
+    let mut covered = HashSet::new();
+    for pending_attestation in
+        state.previous_epoch_attestations.iter().chain(state.current_epoch_attestations.iter())
+    {
+        let data = &pending_attestation.data;
+        for index in
+            get_attesting_indices(state, data, &pending_attestation.aggregation_bits, context)?
+        {
+            covered.insert((data.target.epoch, index));
+        }
+    }
+
+    let mut candidates = vec![];
+    for attestation in attestations {
+        let data = &attestation.data;
+        if let Ok(attesting_indices) =
+            get_attesting_indices(state, data, &attestation.aggregation_bits, context)
+        {
+            let cover = attesting_indices
+                .into_iter()
+                .map(|index| (data.target.epoch, index))
+                .collect::<Vec<_>>();
+            candidates.push((attestation, cover));
+        }
+    }
+
+    let mut state = state.clone();
+    let mut selected = vec![];
+    let signatures = &mut SignatureSets::default();
+    while selected.len() < context.max_attestations {
+        let mut best = None;
+        let mut best_weight = 0;
+        for (i, (_, cover)) in candidates.iter().enumerate() {
+            let weight = cover.iter().filter(|key| !covered.contains(*key)).count();
+            if weight > best_weight {
+                best = Some(i);
+                best_weight = weight;
+            }
+        }
+        let i = match best {
+            Some(i) => i,
+            None => break,
+        };
+        let (attestation, cover) = candidates.remove(i);
+        if process_attestation(&mut state, attestation, signatures, context).is_ok() {
+            covered.extend(cover);
+            selected.push(attestation.clone());
+        }
+    }
+    Ok(selected)
+}
+
+/// Produces an unsigned block for `slot` on top of `state`, including as many of the candidate
+/// `operations` as are valid. The `eth1_data` of the block is the vote in `operations`, if any, or
+/// repeats the current vote of `state`.
+///
+/// The signature of each candidate is verified so that candidates with invalid signatures are
+/// skipped.
+pub fn produce_block<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    slot: Slot,
+    randao_reveal: BlsSignature,
+    graffiti: Bytes32,
+    operations: &BlockOperations<MAX_VALIDATORS_PER_COMMITTEE>,
+    context: &Context,
+) -> Result<
+    BeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+    >,
+> {
+    let mut state = state.clone();
+    if state.slot < slot {
+        process_slots(&mut state, slot, &mut (), context)?;
+    }
+
+    let mut block = BeaconBlock {
+        slot,
+        proposer_index: get_beacon_proposer_index(&state, context)?,
+        parent_root: state.latest_block_header.hash_tree_root()?,
+        ..Default::default()
+    };
+    block.body.randao_reveal = randao_reveal;
+    block.body.eth1_data = operations.eth1_data.clone().unwrap_or_else(|| state.eth1_data.clone());
+    block.body.graffiti = graffiti;
+
+    let signatures = &mut SignatureSets::default();
+    process_block_header(&mut state, &mut block, context)?;
+    process_randao(&mut state, &block.body, signatures, context)?;
+    process_eth1_data(&mut state, &block.body, context);
+    select_operations(&mut state, &mut block.body, operations, signatures, context)?;
+
+    // NOTE: the header was processed before the operations were selected
+    state.latest_block_header.body_root = block.body.hash_tree_root()?;
+    block.state_root = state.hash_tree_root()?;
+    Ok(block)
+}
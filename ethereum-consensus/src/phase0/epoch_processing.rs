@@ -11,9 +11,10 @@ use crate::{
         },
         operations::{Checkpoint, PendingAttestation},
     },
-    primitives::{Epoch, Gwei, ValidatorIndex, GENESIS_EPOCH},
+    primitives::{Epoch, Gwei, Slot, ValidatorIndex, GENESIS_EPOCH},
+    rewards::{AttestationRewards, IdealAttestationRewards, TotalAttestationRewards},
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{Context, Event, Observer, Result},
     Error,
 };
 use integer_sqrt::IntegerSquareRoot;
@@ -190,6 +191,7 @@ pub fn process_justification_and_finalization<
         MAX_VALIDATORS_PER_COMMITTEE,
         PENDING_ATTESTATIONS_BOUND,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     // Initial FFG checkpoint values have a `0x00` stub for `root`.
@@ -210,6 +212,7 @@ pub fn process_justification_and_finalization<
         total_active_balance,
         previous_target_balance,
         current_target_balance,
+        observer,
         context,
     )
 }
@@ -270,6 +273,7 @@ pub fn process_registry_updates<
         MAX_VALIDATORS_PER_COMMITTEE,
         PENDING_ATTESTATIONS_BOUND,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     // Process activation eligibility and ejections
@@ -284,7 +288,7 @@ pub fn process_registry_updates<
         if is_active_validator(validator, current_epoch) &&
             validator.effective_balance <= context.ejection_balance
         {
-            initiate_validator_exit(state, i, context);
+            initiate_validator_exit(state, i, observer, context);
         }
     }
 
@@ -314,6 +318,10 @@ pub fn process_registry_updates<
     for i in activation_queue.into_iter().take(get_validator_churn_limit(state, context)) {
         let validator = &mut state.validators[i];
         validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
     }
 }
 
@@ -411,6 +419,7 @@ pub fn process_effective_balance_updates<
         MAX_VALIDATORS_PER_COMMITTEE,
         PENDING_ATTESTATIONS_BOUND,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     // Update effective balances with hysteresis
@@ -423,10 +432,18 @@ pub fn process_effective_balance_updates<
         if balance + downward_threshold < validator.effective_balance ||
             validator.effective_balance + upward_threshold < balance
         {
+            let previous = validator.effective_balance;
             validator.effective_balance = Gwei::min(
                 balance - balance % context.effective_balance_increment,
                 context.max_effective_balance,
             );
+            if validator.effective_balance != previous {
+                observer.on_event(&Event::EffectiveBalanceChanged {
+                    index: i,
+                    previous,
+                    current: validator.effective_balance,
+                });
+            }
         }
     }
 }
@@ -604,6 +621,7 @@ pub fn weigh_justification_and_finalization<
     total_active_balance: Gwei,
     previous_epoch_target_balance: Gwei,
     current_epoch_target_balance: Gwei,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let previous_epoch = get_previous_epoch(state, context);
@@ -629,8 +647,17 @@ pub fn weigh_justification_and_finalization<
         };
         state.justification_bits.set(0, true);
     }
+    if state.previous_justified_checkpoint != old_previous_justified_checkpoint ||
+        state.current_justified_checkpoint != old_current_justified_checkpoint
+    {
+        observer.on_event(&Event::JustificationChanged {
+            previous_justified: state.previous_justified_checkpoint.clone(),
+            current_justified: state.current_justified_checkpoint.clone(),
+        });
+    }
 
     // Process finalizations
+    let old_finalized_checkpoint = state.finalized_checkpoint.clone();
     let bits = &state.justification_bits;
     // The 2nd/3rd/4th most recent epochs are justified, the 2nd using the 4th as source
     if bits[1..4].all() && old_previous_justified_checkpoint.epoch + 3 == current_epoch {
@@ -648,6 +675,11 @@ pub fn weigh_justification_and_finalization<
     if bits[0..2].all() && old_current_justified_checkpoint.epoch + 1 == current_epoch {
         state.finalized_checkpoint = old_current_justified_checkpoint;
     }
+    if state.finalized_checkpoint != old_finalized_checkpoint {
+        observer.on_event(&Event::FinalizationChanged {
+            finalized: state.finalized_checkpoint.clone(),
+        });
+    }
 
     Ok(())
 }
@@ -1035,6 +1067,101 @@ pub fn get_attestation_deltas<
     Ok((rewards, penalties))
 }
 
+// Return the attestation rewards and penalties for each validator, as applied by
+// `process_rewards_and_penalties` to `state`, along with the ideal rewards for each effective
+// balance.
+pub fn get_attestation_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    context: &Context,
+) -> Result<AttestationRewards> {
+    let validator_count = state.validators.len();
+    if get_current_epoch(state, context) == GENESIS_EPOCH {
+        let total_rewards = (0..validator_count)
+            .map(|validator_index| TotalAttestationRewards {
+                validator_index,
+                inclusion_delay: Some(0),
+                ..Default::default()
+            })
+            .collect();
+        return Ok(AttestationRewards { ideal_rewards: vec![], total_rewards })
+    }
+
+    let previous_epoch = get_previous_epoch(state, context);
+    let total_balance = get_total_active_balance(state, context)?;
+    let increment = context.effective_balance_increment;
+    let in_inactivity_leak = is_in_inactivity_leak(state, context);
+    let source_attestations = get_matching_source_attestations(state, previous_epoch, context)?;
+    let target_attestations = get_matching_target_attestations(state, previous_epoch, context)?;
+    let head_attestations = get_matching_head_attestations(state, previous_epoch, context)?;
+    let source_indices =
+        get_unslashed_attesting_indices(state, source_attestations.iter(), context)?;
+    let target_indices = get_unslashed_attesting_indices(state, target_attestations, context)?;
+    let head_indices = get_unslashed_attesting_indices(state, head_attestations, context)?;
+    let source_balance = get_total_balance(state, &source_indices, context)?;
+    let target_balance = get_total_balance(state, &target_indices, context)?;
+    let head_balance = get_total_balance(state, &head_indices, context)?;
+
+    let ideal_rewards = (1..=context.max_effective_balance / increment)
+        .map(|increments| {
+            let effective_balance = increments * increment;
+            let base_reward = effective_balance * context.base_reward_factor /
+                total_balance.integer_sqrt() /
+                BASE_REWARDS_PER_EPOCH;
+            let component_reward = |attesting_balance: Gwei| {
+                if in_inactivity_leak {
+                    base_reward
+                } else {
+                    base_reward * (attesting_balance / increment) / (total_balance / increment)
+                }
+            };
+            IdealAttestationRewards {
+                effective_balance,
+                head: component_reward(head_balance),
+                target: component_reward(target_balance),
+                source: component_reward(source_balance),
+                inclusion_delay: Some(base_reward - base_reward / context.proposer_reward_quotient),
+                inactivity: 0,
+            }
+        })
+        .collect();
+
+    let (source_rewards, source_penalties) = get_source_deltas(state, context)?;
+    let (target_rewards, target_penalties) = get_target_deltas(state, context)?;
+    let (head_rewards, head_penalties) = get_head_deltas(state, context)?;
+    let (inclusion_delay_rewards, _) = get_inclusion_delay_deltas(state, context)?;
+    let (_, inactivity_penalties) = get_inactivity_penalty_deltas(state, context)?;
+    let total_rewards = (0..validator_count)
+        .map(|i| TotalAttestationRewards {
+            validator_index: i,
+            head: head_rewards[i] as i64 - head_penalties[i] as i64,
+            target: target_rewards[i] as i64 - target_penalties[i] as i64,
+            source: source_rewards[i] as i64 - source_penalties[i] as i64,
+            inclusion_delay: Some(inclusion_delay_rewards[i]),
+            inactivity: -(inactivity_penalties[i] as i64),
+        })
+        .collect();
+
+    Ok(AttestationRewards { ideal_rewards, total_rewards })
+}
+
 pub fn process_epoch<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -1055,14 +1182,530 @@ pub fn process_epoch<
         MAX_VALIDATORS_PER_COMMITTEE,
         PENDING_ATTESTATIONS_BOUND,
     >,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_justification_and_finalization(state, context)?;
+    process_justification_and_finalization(state, observer, context)?;
     process_rewards_and_penalties(state, context)?;
-    process_registry_updates(state, context);
+    process_registry_updates(state, observer, context);
     process_slashings(state, context)?;
     process_eth1_data_reset(state, context);
-    process_effective_balance_updates(state, context);
+    process_effective_balance_updates(state, observer, context);
+    process_slashings_reset(state, context);
+    process_randao_mixes_reset(state, context);
+    process_historical_roots_update(state, context)?;
+    process_participation_record_updates(state);
+    Ok(())
+}
+
+/// Balances used throughout the epoch transition, accumulated in a single pass over the
+/// registry instead of being recomputed by each step.
+///
+/// Each balance has the `EFFECTIVE_BALANCE_INCREMENT` minimum of `get_total_balance`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressiveBalances {
+    pub total_active_balance: Gwei,
+    /// Unslashed participating balances of the previous epoch, ordered by source, target and
+    /// head like the participation flags of later forks.
+    pub previous_epoch_participating_balances: [Gwei; 3],
+    /// Unslashed participating balance of the current epoch for the target vote.
+    pub current_epoch_target_balance: Gwei,
+}
+
+impl ProgressiveBalances {
+    pub fn previous_epoch_target_balance(&self) -> Gwei {
+        self.previous_epoch_participating_balances[1]
+    }
+
+    // Apply the minimum of `get_total_balance` to each balance.
+    pub fn with_minimum_balance(mut self, context: &Context) -> Self {
+        let increment = context.effective_balance_increment;
+        self.total_active_balance = Gwei::max(self.total_active_balance, increment);
+        for balance in self.previous_epoch_participating_balances.iter_mut() {
+            *balance = Gwei::max(*balance, increment);
+        }
+        self.current_epoch_target_balance = Gwei::max(self.current_epoch_target_balance, increment);
+        self
+    }
+}
+
+/// Parameters of single-pass epoch processing that vary across forks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SinglePassParameters {
+    pub inactivity_penalty_quotient: u64,
+    pub proportional_slashing_multiplier: u64,
+    pub activation_churn_limit: usize,
+}
+
+/// The participation of an unslashed validator in the pending attestations of the state.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ValidatorParticipation {
+    pub is_previous_epoch_source_attester: bool,
+    pub is_previous_epoch_target_attester: bool,
+    pub is_previous_epoch_head_attester: bool,
+    pub is_current_epoch_target_attester: bool,
+    /// The inclusion delay and proposer of the earliest included previous epoch attestation.
+    pub inclusion: Option<(Slot, ValidatorIndex)>,
+}
+
+// Return the participation of each validator in the pending attestations of `state`.
+pub fn get_validator_participation<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    context: &Context,
+) -> Result<Vec<ValidatorParticipation>> {
+    let mut participation = vec![ValidatorParticipation::default(); state.validators.len()];
+    let current_epoch = get_current_epoch(state, context);
+    // NOTE: only compute the participation `process_epoch` would use for this epoch
+    if current_epoch == GENESIS_EPOCH {
+        return Ok(participation)
+    }
+
+    let previous_epoch = get_previous_epoch(state, context);
+    let attestations = get_matching_source_attestations(state, previous_epoch, context)?;
+    if !attestations.is_empty() {
+        let target_root = get_block_root(state, previous_epoch, context)?;
+        for attestation in attestations.iter() {
+            let data = &attestation.data;
+            let is_target = data.target.root == *target_root;
+            let is_head =
+                is_target && data.beacon_block_root == *get_block_root_at_slot(state, data.slot)?;
+            for i in get_attesting_indices(state, data, &attestation.aggregation_bits, context)? {
+                if state.validators[i].slashed {
+                    continue
+                }
+                let entry = &mut participation[i];
+                entry.is_previous_epoch_source_attester = true;
+                entry.is_previous_epoch_target_attester |= is_target;
+                entry.is_previous_epoch_head_attester |= is_head;
+                // Keep the first attestation with the minimum inclusion delay
+                let delay = attestation.inclusion_delay;
+                if !matches!(entry.inclusion, Some((min_delay, _)) if min_delay <= delay) {
+                    entry.inclusion = Some((delay, attestation.proposer_index));
+                }
+            }
+        }
+    }
+
+    if current_epoch <= GENESIS_EPOCH + 1 {
+        return Ok(participation)
+    }
+    for attestation in get_matching_target_attestations(state, current_epoch, context)? {
+        let data = &attestation.data;
+        for i in get_attesting_indices(state, data, &attestation.aggregation_bits, context)? {
+            if !state.validators[i].slashed {
+                participation[i].is_current_epoch_target_attester = true;
+            }
+        }
+    }
+    Ok(participation)
+}
+
+pub fn get_progressive_balances<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    participation: &[ValidatorParticipation],
+    context: &Context,
+) -> Result<ProgressiveBalances> {
+    let current_epoch = get_current_epoch(state, context);
+    let mut balances = ProgressiveBalances::default();
+    for (validator, participation) in state.validators.iter().zip(participation) {
+        let effective_balance = validator.effective_balance;
+        let add = |balance: &mut Gwei, is_participating: bool| -> Result<()> {
+            if is_participating {
+                *balance = balance.checked_add(effective_balance).ok_or(Error::Overflow)?;
+            }
+            Ok(())
+        };
+        add(&mut balances.total_active_balance, is_active_validator(validator, current_epoch))?;
+        let [source, target, head] = &mut balances.previous_epoch_participating_balances;
+        add(source, participation.is_previous_epoch_source_attester)?;
+        add(target, participation.is_previous_epoch_target_attester)?;
+        add(head, participation.is_previous_epoch_head_attester)?;
+        add(
+            &mut balances.current_epoch_target_balance,
+            participation.is_current_epoch_target_attester,
+        )?;
+    }
+    Ok(balances.with_minimum_balance(context))
+}
+
+pub fn process_justification_and_finalization_with_balances<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    balances: &ProgressiveBalances,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    // Initial FFG checkpoint values have a `0x00` stub for `root`.
+    // Skip FFG updates in the first two epochs to avoid corner cases that might result in modifying
+    // this stub.
+    if get_current_epoch(state, context) <= GENESIS_EPOCH + 1 {
+        return Ok(())
+    }
+    weigh_justification_and_finalization(
+        state,
+        balances.total_active_balance,
+        balances.previous_epoch_target_balance(),
+        balances.current_epoch_target_balance,
+        observer,
+        context,
+    )
+}
+
+// Apply the registry update, slashing penalty and effective balance update of the validator at
+// `index` in the order of `process_epoch`, once any rewards and penalties have been applied.
+// Return `true` if the validator is eligible for activation.
+//
+// NOTE: `total_active_balance` must be computed before any effective balance update of the epoch.
+pub fn process_validator_updates<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    index: ValidatorIndex,
+    total_active_balance: Gwei,
+    adjusted_total_slashing_balance: Gwei,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> bool {
+    let current_epoch = get_current_epoch(state, context);
+
+    // Registry updates
+    let validator = &mut state.validators[index];
+    if is_eligible_for_activation_queue(validator, context) {
+        validator.activation_eligibility_epoch = current_epoch + 1;
+    }
+    if is_active_validator(validator, current_epoch) &&
+        validator.effective_balance <= context.ejection_balance
+    {
+        initiate_validator_exit(state, index, observer, context);
+    }
+    let validator = &state.validators[index];
+    let is_eligible_for_activation = is_eligible_for_activation(state, validator);
+
+    // Slashings
+    if validator.slashed &&
+        (current_epoch + context.epochs_per_slashings_vector / 2) == validator.withdrawable_epoch
+    {
+        let increment = context.effective_balance_increment;
+        let penalty_numerator =
+            validator.effective_balance / increment * adjusted_total_slashing_balance;
+        let penalty = penalty_numerator / total_active_balance * increment;
+        decrease_balance(state, index, penalty);
+    }
+
+    // Effective balance updates
+    let hysteresis_increment = context.effective_balance_increment / context.hysteresis_quotient;
+    let downward_threshold = hysteresis_increment * context.hysteresis_downward_multiplier;
+    let upward_threshold = hysteresis_increment * context.hysteresis_upward_multiplier;
+    let validator = &mut state.validators[index];
+    let balance = state.balances[index];
+    if balance + downward_threshold < validator.effective_balance ||
+        validator.effective_balance + upward_threshold < balance
+    {
+        let previous = validator.effective_balance;
+        validator.effective_balance = Gwei::min(
+            balance - balance % context.effective_balance_increment,
+            context.max_effective_balance,
+        );
+        if validator.effective_balance != previous {
+            observer.on_event(&Event::EffectiveBalanceChanged {
+                index,
+                previous,
+                current: validator.effective_balance,
+            });
+        }
+    }
+
+    is_eligible_for_activation
+}
+
+// Activate the validators in `activation_queue` as in `process_registry_updates`.
+pub fn process_activation_queue<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    mut activation_queue: Vec<ValidatorIndex>,
+    activation_churn_limit: usize,
+    observer: &mut dyn Observer,
+    context: &Context,
+) {
+    // Order by the sequence of activation_eligibility_epoch setting and then index
+    activation_queue.sort_by(|&i, &j| {
+        let a = &state.validators[i];
+        let b = &state.validators[j];
+        (a.activation_eligibility_epoch, i).cmp(&(b.activation_eligibility_epoch, j))
+    });
+
+    // Dequeued validators for activation up to churn limit
+    let current_epoch = get_current_epoch(state, context);
+    let activation_exit_epoch = compute_activation_exit_epoch(current_epoch, context);
+    for i in activation_queue.into_iter().take(activation_churn_limit) {
+        let validator = &mut state.validators[i];
+        validator.activation_epoch = activation_exit_epoch;
+        observer.on_event(&Event::ValidatorActivated {
+            index: i,
+            activation_epoch: activation_exit_epoch,
+        });
+    }
+}
+
+// Apply the rewards and penalties, registry updates, slashings and effective balance updates of
+// `process_epoch` in a single pass over the registry.
+pub fn process_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    participation: &[ValidatorParticipation],
+    balances: &ProgressiveBalances,
+    parameters: &SinglePassParameters,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let current_epoch = get_current_epoch(state, context);
+    let total_active_balance = balances.total_active_balance;
+    let adjusted_total_slashing_balance = Gwei::min(
+        state.slashings.iter().sum::<Gwei>() * parameters.proportional_slashing_multiplier,
+        total_active_balance,
+    );
+
+    // No rewards are applied at the end of `GENESIS_EPOCH` because rewards are for work done in the
+    // previous epoch
+    let process_rewards = current_epoch != GENESIS_EPOCH;
+    let validator_count = state.validators.len();
+    let sqrt_total_active_balance = total_active_balance.integer_sqrt();
+    let base_reward_for = |effective_balance: Gwei| {
+        effective_balance * context.base_reward_factor /
+            sqrt_total_active_balance /
+            BASE_REWARDS_PER_EPOCH
+    };
+
+    // Proposers are rewarded for the attestations of other validators so these rewards are
+    // accumulated before balances are updated
+    let mut inclusion_delay_rewards = vec![0; validator_count];
+    if process_rewards {
+        for (i, participation) in participation.iter().enumerate() {
+            if let Some((inclusion_delay, proposer_index)) = participation.inclusion {
+                let base_reward = base_reward_for(state.validators[i].effective_balance);
+                let proposer_reward = base_reward / context.proposer_reward_quotient;
+                inclusion_delay_rewards[proposer_index] += proposer_reward;
+                inclusion_delay_rewards[i] += (base_reward - proposer_reward) / inclusion_delay;
+            }
+        }
+    }
+
+    let previous_epoch = get_previous_epoch(state, context);
+    let is_leaking = is_in_inactivity_leak(state, context);
+    let finality_delay = get_finality_delay(state, context);
+    let increment = context.effective_balance_increment;
+    let total_increments = total_active_balance / increment;
+    let mut activation_queue = vec![];
+    for i in 0..validator_count {
+        if process_rewards {
+            let validator = &state.validators[i];
+            let is_eligible = is_active_validator(validator, previous_epoch) ||
+                (validator.slashed && previous_epoch + 1 < validator.withdrawable_epoch);
+            let effective_balance = validator.effective_balance;
+            let mut rewards = inclusion_delay_rewards[i];
+            let mut penalties = 0;
+            if is_eligible {
+                let base_reward = base_reward_for(effective_balance);
+                let validator_participation = &participation[i];
+                let components = [
+                    validator_participation.is_previous_epoch_source_attester,
+                    validator_participation.is_previous_epoch_target_attester,
+                    validator_participation.is_previous_epoch_head_attester,
+                ];
+                for (is_attester, attesting_balance) in
+                    components.into_iter().zip(balances.previous_epoch_participating_balances)
+                {
+                    if is_attester {
+                        if is_leaking {
+                            // Since full base reward will be canceled out by inactivity penalty
+                            // deltas, optimal participation receives full base reward
+                            // compensation here.
+                            rewards += base_reward;
+                        } else {
+                            let reward_numerator = base_reward * (attesting_balance / increment);
+                            rewards += reward_numerator / total_increments;
+                        }
+                    } else {
+                        penalties += base_reward;
+                    }
+                }
+                if is_leaking {
+                    // If validator is performing optimally this cancels all rewards for a
+                    // neutral balance
+                    let proposer_reward = base_reward / context.proposer_reward_quotient;
+                    penalties += BASE_REWARDS_PER_EPOCH * base_reward - proposer_reward;
+                    if !validator_participation.is_previous_epoch_target_attester {
+                        penalties += effective_balance * finality_delay /
+                            parameters.inactivity_penalty_quotient;
+                    }
+                }
+            }
+            increase_balance(state, i, rewards);
+            decrease_balance(state, i, penalties);
+        }
+
+        if process_validator_updates(
+            state,
+            i,
+            total_active_balance,
+            adjusted_total_slashing_balance,
+            observer,
+            context,
+        ) {
+            activation_queue.push(i);
+        }
+    }
+
+    process_activation_queue(
+        state,
+        activation_queue,
+        parameters.activation_churn_limit,
+        observer,
+        context,
+    );
+    Ok(())
+}
+
+/// An optimized `process_epoch` which visits each validator once for the rewards and penalties,
+/// registry updates, slashings and effective balance updates.
+///
+/// The result is identical to `process_epoch`, which is kept for differential testing.
+pub fn process_epoch_single_pass<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    let participation = get_validator_participation(state, context)?;
+    let balances = get_progressive_balances(state, &participation, context)?;
+    process_justification_and_finalization_with_balances(state, &balances, observer, context)?;
+    let parameters = SinglePassParameters {
+        inactivity_penalty_quotient: context.inactivity_penalty_quotient,
+        proportional_slashing_multiplier: context.proportional_slashing_multiplier,
+        activation_churn_limit: get_validator_churn_limit(state, context),
+    };
+    process_single_pass(state, &participation, &balances, &parameters, observer, context)?;
+    process_eth1_data_reset(state, context);
     process_slashings_reset(state, context);
     process_randao_mixes_reset(state, context);
     process_historical_roots_update(state, context)?;
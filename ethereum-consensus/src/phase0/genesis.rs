@@ -82,7 +82,7 @@ pub fn initialize_beacon_state_from_eth1<
     for deposit in deposits.iter_mut() {
         leaves.push(deposit.data.clone());
         state.eth1_data.deposit_root = leaves.hash_tree_root()?;
-        process_deposit(&mut state, deposit, context)?;
+        process_deposit(&mut state, deposit, &mut (), context)?;
     }
 
     for i in 0..state.validators.len() {
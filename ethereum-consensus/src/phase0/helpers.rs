@@ -1,5 +1,5 @@
 use crate::{
-    crypto::{fast_aggregate_verify, hash, verify_signature},
+    crypto::hash,
     error::{
         invalid_operation_error, InvalidAttestation, InvalidIndexedAttestation, InvalidOperation,
     },
@@ -15,7 +15,7 @@ use crate::{
     },
     signing::compute_signing_root,
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{Context, Event, Observer, Result, SignatureSets},
     Error,
 };
 use std::{cmp, collections::HashSet};
@@ -89,6 +89,7 @@ pub fn is_valid_indexed_attestation<
         PENDING_ATTESTATIONS_BOUND,
     >,
     indexed_attestation: &mut IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let attesting_indices = &indexed_attestation.attesting_indices;
@@ -120,14 +121,12 @@ pub fn is_valid_indexed_attestation<
         )))
     }
 
-    let mut public_keys = vec![];
     for &index in &attesting_indices[..] {
-        let public_key = state.validators.get(index).map(|v| &v.public_key).ok_or_else(|| {
-            invalid_operation_error(InvalidOperation::IndexedAttestation(
+        if index >= state.validators.len() {
+            return Err(invalid_operation_error(InvalidOperation::IndexedAttestation(
                 InvalidIndexedAttestation::InvalidIndex(index),
-            ))
-        })?;
-        public_keys.push(public_key);
+            )))
+        }
     }
 
     let domain = get_domain(
@@ -137,8 +136,17 @@ pub fn is_valid_indexed_attestation<
         context,
     )?;
     let signing_root = compute_signing_root(&mut indexed_attestation.data, domain)?;
-    fast_aggregate_verify(&public_keys, signing_root.as_ref(), &indexed_attestation.signature)
-        .map_err(Into::into)
+    let signature_set = context.pubkey_cache.aggregate_signature_set(
+        &state.validators,
+        attesting_indices,
+        signing_root.as_ref(),
+        &indexed_attestation.signature,
+    );
+    signatures.verify(signature_set, || {
+        invalid_operation_error(InvalidOperation::IndexedAttestation(
+            InvalidIndexedAttestation::InvalidSignature(indexed_attestation.signature.clone()),
+        ))
+    })
 }
 
 pub fn verify_block_signature<
@@ -174,18 +182,20 @@ pub fn verify_block_signature<
         MAX_DEPOSITS,
         MAX_VOLUNTARY_EXITS,
     >,
+    signatures: &mut SignatureSets,
     context: &Context,
 ) -> Result<()> {
     let proposer_index = signed_block.message.proposer_index;
-    let proposer = state
-        .validators
-        .get(proposer_index)
-        .ok_or(Error::OutOfBounds { requested: proposer_index, bound: state.validators.len() })?;
     let domain = get_domain(state, DomainType::BeaconProposer, None, context)?;
     let signing_root = compute_signing_root(&mut signed_block.message, domain)?;
 
-    let public_key = &proposer.public_key;
-    verify_signature(public_key, signing_root.as_ref(), &signed_block.signature).map_err(Into::into)
+    let signature_set = context.pubkey_cache.signature_set(
+        &state.validators,
+        proposer_index,
+        signing_root.as_ref(),
+        &signed_block.signature,
+    );
+    signatures.verify(signature_set, || crate::crypto::Error::InvalidSignature.into())
 }
 
 pub fn get_domain<
@@ -283,6 +293,59 @@ pub fn compute_shuffled_index(
     Ok(index)
 }
 
+/// Shuffles the entire list of `indices` with the "swap-or-not" network so that
+/// `compute_shuffled_list(indices, seed, context)[i] ==
+/// indices[compute_shuffled_index(i, indices.len(), seed, context)]`.
+pub fn compute_shuffled_list(
+    indices: &[ValidatorIndex],
+    seed: &Bytes32,
+    context: &Context,
+) -> Vec<ValidatorIndex> {
+    let mut list = indices.to_vec();
+    let index_count = list.len();
+    if index_count < 2 {
+        return list
+    }
+
+    let mut pivot_input = [0u8; 33];
+    pivot_input[..32].copy_from_slice(seed.as_ref());
+    let mut source_input = [0u8; 37];
+    source_input[..32].copy_from_slice(seed.as_ref());
+    // NOTE: rounds are applied in reverse order as the list is permuted in place,
+    // rather than the index being traced through each round
+    for current_round in (0..context.shuffle_round_count).rev() {
+        pivot_input[32] = current_round as u8;
+        let pivot_bytes: [u8; 8] = hash(pivot_input).as_ref()[..8].try_into().unwrap();
+        let pivot = (u64::from_le_bytes(pivot_bytes) as usize) % index_count;
+
+        source_input[32] = current_round as u8;
+        let mut source_position = None;
+        let mut source = Bytes32::default();
+        for index in 0..index_count {
+            let flip = (pivot + index_count - index) % index_count;
+            // each pair is visited once from its lower index; `position` is the larger of the two
+            if index >= flip {
+                continue
+            }
+            let position = flip;
+            if source_position != Some(position / 256) {
+                let position_bytes: [u8; 4] = ((position / 256) as u32).to_le_bytes();
+                source_input[33..].copy_from_slice(&position_bytes);
+                source = hash(source_input);
+                source_position = Some(position / 256);
+            }
+
+            let byte = source.as_ref()[(position % 256) / 8];
+            let bit = (byte >> (position % 8)) % 2;
+            if bit != 0 {
+                list.swap(index, flip);
+            }
+        }
+    }
+
+    list
+}
+
 pub fn compute_proposer_index<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -664,12 +727,29 @@ pub fn get_beacon_committee<
     context: &Context,
 ) -> Result<Vec<ValidatorIndex>> {
     let epoch = compute_epoch_at_slot(slot, context);
-    let committees_per_slot = get_committee_count_per_slot(state, epoch, context);
-    let indices = get_active_validator_indices(state, epoch);
     let seed = get_seed(state, epoch, DomainType::BeaconAttester, context);
-    let index = (slot % context.slots_per_epoch) * committees_per_slot as u64 + index as u64;
-    let count = committees_per_slot as u64 * context.slots_per_epoch;
-    compute_committee(&indices, &seed, index as usize, count as usize, context)
+    // NOTE: equivalent to `compute_committee` but slices a cached shuffling of the entire list,
+    // which holds as many indices as there are active validators in `epoch`
+    let shuffled_indices = context.shuffling_cache.get_or_compute_shuffling(
+        epoch,
+        &seed,
+        || get_active_validator_indices(state, epoch),
+        context,
+    );
+    let active_validator_count = shuffled_indices.len();
+    // NOTE: `get_committee_count_per_slot` without recomputing the active validator indices
+    let committees_per_slot = u64::max(
+        1,
+        u64::min(
+            context.max_committees_per_slot,
+            active_validator_count as u64 / context.slots_per_epoch / context.target_committee_size,
+        ),
+    );
+    let index = (slot % context.slots_per_epoch) * committees_per_slot + index as u64;
+    let count = committees_per_slot * context.slots_per_epoch;
+    let start = (active_validator_count * index as usize) / count as usize;
+    let end = active_validator_count * (index as usize + 1) / count as usize;
+    Ok(shuffled_indices[start..end].to_vec())
 }
 
 pub fn get_beacon_proposer_index<
@@ -700,8 +780,13 @@ pub fn get_beacon_proposer_index<
         .copy_from_slice(get_seed(state, epoch, DomainType::BeaconProposer, context).as_ref());
     input[32..40].copy_from_slice(&state.slot.to_le_bytes());
     let seed = hash(input);
-    let indices = get_active_validator_indices(state, epoch);
-    compute_proposer_index(state, &indices, &seed, context)
+    // NOTE: equivalent to `compute_proposer_index` but reuses a cached selection
+    context.shuffling_cache.get_or_compute_proposer(
+        &seed,
+        || get_active_validator_indices(state, epoch),
+        |index| state.validators[index].effective_balance,
+        context,
+    )
 }
 
 pub fn get_total_balance<
@@ -920,6 +1005,7 @@ pub fn initiate_validator_exit<
         PENDING_ATTESTATIONS_BOUND,
     >,
     index: ValidatorIndex,
+    observer: &mut dyn Observer,
     context: &Context,
 ) {
     if state.validators[index].exit_epoch != FAR_FUTURE_EPOCH {
@@ -947,6 +1033,11 @@ pub fn initiate_validator_exit<
     state.validators[index].exit_epoch = exit_queue_epoch;
     state.validators[index].withdrawable_epoch =
         state.validators[index].exit_epoch + context.min_validator_withdrawability_delay;
+    observer.on_event(&Event::ValidatorExited {
+        index,
+        exit_epoch: exit_queue_epoch,
+        withdrawable_epoch: state.validators[index].withdrawable_epoch,
+    });
 }
 
 pub fn slash_validator<
@@ -971,10 +1062,11 @@ pub fn slash_validator<
     >,
     slashed_index: ValidatorIndex,
     whistleblower_index: Option<ValidatorIndex>,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     let epoch = get_current_epoch(state, context);
-    initiate_validator_exit(state, slashed_index, context);
+    initiate_validator_exit(state, slashed_index, observer, context);
     state.validators[slashed_index].slashed = true;
     state.validators[slashed_index].withdrawable_epoch = u64::max(
         state.validators[slashed_index].withdrawable_epoch,
@@ -991,6 +1083,7 @@ pub fn slash_validator<
     let proposer_index = get_beacon_proposer_index(state, context)?;
 
     let whistleblower_index = whistleblower_index.unwrap_or(proposer_index);
+    observer.on_event(&Event::ValidatorSlashed { index: slashed_index, whistleblower_index });
 
     let whistleblower_reward =
         state.validators[slashed_index].effective_balance / context.whistleblower_reward_quotient;
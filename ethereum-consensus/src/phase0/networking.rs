@@ -1,4 +1,11 @@
-use crate::{primitives::Epoch, ssz::prelude::Bitvector};
+use crate::{
+    crypto::hash,
+    phase0::helpers::compute_shuffled_index,
+    primitives::{CommitteeIndex, Epoch, ForkDigest, Root, Slot},
+    ssz::prelude::*,
+    state_transition::{Context, Result},
+};
+use enr::NodeId;
 use std::time::Duration;
 
 pub const ATTESTATION_SUBNET_COUNT: usize = 64;
@@ -10,10 +17,141 @@ pub const TTFB_TIMEOUT: Duration = Duration::from_secs(5);
 pub const RESP_TIMEOUT: Duration = Duration::from_secs(10);
 pub const ATTESTATION_PROPAGATION_SLOT_RANGE: usize = 32;
 pub const MAXIMUM_GOSSIP_CLOCK_DISPARITY: Duration = Duration::from_millis(500);
+pub const MESSAGE_DOMAIN_INVALID_SNAPPY: [u8; 4] = [0, 0, 0, 0];
+pub const MESSAGE_DOMAIN_VALID_SNAPPY: [u8; 4] = [1, 0, 0, 0];
+pub const SUBNETS_PER_NODE: usize = 2;
+pub const NODE_ID_BITS: usize = 256;
+pub const EPOCHS_PER_SUBNET_SUBSCRIPTION: Epoch = 2u64.pow(8);
+pub const ATTESTATION_SUBNET_EXTRA_BITS: usize = 0;
+// `ceillog2(ATTESTATION_SUBNET_COUNT) + ATTESTATION_SUBNET_EXTRA_BITS`
+pub const ATTESTATION_SUBNET_PREFIX_BITS: usize = 6;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+pub const MAX_ERROR_MESSAGE_LEN: usize = 256;
+pub const GOODBYE_CLIENT_SHUTDOWN: Goodbye = 1;
+pub const GOODBYE_IRRELEVANT_NETWORK: Goodbye = 2;
+pub const GOODBYE_FAULT_OR_ERROR: Goodbye = 3;
+
+pub type MessageId = [u8; 20];
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
 pub struct MetaData {
     #[serde(with = "crate::serde::as_str")]
     pub seq_number: u64,
     pub attnets: Bitvector<ATTESTATION_SUBNET_COUNT>,
 }
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct Status {
+    #[serde(with = "crate::serde::as_hex")]
+    pub fork_digest: ForkDigest,
+    pub finalized_root: Root,
+    #[serde(with = "crate::serde::as_str")]
+    pub finalized_epoch: Epoch,
+    pub head_root: Root,
+    #[serde(with = "crate::serde::as_str")]
+    pub head_slot: Slot,
+}
+
+pub type Goodbye = u64;
+
+pub type Ping = u64;
+
+#[derive(
+    Default, Debug, Clone, SimpleSerialize, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub struct BeaconBlocksByRangeRequest {
+    #[serde(with = "crate::serde::as_str")]
+    pub start_slot: Slot,
+    #[serde(with = "crate::serde::as_str")]
+    pub count: u64,
+    #[serde(with = "crate::serde::as_str")]
+    pub step: u64,
+}
+
+pub type BeaconBlocksByRootRequest = List<Root, MAX_REQUEST_BLOCKS>;
+
+pub type ErrorMessage = ByteList<MAX_ERROR_MESSAGE_LEN>;
+
+// Return the subnet the attestations of the committee at `slot` with `committee_index` are
+// gossiped on, given the number of committees in each slot of its epoch.
+pub fn compute_subnet_for_attestation(
+    committees_per_slot: u64,
+    slot: Slot,
+    committee_index: CommitteeIndex,
+    context: &Context,
+) -> u64 {
+    let slots_since_epoch_start = slot % context.slots_per_epoch;
+    let committees_since_epoch_start = committees_per_slot * slots_since_epoch_start;
+    (committees_since_epoch_start + committee_index as u64) % ATTESTATION_SUBNET_COUNT as u64
+}
+
+// Return the attestation subnet the node with `node_id` subscribes to for the `index`-th of its
+// `SUBNETS_PER_NODE` long-lived subscriptions during `epoch`.
+pub fn compute_subscribed_subnet(
+    node_id: &NodeId,
+    epoch: Epoch,
+    index: usize,
+    context: &Context,
+) -> Result<u64> {
+    let node_id = node_id.raw();
+    // the `ATTESTATION_SUBNET_PREFIX_BITS` most significant bits of the big-endian node id
+    let node_id_prefix = (node_id[0] >> (8 - ATTESTATION_SUBNET_PREFIX_BITS)) as usize;
+    let node_offset = node_id[NODE_ID_BITS / 8 - 1] as u64 % EPOCHS_PER_SUBNET_SUBSCRIPTION;
+    let subscription_period = (epoch + node_offset) / EPOCHS_PER_SUBNET_SUBSCRIPTION;
+    let permutation_seed = hash(subscription_period.to_le_bytes());
+    let permutated_prefix = compute_shuffled_index(
+        node_id_prefix,
+        1 << ATTESTATION_SUBNET_PREFIX_BITS,
+        &permutation_seed,
+        context,
+    )?;
+    Ok(((permutated_prefix + index) % ATTESTATION_SUBNET_COUNT) as u64)
+}
+
+// Return the attestation subnets the node with `node_id` subscribes to for the long term during
+// `epoch`.
+pub fn compute_subscribed_subnets(
+    node_id: &NodeId,
+    epoch: Epoch,
+    context: &Context,
+) -> Result<Vec<u64>> {
+    (0..SUBNETS_PER_NODE)
+        .map(|index| compute_subscribed_subnet(node_id, epoch, index, context))
+        .collect()
+}
+
+// Return the `message-id` of a gossip message with the (snappy compressed) `message_data`,
+// as used before the altair fork.
+pub fn compute_message_id(message_data: &[u8]) -> MessageId {
+    let (domain, data) = decompress_message(message_data, GOSSIP_MAX_SIZE);
+    message_id_from_parts(&[domain.as_slice(), &data[..]])
+}
+
+// Return the domain of a message and the data its `message-id` commits to: the decompressed
+// data if `message_data` is valid snappy that decompresses within `max_size`, the gossip size
+// limit of the fork of the message, and `message_data` itself otherwise.
+pub(crate) fn decompress_message(
+    message_data: &[u8],
+    max_size: usize,
+) -> ([u8; 4], std::borrow::Cow<'_, [u8]>) {
+    let within_bounds =
+        snap::raw::decompress_len(message_data).map(|len| len <= max_size).unwrap_or(false);
+    if within_bounds {
+        if let Ok(data) = snap::raw::Decoder::new().decompress_vec(message_data) {
+            return (MESSAGE_DOMAIN_VALID_SNAPPY, data.into())
+        }
+    }
+    (MESSAGE_DOMAIN_INVALID_SNAPPY, message_data.into())
+}
+
+// Return the first 20 bytes of the SHA256 hash of the concatenation of `parts`.
+pub(crate) fn message_id_from_parts(parts: &[&[u8]]) -> MessageId {
+    let digest = hash(parts.concat());
+    let mut message_id = MessageId::default();
+    message_id.copy_from_slice(&digest[..message_id.len()]);
+    message_id
+}
@@ -39,6 +39,8 @@ pub const TARGET_AGGREGATORS_PER_COMMITTEE: usize = 16;
 pub const RANDOM_SUBNETS_PER_VALIDATOR: usize = 1;
 pub const EPOCHS_PER_RANDOM_SUBNET_SUBSCRIPTION: Epoch = 256;
 
+pub const SAFE_SLOTS_TO_UPDATE_JUSTIFIED: Slot = 2;
+
 pub const PRESET: Preset = Preset {
     max_committees_per_slot: MAX_COMMITTEES_PER_SLOT,
     target_committee_size: TARGET_COMMITTEE_SIZE,
@@ -72,6 +74,8 @@ pub const PRESET: Preset = Preset {
     max_attestations: MAX_ATTESTATIONS,
     max_deposits: MAX_DEPOSITS,
     max_voluntary_exits: MAX_VOLUNTARY_EXITS,
+    target_aggregators_per_committee: TARGET_AGGREGATORS_PER_COMMITTEE as u64,
+    safe_slots_to_update_justified: SAFE_SLOTS_TO_UPDATE_JUSTIFIED,
 };
 
 pub type IndexedAttestation = spec::IndexedAttestation<MAX_VALIDATORS_PER_COMMITTEE>;
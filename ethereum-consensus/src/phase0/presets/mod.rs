@@ -36,4 +36,6 @@ pub struct Preset {
     pub max_attestations: usize,
     pub max_deposits: usize,
     pub max_voluntary_exits: usize,
+    pub target_aggregators_per_committee: u64,
+    pub safe_slots_to_update_justified: Slot,
 }
@@ -1,8 +1,8 @@
 use crate::{
-    phase0::{beacon_state::BeaconState, epoch_processing::process_epoch},
+    phase0::{beacon_state::BeaconState, epoch_processing::process_epoch_single_pass},
     primitives::{Root, Slot},
     ssz::prelude::*,
-    state_transition::{Context, Result},
+    state_transition::{Context, Observer, Result},
     Error,
 };
 
@@ -27,6 +27,7 @@ pub fn process_slots<
         PENDING_ATTESTATIONS_BOUND,
     >,
     slot: Slot,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
     if state.slot >= slot {
@@ -35,7 +36,7 @@ pub fn process_slots<
     while state.slot < slot {
         process_slot(state, context)?;
         if (state.slot + 1) % context.slots_per_epoch == 0 {
-            process_epoch(state, context)?;
+            process_epoch_single_pass(state, observer, context)?;
         }
         state.slot += 1;
     }
@@ -65,6 +66,34 @@ pub fn process_slot<
     context: &Context,
 ) -> Result<()> {
     let previous_state_root = state.hash_tree_root()?;
+    process_slot_with_state_root(state, previous_state_root, context)
+}
+
+/// Performs `process_slot` with the known root of `state` as `previous_state_root`, rather than
+/// computing it.
+pub fn process_slot_with_state_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    previous_state_root: Root,
+    context: &Context,
+) -> Result<()> {
     let root_index = state.slot % context.slots_per_historical_root;
     state.state_roots[root_index as usize] = previous_state_root;
 
@@ -78,3 +107,44 @@ pub fn process_slot<
 
     Ok(())
 }
+
+/// Performs `process_slots` with the known root of `state` as `state_root`, which saves hashing
+/// the state for the first slot processed.
+pub fn process_slots_with_state_root<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+>(
+    state: &mut BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+    >,
+    slot: Slot,
+    state_root: Root,
+    observer: &mut dyn Observer,
+    context: &Context,
+) -> Result<()> {
+    if state.slot >= slot {
+        return Err(Error::TransitionToPreviousSlot { requested: slot, current: state.slot })
+    }
+    process_slot_with_state_root(state, state_root, context)?;
+    if (state.slot + 1) % context.slots_per_epoch == 0 {
+        process_epoch_single_pass(state, observer, context)?;
+    }
+    state.slot += 1;
+    if state.slot < slot {
+        process_slots(state, slot, observer, context)?;
+    }
+    Ok(())
+}
@@ -8,35 +8,43 @@ pub use crate::{
         },
         beacon_state::{BeaconState, Fork, ForkData, HistoricalBatch, HistoricalSummary},
         block_processing::{
-            get_validator_from_deposit, process_attestation, process_attester_slashing,
-            process_block, process_block_header, process_deposit, process_eth1_data,
-            process_operations, process_proposer_slashing, process_randao, process_voluntary_exit,
-            xor,
+            get_block_rewards, get_validator_from_deposit, process_attestation,
+            process_attester_slashing, process_block, process_block_header, process_deposit,
+            process_eth1_data, process_operations, process_proposer_slashing, process_randao,
+            process_voluntary_exit, xor,
+        },
+        block_production::{
+            produce_block, select_attestations, select_operations, BlockOperations,
         },
         constants::{
             BASE_REWARDS_PER_EPOCH, DEPOSIT_CONTRACT_TREE_DEPTH, DEPOSIT_DATA_LIST_BOUND,
             JUSTIFICATION_BITS_LENGTH,
         },
         epoch_processing::{
-            get_attestation_component_deltas, get_attestation_deltas, get_attesting_balance,
-            get_base_reward, get_finality_delay, get_head_deltas, get_inactivity_penalty_deltas,
-            get_inclusion_delay_deltas, get_matching_head_attestations,
-            get_matching_source_attestations, get_matching_target_attestations,
-            get_proposer_reward, get_source_deltas, get_target_deltas,
-            get_unslashed_attesting_indices, is_in_inactivity_leak,
-            process_effective_balance_updates, process_epoch, process_eth1_data_reset,
-            process_historical_roots_update, process_justification_and_finalization,
+            get_attestation_component_deltas, get_attestation_deltas, get_attestation_rewards,
+            get_attesting_balance, get_base_reward, get_finality_delay, get_head_deltas,
+            get_inactivity_penalty_deltas, get_inclusion_delay_deltas,
+            get_matching_head_attestations, get_matching_source_attestations,
+            get_matching_target_attestations, get_progressive_balances, get_proposer_reward,
+            get_source_deltas, get_target_deltas, get_unslashed_attesting_indices,
+            get_validator_participation, is_in_inactivity_leak, process_activation_queue,
+            process_effective_balance_updates, process_epoch, process_epoch_single_pass,
+            process_eth1_data_reset, process_historical_roots_update,
+            process_justification_and_finalization,
+            process_justification_and_finalization_with_balances,
             process_participation_record_updates, process_randao_mixes_reset,
-            process_registry_updates, process_rewards_and_penalties, process_slashings,
-            process_slashings_reset, weigh_justification_and_finalization,
+            process_registry_updates, process_rewards_and_penalties, process_single_pass,
+            process_slashings, process_slashings_reset, process_validator_updates,
+            weigh_justification_and_finalization, ProgressiveBalances, SinglePassParameters,
+            ValidatorParticipation,
         },
         genesis::{get_genesis_block, initialize_beacon_state_from_eth1, is_valid_genesis_state},
         helpers::{
             compute_activation_exit_epoch, compute_committee, compute_domain,
             compute_epoch_at_slot, compute_fork_data_root, compute_fork_digest,
-            compute_proposer_index, compute_shuffled_index, compute_start_slot_at_epoch,
-            decrease_balance, get_active_validator_indices, get_attesting_indices,
-            get_beacon_committee, get_beacon_proposer_index, get_block_root,
+            compute_proposer_index, compute_shuffled_index, compute_shuffled_list,
+            compute_start_slot_at_epoch, decrease_balance, get_active_validator_indices,
+            get_attesting_indices, get_beacon_committee, get_beacon_proposer_index, get_block_root,
             get_block_root_at_slot, get_committee_count_per_slot, get_current_epoch, get_domain,
             get_eligible_validator_indices, get_indexed_attestation, get_previous_epoch,
             get_randao_mix, get_seed, get_total_active_balance, get_total_balance,
@@ -50,11 +58,18 @@ pub use crate::{
             DepositMessage, Eth1Data, IndexedAttestation, PendingAttestation, ProposerSlashing,
             SignedVoluntaryExit, VoluntaryExit,
         },
-        slot_processing::{process_slot, process_slots},
+        slot_processing::{
+            process_slot, process_slot_with_state_root, process_slots,
+            process_slots_with_state_root,
+        },
         state_transition::{state_transition, state_transition_block_in_slot},
-        validator::{AggregateAndProof, Eth1Block, SignedAggregateAndProof, Validator},
+        validator::{
+            is_aggregator, AggregateAndProof, Eth1Block, SignedAggregateAndProof, Validator,
+        },
     },
     primitives::*,
     signing::*,
-    state_transition::{Context, Result, Validation},
+    state_transition::{
+        Context, Event, Observer, Result, SignatureSets, SignatureStrategy, Validation,
+    },
 };
@@ -5,7 +5,7 @@ use crate::{
         slot_processing::process_slots,
     },
     ssz::prelude::*,
-    state_transition::{Context, Result, Validation},
+    state_transition::{Context, Observer, Result, SignatureSets, Validation},
     Error,
 };
 
@@ -46,18 +46,21 @@ pub fn state_transition_block_in_slot<
         MAX_VOLUNTARY_EXITS,
     >,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    let validate_result = match validation {
-        Validation::Enabled => true,
-        Validation::Disabled => false,
+    let mut signatures = SignatureSets::for_validation(&validation);
+    let result = if validation.signatures {
+        verify_block_signature(state, signed_block, &mut signatures, context)
+    } else {
+        Ok(())
     };
-    if validate_result {
-        verify_block_signature(state, signed_block, context)?;
-    }
-    let block = &mut signed_block.message;
-    process_block(state, block, context)?;
-    if validate_result && block.state_root != state.hash_tree_root()? {
+    let result = result.and_then(|_| {
+        process_block(state, &mut signed_block.message, &mut signatures, observer, context)
+    });
+    signatures.finish(result)?;
+    let block = &signed_block.message;
+    if validation.state_root && block.state_root != state.hash_tree_root()? {
         Err(Error::InvalidStateRoot)
     } else {
         Ok(())
@@ -98,9 +101,10 @@ pub fn state_transition<
         MAX_VOLUNTARY_EXITS,
     >,
     validation: Validation,
+    observer: &mut dyn Observer,
     context: &Context,
 ) -> Result<()> {
-    process_slots(state, signed_block.message.slot, context)?;
+    process_slots(state, signed_block.message.slot, observer, context)?;
 
-    state_transition_block_in_slot(state, signed_block, validation, context)
+    state_transition_block_in_slot(state, signed_block, validation, observer, context)
 }
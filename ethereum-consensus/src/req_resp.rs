@@ -0,0 +1,781 @@
+//! The `ssz_snappy` wire encoding of the req/resp domain of the consensus p2p interface as
+//! specified in the consensus specs' `p2p-interface.md`.
+//!
+//! Requests are a single payload. Responses are a stream of chunks, each made of a result byte,
+//! the context bytes of the protocols that use them and a payload. Each payload is the length of
+//! its SSZ encoding as an unsigned LEB128 varint followed by the SSZ encoding in snappy frames.
+use crate::{
+    bellatrix::networking::MAX_CHUNK_SIZE_BELLATRIX,
+    deneb::networking::{
+        BlobSidecarsByRangeRequest, BlobSidecarsByRootRequest, MAX_REQUEST_BLOB_SIDECARS,
+        MAX_REQUEST_BLOCKS_DENEB,
+    },
+    error::ReqRespError,
+    phase0::{
+        compute_fork_digest,
+        networking::{
+            BeaconBlocksByRangeRequest, BeaconBlocksByRootRequest, ErrorMessage, Goodbye, Ping,
+            Status, MAX_CHUNK_SIZE, MAX_ERROR_MESSAGE_LEN, MAX_REQUEST_BLOCKS,
+        },
+    },
+    primitives::{ForkDigest, Root},
+    ssz::prelude::*,
+    state_transition::{Context, Result},
+    types::SignedBeaconBlock,
+    Fork,
+};
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+pub const PROTOCOL_PREFIX: &str = "/eth2/beacon_chain/req";
+pub const SSZ_SNAPPY_ENCODING: &str = "ssz_snappy";
+
+// the longest unsigned LEB128 encoding of a `u64`
+const MAX_VARINT_LEN: usize = 10;
+// the layout of the snappy framing format
+const SNAPPY_STREAM_IDENTIFIER_LEN: usize = 10;
+const SNAPPY_CHUNK_HEADER_LEN: usize = 8;
+const SNAPPY_MAX_BLOCK_SIZE: usize = 1 << 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    Status,
+    Goodbye,
+    Ping,
+    MetaData,
+    BeaconBlocksByRange,
+    BeaconBlocksByRoot,
+    BlobSidecarsByRange,
+    BlobSidecarsByRoot,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Status => "status",
+            Self::Goodbye => "goodbye",
+            Self::Ping => "ping",
+            Self::MetaData => "metadata",
+            Self::BeaconBlocksByRange => "beacon_blocks_by_range",
+            Self::BeaconBlocksByRoot => "beacon_blocks_by_root",
+            Self::BlobSidecarsByRange => "blob_sidecars_by_range",
+            Self::BlobSidecarsByRoot => "blob_sidecars_by_root",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A version of a req/resp protocol, with the `ssz_snappy` encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProtocolId {
+    pub protocol: Protocol,
+    pub version: u8,
+}
+
+impl ProtocolId {
+    pub fn new(protocol: Protocol, version: u8) -> Self {
+        Self { protocol, version }
+    }
+
+    /// Whether the successful response chunks of the protocol carry the fork digest of their
+    /// payload as context bytes.
+    pub fn has_context_bytes(&self) -> bool {
+        match self.protocol {
+            Protocol::BeaconBlocksByRange | Protocol::BeaconBlocksByRoot => self.version >= 2,
+            Protocol::BlobSidecarsByRange | Protocol::BlobSidecarsByRoot => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ProtocolId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{PROTOCOL_PREFIX}/{}/{}/{SSZ_SNAPPY_ENCODING}", self.protocol, self.version)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseCode {
+    Success,
+    InvalidRequest,
+    ServerError,
+    ResourceUnavailable,
+    Other(u8),
+}
+
+impl From<u8> for ResponseCode {
+    fn from(code: u8) -> Self {
+        match code {
+            0 => Self::Success,
+            1 => Self::InvalidRequest,
+            2 => Self::ServerError,
+            3 => Self::ResourceUnavailable,
+            code => Self::Other(code),
+        }
+    }
+}
+
+impl From<ResponseCode> for u8 {
+    fn from(code: ResponseCode) -> Self {
+        match code {
+            ResponseCode::Success => 0,
+            ResponseCode::InvalidRequest => 1,
+            ResponseCode::ServerError => 2,
+            ResponseCode::ResourceUnavailable => 3,
+            ResponseCode::Other(code) => code,
+        }
+    }
+}
+
+/// The fork digests of the forks of a chain, used as the context bytes of response chunks.
+#[derive(Debug, Clone)]
+pub struct ForkDigests {
+    digests: Vec<(Fork, ForkDigest)>,
+}
+
+impl ForkDigests {
+    pub fn new(genesis_validators_root: Root, context: &Context) -> Result<Self> {
+        let digests = [Fork::Phase0, Fork::Altair, Fork::Bellatrix, Fork::Capella, Fork::Deneb]
+            .into_iter()
+            .map(|fork| {
+                let digest =
+                    compute_fork_digest(context.fork_version_for(fork), genesis_validators_root)?;
+                Ok((fork, digest))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { digests })
+    }
+
+    pub fn digest_for(&self, fork: Fork) -> ForkDigest {
+        self.digests
+            .iter()
+            .find_map(|(candidate, digest)| (*candidate == fork).then_some(*digest))
+            .expect("every fork has a digest")
+    }
+
+    pub fn fork_for(&self, fork_digest: &ForkDigest) -> Option<Fork> {
+        self.digests.iter().find_map(|(fork, digest)| (digest == fork_digest).then_some(*fork))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Status(Status),
+    Goodbye(Goodbye),
+    Ping(Ping),
+    MetaData,
+    BeaconBlocksByRange(BeaconBlocksByRangeRequest),
+    BeaconBlocksByRoot(BeaconBlocksByRootRequest),
+    BlobSidecarsByRange(BlobSidecarsByRangeRequest),
+    BlobSidecarsByRoot(BlobSidecarsByRootRequest),
+}
+
+impl Request {
+    pub fn protocol(&self) -> Protocol {
+        match self {
+            Self::Status(_) => Protocol::Status,
+            Self::Goodbye(_) => Protocol::Goodbye,
+            Self::Ping(_) => Protocol::Ping,
+            Self::MetaData => Protocol::MetaData,
+            Self::BeaconBlocksByRange(_) => Protocol::BeaconBlocksByRange,
+            Self::BeaconBlocksByRoot(_) => Protocol::BeaconBlocksByRoot,
+            Self::BlobSidecarsByRange(_) => Protocol::BlobSidecarsByRange,
+            Self::BlobSidecarsByRoot(_) => Protocol::BlobSidecarsByRoot,
+        }
+    }
+
+    /// Writes the payload of the request; `MetaData` requests have no payload. The requests of
+    /// every fork are within the phase0 `MAX_CHUNK_SIZE`.
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        match self {
+            Self::Status(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+            Self::Goodbye(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+            Self::Ping(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+            Self::MetaData => Ok(()),
+            Self::BeaconBlocksByRange(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+            Self::BeaconBlocksByRoot(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+            Self::BlobSidecarsByRange(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+            Self::BlobSidecarsByRoot(request) => write_ssz(writer, request, MAX_CHUNK_SIZE),
+        }
+    }
+
+    /// Reads a request for `protocol` received during `fork` and checks it against the request
+    /// limits of `fork`.
+    pub fn read<R: Read>(
+        reader: &mut R,
+        protocol: Protocol,
+        fork: Fork,
+        context: &Context,
+    ) -> Result<Self> {
+        let max_length = max_chunk_size(fork);
+        let request = match protocol {
+            Protocol::Status => Self::Status(read_ssz(reader, max_length)?),
+            Protocol::Goodbye => Self::Goodbye(read_ssz(reader, max_length)?),
+            Protocol::Ping => Self::Ping(read_ssz(reader, max_length)?),
+            Protocol::MetaData => Self::MetaData,
+            Protocol::BeaconBlocksByRange => {
+                Self::BeaconBlocksByRange(read_ssz(reader, max_length)?)
+            }
+            Protocol::BeaconBlocksByRoot => Self::BeaconBlocksByRoot(read_ssz(reader, max_length)?),
+            Protocol::BlobSidecarsByRange => {
+                Self::BlobSidecarsByRange(read_ssz(reader, max_length)?)
+            }
+            Protocol::BlobSidecarsByRoot => Self::BlobSidecarsByRoot(read_ssz(reader, max_length)?),
+        };
+        request.validate(fork, context)?;
+        Ok(request)
+    }
+
+    /// Checks the number of items the request asks for against the request limits of `fork`.
+    pub fn validate(&self, fork: Fork, context: &Context) -> Result<(), ReqRespError> {
+        let max_request_blocks =
+            (if fork >= Fork::Deneb { MAX_REQUEST_BLOCKS_DENEB } else { MAX_REQUEST_BLOCKS })
+                as u64;
+        let check_limit = |count: u64, limit: u64| {
+            if count > limit {
+                Err(ReqRespError::RequestTooLarge { count, limit })
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            Self::BeaconBlocksByRange(request) => {
+                if request.step == 0 {
+                    return Err(ReqRespError::InvalidStep(request.step))
+                }
+                check_limit(request.count, max_request_blocks)
+            }
+            Self::BeaconBlocksByRoot(request) => {
+                check_limit(request.len() as u64, max_request_blocks)
+            }
+            Self::BlobSidecarsByRange(request) => check_limit(
+                request.count.saturating_mul(context.max_blobs_per_block as u64),
+                MAX_REQUEST_BLOB_SIDECARS as u64,
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A chunk of a response stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseChunk {
+    Success { context_bytes: Option<ForkDigest>, ssz_bytes: Vec<u8> },
+    Error { code: ResponseCode, message: ErrorMessage },
+}
+
+impl ResponseChunk {
+    /// Decodes the payload of a successful chunk, or returns the error an error chunk carries.
+    pub fn decode<T: SimpleSerialize>(&self) -> Result<T> {
+        let (_, ssz_bytes) = self.success_payload()?;
+        decode_ssz(ssz_bytes)
+    }
+
+    /// Decodes the `SignedBeaconBlock` of the fork its context bytes belong to. Chunks without
+    /// context bytes, from the first version of the block protocols, carry phase0 blocks.
+    pub fn decode_signed_beacon_block<
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+    >(
+        &self,
+        fork_digests: &ForkDigests,
+    ) -> Result<
+        SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+    > {
+        let (context_bytes, ssz_bytes) = self.success_payload()?;
+        let fork = match context_bytes {
+            Some(fork_digest) => fork_digests
+                .fork_for(fork_digest)
+                .ok_or(ReqRespError::UnknownForkDigest(*fork_digest))?,
+            None => Fork::Phase0,
+        };
+        let block = match fork {
+            Fork::Phase0 => SignedBeaconBlock::Phase0(decode_ssz(ssz_bytes)?),
+            Fork::Altair => SignedBeaconBlock::Altair(decode_ssz(ssz_bytes)?),
+            Fork::Bellatrix => SignedBeaconBlock::Bellatrix(decode_ssz(ssz_bytes)?),
+            Fork::Capella => SignedBeaconBlock::Capella(decode_ssz(ssz_bytes)?),
+            Fork::Deneb => SignedBeaconBlock::Deneb(decode_ssz(ssz_bytes)?),
+        };
+        Ok(block)
+    }
+
+    fn success_payload(&self) -> Result<(Option<&ForkDigest>, &[u8]), ReqRespError> {
+        match self {
+            Self::Success { context_bytes, ssz_bytes } => Ok((context_bytes.as_ref(), ssz_bytes)),
+            Self::Error { code, message } => Err(ReqRespError::ErrorResponse {
+                code: (*code).into(),
+                message: String::from_utf8_lossy(message).into_owned(),
+            }),
+        }
+    }
+}
+
+/// Writes a successful response chunk carrying `response` of `fork` for `protocol`, with the
+/// digest of `fork` as its context bytes if the protocol uses them.
+pub fn write_response<W: Write, T: SimpleSerialize>(
+    writer: &mut W,
+    protocol: ProtocolId,
+    response: &T,
+    fork: Fork,
+    fork_digests: &ForkDigests,
+) -> Result<()> {
+    let ssz_bytes = encode_ssz(response)?;
+    write_success_chunk(writer, protocol, fork, fork_digests, &ssz_bytes)
+}
+
+/// Writes a successful response chunk carrying `block` for `protocol`, with the fork digest of the
+/// fork of `block` as its context bytes if the protocol uses them.
+pub fn write_signed_beacon_block<
+    W: Write,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    writer: &mut W,
+    protocol: ProtocolId,
+    block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    fork_digests: &ForkDigests,
+) -> Result<()> {
+    let ssz_bytes = match block {
+        SignedBeaconBlock::Phase0(inner) => encode_ssz(inner)?,
+        SignedBeaconBlock::Altair(inner) => encode_ssz(inner)?,
+        SignedBeaconBlock::Bellatrix(inner) => encode_ssz(inner)?,
+        SignedBeaconBlock::Capella(inner) => encode_ssz(inner)?,
+        SignedBeaconBlock::Deneb(inner) => encode_ssz(inner)?,
+    };
+    write_success_chunk(writer, protocol, block.version(), fork_digests, &ssz_bytes)
+}
+
+/// Writes an error response chunk with `code`, truncating `message` to the longest
+/// `ErrorMessage`.
+pub fn write_error_response<W: Write>(
+    writer: &mut W,
+    code: ResponseCode,
+    message: &str,
+) -> Result<()> {
+    let message = &message.as_bytes()[..message.len().min(MAX_ERROR_MESSAGE_LEN)];
+    let message =
+        ErrorMessage::try_from(message).map_err(|err| -> SimpleSerializeError { err.into() })?;
+    writer.write_all(&[u8::from(code)]).map_err(ReqRespError::from)?;
+    write_ssz(writer, &message, MAX_CHUNK_SIZE)
+}
+
+/// Reads the next chunk of a response stream for `protocol`, or `None` at the end of the stream.
+/// The payload of the chunk is limited to the maximum chunk size of `fork`, the fork of the chain
+/// when the request is made.
+pub fn read_response_chunk<R: Read>(
+    reader: &mut R,
+    protocol: ProtocolId,
+    fork: Fork,
+) -> Result<Option<ResponseChunk>> {
+    let max_length = max_chunk_size(fork);
+    let mut result = [0u8];
+    match reader.read_exact(&mut result) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(ReqRespError::from(err).into()),
+    }
+    let chunk = match ResponseCode::from(result[0]) {
+        ResponseCode::Success => {
+            let context_bytes = if protocol.has_context_bytes() {
+                let mut fork_digest = ForkDigest::default();
+                reader.read_exact(&mut fork_digest).map_err(|err| {
+                    if err.kind() == io::ErrorKind::UnexpectedEof {
+                        ReqRespError::MissingContextBytes
+                    } else {
+                        err.into()
+                    }
+                })?;
+                Some(fork_digest)
+            } else {
+                None
+            };
+            let ssz_bytes = read_payload(reader, max_length)?;
+            ResponseChunk::Success { context_bytes, ssz_bytes }
+        }
+        code => ResponseChunk::Error { code, message: read_ssz(reader, max_length)? },
+    };
+    Ok(Some(chunk))
+}
+
+/// The largest payload of a request or response chunk during `fork`.
+pub fn max_chunk_size(fork: Fork) -> usize {
+    if fork >= Fork::Bellatrix {
+        MAX_CHUNK_SIZE_BELLATRIX
+    } else {
+        MAX_CHUNK_SIZE
+    }
+}
+
+fn write_success_chunk<W: Write>(
+    writer: &mut W,
+    protocol: ProtocolId,
+    fork: Fork,
+    fork_digests: &ForkDigests,
+    ssz_bytes: &[u8],
+) -> Result<()> {
+    writer.write_all(&[u8::from(ResponseCode::Success)]).map_err(ReqRespError::from)?;
+    if protocol.has_context_bytes() {
+        writer.write_all(&fork_digests.digest_for(fork)).map_err(ReqRespError::from)?;
+    }
+    write_payload(writer, ssz_bytes, max_chunk_size(fork))?;
+    Ok(())
+}
+
+fn encode_ssz<T: SimpleSerialize>(value: &T) -> Result<Vec<u8>> {
+    let ssz_bytes = serialize(value).map_err(|err| -> SimpleSerializeError { err.into() })?;
+    Ok(ssz_bytes)
+}
+
+fn decode_ssz<T: SimpleSerialize>(ssz_bytes: &[u8]) -> Result<T> {
+    let value = T::deserialize(ssz_bytes).map_err(|err| -> SimpleSerializeError { err.into() })?;
+    Ok(value)
+}
+
+fn write_ssz<W: Write, T: SimpleSerialize>(
+    writer: &mut W,
+    value: &T,
+    max_length: usize,
+) -> Result<()> {
+    let ssz_bytes = encode_ssz(value)?;
+    write_payload(writer, &ssz_bytes, max_length)?;
+    Ok(())
+}
+
+fn read_ssz<R: Read, T: SimpleSerialize>(reader: &mut R, max_length: usize) -> Result<T> {
+    let ssz_bytes = read_payload(reader, max_length)?;
+    decode_ssz(&ssz_bytes)
+}
+
+// Writes the length prefix of `ssz_bytes` followed by `ssz_bytes` in snappy frames, if there are
+// no more than `max_length` of them.
+fn write_payload<W: Write>(
+    writer: &mut W,
+    ssz_bytes: &[u8],
+    max_length: usize,
+) -> Result<(), ReqRespError> {
+    let length = ssz_bytes.len();
+    if length > max_length {
+        return Err(ReqRespError::ChunkTooLarge { length, limit: max_length })
+    }
+    write_length_prefix(writer, length)?;
+    let mut encoder = snap::write::FrameEncoder::new(writer);
+    encoder.write_all(ssz_bytes)?;
+    encoder.flush()?;
+    Ok(())
+}
+
+// Reads a payload of at most `max_length` bytes, consuming no more snappy frames than the length
+// prefix allows for.
+fn read_payload<R: Read>(reader: &mut R, max_length: usize) -> Result<Vec<u8>, ReqRespError> {
+    let length = read_length_prefix(reader)?;
+    if length > max_length {
+        return Err(ReqRespError::ChunkTooLarge { length, limit: max_length })
+    }
+    let frames = reader.by_ref().take(max_encoded_len(length) as u64);
+    let mut decoder = snap::read::FrameDecoder::new(frames);
+    let mut ssz_bytes = vec![0u8; length];
+    decoder.read_exact(&mut ssz_bytes)?;
+    Ok(ssz_bytes)
+}
+
+fn write_length_prefix<W: Write>(writer: &mut W, mut length: usize) -> io::Result<()> {
+    let mut buffer = [0u8; MAX_VARINT_LEN];
+    let mut len = 0;
+    loop {
+        let byte = (length & 0x7f) as u8;
+        length >>= 7;
+        if length == 0 {
+            buffer[len] = byte;
+            len += 1;
+            break
+        }
+        buffer[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buffer[..len])
+}
+
+fn read_length_prefix<R: Read>(reader: &mut R) -> Result<usize, ReqRespError> {
+    let mut length = 0u64;
+    for i in 0..MAX_VARINT_LEN {
+        let mut byte = [0u8];
+        reader.read_exact(&mut byte)?;
+        let bits = (byte[0] & 0x7f) as u64;
+        // the last byte of a `u64` only has room for its most significant bit
+        if i == MAX_VARINT_LEN - 1 && bits > 1 {
+            return Err(ReqRespError::InvalidLengthPrefix)
+        }
+        length |= bits << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return usize::try_from(length).map_err(|_| ReqRespError::InvalidLengthPrefix)
+        }
+    }
+    Err(ReqRespError::InvalidLengthPrefix)
+}
+
+// The most bytes the snappy frames of `length` bytes of data can take.
+fn max_encoded_len(length: usize) -> usize {
+    let max_chunk_len = |len| SNAPPY_CHUNK_HEADER_LEN + snap::raw::max_compress_len(len);
+    let full_chunks = length / SNAPPY_MAX_BLOCK_SIZE;
+    let remainder = length % SNAPPY_MAX_BLOCK_SIZE;
+    let mut max_len =
+        SNAPPY_STREAM_IDENTIFIER_LEN + full_chunks * max_chunk_len(SNAPPY_MAX_BLOCK_SIZE);
+    if remainder > 0 {
+        max_len += max_chunk_len(remainder);
+    }
+    max_len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        altair::mainnet as altair, bellatrix::mainnet as bellatrix, phase0::mainnet as phase0,
+        types::mainnet,
+    };
+
+    #[test]
+    fn test_length_prefix() {
+        for length in [0, 1, 127, 128, 300, MAX_CHUNK_SIZE] {
+            let mut buffer = vec![];
+            write_length_prefix(&mut buffer, length).unwrap();
+            assert_eq!(read_length_prefix(&mut buffer.as_slice()).unwrap(), length);
+        }
+        let mut buffer = vec![];
+        write_length_prefix(&mut buffer, 300).unwrap();
+        assert_eq!(buffer, [0xac, 0x02]);
+
+        let too_long = [0xffu8; MAX_VARINT_LEN + 1];
+        assert!(matches!(
+            read_length_prefix(&mut too_long.as_slice()),
+            Err(ReqRespError::InvalidLengthPrefix)
+        ));
+
+        let mut oversized = vec![];
+        write_length_prefix(&mut oversized, MAX_CHUNK_SIZE + 1).unwrap();
+        oversized.extend_from_slice(&[0u8; 32]);
+        assert!(matches!(
+            read_payload(&mut oversized.as_slice(), MAX_CHUNK_SIZE),
+            Err(ReqRespError::ChunkTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        let context = Context::for_mainnet();
+        let status = Status { head_slot: 42, finalized_epoch: 1, ..Default::default() };
+        let requests = [
+            Request::Status(status),
+            Request::Goodbye(crate::phase0::networking::GOODBYE_CLIENT_SHUTDOWN),
+            Request::Ping(7),
+            Request::MetaData,
+            Request::BeaconBlocksByRange(BeaconBlocksByRangeRequest {
+                start_slot: 64,
+                count: 32,
+                step: 1,
+            }),
+            Request::BeaconBlocksByRoot(
+                BeaconBlocksByRootRequest::try_from(vec![Root::default(); 3]).unwrap(),
+            ),
+            Request::BlobSidecarsByRange(BlobSidecarsByRangeRequest { start_slot: 64, count: 16 }),
+        ];
+        for request in requests {
+            let mut buffer = vec![];
+            request.write(&mut buffer).unwrap();
+            let mut reader = buffer.as_slice();
+            let decoded =
+                Request::read(&mut reader, request.protocol(), Fork::Deneb, &context).unwrap();
+            assert_eq!(decoded, request);
+            assert!(reader.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_request_limits() {
+        let context = Context::for_mainnet();
+        let request = Request::BeaconBlocksByRange(BeaconBlocksByRangeRequest {
+            start_slot: 0,
+            count: MAX_REQUEST_BLOCKS_DENEB as u64 + 1,
+            step: 1,
+        });
+        let mut buffer = vec![];
+        request.write(&mut buffer).unwrap();
+        let protocol = request.protocol();
+        assert!(Request::read(&mut buffer.as_slice(), protocol, Fork::Capella, &context).is_ok());
+        assert!(matches!(
+            Request::read(&mut buffer.as_slice(), protocol, Fork::Deneb, &context),
+            Err(crate::Error::ReqResp(ReqRespError::RequestTooLarge { .. }))
+        ));
+
+        let request = Request::BlobSidecarsByRange(BlobSidecarsByRangeRequest {
+            start_slot: 0,
+            count: MAX_REQUEST_BLOCKS_DENEB as u64 + 1,
+        });
+        assert!(matches!(
+            request.validate(Fork::Deneb, &context),
+            Err(ReqRespError::RequestTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_response_stream() {
+        let context = Context::for_mainnet();
+        let fork_digests = ForkDigests::new(Root::default(), &context).unwrap();
+        let protocol = ProtocolId::new(Protocol::BeaconBlocksByRange, 2);
+        assert_eq!(
+            protocol.to_string(),
+            "/eth2/beacon_chain/req/beacon_blocks_by_range/2/ssz_snappy"
+        );
+
+        let mut phase0_block = phase0::SignedBeaconBlock::default();
+        phase0_block.message.slot = 1;
+        let phase0_block = mainnet::SignedBeaconBlock::Phase0(phase0_block);
+        let mut altair_block = altair::SignedBeaconBlock::default();
+        altair_block.message.slot = 2;
+        let altair_block = mainnet::SignedBeaconBlock::Altair(altair_block);
+
+        let mut buffer = vec![];
+        write_signed_beacon_block(&mut buffer, protocol, &phase0_block, &fork_digests).unwrap();
+        write_signed_beacon_block(&mut buffer, protocol, &altair_block, &fork_digests).unwrap();
+        write_error_response(&mut buffer, ResponseCode::ResourceUnavailable, "no more blocks")
+            .unwrap();
+
+        let mut reader = buffer.as_slice();
+        let chunk = read_response_chunk(&mut reader, protocol, Fork::Altair).unwrap().unwrap();
+        let block: mainnet::SignedBeaconBlock =
+            chunk.decode_signed_beacon_block(&fork_digests).unwrap();
+        assert_eq!(block, phase0_block);
+        let chunk = read_response_chunk(&mut reader, protocol, Fork::Altair).unwrap().unwrap();
+        let block: mainnet::SignedBeaconBlock =
+            chunk.decode_signed_beacon_block(&fork_digests).unwrap();
+        assert_eq!(block, altair_block);
+        let chunk = read_response_chunk(&mut reader, protocol, Fork::Altair).unwrap().unwrap();
+        assert!(matches!(
+            chunk.decode::<phase0::SignedBeaconBlock>(),
+            Err(crate::Error::ReqResp(ReqRespError::ErrorResponse { code: 3, .. }))
+        ));
+        assert!(read_response_chunk(&mut reader, protocol, Fork::Altair).unwrap().is_none());
+
+        // context bytes of an unknown fork
+        let unknown = ResponseChunk::Success {
+            context_bytes: Some([0xff; 4]),
+            ssz_bytes: encode_ssz(phase0_block.phase0().unwrap()).unwrap(),
+        };
+        let block: Result<mainnet::SignedBeaconBlock> =
+            unknown.decode_signed_beacon_block(&fork_digests);
+        assert!(matches!(block, Err(crate::Error::ReqResp(ReqRespError::UnknownForkDigest(_)))));
+    }
+
+    #[test]
+    fn test_response_without_context_bytes() {
+        let context = Context::for_mainnet();
+        let fork_digests = ForkDigests::new(Root::default(), &context).unwrap();
+        let protocol = ProtocolId::new(Protocol::Ping, 1);
+        let mut buffer = vec![];
+        write_response(&mut buffer, protocol, &12u64, Fork::Altair, &fork_digests).unwrap();
+        let mut reader = buffer.as_slice();
+        let chunk = read_response_chunk(&mut reader, protocol, Fork::Altair).unwrap().unwrap();
+        assert_eq!(
+            chunk,
+            ResponseChunk::Success { context_bytes: None, ssz_bytes: encode_ssz(&12u64).unwrap() }
+        );
+        assert_eq!(chunk.decode::<Ping>().unwrap(), 12);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_chunk_size_limits() {
+        let context = Context::for_mainnet();
+        let fork_digests = ForkDigests::new(Root::default(), &context).unwrap();
+        let protocol = ProtocolId::new(Protocol::BeaconBlocksByRoot, 2);
+        assert_eq!(max_chunk_size(Fork::Altair), MAX_CHUNK_SIZE);
+        assert_eq!(max_chunk_size(Fork::Bellatrix), MAX_CHUNK_SIZE_BELLATRIX);
+
+        // a block between the phase0 and the bellatrix limits
+        let mut inner = bellatrix::SignedBeaconBlock::default();
+        let transaction = bellatrix::Transaction::try_from(vec![1u8; 3 * MAX_CHUNK_SIZE]).unwrap();
+        inner.message.body.execution_payload.transactions.push(transaction);
+        let block = mainnet::SignedBeaconBlock::Bellatrix(inner);
+        let mut buffer = vec![];
+        write_signed_beacon_block(&mut buffer, protocol, &block, &fork_digests).unwrap();
+
+        let chunk = read_response_chunk(&mut buffer.as_slice(), protocol, Fork::Bellatrix)
+            .unwrap()
+            .unwrap();
+        let decoded: mainnet::SignedBeaconBlock =
+            chunk.decode_signed_beacon_block(&fork_digests).unwrap();
+        assert_eq!(decoded, block);
+        assert!(matches!(
+            read_response_chunk(&mut buffer.as_slice(), protocol, Fork::Altair),
+            Err(crate::Error::ReqResp(ReqRespError::ChunkTooLarge { .. }))
+        ));
+
+        let payload =
+            List::<u8, { 4 * MAX_CHUNK_SIZE }>::try_from(vec![0u8; 2 * MAX_CHUNK_SIZE]).unwrap();
+        let mut buffer = vec![];
+        assert!(matches!(
+            write_response(&mut buffer, protocol, &payload, Fork::Altair, &fork_digests),
+            Err(crate::Error::ReqResp(ReqRespError::ChunkTooLarge { .. }))
+        ));
+        write_response(&mut buffer, protocol, &payload, Fork::Deneb, &fork_digests).unwrap();
+        let chunk =
+            read_response_chunk(&mut buffer.as_slice(), protocol, Fork::Deneb).unwrap().unwrap();
+        assert_eq!(chunk.decode::<List<u8, { 4 * MAX_CHUNK_SIZE }>>().unwrap(), payload);
+    }
+}
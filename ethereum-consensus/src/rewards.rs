@@ -0,0 +1,510 @@
+//! Attribution of the rewards and penalties applied during state transitions, in the shape served
+//! by the Beacon API (`/eth/v1/beacon/rewards/*`).
+use crate::{
+    altair, bellatrix, capella, deneb, phase0,
+    primitives::{Gwei, ValidatorIndex},
+    state_transition::Context,
+    types::{BeaconState, SignedBeaconBlock},
+    Error,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IdealAttestationRewards {
+    #[serde(with = "crate::serde::as_str")]
+    pub effective_balance: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub head: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub target: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub source: Gwei,
+    // Only present before Altair
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "optional_str")]
+    pub inclusion_delay: Option<Gwei>,
+    #[serde(with = "crate::serde::as_str")]
+    pub inactivity: Gwei,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TotalAttestationRewards {
+    #[serde(with = "crate::serde::as_str")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "crate::serde::as_str")]
+    pub head: i64,
+    #[serde(with = "crate::serde::as_str")]
+    pub target: i64,
+    #[serde(with = "crate::serde::as_str")]
+    pub source: i64,
+    // Only present before Altair; also carries any proposer micro-rewards the validator earned
+    // for including attestations, as in `get_inclusion_delay_deltas`
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "optional_str")]
+    pub inclusion_delay: Option<Gwei>,
+    #[serde(with = "crate::serde::as_str")]
+    pub inactivity: i64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AttestationRewards {
+    // The rewards of a validator with perfect participation, for each effective balance
+    pub ideal_rewards: Vec<IdealAttestationRewards>,
+    pub total_rewards: Vec<TotalAttestationRewards>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BlockRewards {
+    #[serde(with = "crate::serde::as_str")]
+    pub proposer_index: ValidatorIndex,
+    #[serde(with = "crate::serde::as_str")]
+    pub total: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub attestations: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub sync_aggregate: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub proposer_slashings: Gwei,
+    #[serde(with = "crate::serde::as_str")]
+    pub attester_slashings: Gwei,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SyncCommitteeReward {
+    #[serde(with = "crate::serde::as_str")]
+    pub validator_index: ValidatorIndex,
+    #[serde(with = "crate::serde::as_str")]
+    pub reward: i64,
+}
+
+/// Computes the attestation rewards and penalties applied by the epoch transition at the end of
+/// the epoch of `state`, i.e. those for the attestations made in the epoch preceding `state`.
+///
+/// `state` is advanced to the last slot of its epoch, after which the parts of the epoch
+/// transition that precede `process_rewards_and_penalties` are run. The `total_rewards` are
+/// returned for each of `validator_indices` in the order of the registry, or for every validator
+/// if `validator_indices` is empty.
+pub fn compute_attestation_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    validator_indices: &[ValidatorIndex],
+    context: &Context,
+) -> Result<AttestationRewards, Error> {
+    let validator_count = state.validators().len();
+    if let Some(&index) = validator_indices.iter().find(|&&index| index >= validator_count) {
+        return Err(Error::OutOfBounds { requested: index, bound: validator_count })
+    }
+
+    let last_slot_in_epoch =
+        (state.slot() / context.slots_per_epoch + 1) * context.slots_per_epoch - 1;
+    let mut state = state.clone();
+    let mut rewards = match &mut state {
+        BeaconState::Phase0(state) => {
+            if state.slot < last_slot_in_epoch {
+                phase0::process_slots(state, last_slot_in_epoch, &mut (), context)?;
+            }
+            phase0::process_justification_and_finalization(state, &mut (), context)?;
+            phase0::get_attestation_rewards(state, context)?
+        }
+        BeaconState::Altair(state) => {
+            if state.slot < last_slot_in_epoch {
+                altair::process_slots(state, last_slot_in_epoch, &mut (), context)?;
+            }
+            altair::process_justification_and_finalization(state, &mut (), context)?;
+            altair::process_inactivity_updates(state, context)?;
+            altair::get_attestation_rewards(state, context)?
+        }
+        BeaconState::Bellatrix(state) => {
+            if state.slot < last_slot_in_epoch {
+                bellatrix::process_slots(state, last_slot_in_epoch, &mut (), context)?;
+            }
+            bellatrix::process_justification_and_finalization(state, &mut (), context)?;
+            bellatrix::process_inactivity_updates(state, context)?;
+            bellatrix::get_attestation_rewards(state, context)?
+        }
+        BeaconState::Capella(state) => {
+            if state.slot < last_slot_in_epoch {
+                capella::process_slots(state, last_slot_in_epoch, &mut (), context)?;
+            }
+            capella::process_justification_and_finalization(state, &mut (), context)?;
+            capella::process_inactivity_updates(state, context)?;
+            capella::get_attestation_rewards(state, context)?
+        }
+        BeaconState::Deneb(state) => {
+            if state.slot < last_slot_in_epoch {
+                deneb::process_slots(state, last_slot_in_epoch, &mut (), context)?;
+            }
+            deneb::process_justification_and_finalization(state, &mut (), context)?;
+            deneb::process_inactivity_updates(state, context)?;
+            deneb::get_attestation_rewards(state, context)?
+        }
+    };
+
+    if !validator_indices.is_empty() {
+        rewards.total_rewards.retain(|entry| validator_indices.contains(&entry.validator_index));
+    }
+    Ok(rewards)
+}
+
+/// Computes the rewards paid to the proposer of `signed_block` split by the operations that pay
+/// them, along with the rewards and penalties of each sync committee member for its participation
+/// in the block's `SyncAggregate` (which is empty before Altair).
+///
+/// `state` is advanced to the slot of the block and must already be of the block's fork. The
+/// block is assumed to be valid so signatures are not verified. Before Altair, proposers are paid
+/// for including attestations at the end of the epoch; this is estimated with `state`.
+pub fn compute_block_rewards<
+    const SLOTS_PER_HISTORICAL_ROOT: usize,
+    const HISTORICAL_ROOTS_LIMIT: usize,
+    const ETH1_DATA_VOTES_BOUND: usize,
+    const VALIDATOR_REGISTRY_LIMIT: usize,
+    const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+    const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+    const MAX_VALIDATORS_PER_COMMITTEE: usize,
+    const PENDING_ATTESTATIONS_BOUND: usize,
+    const SYNC_COMMITTEE_SIZE: usize,
+    const BYTES_PER_LOGS_BLOOM: usize,
+    const MAX_EXTRA_DATA_BYTES: usize,
+    const MAX_PROPOSER_SLASHINGS: usize,
+    const MAX_ATTESTER_SLASHINGS: usize,
+    const MAX_ATTESTATIONS: usize,
+    const MAX_DEPOSITS: usize,
+    const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_BYTES_PER_TRANSACTION: usize,
+    const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+>(
+    state: &BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >,
+    signed_block: &SignedBeaconBlock<
+        MAX_PROPOSER_SLASHINGS,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        MAX_ATTESTER_SLASHINGS,
+        MAX_ATTESTATIONS,
+        MAX_DEPOSITS,
+        MAX_VOLUNTARY_EXITS,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
+    >,
+    context: &Context,
+) -> Result<(BlockRewards, Vec<SyncCommitteeReward>), Error> {
+    let mut state = state.clone();
+    let mut signed_block = signed_block.clone();
+    match (&mut state, &mut signed_block) {
+        (BeaconState::Phase0(state), SignedBeaconBlock::Phase0(signed_block)) => {
+            let block = &mut signed_block.message;
+            if state.slot < block.slot {
+                phase0::process_slots(state, block.slot, &mut (), context)?;
+            }
+            phase0::get_block_rewards(state, block, context)
+        }
+        (BeaconState::Altair(state), SignedBeaconBlock::Altair(signed_block)) => {
+            let block = &mut signed_block.message;
+            if state.slot < block.slot {
+                altair::process_slots(state, block.slot, &mut (), context)?;
+            }
+            altair::get_block_rewards(state, block, context)
+        }
+        (BeaconState::Bellatrix(state), SignedBeaconBlock::Bellatrix(signed_block)) => {
+            let block = &mut signed_block.message;
+            if state.slot < block.slot {
+                bellatrix::process_slots(state, block.slot, &mut (), context)?;
+            }
+            bellatrix::get_block_rewards(state, block, context)
+        }
+        (BeaconState::Capella(state), SignedBeaconBlock::Capella(signed_block)) => {
+            let block = &mut signed_block.message;
+            if state.slot < block.slot {
+                capella::process_slots(state, block.slot, &mut (), context)?;
+            }
+            capella::get_block_rewards(state, block, context)
+        }
+        (BeaconState::Deneb(state), SignedBeaconBlock::Deneb(signed_block)) => {
+            let block = &mut signed_block.message;
+            if state.slot < block.slot {
+                deneb::process_slots(state, block.slot, &mut (), context)?;
+            }
+            deneb::get_block_rewards(state, block, context)
+        }
+        (state, signed_block) => Err(Error::IncompatibleFork {
+            source_fork: state.version(),
+            destination_fork: signed_block.version(),
+        }),
+    }
+}
+
+mod optional_str {
+    use crate::primitives::Gwei;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Gwei>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Gwei>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| value.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{harness::minimal::Harness, Fork};
+
+    fn context_at_genesis(fork: Fork) -> Context {
+        let mut context = Context::for_minimal();
+        if fork >= Fork::Altair {
+            context.altair_fork_epoch = 0;
+        }
+        if fork >= Fork::Bellatrix {
+            context.bellatrix_fork_epoch = 0;
+        }
+        if fork >= Fork::Capella {
+            context.capella_fork_epoch = 0;
+        }
+        if fork >= Fork::Deneb {
+            context.deneb_fork_epoch = 0;
+        }
+        context
+    }
+
+    #[test]
+    fn test_serde_matches_beacon_api() {
+        let rewards = AttestationRewards {
+            ideal_rewards: vec![IdealAttestationRewards {
+                effective_balance: 1_000_000_000,
+                head: 2500,
+                target: 5000,
+                source: 5000,
+                inclusion_delay: None,
+                inactivity: 0,
+            }],
+            total_rewards: vec![TotalAttestationRewards {
+                validator_index: 0,
+                head: 2000,
+                target: 2000,
+                source: 4000,
+                inclusion_delay: None,
+                inactivity: -1000,
+            }],
+        };
+        let expected = r#"{"ideal_rewards":[{"effective_balance":"1000000000","head":"2500","target":"5000","source":"5000","inactivity":"0"}],"total_rewards":[{"validator_index":"0","head":"2000","target":"2000","source":"4000","inactivity":"-1000"}]}"#;
+        let json = serde_json::to_string(&rewards).unwrap();
+        assert_eq!(json, expected);
+        let recovered: AttestationRewards = serde_json::from_str(&json).unwrap();
+        assert_eq!(recovered, rewards);
+
+        let phase0_rewards =
+            TotalAttestationRewards { inclusion_delay: Some(300), ..Default::default() };
+        let json = serde_json::to_string(&phase0_rewards).unwrap();
+        assert!(json.contains(r#""inclusion_delay":"300""#));
+        let recovered: TotalAttestationRewards = serde_json::from_str(&json).unwrap();
+        assert_eq!(recovered, phase0_rewards);
+    }
+    #[test]
+    fn test_block_rewards_serde_matches_beacon_api() {
+        let rewards = BlockRewards {
+            proposer_index: 123,
+            total: 123,
+            attestations: 100,
+            sync_aggregate: 20,
+            proposer_slashings: 2,
+            attester_slashings: 1,
+        };
+        let expected = r#"{"proposer_index":"123","total":"123","attestations":"100","sync_aggregate":"20","proposer_slashings":"2","attester_slashings":"1"}"#;
+        assert_eq!(serde_json::to_string(&rewards).unwrap(), expected);
+
+        let reward = SyncCommitteeReward { validator_index: 1, reward: -2000 };
+        let expected = r#"{"validator_index":"1","reward":"-2000"}"#;
+        assert_eq!(serde_json::to_string(&reward).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_attestation_rewards_match_balance_changes() {
+        for fork in [Fork::Phase0, Fork::Altair, Fork::Bellatrix, Fork::Capella, Fork::Deneb] {
+            let mut harness = Harness::new(64, context_at_genesis(fork)).unwrap();
+            harness.extend_chain(23).unwrap();
+            let slot = 4 * harness.context().slots_per_epoch - 1;
+            let mut state = harness.advance(harness.head_root(), slot).unwrap();
+            // slashed validators are penalized as if they had not attested
+            for index in (0..state.validators().len()).step_by(5) {
+                state.validators_mut()[index].slashed = true;
+            }
+
+            // without finality for longer than allowed the chain is in an inactivity leak
+            let mut leaking_context = harness.context().clone();
+            leaking_context.min_epochs_to_inactivity_penalty = 0;
+            for context in [harness.context().clone(), leaking_context] {
+                let rewards = compute_attestation_rewards(&state, &[], &context).unwrap();
+
+                let mut post_state = state.clone();
+                let pre_balances = match &mut post_state {
+                    BeaconState::Phase0(state) => {
+                        phase0::process_justification_and_finalization(state, &mut (), &context)
+                            .unwrap();
+                        let balances = state.balances.clone();
+                        phase0::process_rewards_and_penalties(state, &context).unwrap();
+                        balances
+                    }
+                    BeaconState::Altair(state) => {
+                        altair::process_justification_and_finalization(state, &mut (), &context)
+                            .unwrap();
+                        altair::process_inactivity_updates(state, &context).unwrap();
+                        let balances = state.balances.clone();
+                        altair::process_rewards_and_penalties(state, &context).unwrap();
+                        balances
+                    }
+                    BeaconState::Bellatrix(state) => {
+                        bellatrix::process_justification_and_finalization(state, &mut (), &context)
+                            .unwrap();
+                        bellatrix::process_inactivity_updates(state, &context).unwrap();
+                        let balances = state.balances.clone();
+                        bellatrix::process_rewards_and_penalties(state, &context).unwrap();
+                        balances
+                    }
+                    BeaconState::Capella(state) => {
+                        capella::process_justification_and_finalization(state, &mut (), &context)
+                            .unwrap();
+                        capella::process_inactivity_updates(state, &context).unwrap();
+                        let balances = state.balances.clone();
+                        capella::process_rewards_and_penalties(state, &context).unwrap();
+                        balances
+                    }
+                    BeaconState::Deneb(state) => {
+                        deneb::process_justification_and_finalization(state, &mut (), &context)
+                            .unwrap();
+                        deneb::process_inactivity_updates(state, &context).unwrap();
+                        let balances = state.balances.clone();
+                        deneb::process_rewards_and_penalties(state, &context).unwrap();
+                        balances
+                    }
+                };
+
+                assert_eq!(rewards.total_rewards.len(), pre_balances.len());
+                let mut penalized = false;
+                for (index, reward) in rewards.total_rewards.iter().enumerate() {
+                    assert_eq!(reward.validator_index, index);
+                    let total = reward.head +
+                        reward.target +
+                        reward.source +
+                        reward.inactivity +
+                        reward.inclusion_delay.unwrap_or_default() as i64;
+                    let delta = post_state.balances()[index] as i64 - pre_balances[index] as i64;
+                    assert_eq!(total, delta, "{fork:?} validator {index}");
+                    penalized |= total < 0;
+                }
+                assert!(penalized, "{fork:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_block_rewards_match_proposer_balance_change() {
+        for fork in [Fork::Altair, Fork::Bellatrix, Fork::Capella, Fork::Deneb] {
+            let mut harness = Harness::new(64, context_at_genesis(fork)).unwrap();
+            let context = harness.context().clone();
+            // the deposit is included by the block completing the eth1 vote for it
+            harness.add_deposit(context.max_effective_balance).unwrap();
+            let votes = context.epochs_per_eth1_voting_period * context.slots_per_epoch / 2;
+            harness.extend_chain(votes as usize).unwrap();
+            let parent_root = harness.head_root();
+            let slot = harness.head_state().slot() + 1;
+
+            let proposer_index =
+                harness.produce_block(parent_root, slot).unwrap().message().proposer_index();
+            let validator_count = harness.head_state().validators().len();
+            let proposer_slashing = harness
+                .make_proposer_slashing((proposer_index + 1) % validator_count, slot)
+                .unwrap();
+            let attester_slashing = harness
+                .make_attester_slashing(&[(proposer_index + 2) % validator_count], 0)
+                .unwrap();
+            harness.pool.insert_proposer_slashing(proposer_slashing);
+            harness.pool.insert_attester_slashing(attester_slashing);
+            let signed_block = harness.produce_block(parent_root, slot).unwrap();
+            let body = signed_block.message().body();
+            assert_eq!(body.proposer_slashings().len(), 1);
+            assert_eq!(body.attester_slashings().len(), 1);
+            assert_eq!(body.deposits().len(), 1);
+
+            let state = harness.advance(parent_root, slot).unwrap();
+            let (rewards, sync_committee_rewards) =
+                compute_block_rewards(&state, &signed_block, &context).unwrap();
+            assert_eq!(rewards.proposer_index, proposer_index);
+            assert!(rewards.proposer_slashings > 0);
+            assert!(rewards.attester_slashings > 0);
+            assert!(rewards.attestations > 0);
+            assert!(rewards.sync_aggregate > 0);
+
+            let root = harness.apply_block(signed_block).unwrap();
+            let post_state = harness.state(&root).unwrap();
+            let mut delta = post_state.balances()[proposer_index] as i64 -
+                state.balances()[proposer_index] as i64;
+            // the proposer may also be paid or penalized as a member of the sync committee
+            if let Some(reward) = sync_committee_rewards
+                .iter()
+                .find(|reward| reward.validator_index == proposer_index)
+            {
+                delta -= reward.reward;
+            }
+            assert_eq!(rewards.total as i64, delta, "{fork:?}");
+            for reward in sync_committee_rewards.iter().filter(|reward| {
+                reward.validator_index != proposer_index &&
+                    !post_state.validators()[reward.validator_index].slashed
+            }) {
+                let index = reward.validator_index;
+                let delta = post_state.balances()[index] as i64 - state.balances()[index] as i64;
+                assert_eq!(reward.reward, delta, "{fork:?} validator {index}");
+            }
+        }
+    }
+}
@@ -1,7 +1,17 @@
 mod byte_list;
 mod byte_vector;
+pub mod proofs;
 
 pub mod prelude {
-    pub use super::{byte_list::ByteList, byte_vector::ByteVector};
+    pub use super::{
+        byte_list::ByteList,
+        byte_vector::ByteVector,
+        proofs::{
+            compute_merkle_multiproof, compute_merkle_proof, get_generalized_index,
+            verify_merkle_multiproof, verify_merkle_proof, GeneralizedIndex, MerkleTree,
+            PathElement, Prove,
+        },
+    };
+    pub use ethereum_consensus_derive::Prove;
     pub use ssz_rs::prelude::*;
 }
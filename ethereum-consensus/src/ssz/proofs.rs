@@ -0,0 +1,771 @@
+//! Merkle proofs against the `hash_tree_root` of SSZ types.
+//!
+//! Proofs are addressed by "generalized index" as defined in the consensus specs'
+//! `ssz/merkle-proofs.md`: the root of a type has index `1` and the children of the node at index
+//! `i` are `2i` and `2i + 1`.
+use crate::{
+    crypto::hash,
+    primitives::Version,
+    ssz::prelude::{
+        Bitlist, Bitvector, ByteList, ByteVector, List, MerkleizationError, Merkleized, Node,
+        Vector, U256,
+    },
+    Error,
+};
+use std::collections::{HashMap, HashSet};
+
+pub type GeneralizedIndex = usize;
+
+const BYTES_PER_CHUNK: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathElement<'a> {
+    // A field of a container, by name
+    Field(&'a str),
+    // An element of a list or vector, by position
+    Index(usize),
+    // The length mixed into the root of a list
+    Length,
+}
+
+impl<'a> From<&'a str> for PathElement<'a> {
+    fn from(name: &'a str) -> Self {
+        Self::Field(name)
+    }
+}
+
+impl<'a> From<usize> for PathElement<'a> {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+// `Prove` exposes the Merkle tree of a type so that proofs can be computed for it.
+pub trait Prove {
+    // The size in bytes of this type if it is a basic type, whose values are packed into chunks
+    // when part of a vector or list.
+    fn basic_size() -> Option<usize>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    // The number of chunks forming the leaves of this type's Merkle tree, before padding.
+    // For lists, this follows from the limit rather than the current length.
+    fn chunk_count(&self) -> usize;
+
+    // The roots of the chunks currently in this type's Merkle tree, in order.
+    // For containers, this is the `hash_tree_root` of each field.
+    fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError>;
+
+    // The length mixed into the root of this type, if it is a list.
+    fn length(&self) -> Option<usize> {
+        None
+    }
+
+    // The index of the chunk holding `element`, if `element` is part of this type.
+    fn chunk_index(&self, _element: &PathElement) -> Option<usize> {
+        None
+    }
+
+    // The object whose root is the chunk at `index`, if it supports proofs into it.
+    fn child(&mut self, _index: usize) -> Option<&mut dyn Prove> {
+        None
+    }
+}
+
+pub fn generalized_index_sibling(index: GeneralizedIndex) -> GeneralizedIndex {
+    index ^ 1
+}
+
+pub fn generalized_index_child(index: GeneralizedIndex, right_side: bool) -> GeneralizedIndex {
+    index * 2 + right_side as usize
+}
+
+pub fn generalized_index_parent(index: GeneralizedIndex) -> GeneralizedIndex {
+    index / 2
+}
+
+pub fn get_generalized_index_length(index: GeneralizedIndex) -> usize {
+    index.ilog2() as usize
+}
+
+pub fn get_generalized_index_bit(index: GeneralizedIndex, position: usize) -> bool {
+    index & (1 << position) > 0
+}
+
+pub fn concat_generalized_indices(indices: &[GeneralizedIndex]) -> GeneralizedIndex {
+    let mut result = 1;
+    for &index in indices {
+        let length = get_generalized_index_length(index);
+        result = result * (1 << length) + (index - (1 << length));
+    }
+    result
+}
+
+// Returns the generalized index of the node reached by following `path` from the root of `object`.
+pub fn get_generalized_index<T: Prove + ?Sized>(
+    object: &mut T,
+    path: &[PathElement],
+) -> Result<GeneralizedIndex, Error> {
+    let Some((element, rest)) = path.split_first() else { return Ok(1) };
+
+    let is_list = object.length().is_some();
+    if let PathElement::Length = element {
+        if !is_list || !rest.is_empty() {
+            return Err(Error::InvalidPathElement(format!("{element:?}")))
+        }
+        return Ok(3)
+    }
+
+    let chunk_index = object
+        .chunk_index(element)
+        .ok_or_else(|| Error::InvalidPathElement(format!("{element:?}")))?;
+    let depth = get_depth(object.chunk_count());
+    let root = if is_list { 2 } else { 1 };
+    let index = root * (1 << depth) + chunk_index;
+    if rest.is_empty() {
+        return Ok(index)
+    }
+
+    let child = object
+        .child(chunk_index)
+        .ok_or_else(|| Error::InvalidPathElement(format!("{element:?}")))?;
+    let child_index = get_generalized_index(child, rest)?;
+    Ok(concat_generalized_indices(&[index, child_index]))
+}
+
+// Returns the generalized indices of the sister chunks along the path from `index` to the root.
+pub fn get_branch_indices(index: GeneralizedIndex) -> Vec<GeneralizedIndex> {
+    let mut indices = vec![generalized_index_sibling(index)];
+    while *indices.last().expect("not empty") > 1 {
+        let last = *indices.last().expect("not empty");
+        indices.push(generalized_index_sibling(generalized_index_parent(last)));
+    }
+    indices.pop();
+    indices
+}
+
+// Returns the generalized indices of the chunks along the path from `index` to the root.
+pub fn get_path_indices(index: GeneralizedIndex) -> Vec<GeneralizedIndex> {
+    let mut indices = vec![index];
+    while *indices.last().expect("not empty") > 1 {
+        let last = *indices.last().expect("not empty");
+        indices.push(generalized_index_parent(last));
+    }
+    indices.pop();
+    indices
+}
+
+// Returns the generalized indices of the auxiliary chunks needed to prove all of `indices`,
+// in decreasing order.
+pub fn get_helper_indices(indices: &[GeneralizedIndex]) -> Vec<GeneralizedIndex> {
+    let mut helper_indices = HashSet::new();
+    let mut path_indices = HashSet::new();
+    for &index in indices {
+        helper_indices.extend(get_branch_indices(index));
+        path_indices.extend(get_path_indices(index));
+    }
+    let mut helper_indices = helper_indices.difference(&path_indices).copied().collect::<Vec<_>>();
+    helper_indices.sort_unstable_by(|a, b| b.cmp(a));
+    helper_indices
+}
+
+fn hash_pair(left: &Node, right: &Node) -> Node {
+    let mut data = [0u8; 2 * BYTES_PER_CHUNK];
+    data[..BYTES_PER_CHUNK].copy_from_slice(left.as_ref());
+    data[BYTES_PER_CHUNK..].copy_from_slice(right.as_ref());
+    let root = hash(data);
+    Node::try_from(root.as_ref()).expect("is valid instance")
+}
+
+fn length_node(length: usize) -> Node {
+    let mut node = [0u8; BYTES_PER_CHUNK];
+    node[..8].copy_from_slice(&(length as u64).to_le_bytes());
+    Node::try_from(node.as_ref()).expect("is valid instance")
+}
+
+fn get_depth(chunk_count: usize) -> usize {
+    chunk_count.next_power_of_two().trailing_zeros() as usize
+}
+
+// Returns the roots of empty subtrees of each height up to and including `depth`.
+fn zero_hashes(depth: usize) -> Vec<Node> {
+    let mut hashes = vec![Node::default()];
+    for height in 0..depth {
+        hashes.push(hash_pair(&hashes[height], &hashes[height]));
+    }
+    hashes
+}
+
+// Returns each layer of the tree of the given `depth` over `leaves`, from the leaves to the root.
+// Layers only hold the nodes over `leaves`; the rest are the roots of empty subtrees.
+fn compute_layers(leaves: &[Node], depth: usize, zero_hashes: &[Node]) -> Vec<Vec<Node>> {
+    let mut layers = vec![leaves.to_vec()];
+    for zero_hash in zero_hashes.iter().take(depth) {
+        let layer = layers
+            .last()
+            .expect("not empty")
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(zero_hash)))
+            .collect();
+        layers.push(layer);
+    }
+    layers
+}
+
+// The Merkle tree of an object implementing `Prove`, built once so that any number of nodes and
+// proofs can be read from it.
+//
+// Only the trees of the chunks holding the indices given to `MerkleTree::new` are built, so nodes
+// can only be read along the paths to those indices.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    depth: usize,
+    // The length mixed into the root, if the object is a list
+    length: Option<usize>,
+    zero_hashes: Vec<Node>,
+    layers: Vec<Vec<Node>>,
+    // The trees of the chunks holding any of the indices the tree was built for, by chunk index
+    children: HashMap<usize, MerkleTree>,
+}
+
+// Where the node at some generalized index lives in a `MerkleTree`.
+enum Location {
+    // The node is part of the tree itself
+    Node(Node),
+    // The node is at the given index in the tree of the chunk at the given position
+    Chunk(usize, GeneralizedIndex),
+}
+
+impl MerkleTree {
+    // Builds the tree of `object`, along with the trees of the chunks holding any of `indices`.
+    pub fn new<T: Prove + ?Sized>(
+        object: &mut T,
+        indices: &[GeneralizedIndex],
+    ) -> Result<Self, Error> {
+        let chunks = object.chunks()?;
+        let depth = get_depth(object.chunk_count());
+        let zero_hashes = zero_hashes(depth);
+        let layers = compute_layers(&chunks, depth, &zero_hashes);
+        let mut tree =
+            Self { depth, length: object.length(), zero_hashes, layers, children: HashMap::new() };
+
+        // The indices in the tree of each chunk, along with one of `indices` they come from
+        let mut chunk_indices = HashMap::<usize, (GeneralizedIndex, Vec<GeneralizedIndex>)>::new();
+        for &index in indices {
+            if let Location::Chunk(chunk_index, child_index) = tree.locate(index)? {
+                chunk_indices.entry(chunk_index).or_insert((index, vec![])).1.push(child_index);
+            }
+        }
+        for (chunk_index, (index, child_indices)) in chunk_indices {
+            let child = object.child(chunk_index).ok_or(Error::InvalidGeneralizedIndex(index))?;
+            tree.children.insert(chunk_index, Self::new(child, &child_indices)?);
+        }
+        Ok(tree)
+    }
+
+    pub fn root(&self) -> Node {
+        self.node(1).expect("root is always part of the tree")
+    }
+
+    // Returns the node at `generalized_index`.
+    pub fn node(&self, generalized_index: GeneralizedIndex) -> Result<Node, Error> {
+        match self.locate(generalized_index)? {
+            Location::Node(node) => Ok(node),
+            Location::Chunk(chunk_index, child_index) => self
+                .children
+                .get(&chunk_index)
+                .ok_or(Error::InvalidGeneralizedIndex(generalized_index))?
+                .node(child_index),
+        }
+    }
+
+    // Returns the Merkle branch proving the node at `generalized_index` against the root,
+    // ordered from the leaf to the root as `is_valid_merkle_branch` expects.
+    pub fn proof(&self, generalized_index: GeneralizedIndex) -> Result<Vec<Node>, Error> {
+        if generalized_index == 0 {
+            return Err(Error::InvalidGeneralizedIndex(generalized_index))
+        }
+        get_branch_indices(generalized_index).into_iter().map(|index| self.node(index)).collect()
+    }
+
+    // Returns the auxiliary nodes proving all of `indices` against the root, ordered as
+    // `get_helper_indices`.
+    pub fn multiproof(&self, indices: &[GeneralizedIndex]) -> Result<Vec<Node>, Error> {
+        if indices.contains(&0) {
+            return Err(Error::InvalidGeneralizedIndex(0))
+        }
+        get_helper_indices(indices).into_iter().map(|index| self.node(index)).collect()
+    }
+
+    fn locate(&self, generalized_index: GeneralizedIndex) -> Result<Location, Error> {
+        if generalized_index == 0 {
+            return Err(Error::InvalidGeneralizedIndex(generalized_index))
+        }
+        let mut index = generalized_index;
+        if let Some(length) = self.length {
+            // The root of a list mixes the root of its chunk tree at index `2` with its length at
+            // index `3`
+            let path_length = get_generalized_index_length(index);
+            if path_length == 0 {
+                let root = hash_pair(&self.chunk_tree_root(), &length_node(length));
+                return Ok(Location::Node(root))
+            }
+            let top = index >> (path_length - 1);
+            if top == 3 {
+                if path_length != 1 {
+                    return Err(Error::InvalidGeneralizedIndex(generalized_index))
+                }
+                return Ok(Location::Node(length_node(length)))
+            }
+            index = (1 << (path_length - 1)) | (index - (top << (path_length - 1)));
+        }
+
+        let path_length = get_generalized_index_length(index);
+        if path_length <= self.depth {
+            let height = self.depth - path_length;
+            let layer_index = index - (1 << path_length);
+            let node =
+                self.layers[height].get(layer_index).copied().unwrap_or(self.zero_hashes[height]);
+            return Ok(Location::Node(node))
+        }
+        let remaining = path_length - self.depth;
+        let chunk_index = (index >> remaining) - (1 << self.depth);
+        if chunk_index >= self.layers[0].len() {
+            return Err(Error::InvalidGeneralizedIndex(generalized_index))
+        }
+        let child_index = (1 << remaining) | (index & ((1 << remaining) - 1));
+        Ok(Location::Chunk(chunk_index, child_index))
+    }
+
+    fn chunk_tree_root(&self) -> Node {
+        self.layers[self.depth].first().copied().unwrap_or(self.zero_hashes[self.depth])
+    }
+}
+
+// Returns the node at `generalized_index` in the tree of `object` along with its branch,
+// ordered from the node to the root, building the tree of `object` only once.
+pub fn compute_node_and_branch<T: Prove + ?Sized>(
+    object: &mut T,
+    generalized_index: GeneralizedIndex,
+) -> Result<(Node, Vec<Node>), Error> {
+    let tree = MerkleTree::new(object, &[generalized_index])?;
+    Ok((tree.node(generalized_index)?, tree.proof(generalized_index)?))
+}
+
+// Computes the node at `generalized_index` in the Merkle tree of `object`.
+pub fn compute_merkle_node<T: Prove + ?Sized>(
+    object: &mut T,
+    generalized_index: GeneralizedIndex,
+) -> Result<Node, Error> {
+    MerkleTree::new(object, &[generalized_index])?.node(generalized_index)
+}
+
+// Computes the Merkle branch proving the node at `generalized_index` against the root of `object`,
+// ordered from the leaf to the root as `is_valid_merkle_branch` expects.
+pub fn compute_merkle_proof<T: Prove + ?Sized>(
+    object: &mut T,
+    generalized_index: GeneralizedIndex,
+) -> Result<Vec<Node>, Error> {
+    MerkleTree::new(object, &[generalized_index])?.proof(generalized_index)
+}
+
+// Computes the auxiliary nodes proving all of `indices` against the root of `object`,
+// ordered as `get_helper_indices`.
+//
+// To also read the leaves at `indices`, build a `MerkleTree` for them and use it for both.
+pub fn compute_merkle_multiproof<T: Prove + ?Sized>(
+    object: &mut T,
+    indices: &[GeneralizedIndex],
+) -> Result<Vec<Node>, Error> {
+    MerkleTree::new(object, indices)?.multiproof(indices)
+}
+
+pub fn calculate_merkle_root(
+    leaf: &Node,
+    proof: &[Node],
+    index: GeneralizedIndex,
+) -> Result<Node, Error> {
+    if index == 0 || proof.len() != get_generalized_index_length(index) {
+        return Err(Error::InvalidMerkleProof)
+    }
+    let mut root = *leaf;
+    for (i, node) in proof.iter().enumerate() {
+        root = if get_generalized_index_bit(index, i) {
+            hash_pair(node, &root)
+        } else {
+            hash_pair(&root, node)
+        };
+    }
+    Ok(root)
+}
+
+pub fn verify_merkle_proof(
+    leaf: &Node,
+    proof: &[Node],
+    index: GeneralizedIndex,
+    root: &Node,
+) -> bool {
+    matches!(calculate_merkle_root(leaf, proof, index), Ok(computed) if &computed == root)
+}
+
+pub fn calculate_multi_merkle_root(
+    leaves: &[Node],
+    proof: &[Node],
+    indices: &[GeneralizedIndex],
+) -> Result<Node, Error> {
+    if leaves.len() != indices.len() || indices.contains(&0) {
+        return Err(Error::InvalidMerkleProof)
+    }
+    let helper_indices = get_helper_indices(indices);
+    if proof.len() != helper_indices.len() {
+        return Err(Error::InvalidMerkleProof)
+    }
+
+    let mut objects = indices
+        .iter()
+        .copied()
+        .zip(leaves.iter().copied())
+        .chain(helper_indices.into_iter().zip(proof.iter().copied()))
+        .collect::<HashMap<_, _>>();
+    let mut keys = objects.keys().copied().collect::<Vec<_>>();
+    keys.sort_unstable_by(|a, b| b.cmp(a));
+    let mut position = 0;
+    while position < keys.len() {
+        let key = keys[position];
+        let parent = generalized_index_parent(key);
+        if key > 1 &&
+            objects.contains_key(&generalized_index_sibling(key)) &&
+            !objects.contains_key(&parent)
+        {
+            let right = key | 1;
+            let node = hash_pair(&objects[&(right ^ 1)], &objects[&right]);
+            objects.insert(parent, node);
+            keys.push(parent);
+        }
+        position += 1;
+    }
+    objects.get(&1).copied().ok_or(Error::InvalidMerkleProof)
+}
+
+pub fn verify_merkle_multiproof(
+    leaves: &[Node],
+    proof: &[Node],
+    indices: &[GeneralizedIndex],
+    root: &Node,
+) -> bool {
+    matches!(calculate_multi_merkle_root(leaves, proof, indices), Ok(computed) if &computed == root)
+}
+
+fn pack(bytes: &[u8]) -> Vec<Node> {
+    bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|chunk| {
+            let mut node = [0u8; BYTES_PER_CHUNK];
+            node[..chunk.len()].copy_from_slice(chunk);
+            Node::try_from(node.as_ref()).expect("is valid instance")
+        })
+        .collect()
+}
+
+// The chunks of a vector or list of `T` holding `elements`, packing them if `T` is a basic type.
+fn compute_element_chunks<'a, T: Prove + Merkleized + 'a>(
+    elements: impl Iterator<Item = &'a mut T>,
+) -> Result<Vec<Node>, MerkleizationError> {
+    let roots = elements.map(|element| element.hash_tree_root());
+    let Some(size) = T::basic_size() else { return roots.collect() };
+    // the root of a basic value is its serialization, padded to a chunk
+    let mut bytes = vec![];
+    for root in roots {
+        bytes.extend_from_slice(&root?.as_ref()[..size]);
+    }
+    Ok(pack(&bytes))
+}
+
+// The number of chunks in a vector or list of up to `limit` elements of `T`.
+fn element_chunk_count<T: Prove>(limit: usize) -> usize {
+    match T::basic_size() {
+        Some(size) => (limit * size + BYTES_PER_CHUNK - 1) / BYTES_PER_CHUNK,
+        None => limit,
+    }
+}
+
+// The index of the chunk holding `element` in a vector or list of up to `limit` elements of `T`.
+fn element_chunk_index<T: Prove>(element: &PathElement, limit: usize) -> Option<usize> {
+    match element {
+        PathElement::Index(index) if *index < limit => {
+            Some(T::basic_size().map_or(*index, |size| index * size / BYTES_PER_CHUNK))
+        }
+        _ => None,
+    }
+}
+
+// The element of a vector or list of `T` whose root is the chunk at `index`, unless the elements
+// are packed into chunks.
+fn element_child<'a, T: Prove + 'a>(
+    mut elements: impl Iterator<Item = &'a mut T>,
+    index: usize,
+) -> Option<&'a mut dyn Prove> {
+    if T::basic_size().is_some() {
+        return None
+    }
+    elements.nth(index).map(|element| element as &mut dyn Prove)
+}
+
+// Implements `Prove` for basic types of the given size in bytes, which are single chunks and are
+// packed together in vectors and lists.
+macro_rules! impl_prove_for_basic_type {
+    ($($t:ty => $size:expr),* $(,)?) => {
+        $(
+            impl Prove for $t {
+                fn basic_size() -> Option<usize> {
+                    Some($size)
+                }
+
+                fn chunk_count(&self) -> usize {
+                    1
+                }
+
+                fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError> {
+                    Ok(vec![self.hash_tree_root()?])
+                }
+            }
+        )*
+    };
+}
+
+impl_prove_for_basic_type!(bool => 1, u8 => 1, u64 => 8, usize => 8, U256 => 32);
+
+// Implements `Prove` for types treated as opaque leaves, whose root is the only node in their tree.
+macro_rules! impl_prove_for_opaque_type {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl<const N: usize> Prove for $t {
+                fn chunk_count(&self) -> usize {
+                    1
+                }
+
+                fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError> {
+                    Ok(vec![self.hash_tree_root()?])
+                }
+            }
+        )*
+    };
+}
+
+// NOTE: byte vectors, byte lists and bitfields are treated as opaque leaves
+impl_prove_for_opaque_type!(ByteVector<N>, ByteList<N>, Bitvector<N>, Bitlist<N>);
+
+impl Prove for Version {
+    fn chunk_count(&self) -> usize {
+        1
+    }
+
+    fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError> {
+        Ok(vec![self.hash_tree_root()?])
+    }
+}
+
+impl Prove for Node {
+    fn chunk_count(&self) -> usize {
+        1
+    }
+
+    fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError> {
+        Ok(vec![*self])
+    }
+}
+
+impl<T: Prove + Merkleized, const N: usize> Prove for Vector<T, N> {
+    fn chunk_count(&self) -> usize {
+        element_chunk_count::<T>(N)
+    }
+
+    fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError> {
+        compute_element_chunks(self.iter_mut())
+    }
+
+    fn chunk_index(&self, element: &PathElement) -> Option<usize> {
+        element_chunk_index::<T>(element, N)
+    }
+
+    fn child(&mut self, index: usize) -> Option<&mut dyn Prove> {
+        element_child(self.iter_mut(), index)
+    }
+}
+
+impl<T: Prove + Merkleized, const N: usize> Prove for List<T, N> {
+    fn chunk_count(&self) -> usize {
+        element_chunk_count::<T>(N)
+    }
+
+    fn chunks(&mut self) -> Result<Vec<Node>, MerkleizationError> {
+        compute_element_chunks(self.iter_mut())
+    }
+
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn chunk_index(&self, element: &PathElement) -> Option<usize> {
+        element_chunk_index::<T>(element, N)
+    }
+
+    fn child(&mut self, index: usize) -> Option<&mut dyn Prove> {
+        element_child(self.iter_mut(), index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::phase0::Validator;
+
+    fn make_validators() -> List<Validator, 16> {
+        let validators = (0..5)
+            .map(|i| Validator {
+                effective_balance: 32 * i,
+                slashed: i % 2 == 1,
+                activation_eligibility_epoch: i,
+                withdrawable_epoch: 2 * i,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        List::try_from(validators).unwrap()
+    }
+
+    fn make_indices(validators: &mut List<Validator, 16>) -> Vec<GeneralizedIndex> {
+        let paths: [&[PathElement]; 5] = [
+            &[PathElement::Index(1), "effective_balance".into()],
+            &[PathElement::Index(3), "activation_eligibility_epoch".into()],
+            &[PathElement::Index(3), "slashed".into()],
+            &[PathElement::Index(12)],
+            &[PathElement::Length],
+        ];
+        paths.iter().map(|path| get_generalized_index(validators, path).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_merkle_multiproof_round_trip() {
+        let mut validators = make_validators();
+        let root = validators.hash_tree_root().unwrap();
+        let indices = make_indices(&mut validators);
+        let tree = MerkleTree::new(&mut validators, &indices).unwrap();
+        assert_eq!(tree.root(), root);
+        let leaves = indices.iter().map(|&index| tree.node(index).unwrap()).collect::<Vec<_>>();
+        assert_eq!(leaves[0], 32u64.hash_tree_root().unwrap());
+        assert_eq!(leaves[3], Node::default());
+
+        let proof = tree.multiproof(&indices).unwrap();
+        assert_eq!(proof, compute_merkle_multiproof(&mut validators, &indices).unwrap());
+        let helper_indices = get_helper_indices(&indices);
+        assert_eq!(proof.len(), helper_indices.len());
+        for (node, &index) in proof.iter().zip(helper_indices.iter()) {
+            assert_eq!(*node, compute_merkle_node(&mut validators, index).unwrap());
+        }
+        assert_eq!(calculate_multi_merkle_root(&leaves, &proof, &indices).unwrap(), root);
+        assert!(verify_merkle_multiproof(&leaves, &proof, &indices, &root));
+
+        for (leaf, &index) in leaves.iter().zip(indices.iter()) {
+            let proof = tree.proof(index).unwrap();
+            assert_eq!(proof, compute_merkle_proof(&mut validators, index).unwrap());
+            assert_eq!(calculate_merkle_root(leaf, &proof, index).unwrap(), root);
+            assert!(verify_merkle_multiproof(&[*leaf], &proof, &[index], &root));
+        }
+
+        // the root itself needs no auxiliary nodes
+        assert_eq!(compute_merkle_multiproof(&mut validators, &[1]).unwrap(), vec![]);
+        assert!(verify_merkle_multiproof(&[root], &[], &[1], &root));
+    }
+
+    #[test]
+    fn test_derived_generalized_indices() {
+        use crate::{
+            altair::{light_client, mainnet::BeaconState},
+            capella::{light_client::EXECUTION_PAYLOAD_INDEX, mainnet::BeaconBlockBody},
+        };
+
+        let mut state = BeaconState::default();
+        let path = ["finalized_checkpoint".into(), "root".into()];
+        let index = get_generalized_index(&mut state, &path).unwrap();
+        assert_eq!(index, light_client::FINALIZED_ROOT_INDEX);
+        let index = get_generalized_index(&mut state, &["current_sync_committee".into()]).unwrap();
+        assert_eq!(index, light_client::CURRENT_SYNC_COMMITTEE_INDEX);
+        let index = get_generalized_index(&mut state, &["next_sync_committee".into()]).unwrap();
+        assert_eq!(index, light_client::NEXT_SYNC_COMMITTEE_INDEX);
+
+        let mut body = BeaconBlockBody::default();
+        let index = get_generalized_index(&mut body, &["execution_payload".into()]).unwrap();
+        assert_eq!(index, EXECUTION_PAYLOAD_INDEX);
+        // nested containers and packed lists are reachable through the derived impls
+        let path = ["sync_aggregate".into(), "sync_committee_signature".into()];
+        let index = get_generalized_index(&mut body, &path).unwrap();
+        assert_eq!(index, 2 * (16 + 8) + 1);
+        let signature_root = body.sync_aggregate.sync_committee_signature.hash_tree_root().unwrap();
+        assert_eq!(compute_merkle_node(&mut body, index).unwrap(), signature_root);
+        let mut balances = List::<u64, 16>::try_from(vec![1, 2, 3, 4, 5]).unwrap();
+        let index = get_generalized_index(&mut balances, &[PathElement::Index(4)]).unwrap();
+        assert_eq!(index, 2 * 4 + 1);
+        let mut chunk = [0u8; BYTES_PER_CHUNK];
+        chunk[..8].copy_from_slice(&5u64.to_le_bytes());
+        assert_eq!(compute_merkle_node(&mut balances, index).unwrap().as_ref(), chunk);
+    }
+
+    #[test]
+    fn test_tampered_merkle_multiproof() {
+        let mut validators = make_validators();
+        let root = validators.hash_tree_root().unwrap();
+        let indices = make_indices(&mut validators);
+        let leaves = indices
+            .iter()
+            .map(|&index| compute_merkle_node(&mut validators, index).unwrap())
+            .collect::<Vec<_>>();
+        let proof = compute_merkle_multiproof(&mut validators, &indices).unwrap();
+        let tampered = |node: &Node| {
+            let mut bytes = node.as_ref().to_vec();
+            bytes[0] ^= 1;
+            Node::try_from(bytes.as_ref()).unwrap()
+        };
+
+        for i in 0..leaves.len() {
+            let mut tampered_leaves = leaves.clone();
+            tampered_leaves[i] = tampered(&leaves[i]);
+            assert!(!verify_merkle_multiproof(&tampered_leaves, &proof, &indices, &root));
+        }
+        for i in 0..proof.len() {
+            let mut tampered_proof = proof.clone();
+            tampered_proof[i] = tampered(&proof[i]);
+            assert!(!verify_merkle_multiproof(&leaves, &tampered_proof, &indices, &root));
+        }
+        let mut swapped_indices = indices.clone();
+        swapped_indices.swap(0, 1);
+        assert!(!verify_merkle_multiproof(&leaves, &proof, &swapped_indices, &root));
+        assert!(!verify_merkle_multiproof(&leaves, &proof, &indices, &tampered(&root)));
+
+        assert!(matches!(
+            calculate_multi_merkle_root(&leaves, &proof[1..], &indices),
+            Err(Error::InvalidMerkleProof)
+        ));
+        assert!(matches!(
+            calculate_multi_merkle_root(&leaves[1..], &proof, &indices),
+            Err(Error::InvalidMerkleProof)
+        ));
+        assert!(matches!(
+            calculate_multi_merkle_root(&[leaves[0]], &[], &[0]),
+            Err(Error::InvalidMerkleProof)
+        ));
+        assert!(matches!(
+            compute_merkle_multiproof(&mut validators, &[0]),
+            Err(Error::InvalidGeneralizedIndex(0))
+        ));
+        // the validator at this position is beyond the length of the list
+        let index = get_generalized_index(&mut validators, &[PathElement::Index(12)]).unwrap();
+        let index = concat_generalized_indices(&[index, 8]);
+        assert!(matches!(
+            compute_merkle_multiproof(&mut validators, &[index]),
+            Err(Error::InvalidGeneralizedIndex(_))
+        ));
+    }
+}
@@ -1,9 +1,14 @@
 /// This module contains "wrapper" types for beacon states so that
 /// the state transition machinery can be polymorphic with respect to forks.
-use crate::altair;
-use crate::{bellatrix, phase0};
+use crate::{
+    altair, bellatrix, capella, deneb,
+    phase0::{self, Checkpoint, Validator},
+    primitives::Slot,
+    ssz::prelude::List,
+    Fork,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum BeaconState<
     const SLOTS_PER_HISTORICAL_ROOT: usize,
     const HISTORICAL_ROOTS_LIMIT: usize,
@@ -61,6 +66,38 @@ pub enum BeaconState<
             >,
         >,
     ),
+    Capella(
+        Box<
+            capella::BeaconState<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+            >,
+        >,
+    ),
+    Deneb(
+        Box<
+            deneb::BeaconState<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+            >,
+        >,
+    ),
 }
 
 impl<
@@ -232,6 +269,124 @@ impl<
     }
 }
 
+impl<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+    >
+    From<
+        capella::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >
+    for BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >
+{
+    fn from(
+        state: capella::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> Self {
+        Self::Capella(Box::new(state))
+    }
+}
+
+impl<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+    >
+    From<
+        deneb::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    >
+    for BeaconState<
+        SLOTS_PER_HISTORICAL_ROOT,
+        HISTORICAL_ROOTS_LIMIT,
+        ETH1_DATA_VOTES_BOUND,
+        VALIDATOR_REGISTRY_LIMIT,
+        EPOCHS_PER_HISTORICAL_VECTOR,
+        EPOCHS_PER_SLASHINGS_VECTOR,
+        MAX_VALIDATORS_PER_COMMITTEE,
+        PENDING_ATTESTATIONS_BOUND,
+        SYNC_COMMITTEE_SIZE,
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+    >
+{
+    fn from(
+        state: deneb::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    ) -> Self {
+        Self::Deneb(Box::new(state))
+    }
+}
+
 impl<
         const SLOTS_PER_HISTORICAL_ROOT: usize,
         const HISTORICAL_ROOTS_LIMIT: usize,
@@ -259,6 +414,66 @@ impl<
         MAX_EXTRA_DATA_BYTES,
     >
 {
+    pub fn version(&self) -> Fork {
+        match self {
+            Self::Phase0(_) => Fork::Phase0,
+            Self::Altair(_) => Fork::Altair,
+            Self::Bellatrix(_) => Fork::Bellatrix,
+            Self::Capella(_) => Fork::Capella,
+            Self::Deneb(_) => Fork::Deneb,
+        }
+    }
+
+    pub fn slot(&self) -> Slot {
+        match self {
+            Self::Phase0(state) => state.slot,
+            Self::Altair(state) => state.slot,
+            Self::Bellatrix(state) => state.slot,
+            Self::Capella(state) => state.slot,
+            Self::Deneb(state) => state.slot,
+        }
+    }
+
+    pub fn genesis_time(&self) -> u64 {
+        match self {
+            Self::Phase0(state) => state.genesis_time,
+            Self::Altair(state) => state.genesis_time,
+            Self::Bellatrix(state) => state.genesis_time,
+            Self::Capella(state) => state.genesis_time,
+            Self::Deneb(state) => state.genesis_time,
+        }
+    }
+
+    pub fn validators(&self) -> &List<Validator, VALIDATOR_REGISTRY_LIMIT> {
+        match self {
+            Self::Phase0(state) => &state.validators,
+            Self::Altair(state) => &state.validators,
+            Self::Bellatrix(state) => &state.validators,
+            Self::Capella(state) => &state.validators,
+            Self::Deneb(state) => &state.validators,
+        }
+    }
+
+    pub fn current_justified_checkpoint(&self) -> &Checkpoint {
+        match self {
+            Self::Phase0(state) => &state.current_justified_checkpoint,
+            Self::Altair(state) => &state.current_justified_checkpoint,
+            Self::Bellatrix(state) => &state.current_justified_checkpoint,
+            Self::Capella(state) => &state.current_justified_checkpoint,
+            Self::Deneb(state) => &state.current_justified_checkpoint,
+        }
+    }
+
+    pub fn finalized_checkpoint(&self) -> &Checkpoint {
+        match self {
+            Self::Phase0(state) => &state.finalized_checkpoint,
+            Self::Altair(state) => &state.finalized_checkpoint,
+            Self::Bellatrix(state) => &state.finalized_checkpoint,
+            Self::Capella(state) => &state.finalized_checkpoint,
+            Self::Deneb(state) => &state.finalized_checkpoint,
+        }
+    }
+
     pub fn phase0(
         self,
     ) -> Option<
@@ -320,4 +535,48 @@ impl<
             _ => None,
         }
     }
+
+    pub fn capella(
+        self,
+    ) -> Option<
+        capella::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    > {
+        match self {
+            Self::Capella(state) => Some(*state),
+            _ => None,
+        }
+    }
+
+    pub fn deneb(
+        self,
+    ) -> Option<
+        deneb::BeaconState<
+            SLOTS_PER_HISTORICAL_ROOT,
+            HISTORICAL_ROOTS_LIMIT,
+            ETH1_DATA_VOTES_BOUND,
+            VALIDATOR_REGISTRY_LIMIT,
+            EPOCHS_PER_HISTORICAL_VECTOR,
+            EPOCHS_PER_SLASHINGS_VECTOR,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+        >,
+    > {
+        match self {
+            Self::Deneb(state) => Some(*state),
+            _ => None,
+        }
+    }
 }
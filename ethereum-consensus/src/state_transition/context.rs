@@ -6,6 +6,7 @@ use crate::{
     networks::Network,
     phase0,
     primitives::{Epoch, ExecutionAddress, Gwei, Hash32, Slot, Version, U256},
+    state_transition::{PubkeyCache, ShufflingCache},
     Error, Fork,
 };
 
@@ -44,6 +45,8 @@ pub struct Context {
     pub max_attestations: usize,
     pub max_deposits: usize,
     pub max_voluntary_exits: usize,
+    pub target_aggregators_per_committee: u64,
+    pub safe_slots_to_update_justified: Slot,
 
     // altair preset
     pub inactivity_penalty_quotient_altair: u64,
@@ -53,6 +56,7 @@ pub struct Context {
     pub epochs_per_sync_committee_period: Epoch,
     pub min_sync_committee_participants: usize,
     pub update_timeout: usize,
+    pub target_aggregators_per_sync_subcommittee: u64,
 
     // bellatrix preset
     pub inactivity_penalty_quotient_bellatrix: u64,
@@ -112,6 +116,11 @@ pub struct Context {
     pub deposit_chain_id: usize,
     pub deposit_network_id: usize,
     pub deposit_contract_address: ExecutionAddress,
+
+    #[serde(skip)]
+    pub shuffling_cache: ShufflingCache,
+    #[serde(skip)]
+    pub pubkey_cache: PubkeyCache,
 }
 
 impl Context {
@@ -197,6 +206,8 @@ impl Context {
             max_attestations: phase0_preset.max_attestations,
             max_deposits: phase0_preset.max_deposits,
             max_voluntary_exits: phase0_preset.max_voluntary_exits,
+            target_aggregators_per_committee: phase0_preset.target_aggregators_per_committee,
+            safe_slots_to_update_justified: phase0_preset.safe_slots_to_update_justified,
             // altair
             inactivity_penalty_quotient_altair: altair_preset.inactivity_penalty_quotient_altair,
             min_slashing_penalty_quotient_altair: altair_preset
@@ -207,6 +218,8 @@ impl Context {
             epochs_per_sync_committee_period: altair_preset.epochs_per_sync_committee_period,
             min_sync_committee_participants: altair_preset.min_sync_committee_participants,
             update_timeout: altair_preset.update_timeout,
+            target_aggregators_per_sync_subcommittee: altair_preset
+                .target_aggregators_per_sync_subcommittee,
             // bellatrix
             inactivity_penalty_quotient_bellatrix: bellatrix_preset
                 .inactivity_penalty_quotient_bellatrix,
@@ -259,6 +272,8 @@ impl Context {
             deposit_chain_id: config.deposit_chain_id,
             deposit_network_id: config.deposit_network_id,
             deposit_contract_address: config.deposit_contract_address.clone(),
+            shuffling_cache: Default::default(),
+            pubkey_cache: Default::default(),
         }
     }
 
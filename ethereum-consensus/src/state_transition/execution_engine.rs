@@ -1,19 +1,38 @@
-use crate::bellatrix;
+use crate::{bellatrix, capella, deneb};
 
+// `ExecutionEngine` bundles the fork-specific execution engines so that
+// the `Executor` can drive the state transition across the merge and beyond.
 #[derive(Debug)]
-pub enum ExecutionEngine<
+pub struct ExecutionEngine<
     const BYTES_PER_LOGS_BLOOM: usize,
     const MAX_EXTRA_DATA_BYTES: usize,
     const MAX_BYTES_PER_TRANSACTION: usize,
     const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
     B: bellatrix::ExecutionEngine<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
         MAX_BYTES_PER_TRANSACTION,
         MAX_TRANSACTIONS_PER_PAYLOAD,
     >,
+    C: capella::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+    D: deneb::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
 > {
-    Bellatrix(B),
+    bellatrix: B,
+    capella: C,
+    deneb: D,
 }
 
 impl<
@@ -21,28 +40,106 @@ impl<
         const MAX_EXTRA_DATA_BYTES: usize,
         const MAX_BYTES_PER_TRANSACTION: usize,
         const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
         B: bellatrix::ExecutionEngine<
             BYTES_PER_LOGS_BLOOM,
             MAX_EXTRA_DATA_BYTES,
             MAX_BYTES_PER_TRANSACTION,
             MAX_TRANSACTIONS_PER_PAYLOAD,
         >,
+        C: capella::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
+        D: deneb::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
     >
     ExecutionEngine<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
         MAX_BYTES_PER_TRANSACTION,
         MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
         B,
+        C,
+        D,
     >
 {
-    pub fn new(bellatrix: B) -> Self {
-        Self::Bellatrix(bellatrix)
+    pub fn new(bellatrix: B, capella: C, deneb: D) -> Self {
+        Self { bellatrix, capella, deneb }
     }
 
     pub fn bellatrix(&self) -> &B {
-        match self {
-            Self::Bellatrix(engine) => engine,
-        }
+        &self.bellatrix
+    }
+
+    pub fn capella(&self) -> &C {
+        &self.capella
+    }
+
+    pub fn deneb(&self) -> &D {
+        &self.deneb
+    }
+}
+
+impl<
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    > Default
+    for ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        bellatrix::DefaultExecutionEngine,
+        capella::DefaultExecutionEngine,
+        deneb::DefaultExecutionEngine,
+    >
+{
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), Default::default())
+    }
+}
+
+// Allows building an `ExecutionEngine` from just a Bellatrix engine, as before the Capella and
+// Deneb engines were added; the later forks use their default engines.
+impl<
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        B: bellatrix::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+        >,
+    > From<B>
+    for ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        B,
+        capella::DefaultExecutionEngine,
+        deneb::DefaultExecutionEngine,
+    >
+{
+    fn from(bellatrix: B) -> Self {
+        Self::new(bellatrix, Default::default(), Default::default())
     }
 }
@@ -1,8 +1,9 @@
 use crate::{
-    altair, bellatrix, phase0,
+    altair, bellatrix, capella, deneb, phase0,
+    primitives::Slot,
     state_transition::{
-        execution_engine::ExecutionEngine, BeaconState, Context, Result, SignedBeaconBlock,
-        Validation,
+        execution_engine::ExecutionEngine, BeaconState, Context, Observer, Result,
+        SignedBeaconBlock, Validation,
     },
     Error, Fork,
 };
@@ -27,12 +28,29 @@ pub struct Executor<
     const MAX_ATTESTATIONS: usize,
     const MAX_DEPOSITS: usize,
     const MAX_VOLUNTARY_EXITS: usize,
+    const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+    const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+    const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
     B: bellatrix::ExecutionEngine<
         BYTES_PER_LOGS_BLOOM,
         MAX_EXTRA_DATA_BYTES,
         MAX_BYTES_PER_TRANSACTION,
         MAX_TRANSACTIONS_PER_PAYLOAD,
     >,
+    C: capella::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
+    D: deneb::ExecutionEngine<
+        BYTES_PER_LOGS_BLOOM,
+        MAX_EXTRA_DATA_BYTES,
+        MAX_BYTES_PER_TRANSACTION,
+        MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+    >,
 > {
     pub state: BeaconState<
         SLOTS_PER_HISTORICAL_ROOT,
@@ -52,7 +70,10 @@ pub struct Executor<
         MAX_EXTRA_DATA_BYTES,
         MAX_BYTES_PER_TRANSACTION,
         MAX_TRANSACTIONS_PER_PAYLOAD,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
         B,
+        C,
+        D,
     >,
     pub context: Context,
 }
@@ -76,12 +97,29 @@ impl<
         const MAX_ATTESTATIONS: usize,
         const MAX_DEPOSITS: usize,
         const MAX_VOLUNTARY_EXITS: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
         B: bellatrix::ExecutionEngine<
             BYTES_PER_LOGS_BLOOM,
             MAX_EXTRA_DATA_BYTES,
             MAX_BYTES_PER_TRANSACTION,
             MAX_TRANSACTIONS_PER_PAYLOAD,
         >,
+        C: capella::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
+        D: deneb::ExecutionEngine<
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+        >,
     >
     Executor<
         SLOTS_PER_HISTORICAL_ROOT,
@@ -102,7 +140,12 @@ impl<
         MAX_ATTESTATIONS,
         MAX_DEPOSITS,
         MAX_VOLUNTARY_EXITS,
+        MAX_WITHDRAWALS_PER_PAYLOAD,
+        MAX_BLS_TO_EXECUTION_CHANGES,
+        MAX_BLOB_COMMITMENTS_PER_BLOCK,
         B,
+        C,
+        D,
     >
 {
     pub fn new(
@@ -124,7 +167,10 @@ impl<
             MAX_EXTRA_DATA_BYTES,
             MAX_BYTES_PER_TRANSACTION,
             MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
             B,
+            C,
+            D,
         >,
         context: Context,
     ) -> Self {
@@ -145,9 +191,12 @@ impl<
             MAX_EXTRA_DATA_BYTES,
             MAX_BYTES_PER_TRANSACTION,
             MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
         >,
     ) -> Result<()> {
-        self.apply_block_with_validation(signed_block, Validation::Enabled)
+        self.apply_block_with_validation(signed_block, Validation::enabled(), &mut ())
     }
 
     pub fn apply_block_with_validation(
@@ -164,22 +213,99 @@ impl<
             MAX_EXTRA_DATA_BYTES,
             MAX_BYTES_PER_TRANSACTION,
             MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
         >,
         validation: Validation,
+        observer: &mut dyn Observer,
     ) -> Result<()> {
         match signed_block {
             SignedBeaconBlock::Phase0(signed_block) => {
-                self.apply_phase0_block_with_validation(signed_block, validation)
+                self.apply_phase0_block_with_validation(signed_block, validation, observer)
             }
             SignedBeaconBlock::Altair(signed_block) => {
-                self.apply_altair_block_with_validation(signed_block, validation)
+                self.apply_altair_block_with_validation(signed_block, validation, observer)
             }
             SignedBeaconBlock::Bellatrix(signed_block) => {
-                self.apply_bellatrix_block_with_validation(signed_block, validation)
+                self.apply_bellatrix_block_with_validation(signed_block, validation, observer)
+            }
+            SignedBeaconBlock::Capella(signed_block) => {
+                self.apply_capella_block_with_validation(signed_block, validation, observer)
+            }
+            SignedBeaconBlock::Deneb(signed_block) => {
+                self.apply_deneb_block_with_validation(signed_block, validation, observer)
             }
         }
     }
 
+    /// Advances the state to `slot` without applying a block, upgrading it at each fork boundary
+    /// on the way.
+    pub fn process_slots(&mut self, slot: Slot, observer: &mut dyn Observer) -> Result<()> {
+        self.upgrade_to(self.context.fork_for(slot), observer)?;
+        match &mut self.state {
+            BeaconState::Phase0(state) if state.slot < slot => {
+                phase0::process_slots(state, slot, observer, &self.context)
+            }
+            BeaconState::Altair(state) if state.slot < slot => {
+                altair::process_slots(state, slot, observer, &self.context)
+            }
+            BeaconState::Bellatrix(state) if state.slot < slot => {
+                bellatrix::process_slots(state, slot, observer, &self.context)
+            }
+            BeaconState::Capella(state) if state.slot < slot => {
+                capella::process_slots(state, slot, observer, &self.context)
+            }
+            BeaconState::Deneb(state) if state.slot < slot => {
+                deneb::process_slots(state, slot, observer, &self.context)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Advance the state through each fork boundary until it reaches `fork`,
+    // processing slots up to each boundary and then applying the relevant upgrade.
+    // Returns `true` if any upgrade was performed.
+    fn upgrade_to(&mut self, fork: Fork, observer: &mut dyn Observer) -> Result<bool> {
+        let mut upgraded = false;
+        loop {
+            let state = match &mut self.state {
+                BeaconState::Phase0(state) if fork > Fork::Phase0 => {
+                    let fork_slot = self.context.altair_fork_epoch * self.context.slots_per_epoch;
+                    if state.slot < fork_slot {
+                        phase0::process_slots(state, fork_slot, observer, &self.context)?;
+                    }
+                    altair::upgrade_to_altair(state, &self.context)?.into()
+                }
+                BeaconState::Altair(state) if fork > Fork::Altair => {
+                    let fork_slot =
+                        self.context.bellatrix_fork_epoch * self.context.slots_per_epoch;
+                    if state.slot < fork_slot {
+                        altair::process_slots(state, fork_slot, observer, &self.context)?;
+                    }
+                    bellatrix::upgrade_to_bellatrix(state, &self.context).into()
+                }
+                BeaconState::Bellatrix(state) if fork > Fork::Bellatrix => {
+                    let fork_slot = self.context.capella_fork_epoch * self.context.slots_per_epoch;
+                    if state.slot < fork_slot {
+                        bellatrix::process_slots(state, fork_slot, observer, &self.context)?;
+                    }
+                    capella::upgrade_to_capella(state, &self.context).into()
+                }
+                BeaconState::Capella(state) if fork > Fork::Capella => {
+                    let fork_slot = self.context.deneb_fork_epoch * self.context.slots_per_epoch;
+                    if state.slot < fork_slot {
+                        capella::process_slots(state, fork_slot, observer, &self.context)?;
+                    }
+                    deneb::upgrade_to_deneb(state, &self.context).into()
+                }
+                _ => return Ok(upgraded),
+            };
+            self.state = state;
+            upgraded = true;
+        }
+    }
+
     pub fn apply_phase0_block_with_validation(
         &mut self,
         signed_block: &mut phase0::SignedBeaconBlock<
@@ -191,17 +317,14 @@ impl<
             MAX_VOLUNTARY_EXITS,
         >,
         validation: Validation,
+        observer: &mut dyn Observer,
     ) -> Result<()> {
         match &mut self.state {
             BeaconState::Phase0(state) => {
-                phase0::state_transition(state, signed_block, validation, &self.context)
+                phase0::state_transition(state, signed_block, validation, observer, &self.context)
             }
-            BeaconState::Altair(_) => Err(Error::IncompatibleFork {
-                source_fork: Fork::Altair,
-                destination_fork: Fork::Phase0,
-            }),
-            BeaconState::Bellatrix(_) => Err(Error::IncompatibleFork {
-                source_fork: Fork::Bellatrix,
+            state => Err(Error::IncompatibleFork {
+                source_fork: state.version(),
                 destination_fork: Fork::Phase0,
             }),
         }
@@ -219,30 +342,31 @@ impl<
             SYNC_COMMITTEE_SIZE,
         >,
         validation: Validation,
+        observer: &mut dyn Observer,
     ) -> Result<()> {
+        let upgraded = self.upgrade_to(Fork::Altair, observer)?;
         match &mut self.state {
-            BeaconState::Phase0(state) => {
-                let fork_slot = self.context.altair_fork_epoch * self.context.slots_per_epoch;
-                phase0::process_slots(state, fork_slot, &self.context)?;
-                let mut state = altair::upgrade_to_altair(state, &self.context)?;
-                if signed_block.message.slot == state.slot {
+            BeaconState::Altair(state) => {
+                if upgraded && signed_block.message.slot == state.slot {
                     altair::state_transition_block_in_slot(
-                        &mut state,
+                        state,
                         signed_block,
                         validation,
+                        observer,
                         &self.context,
-                    )?;
+                    )
                 } else {
-                    altair::state_transition(&mut state, signed_block, validation, &self.context)?;
+                    altair::state_transition(
+                        state,
+                        signed_block,
+                        validation,
+                        observer,
+                        &self.context,
+                    )
                 }
-                self.state = state.into();
-                Ok(())
-            }
-            BeaconState::Altair(state) => {
-                altair::state_transition(state, signed_block, validation, &self.context)
             }
-            BeaconState::Bellatrix(_) => Err(Error::IncompatibleFork {
-                source_fork: Fork::Bellatrix,
+            state => Err(Error::IncompatibleFork {
+                source_fork: state.version(),
                 destination_fork: Fork::Altair,
             }),
         }
@@ -264,67 +388,274 @@ impl<
             MAX_TRANSACTIONS_PER_PAYLOAD,
         >,
         validation: Validation,
+        observer: &mut dyn Observer,
     ) -> Result<()> {
+        let upgraded = self.upgrade_to(Fork::Bellatrix, observer)?;
         match &mut self.state {
-            BeaconState::Phase0(state) => {
-                let fork_slot = self.context.altair_fork_epoch * self.context.slots_per_epoch;
-                phase0::process_slots(state, fork_slot, &self.context)?;
-                let mut state = altair::upgrade_to_altair(state, &self.context)?;
-
-                let fork_slot = self.context.bellatrix_fork_epoch * self.context.slots_per_epoch;
-                altair::process_slots(&mut state, fork_slot, &self.context)?;
-                let mut state = bellatrix::upgrade_to_bellatrix(&state, &self.context);
-                if signed_block.message.slot == state.slot {
+            BeaconState::Bellatrix(state) => {
+                if upgraded && signed_block.message.slot == state.slot {
                     bellatrix::state_transition_block_in_slot(
-                        &mut state,
+                        state,
                         signed_block,
                         self.execution_engine.bellatrix(),
                         validation,
+                        observer,
                         &self.context,
-                    )?;
+                    )
                 } else {
                     bellatrix::state_transition(
-                        &mut state,
+                        state,
                         signed_block,
                         self.execution_engine.bellatrix(),
                         validation,
+                        observer,
                         &self.context,
-                    )?;
+                    )
                 }
-                self.state = state.into();
-                Ok(())
             }
-            BeaconState::Altair(state) => {
-                let fork_slot = self.context.bellatrix_fork_epoch * self.context.slots_per_epoch;
-                altair::process_slots(state, fork_slot, &self.context)?;
-                let mut state = bellatrix::upgrade_to_bellatrix(state, &self.context);
-                if signed_block.message.slot == state.slot {
-                    bellatrix::state_transition_block_in_slot(
-                        &mut state,
+            state => Err(Error::IncompatibleFork {
+                source_fork: state.version(),
+                destination_fork: Fork::Bellatrix,
+            }),
+        }
+    }
+
+    pub fn apply_capella_block_with_validation(
+        &mut self,
+        signed_block: &mut capella::SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+        >,
+        validation: Validation,
+        observer: &mut dyn Observer,
+    ) -> Result<()> {
+        let upgraded = self.upgrade_to(Fork::Capella, observer)?;
+        match &mut self.state {
+            BeaconState::Capella(state) => {
+                if upgraded && signed_block.message.slot == state.slot {
+                    capella::state_transition_block_in_slot(
+                        state,
                         signed_block,
-                        self.execution_engine.bellatrix(),
+                        self.execution_engine.capella(),
                         validation,
+                        observer,
                         &self.context,
-                    )?;
+                    )
                 } else {
-                    bellatrix::state_transition(
-                        &mut state,
+                    capella::state_transition(
+                        state,
                         signed_block,
-                        self.execution_engine.bellatrix(),
+                        self.execution_engine.capella(),
+                        validation,
+                        observer,
+                        &self.context,
+                    )
+                }
+            }
+            state => Err(Error::IncompatibleFork {
+                source_fork: state.version(),
+                destination_fork: Fork::Capella,
+            }),
+        }
+    }
+
+    pub fn apply_deneb_block_with_validation(
+        &mut self,
+        signed_block: &mut deneb::SignedBeaconBlock<
+            MAX_PROPOSER_SLASHINGS,
+            MAX_VALIDATORS_PER_COMMITTEE,
+            MAX_ATTESTER_SLASHINGS,
+            MAX_ATTESTATIONS,
+            MAX_DEPOSITS,
+            MAX_VOLUNTARY_EXITS,
+            SYNC_COMMITTEE_SIZE,
+            BYTES_PER_LOGS_BLOOM,
+            MAX_EXTRA_DATA_BYTES,
+            MAX_BYTES_PER_TRANSACTION,
+            MAX_TRANSACTIONS_PER_PAYLOAD,
+            MAX_WITHDRAWALS_PER_PAYLOAD,
+            MAX_BLS_TO_EXECUTION_CHANGES,
+            MAX_BLOB_COMMITMENTS_PER_BLOCK,
+        >,
+        validation: Validation,
+        observer: &mut dyn Observer,
+    ) -> Result<()> {
+        let upgraded = self.upgrade_to(Fork::Deneb, observer)?;
+        match &mut self.state {
+            BeaconState::Deneb(state) => {
+                if upgraded && signed_block.message.slot == state.slot {
+                    deneb::state_transition_block_in_slot(
+                        state,
+                        signed_block,
+                        self.execution_engine.deneb(),
+                        validation,
+                        observer,
+                        &self.context,
+                    )
+                } else {
+                    deneb::state_transition(
+                        state,
+                        signed_block,
+                        self.execution_engine.deneb(),
                         validation,
+                        observer,
                         &self.context,
-                    )?;
+                    )
+                }
+            }
+            state => Err(Error::IncompatibleFork {
+                source_fork: state.version(),
+                destination_fork: Fork::Deneb,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        harness::minimal::Harness, primitives::FAR_FUTURE_EPOCH, state_transition::Event,
+        types::minimal::BeaconState,
+    };
+
+    fn context_at_genesis(fork: Fork) -> Context {
+        let mut context = Context::for_minimal();
+        if fork >= Fork::Altair {
+            context.altair_fork_epoch = 0;
+        }
+        if fork >= Fork::Bellatrix {
+            context.bellatrix_fork_epoch = 0;
+        }
+        if fork >= Fork::Capella {
+            context.capella_fork_epoch = 0;
+        }
+        if fork >= Fork::Deneb {
+            context.deneb_fork_epoch = 0;
+        }
+        context
+    }
+
+    // Changes the registry of `state` so that processing its epoch applies slashing penalties,
+    // ejects and activates more validators than the churn limit allows and moves effective
+    // balances in both directions. Validators are only added or exited in a later epoch, so the
+    // committees of the attestations in `state` are unchanged.
+    fn add_registry_changes(state: &mut BeaconState, context: &Context) {
+        let current_epoch = state.slot() / context.slots_per_epoch;
+        let finalized_epoch = state.finalized_checkpoint().epoch;
+        let increment = context.effective_balance_increment;
+
+        let mut total_slashed = 0;
+        for (index, validator) in state.validators_mut().iter_mut().enumerate() {
+            match index % 4 {
+                1 => {
+                    validator.slashed = true;
+                    validator.exit_epoch = current_epoch + 1;
+                    validator.withdrawable_epoch =
+                        current_epoch + context.epochs_per_slashings_vector / 2;
+                    total_slashed += validator.effective_balance;
+                }
+                2 => validator.effective_balance = context.ejection_balance,
+                3 => validator.effective_balance -= 2 * increment,
+                _ => {}
+            }
+        }
+        let slashings = state.slashings_mut();
+        let slashings_index = current_epoch as usize % slashings.len();
+        slashings[slashings_index] += total_slashed;
+        for (index, balance) in state.balances_mut().iter_mut().enumerate() {
+            if index % 4 == 0 {
+                *balance -= increment;
+            }
+        }
+
+        let template = state.validators()[0].clone();
+        for index in 0..12 {
+            let mut validator = template.clone();
+            validator.activation_eligibility_epoch =
+                if index % 3 == 0 { FAR_FUTURE_EPOCH } else { finalized_epoch };
+            validator.activation_epoch = FAR_FUTURE_EPOCH;
+            state.validators_mut().push(validator);
+            state.balances_mut().push(context.max_effective_balance);
+            if let Some(participation) = state.previous_epoch_participation_mut() {
+                participation.push(0);
+            }
+            if let Some(participation) = state.current_epoch_participation_mut() {
+                participation.push(0);
+            }
+            if let Some(inactivity_scores) = state.inactivity_scores_mut() {
+                inactivity_scores.push(0);
+            }
+        }
+        if let Some(inactivity_scores) = state.inactivity_scores_mut() {
+            for (index, score) in inactivity_scores.iter_mut().enumerate() {
+                *score = 3 * index as u64;
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_pass_epoch_processing_matches_process_epoch() {
+        for fork in [Fork::Phase0, Fork::Altair, Fork::Bellatrix, Fork::Capella, Fork::Deneb] {
+            let mut harness = Harness::new(64, context_at_genesis(fork)).unwrap();
+            harness.extend_chain(23).unwrap();
+            // last slot of epoch 3, after the chain has justified and finalized checkpoints
+            let slot = 4 * harness.context().slots_per_epoch - 1;
+            let mut state = harness.advance(harness.head_root(), slot).unwrap();
+            assert!(state.finalized_checkpoint().epoch > 0);
+            add_registry_changes(&mut state, harness.context());
+
+            // without finality for longer than allowed the chain is in an inactivity leak
+            let mut leaking_context = harness.context().clone();
+            leaking_context.min_epochs_to_inactivity_penalty = 0;
+            for context in [harness.context().clone(), leaking_context] {
+                let mut expected = state.clone();
+                let mut state = state.clone();
+                let mut expected_events: Vec<Event> = vec![];
+                let mut events: Vec<Event> = vec![];
+                match (&mut expected, &mut state) {
+                    (BeaconState::Phase0(expected), BeaconState::Phase0(state)) => {
+                        phase0::process_epoch(expected, &mut expected_events, &context).unwrap();
+                        phase0::process_epoch_single_pass(state, &mut events, &context).unwrap();
+                    }
+                    (BeaconState::Altair(expected), BeaconState::Altair(state)) => {
+                        altair::process_epoch(expected, &mut expected_events, &context).unwrap();
+                        altair::process_epoch_single_pass(state, &mut events, &context).unwrap();
+                    }
+                    (BeaconState::Bellatrix(expected), BeaconState::Bellatrix(state)) => {
+                        bellatrix::process_epoch(expected, &mut expected_events, &context).unwrap();
+                        bellatrix::process_epoch_single_pass(state, &mut events, &context).unwrap();
+                    }
+                    (BeaconState::Capella(expected), BeaconState::Capella(state)) => {
+                        capella::process_epoch(expected, &mut expected_events, &context).unwrap();
+                        capella::process_epoch_single_pass(state, &mut events, &context).unwrap();
+                    }
+                    (BeaconState::Deneb(expected), BeaconState::Deneb(state)) => {
+                        deneb::process_epoch(expected, &mut expected_events, &context).unwrap();
+                        deneb::process_epoch_single_pass(state, &mut events, &context).unwrap();
+                    }
+                    _ => unreachable!(),
                 }
-                self.state = state.into();
-                Ok(())
+                assert_eq!(state, expected, "{fork:?}");
+                // the same events are emitted, although not in the same order
+                assert_eq!(events.len(), expected_events.len(), "{fork:?}");
+                assert!(expected_events.iter().all(|event| events.contains(event)), "{fork:?}");
+                assert!(events
+                    .iter()
+                    .any(|event| matches!(event, Event::ValidatorActivated { .. })));
+                assert!(events.iter().any(|event| matches!(event, Event::ValidatorExited { .. })));
             }
-            BeaconState::Bellatrix(state) => bellatrix::state_transition(
-                state,
-                signed_block,
-                self.execution_engine.bellatrix(),
-                validation,
-                &self.context,
-            ),
         }
     }
 }
@@ -3,22 +3,26 @@ mod beacon_state;
 mod context;
 mod execution_engine;
 mod executor;
+mod observer;
 mod presets;
+mod pubkey_cache;
+mod shuffling_cache;
+mod signature_batch;
+mod validation;
 
 pub use beacon_block::*;
 pub use beacon_state::*;
 pub use context::*;
 pub use execution_engine::*;
 pub use executor::*;
+pub use observer::*;
+pub use pubkey_cache::*;
+pub use shuffling_cache::*;
+pub use signature_batch::*;
+pub use validation::*;
 
 pub type Result<T> = std::result::Result<T, crate::Error>;
 
-#[derive(Clone, Copy)]
-pub enum Validation {
-    Enabled,
-    Disabled,
-}
-
 pub mod mainnet {
     pub use super::presets::mainnet::*;
 }
@@ -0,0 +1,69 @@
+use crate::{
+    capella::Withdrawal,
+    phase0::Checkpoint,
+    primitives::{BlsPublicKey, Epoch, ExecutionAddress, Gwei, ValidatorIndex},
+};
+
+/// A change made to a state by the state transition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The validator was dequeued for activation at `activation_epoch`.
+    ValidatorActivated {
+        index: ValidatorIndex,
+        activation_epoch: Epoch,
+    },
+    /// The validator initiated its exit, leaving the active set at `exit_epoch`.
+    ValidatorExited {
+        index: ValidatorIndex,
+        exit_epoch: Epoch,
+        withdrawable_epoch: Epoch,
+    },
+    ValidatorSlashed {
+        index: ValidatorIndex,
+        whistleblower_index: ValidatorIndex,
+    },
+    /// A deposit of `amount` was credited to the validator, which is new if `is_new_validator`.
+    DepositApplied {
+        index: ValidatorIndex,
+        public_key: BlsPublicKey,
+        amount: Gwei,
+        is_new_validator: bool,
+    },
+    WithdrawalProcessed(Withdrawal),
+    BlsToExecutionChangeApplied {
+        index: ValidatorIndex,
+        address: ExecutionAddress,
+    },
+    JustificationChanged {
+        previous_justified: Checkpoint,
+        current_justified: Checkpoint,
+    },
+    FinalizationChanged {
+        finalized: Checkpoint,
+    },
+    /// The sync committees were rotated at the start of `epoch`.
+    SyncCommitteeRotated {
+        epoch: Epoch,
+    },
+    EffectiveBalanceChanged {
+        index: ValidatorIndex,
+        previous: Gwei,
+        current: Gwei,
+    },
+}
+
+/// Receives the `Event`s emitted by the state transition.
+pub trait Observer {
+    fn on_event(&mut self, event: &Event);
+}
+
+// Discards every `Event`, for callers not interested in them.
+impl Observer for () {
+    fn on_event(&mut self, _: &Event) {}
+}
+
+impl Observer for Vec<Event> {
+    fn on_event(&mut self, event: &Event) {
+        self.push(event.clone());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    crypto::{DecompressedPublicKey, SignatureSet},
+    phase0::Validator,
+    primitives::{BlsPublicKey, BlsSignature, ValidatorIndex},
+    state_transition::Result,
+    Error,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Default)]
+struct Inner {
+    public_keys: Vec<BlsPublicKey>,
+    indices: HashMap<BlsPublicKey, ValidatorIndex>,
+    // decompressed lazily as validators are first used for signature verification
+    decompressed_public_keys: Vec<Option<DecompressedPublicKey>>,
+}
+
+impl Inner {
+    fn clear(&mut self) {
+        self.public_keys.clear();
+        self.indices.clear();
+        self.decompressed_public_keys.clear();
+    }
+
+    // Extend the cache with any validators it has not seen yet.
+    //
+    // Validator indices are assigned in the order of the deposit contract so the registry of any
+    // state on a given chain is a prefix of the registry of its descendants. The cache is only
+    // rebuilt if `validators` is from a different chain.
+    fn sync(&mut self, validators: &[Validator]) {
+        let count = self.public_keys.len().min(validators.len());
+        if count > 0 && self.public_keys[count - 1] != validators[count - 1].public_key {
+            self.clear();
+        }
+
+        for (index, validator) in validators.iter().enumerate().skip(self.public_keys.len()) {
+            self.public_keys.push(validator.public_key.clone());
+            self.indices.insert(validator.public_key.clone(), index);
+            self.decompressed_public_keys.push(None);
+        }
+    }
+
+    fn get_index(
+        &mut self,
+        public_key: &BlsPublicKey,
+        validators: &[Validator],
+    ) -> Option<ValidatorIndex> {
+        self.sync(validators);
+        let index = *self.indices.get(public_key)?;
+        let validator = validators.get(index)?;
+        if &validator.public_key == public_key {
+            Some(index)
+        } else {
+            // the cache has diverged from `validators` so fall back to a full rebuild
+            self.clear();
+            self.sync(validators);
+            self.indices.get(public_key).copied()
+        }
+    }
+
+    fn get_decompressed_public_key(
+        &mut self,
+        index: ValidatorIndex,
+        validators: &[Validator],
+    ) -> Result<DecompressedPublicKey> {
+        let validator = validators
+            .get(index)
+            .ok_or(Error::OutOfBounds { requested: index, bound: validators.len() })?;
+
+        self.sync(validators);
+        if self.public_keys[index] != validator.public_key {
+            self.clear();
+            self.sync(validators);
+        }
+        if let Some(public_key) = &self.decompressed_public_keys[index] {
+            return Ok(public_key.clone())
+        }
+
+        let public_key = DecompressedPublicKey::try_from(&validator.public_key)?;
+        self.decompressed_public_keys[index] = Some(public_key.clone());
+        Ok(public_key)
+    }
+}
+
+/// A cache from validator public keys to their index in the registry, along with the
+/// decompressed public key used for signature verification.
+///
+/// The cache is shared between clones so that a single `Context` can be reused across states.
+#[derive(Default, Clone)]
+pub struct PubkeyCache(Arc<Mutex<Inner>>);
+
+impl fmt::Debug for PubkeyCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.0.lock().expect("can lock");
+        f.debug_struct("PubkeyCache").field("public_keys", &inner.public_keys.len()).finish()
+    }
+}
+
+impl PubkeyCache {
+    /// Returns the index of the validator with `public_key` in `validators`, if any.
+    pub fn get_index(
+        &self,
+        public_key: &BlsPublicKey,
+        validators: &[Validator],
+    ) -> Option<ValidatorIndex> {
+        let mut inner = self.0.lock().expect("can lock");
+        inner.get_index(public_key, validators)
+    }
+
+    /// Returns the decompressed public key of the validator at `index` in `validators`.
+    pub fn get_decompressed_public_key(
+        &self,
+        index: ValidatorIndex,
+        validators: &[Validator],
+    ) -> Result<DecompressedPublicKey> {
+        let mut inner = self.0.lock().expect("can lock");
+        inner.get_decompressed_public_key(index, validators)
+    }
+
+    /// Returns the signature set for `signature` over `msg` by the validator at `index` in
+    /// `validators`.
+    pub fn signature_set(
+        &self,
+        validators: &[Validator],
+        index: ValidatorIndex,
+        msg: &[u8],
+        signature: &BlsSignature,
+    ) -> Result<SignatureSet> {
+        let public_key = self.get_decompressed_public_key(index, validators)?;
+        SignatureSet::new(vec![public_key], msg, signature).map_err(Into::into)
+    }
+
+    /// Returns the signature set for the aggregate `signature` over `msg` by the validators at
+    /// `indices` in `validators`.
+    pub fn aggregate_signature_set(
+        &self,
+        validators: &[Validator],
+        indices: &[ValidatorIndex],
+        msg: &[u8],
+        signature: &BlsSignature,
+    ) -> Result<SignatureSet> {
+        let public_keys = self.get_decompressed_public_keys(indices, validators)?;
+        SignatureSet::new(public_keys, msg, signature).map_err(Into::into)
+    }
+
+    fn get_decompressed_public_keys(
+        &self,
+        indices: &[ValidatorIndex],
+        validators: &[Validator],
+    ) -> Result<Vec<DecompressedPublicKey>> {
+        let mut inner = self.0.lock().expect("can lock");
+        indices.iter().map(|&index| inner.get_decompressed_public_key(index, validators)).collect()
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.0.lock().expect("can lock");
+        inner.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::SecretKey;
+
+    fn validator(seed: u8) -> Validator {
+        let secret_key = SecretKey::key_gen(&[seed; 32]).unwrap();
+        Validator { public_key: secret_key.public_key(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_pubkey_cache_follows_registry() {
+        let cache = PubkeyCache::default();
+        let mut validators = (0..4).map(validator).collect::<Vec<_>>();
+
+        assert_eq!(cache.get_index(&validators[2].public_key, &validators), Some(2));
+        let new_validator = validator(4);
+        assert_eq!(cache.get_index(&new_validator.public_key, &validators), None);
+
+        validators.push(new_validator);
+        assert_eq!(cache.get_index(&validators[4].public_key, &validators), Some(4));
+        let public_key = cache.get_decompressed_public_key(4, &validators).unwrap();
+        assert_eq!(BlsPublicKey::from(&public_key), validators[4].public_key);
+
+        // a registry from another chain
+        let validators = (5..8).map(validator).collect::<Vec<_>>();
+        assert_eq!(cache.get_index(&validators[0].public_key, &validators), Some(0));
+        assert!(cache.get_decompressed_public_key(3, &validators).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    crypto::hash,
+    phase0::{compute_shuffled_index, compute_shuffled_list},
+    primitives::{Bytes32, Epoch, Gwei, ValidatorIndex},
+    state_transition::{Context, Result},
+    Error,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+const SHUFFLING_CACHE_CAPACITY: usize = 16;
+const PROPOSER_CACHE_CAPACITY: usize = 64;
+
+struct Shuffling {
+    epoch: Epoch,
+    seed: Bytes32,
+    shuffled_indices: Arc<Vec<ValidatorIndex>>,
+}
+
+struct ProposerSelection {
+    seed: Bytes32,
+    // every candidate considered, with the effective balance it was considered with,
+    // so that a hit can be checked against the current state
+    candidates: Vec<(ValidatorIndex, Gwei)>,
+}
+
+#[derive(Default)]
+struct Inner {
+    shufflings: VecDeque<Shuffling>,
+    proposers: VecDeque<ProposerSelection>,
+}
+
+/// A cache of committee shufflings and proposer selections, keyed by the epoch and seed they are
+/// computed for.
+///
+/// The seed of an epoch mixes in randao from after the active validators of the epoch are fixed,
+/// as activations and exits only take effect `MAX_SEED_LOOKAHEAD` epochs after they are
+/// processed, so every state with the same seed for an epoch has the same active validators in
+/// it. The active validator indices are then only computed when an entry is missing.
+///
+/// The cache is shared between clones so that a single `Context` can be reused across states.
+#[derive(Default, Clone)]
+pub struct ShufflingCache(Arc<Mutex<Inner>>);
+
+impl fmt::Debug for ShufflingCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.0.lock().expect("can lock");
+        f.debug_struct("ShufflingCache")
+            .field("shufflings", &inner.shufflings.len())
+            .field("proposers", &inner.proposers.len())
+            .finish()
+    }
+}
+
+impl ShufflingCache {
+    /// Returns the whole-list shuffling of the active validator indices of `epoch` under `seed`.
+    /// If missing, it is computed from the indices returned by `active_validator_indices`.
+    pub fn get_or_compute_shuffling<F>(
+        &self,
+        epoch: Epoch,
+        seed: &Bytes32,
+        active_validator_indices: F,
+        context: &Context,
+    ) -> Arc<Vec<ValidatorIndex>>
+    where
+        F: FnOnce() -> Vec<ValidatorIndex>,
+    {
+        {
+            let inner = self.0.lock().expect("can lock");
+            let entry =
+                inner.shufflings.iter().find(|entry| entry.epoch == epoch && &entry.seed == seed);
+            if let Some(entry) = entry {
+                return entry.shuffled_indices.clone()
+            }
+        }
+
+        let indices = active_validator_indices();
+        let shuffled_indices = Arc::new(compute_shuffled_list(&indices, seed, context));
+
+        let mut inner = self.0.lock().expect("can lock");
+        if inner.shufflings.len() == SHUFFLING_CACHE_CAPACITY {
+            inner.shufflings.pop_front();
+        }
+        inner.shufflings.push_back(Shuffling {
+            epoch,
+            seed: seed.clone(),
+            shuffled_indices: shuffled_indices.clone(),
+        });
+        shuffled_indices
+    }
+
+    /// Returns the proposer selected under `seed` from the indices returned by
+    /// `active_validator_indices`, following `compute_proposer_index`. The effective balance of
+    /// a validator is read with `effective_balance` so that a cached selection is only reused if
+    /// every candidate it considered still has the same effective balance.
+    pub fn get_or_compute_proposer<F, G>(
+        &self,
+        seed: &Bytes32,
+        active_validator_indices: F,
+        effective_balance: G,
+        context: &Context,
+    ) -> Result<ValidatorIndex>
+    where
+        F: FnOnce() -> Vec<ValidatorIndex>,
+        G: Fn(ValidatorIndex) -> Gwei,
+    {
+        {
+            let inner = self.0.lock().expect("can lock");
+            let entry = inner.proposers.iter().find(|entry| {
+                &entry.seed == seed &&
+                    entry
+                        .candidates
+                        .iter()
+                        .all(|(index, balance)| effective_balance(*index) == *balance)
+            });
+            if let Some(entry) = entry {
+                let (proposer, _) = entry.candidates.last().expect("at least one candidate");
+                return Ok(*proposer)
+            }
+        }
+
+        let indices = active_validator_indices();
+        let candidates = compute_proposer_candidates(&indices, seed, effective_balance, context)?;
+        let (proposer, _) = *candidates.last().expect("at least one candidate");
+
+        let mut inner = self.0.lock().expect("can lock");
+        if inner.proposers.len() == PROPOSER_CACHE_CAPACITY {
+            inner.proposers.pop_front();
+        }
+        inner.proposers.push_back(ProposerSelection { seed: seed.clone(), candidates });
+        Ok(proposer)
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.0.lock().expect("can lock");
+        inner.shufflings.clear();
+        inner.proposers.clear();
+    }
+}
+
+// Returns every candidate considered by `compute_proposer_index`, ending with the proposer.
+fn compute_proposer_candidates<F>(
+    indices: &[ValidatorIndex],
+    seed: &Bytes32,
+    effective_balance: F,
+    context: &Context,
+) -> Result<Vec<(ValidatorIndex, Gwei)>>
+where
+    F: Fn(ValidatorIndex) -> Gwei,
+{
+    if indices.is_empty() {
+        return Err(Error::CollectionCannotBeEmpty)
+    }
+    let max_byte = u8::MAX as u64;
+    let mut i = 0;
+    let total = indices.len();
+    let mut candidates = vec![];
+
+    let mut hash_input = [0u8; 40];
+    hash_input[..32].copy_from_slice(seed.as_ref());
+    loop {
+        let shuffled_index = compute_shuffled_index(i % total, total, seed, context)?;
+        let candidate_index = indices[shuffled_index];
+
+        let i_bytes: [u8; 8] = (i / 32).to_le_bytes();
+        hash_input[32..].copy_from_slice(&i_bytes);
+        let random_byte = hash(hash_input).as_ref()[i % 32] as u64;
+
+        let balance = effective_balance(candidate_index);
+        candidates.push((candidate_index, balance));
+        if balance * max_byte >= context.max_effective_balance * random_byte {
+            return Ok(candidates)
+        }
+        i += 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_proposer_checks_balances() {
+        let context = Context::for_minimal();
+        let cache = ShufflingCache::default();
+        let seed = hash([11u8; 32]);
+        let indices = (0..64).collect::<Vec<_>>();
+
+        let balance = |_| context.max_effective_balance;
+        let proposer =
+            cache.get_or_compute_proposer(&seed, || indices.clone(), balance, &context).unwrap();
+        let candidates = compute_proposer_candidates(&indices, &seed, balance, &context).unwrap();
+        assert_eq!(candidates.last().unwrap().0, proposer);
+
+        // changing the balance of a considered candidate must not return the stale selection
+        let balance = |index| if index == proposer { 0 } else { context.max_effective_balance };
+        let other =
+            cache.get_or_compute_proposer(&seed, || indices.clone(), balance, &context).unwrap();
+        let candidates = compute_proposer_candidates(&indices, &seed, balance, &context).unwrap();
+        assert_eq!(candidates.last().unwrap().0, other);
+    }
+
+    #[test]
+    fn test_cached_shuffling_skips_active_indices() {
+        let context = Context::for_minimal();
+        let cache = ShufflingCache::default();
+        let seed = hash([7u8; 32]);
+        let indices = (0..64).collect::<Vec<_>>();
+
+        let shuffling = cache.get_or_compute_shuffling(2, &seed, || indices.clone(), &context);
+        assert_eq!(*shuffling, compute_shuffled_list(&indices, &seed, &context));
+        let cached = cache.get_or_compute_shuffling(
+            2,
+            &seed,
+            || unreachable!("the active indices are not needed on a hit"),
+            &context,
+        );
+        assert!(Arc::ptr_eq(&cached, &shuffling));
+
+        // another epoch with the same seed is a miss
+        let other_indices = (0..32).collect::<Vec<_>>();
+        let other = cache.get_or_compute_shuffling(3, &seed, || other_indices.clone(), &context);
+        assert_eq!(*other, compute_shuffled_list(&other_indices, &seed, &context));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{
+    crypto::{self, verify_signature_sets, SignatureSet},
+    state_transition::{Result, Validation},
+    Error,
+};
+
+/// How a `SignatureSets` verifies the signatures given to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SignatureStrategy {
+    /// Verify each signature as soon as it is given.
+    #[default]
+    VerifyEach,
+    /// Defer every signature and verify them together in one batch in `SignatureSets::finish`.
+    Batch,
+    /// Do not verify signatures.
+    Skip,
+}
+
+/// Collects the signatures checked while processing a block, verifying them according to its
+/// `SignatureStrategy`.
+#[derive(Default)]
+pub struct SignatureSets {
+    strategy: SignatureStrategy,
+    // Signature sets deferred by `verify` along with the error to return for each if it turns out
+    // to be invalid.
+    sets: Vec<SignatureSet>,
+    errors: Vec<Error>,
+}
+
+impl SignatureSets {
+    pub fn new(strategy: SignatureStrategy) -> Self {
+        Self { strategy, ..Default::default() }
+    }
+
+    /// Returns the `SignatureSets` performing the signature checks requested by `validation`.
+    pub fn for_validation(validation: &Validation) -> Self {
+        let strategy = match (validation.signatures, validation.batch_signatures) {
+            (false, _) => SignatureStrategy::Skip,
+            (true, false) => SignatureStrategy::VerifyEach,
+            (true, true) => SignatureStrategy::Batch,
+        };
+        Self::new(strategy)
+    }
+
+    /// Verifies the signature `set`, returning the error from `error` if it is invalid.
+    ///
+    /// When batching, verification is deferred to `finish` and this function returns `Ok(())`
+    /// for any well-formed `set`.
+    pub fn verify<F>(&mut self, set: Result<SignatureSet>, error: F) -> Result<()>
+    where
+        F: FnOnce() -> Error,
+    {
+        if self.strategy == SignatureStrategy::Skip {
+            return Ok(())
+        }
+        let set = match set {
+            Ok(set) => set,
+            Err(_) => return Err(error()),
+        };
+        match self.strategy {
+            SignatureStrategy::Batch => {
+                self.sets.push(set);
+                self.errors.push(error());
+                Ok(())
+            }
+            _ => set.verify().map_err(|_| error()),
+        }
+    }
+
+    /// Verifies any deferred signatures together in one batch, given the `result` of the
+    /// processing that deferred them.
+    ///
+    /// If the batch is invalid, each signature is checked in the order it was deferred so that the
+    /// error matches the one the processing would return without batching.
+    pub fn finish<T>(self, result: Result<T>) -> Result<T> {
+        match result {
+            Ok(value) => {
+                if verify_signature_sets(&self.sets).is_ok() {
+                    Ok(value)
+                } else {
+                    Err(self.first_invalid().unwrap_or(crypto::Error::InvalidSignature.into()))
+                }
+            }
+            // any invalid signature deferred before the error would have been returned first
+            Err(err) => Err(self.first_invalid().unwrap_or(err)),
+        }
+    }
+
+    fn first_invalid(self) -> Option<Error> {
+        self.sets.iter().zip(self.errors).find_map(|(set, error)| set.verify().err().map(|_| error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{DecompressedPublicKey, SecretKey};
+
+    fn signature_set(seed: u8, message: &[u8; 32], valid: bool) -> SignatureSet {
+        let secret_key = SecretKey::key_gen(&[seed; 32]).unwrap();
+        let public_key = DecompressedPublicKey::try_from(&secret_key.public_key()).unwrap();
+        let signature = if valid { secret_key.sign(message) } else { secret_key.sign(&[0u8; 32]) };
+        SignatureSet::new(vec![public_key], message, &signature).unwrap()
+    }
+
+    fn verify_all(signatures: &mut SignatureSets, sets: Vec<(SignatureSet, Error)>) -> Result<()> {
+        for (set, error) in sets {
+            signatures.verify(Ok(set), || error)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch_reports_first_invalid_signature() {
+        let message = [1u8; 32];
+        let mut signatures = SignatureSets::new(SignatureStrategy::Batch);
+        let sets = vec![
+            (signature_set(1, &message, true), Error::InvalidStateRoot),
+            (signature_set(2, &message, false), Error::Overflow),
+            (signature_set(3, &message, false), Error::Underflow),
+        ];
+        let result = verify_all(&mut signatures, sets);
+        assert!(result.is_ok());
+        assert!(matches!(signatures.finish(result), Err(Error::Overflow)));
+
+        let mut signatures = SignatureSets::new(SignatureStrategy::Batch);
+        let sets =
+            (0..8).map(|seed| (signature_set(seed, &message, true), Error::InvalidStateRoot));
+        let result = verify_all(&mut signatures, sets.collect());
+        assert!(signatures.finish(result).is_ok());
+
+        let mut signatures = SignatureSets::new(SignatureStrategy::Skip);
+        let sets = vec![(signature_set(4, &message, false), Error::Overflow)];
+        let result = verify_all(&mut signatures, sets);
+        assert!(signatures.finish(result).is_ok());
+    }
+}
@@ -0,0 +1,50 @@
+/// The checks performed when applying a block to a state.
+///
+/// The checks not covered here, like those of the block header and each operation, are always
+/// performed as they determine how the block changes the state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Validation {
+    /// Verify the BLS signatures over the block and its operations.
+    ///
+    /// NOTE: deposit signatures are always verified as an invalid deposit signature changes the
+    /// state rather than invalidating the block.
+    pub signatures: bool,
+    /// Verify all signatures in a block together in one batch, if `signatures` is set.
+    pub batch_signatures: bool,
+    /// Check the state root of the block against the post-state.
+    pub state_root: bool,
+    /// Verify the execution payload with the execution engine.
+    pub execution_payload: bool,
+    /// Check the blob KZG commitments in the block body.
+    pub kzg_commitments: bool,
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Self::enabled()
+    }
+}
+
+impl Validation {
+    /// Perform every check.
+    pub const fn enabled() -> Self {
+        Self {
+            signatures: true,
+            batch_signatures: false,
+            state_root: true,
+            execution_payload: true,
+            kzg_commitments: true,
+        }
+    }
+
+    /// Perform none of the optional checks.
+    pub const fn disabled() -> Self {
+        Self {
+            signatures: false,
+            batch_signatures: false,
+            state_root: false,
+            execution_payload: false,
+            kzg_commitments: false,
+        }
+    }
+}
@@ -12,7 +12,7 @@ fn test_attestation() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -26,7 +26,7 @@ fn test_attester_slashing() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -38,7 +38,7 @@ fn test_balance_driven_status_transitions() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -52,7 +52,7 @@ fn test_deposit_in_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -66,7 +66,7 @@ fn test_deposit_top_up() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -78,7 +78,7 @@ fn test_double_same_proposer_slashings_same_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -90,7 +90,7 @@ fn test_double_similar_proposer_slashings_same_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -102,7 +102,7 @@ fn test_double_validator_exit_same_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -114,7 +114,7 @@ fn test_duplicate_attester_slashing() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -126,7 +126,7 @@ fn test_empty_block_transition() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -138,7 +138,7 @@ fn test_empty_epoch_transition() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -150,7 +150,7 @@ fn test_empty_sync_committee_committee() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -162,7 +162,7 @@ fn test_empty_sync_committee_committee_genesis() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -174,7 +174,7 @@ fn test_expected_deposit_in_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -186,7 +186,7 @@ fn test_full_random_operations_0() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -198,7 +198,7 @@ fn test_full_random_operations_1() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -210,7 +210,7 @@ fn test_full_random_operations_2() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -222,7 +222,7 @@ fn test_full_random_operations_3() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -234,7 +234,7 @@ fn test_full_sync_committee_committee() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -246,7 +246,7 @@ fn test_full_sync_committee_committee_genesis() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -258,7 +258,7 @@ fn test_half_sync_committee_committee() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -270,7 +270,7 @@ fn test_half_sync_committee_committee_genesis() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -282,7 +282,7 @@ fn test_high_proposer_index() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -296,7 +296,7 @@ fn test_historical_batch() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -308,7 +308,7 @@ fn test_inactivity_scores_full_participation_leaking() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -320,7 +320,7 @@ fn test_inactivity_scores_leaking() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -334,7 +334,7 @@ fn test_invalid_block_sig() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -346,7 +346,7 @@ fn test_invalid_proposer_index_sig_from_expected_proposer() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -358,7 +358,7 @@ fn test_invalid_proposer_index_sig_from_proposer_index() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -370,7 +370,7 @@ fn test_invalid_state_root() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -382,7 +382,7 @@ fn test_multiple_attester_slashings_no_overlap() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -394,7 +394,7 @@ fn test_multiple_attester_slashings_partial_overlap() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -406,7 +406,7 @@ fn test_multiple_different_proposer_slashings_same_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -418,7 +418,7 @@ fn test_multiple_different_validator_exits_same_block() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -430,7 +430,7 @@ fn test_parent_from_same_slot() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -442,7 +442,7 @@ fn test_prev_slot_block_transition() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -454,7 +454,7 @@ fn test_proposer_after_inactive_index() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -466,7 +466,7 @@ fn test_proposer_self_slashing() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -480,7 +480,7 @@ fn test_proposer_slashing() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -492,7 +492,7 @@ fn test_same_slot_block_transition() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -506,7 +506,7 @@ fn test_skipped_slots() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -518,7 +518,7 @@ fn test_slash_and_exit_diff_index() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -530,7 +530,7 @@ fn test_slash_and_exit_same_index() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -544,7 +544,7 @@ fn test_voluntary_exit() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -558,7 +558,7 @@ fn test_zero_block_sig() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
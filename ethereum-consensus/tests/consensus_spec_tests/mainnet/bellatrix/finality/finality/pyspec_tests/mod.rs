@@ -10,7 +10,7 @@ fn test_finality_no_updates_at_genesis() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -22,7 +22,7 @@ fn test_finality_rule_1() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -34,7 +34,7 @@ fn test_finality_rule_2() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -46,7 +46,7 @@ fn test_finality_rule_3() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -58,7 +58,7 @@ fn test_finality_rule_4() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -12,7 +12,7 @@ fn test_attestation() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -24,7 +24,7 @@ fn test_attester_slashing() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -36,7 +36,7 @@ fn test_balance_driven_status_transitions() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -48,7 +48,7 @@ fn test_deposit_in_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -62,7 +62,7 @@ fn test_deposit_top_up() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -74,7 +74,7 @@ fn test_double_same_proposer_slashings_same_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -86,7 +86,7 @@ fn test_double_similar_proposer_slashings_same_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -98,7 +98,7 @@ fn test_double_validator_exit_same_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -110,7 +110,7 @@ fn test_duplicate_attester_slashing() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -122,7 +122,7 @@ fn test_empty_block_transition() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -134,7 +134,7 @@ fn test_empty_block_transition_no_tx() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -146,7 +146,7 @@ fn test_empty_epoch_transition() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -158,7 +158,7 @@ fn test_empty_sync_committee_committee() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -170,7 +170,7 @@ fn test_empty_sync_committee_committee_genesis() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -182,7 +182,7 @@ fn test_expected_deposit_in_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -194,7 +194,7 @@ fn test_full_random_operations_0() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -206,7 +206,7 @@ fn test_full_random_operations_1() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -218,7 +218,7 @@ fn test_full_random_operations_2() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -230,7 +230,7 @@ fn test_full_random_operations_3() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -242,7 +242,7 @@ fn test_full_sync_committee_committee() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -254,7 +254,7 @@ fn test_full_sync_committee_committee_genesis() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -266,7 +266,7 @@ fn test_half_sync_committee_committee() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -278,7 +278,7 @@ fn test_half_sync_committee_committee_genesis() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -290,7 +290,7 @@ fn test_high_proposer_index() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -302,7 +302,7 @@ fn test_historical_batch() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -314,7 +314,7 @@ fn test_inactivity_scores_full_participation_leaking() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -326,7 +326,7 @@ fn test_inactivity_scores_leaking() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -338,7 +338,7 @@ fn test_invalid_block_sig() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -350,7 +350,7 @@ fn test_invalid_proposer_index_sig_from_expected_proposer() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -362,7 +362,7 @@ fn test_invalid_proposer_index_sig_from_proposer_index() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -374,7 +374,7 @@ fn test_invalid_state_root() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -386,7 +386,7 @@ fn test_is_execution_enabled_false() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -398,7 +398,7 @@ fn test_multiple_attester_slashings_no_overlap() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -410,7 +410,7 @@ fn test_multiple_attester_slashings_partial_overlap() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -422,7 +422,7 @@ fn test_multiple_different_proposer_slashings_same_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -434,7 +434,7 @@ fn test_multiple_different_validator_exits_same_block() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -446,7 +446,7 @@ fn test_parent_from_same_slot() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -458,7 +458,7 @@ fn test_prev_slot_block_transition() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -470,7 +470,7 @@ fn test_proposer_after_inactive_index() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -482,7 +482,7 @@ fn test_proposer_self_slashing() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -494,7 +494,7 @@ fn test_proposer_slashing() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -506,7 +506,7 @@ fn test_same_slot_block_transition() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -520,7 +520,7 @@ fn test_skipped_slots() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -532,7 +532,7 @@ fn test_slash_and_exit_diff_index() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -544,7 +544,7 @@ fn test_slash_and_exit_same_index() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -558,7 +558,7 @@ fn test_voluntary_exit() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -572,7 +572,7 @@ fn test_zero_block_sig() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -2,40 +2,101 @@
 // This file was generated with `gen-tests`. Do NOT edit manually.
 
 use crate::spec_test_runners::fork_choice::GetHeadTestCase;
+use ethereum_consensus::{
+    fork_choice::mainnet::Store,
+    phase0::{mainnet as spec, BeaconBlockHeader},
+    ssz::prelude::*,
+    state_transition::mainnet::ExecutionEngine,
+};
 
 #[test]
 fn test_chain_no_attestations() {
-    let  test_case = GetHeadTestCase::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/chain_no_attestations");
+    let mut test_case = GetHeadTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/chain_no_attestations");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_genesis() {
-    let test_case = GetHeadTestCase::from(
+    let mut test_case = GetHeadTestCase::<spec::SignedBeaconBlock>::from(
         "../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/genesis",
     );
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_proposer_boost_correct_head() {
-    let  test_case = GetHeadTestCase::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/proposer_boost_correct_head");
+    let mut test_case = GetHeadTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/proposer_boost_correct_head");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_shorter_chain_but_heavier_weight() {
-    let  test_case = GetHeadTestCase::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/shorter_chain_but_heavier_weight");
+    let mut test_case = GetHeadTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/shorter_chain_but_heavier_weight");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_split_tie_breaker_no_attestations() {
-    let  test_case = GetHeadTestCase::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/split_tie_breaker_no_attestations");
+    let mut test_case = GetHeadTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/mainnet/phase0/fork_choice/get_head/pyspec_tests/split_tie_breaker_no_attestations");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
@@ -12,7 +12,7 @@ fn test_double_empty_epoch() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -24,7 +24,7 @@ fn test_empty_epoch() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -34,7 +34,7 @@ fn test_over_epoch_boundary() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -46,7 +46,7 @@ fn test_slots_1() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -58,6 +58,6 @@ fn test_slots_2() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
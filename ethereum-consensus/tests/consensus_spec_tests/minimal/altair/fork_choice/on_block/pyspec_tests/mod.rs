@@ -2,103 +2,261 @@
 // This file was generated with `gen-tests`. Do NOT edit manually.
 
 use crate::spec_test_runners::fork_choice::OnBlockTestCase;
+use ethereum_consensus::{
+    altair::minimal as spec, fork_choice::minimal::Store, phase0::BeaconBlockHeader,
+    ssz::prelude::*, state_transition::minimal::ExecutionEngine,
+};
 
 #[test]
 fn test_basic() {
-    let test_case = OnBlockTestCase::from(
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from(
         "../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/basic",
     );
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_new_finalized_slot_is_justified_checkpoint_ancestor() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/new_finalized_slot_is_justified_checkpoint_ancestor");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/new_finalized_slot_is_justified_checkpoint_ancestor");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_new_finalized_slot_is_not_justified_checkpoint_ancestor() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/new_finalized_slot_is_not_justified_checkpoint_ancestor");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/new_finalized_slot_is_not_justified_checkpoint_ancestor");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_new_justified_is_later_than_store_justified() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/new_justified_is_later_than_store_justified");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/new_justified_is_later_than_store_justified");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_bad_parent_root() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_bad_parent_root");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_bad_parent_root");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_before_finalized() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_before_finalized");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_before_finalized");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_checkpoints() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_checkpoints");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_checkpoints");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_finalized_skip_slots() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_finalized_skip_slots");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_finalized_skip_slots");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_finalized_skip_slots_not_in_skip_chain() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_finalized_skip_slots_not_in_skip_chain");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_finalized_skip_slots_not_in_skip_chain");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_future_block() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_future_block");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_future_block");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_outside_safe_slots_but_finality() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_outside_safe_slots_but_finality");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_outside_safe_slots_but_finality");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_on_block_update_justified_checkpoint_within_safe_slots() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_update_justified_checkpoint_within_safe_slots");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/on_block_update_justified_checkpoint_within_safe_slots");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_proposer_boost() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/proposer_boost");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/proposer_boost");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_proposer_boost_root_same_slot_untimely_block() {
-    let  test_case = OnBlockTestCase::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/proposer_boost_root_same_slot_untimely_block");
+    let mut test_case = OnBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/altair/fork_choice/on_block/pyspec_tests/proposer_boost_root_same_slot_untimely_block");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
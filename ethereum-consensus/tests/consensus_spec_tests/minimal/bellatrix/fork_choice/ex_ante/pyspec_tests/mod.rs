@@ -2,24 +2,61 @@
 // This file was generated with `gen-tests`. Do NOT edit manually.
 
 use crate::spec_test_runners::fork_choice::ExAnteTestCase;
+use ethereum_consensus::{
+    bellatrix::minimal as spec, fork_choice::minimal::Store, phase0::BeaconBlockHeader,
+    ssz::prelude::*, state_transition::minimal::ExecutionEngine,
+};
 
 #[test]
 fn test_ex_ante_sandwich_with_honest_attestation() {
-    let  test_case = ExAnteTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/ex_ante/pyspec_tests/ex_ante_sandwich_with_honest_attestation");
+    let mut test_case = ExAnteTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/ex_ante/pyspec_tests/ex_ante_sandwich_with_honest_attestation");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_ex_ante_sandwich_without_attestations() {
-    let  test_case = ExAnteTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/ex_ante/pyspec_tests/ex_ante_sandwich_without_attestations");
+    let mut test_case = ExAnteTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/ex_ante/pyspec_tests/ex_ante_sandwich_without_attestations");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_ex_ante_vanilla() {
-    let  test_case = ExAnteTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/ex_ante/pyspec_tests/ex_ante_vanilla");
+    let mut test_case = ExAnteTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/ex_ante/pyspec_tests/ex_ante_vanilla");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
@@ -2,31 +2,79 @@
 // This file was generated with `gen-tests`. Do NOT edit manually.
 
 use crate::spec_test_runners::fork_choice::OnMergeBlockTestCase;
+use ethereum_consensus::{
+    bellatrix::minimal as spec, fork_choice::minimal::Store, phase0::BeaconBlockHeader,
+    ssz::prelude::*, state_transition::minimal::ExecutionEngine,
+};
 
 #[test]
 fn test_all_valid() {
-    let  test_case = OnMergeBlockTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/all_valid");
+    let mut test_case = OnMergeBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/all_valid");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_block_lookup_failed() {
-    let  test_case = OnMergeBlockTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/block_lookup_failed");
+    let mut test_case = OnMergeBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/block_lookup_failed");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_too_early_for_merge() {
-    let  test_case = OnMergeBlockTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/too_early_for_merge");
+    let mut test_case = OnMergeBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/too_early_for_merge");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
 
 #[test]
 fn test_too_late_for_merge() {
-    let  test_case = OnMergeBlockTestCase::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/too_late_for_merge");
+    let mut test_case = OnMergeBlockTestCase::<spec::SignedBeaconBlock>::from("../consensus-spec-tests/tests/minimal/bellatrix/fork_choice/on_merge_block/pyspec_tests/too_late_for_merge");
 
-    test_case.execute();
+    test_case.execute(
+        |anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+            let anchor_block = BeaconBlockHeader {
+                slot: anchor_block.slot,
+                proposer_index: anchor_block.proposer_index,
+                parent_root: anchor_block.parent_root,
+                state_root: anchor_block.state_root,
+                body_root: anchor_block.body.hash_tree_root()?,
+            };
+            Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+        },
+    );
 }
@@ -10,7 +10,13 @@ fn test_bad_everything_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -20,7 +26,13 @@ fn test_bad_execution_first_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -30,7 +42,13 @@ fn test_bad_execution_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -40,7 +58,13 @@ fn test_bad_parent_hash_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -50,7 +74,13 @@ fn test_bad_random_first_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -60,7 +90,13 @@ fn test_bad_random_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -70,7 +106,13 @@ fn test_bad_timestamp_first_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -80,7 +122,13 @@ fn test_bad_timestamp_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -90,7 +138,13 @@ fn test_non_empty_extra_data_first_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -100,7 +154,13 @@ fn test_non_empty_extra_data_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -110,7 +170,13 @@ fn test_success_first_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -120,7 +186,13 @@ fn test_success_first_payload_with_gap_slot() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -130,7 +202,13 @@ fn test_success_regular_payload() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
 
@@ -140,6 +218,12 @@ fn test_success_regular_payload_with_gap_slot() {
 
     test_case.execute(|state, operation, context, execution_valid| {
         let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-        spec::process_execution_payload(state, operation, &execution_engine, context)
+        spec::process_execution_payload(
+            state,
+            operation,
+            &execution_engine,
+            spec::Validation::default(),
+            context,
+        )
     });
 }
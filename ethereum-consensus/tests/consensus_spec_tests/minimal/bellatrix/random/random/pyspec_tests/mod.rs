@@ -12,7 +12,7 @@ fn test_randomized_0() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -26,7 +26,7 @@ fn test_randomized_1() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -40,7 +40,7 @@ fn test_randomized_10() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -54,7 +54,7 @@ fn test_randomized_11() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -68,7 +68,7 @@ fn test_randomized_12() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -82,7 +82,7 @@ fn test_randomized_13() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -96,7 +96,7 @@ fn test_randomized_14() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -110,7 +110,7 @@ fn test_randomized_15() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -124,7 +124,7 @@ fn test_randomized_2() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -138,7 +138,7 @@ fn test_randomized_3() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -152,7 +152,7 @@ fn test_randomized_4() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -166,7 +166,7 @@ fn test_randomized_5() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -180,7 +180,7 @@ fn test_randomized_6() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -194,7 +194,7 @@ fn test_randomized_7() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -208,7 +208,7 @@ fn test_randomized_8() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -222,7 +222,7 @@ fn test_randomized_9() {
     let execution_engine = spec::DefaultExecutionEngine::default();
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, &execution_engine, validation, context)?;
+            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -10,7 +10,7 @@ fn test_double_empty_epoch() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -22,7 +22,7 @@ fn test_empty_epoch() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -32,7 +32,7 @@ fn test_over_epoch_boundary() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -44,7 +44,7 @@ fn test_slots_1() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
 
@@ -56,6 +56,6 @@ fn test_slots_2() {
 
     test_case.execute(|state, offset, context| {
         let target_slot = state.slot + offset;
-        spec::process_slots(state, target_slot, context).unwrap();
+        spec::process_slots(state, target_slot, &mut (), context).unwrap();
     });
 }
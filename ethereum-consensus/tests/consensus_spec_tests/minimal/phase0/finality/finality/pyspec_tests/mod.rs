@@ -10,7 +10,7 @@ fn test_finality_no_updates_at_genesis() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -22,7 +22,7 @@ fn test_finality_rule_1() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -34,7 +34,7 @@ fn test_finality_rule_2() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -46,7 +46,7 @@ fn test_finality_rule_3() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -58,7 +58,7 @@ fn test_finality_rule_4() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -12,7 +12,7 @@ fn test_randomized_0() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -26,7 +26,7 @@ fn test_randomized_1() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -40,7 +40,7 @@ fn test_randomized_10() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -54,7 +54,7 @@ fn test_randomized_11() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -68,7 +68,7 @@ fn test_randomized_12() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -82,7 +82,7 @@ fn test_randomized_13() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -96,7 +96,7 @@ fn test_randomized_14() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -110,7 +110,7 @@ fn test_randomized_15() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -124,7 +124,7 @@ fn test_randomized_2() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -138,7 +138,7 @@ fn test_randomized_3() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -152,7 +152,7 @@ fn test_randomized_4() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -166,7 +166,7 @@ fn test_randomized_5() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -180,7 +180,7 @@ fn test_randomized_6() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -194,7 +194,7 @@ fn test_randomized_7() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -208,7 +208,7 @@ fn test_randomized_8() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
@@ -222,7 +222,7 @@ fn test_randomized_9() {
 
     test_case.execute(|state, blocks, validation, context| {
         for block in blocks.iter_mut() {
-            spec::state_transition(state, block, validation, context)?;
+            spec::state_transition(state, block, validation, &mut (), context)?;
         }
         Ok(())
     });
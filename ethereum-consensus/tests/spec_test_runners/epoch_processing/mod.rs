@@ -1,5 +1,5 @@
 use crate::test_utils::{load_snappy_ssz, Config};
-use ethereum_consensus::state_transition::{Context, Result};
+use ethereum_consensus::state_transition::{Context, Observer, Result};
 use std::fmt;
 
 fn load_epoch_test<S: ssz_rs::Deserialize>(test_case_path: &str) -> (S, Option<S>, Config) {
@@ -159,13 +159,13 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &mut dyn Observer, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
-        let result = f(&mut self.pre, &context);
+        let result = f(&mut self.pre, &mut (), &context);
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
         } else {
@@ -415,13 +415,13 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &mut dyn Observer, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
-        let result = f(&mut self.pre, &context);
+        let result = f(&mut self.pre, &mut (), &context);
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
         } else {
@@ -53,7 +53,7 @@ where
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
-        let result = f(&mut self.pre, &mut self.blocks, Validation::Enabled, &context);
+        let result = f(&mut self.pre, &mut self.blocks, Validation::enabled(), &context);
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
         } else {
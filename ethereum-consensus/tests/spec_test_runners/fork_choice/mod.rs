@@ -1,47 +1,253 @@
-pub struct ExAnteTestCase;
+use crate::test_utils::{load_snappy_ssz, load_yaml, Config};
+use ethereum_consensus::{
+    bellatrix::{self, PowBlock},
+    capella, deneb,
+    fork_choice::Store,
+    phase0::{Attestation, AttesterSlashing},
+    primitives::{Epoch, Root, Slot},
+    state_transition::{Context, Result, SignedBeaconBlock},
+};
+use serde::Deserialize;
+use std::marker::PhantomData;
 
-impl ExAnteTestCase {
-    pub fn from(test_case_path: &str) -> Self {
-        Self
-    }
+#[derive(Debug, Deserialize)]
+struct Head {
+    slot: Slot,
+    root: Root,
+}
 
-    pub fn execute(&self) {
-        unimplemented!();
-    }
+#[derive(Debug, Deserialize)]
+struct CheckpointCheck {
+    epoch: Epoch,
+    root: Root,
 }
 
-pub struct GetHeadTestCase;
+#[derive(Debug, Deserialize)]
+struct Checks {
+    time: Option<u64>,
+    head: Option<Head>,
+    justified_checkpoint: Option<CheckpointCheck>,
+    finalized_checkpoint: Option<CheckpointCheck>,
+    best_justified_checkpoint: Option<CheckpointCheck>,
+    proposer_boost_root: Option<Root>,
+}
 
-impl GetHeadTestCase {
-    pub fn from(test_case_path: &str) -> Self {
-        Self
-    }
+fn default_valid() -> bool {
+    true
+}
 
-    pub fn execute(&self) {
-        unimplemented!();
-    }
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Step {
+    Checks {
+        checks: Box<Checks>,
+    },
+    Tick {
+        tick: u64,
+    },
+    Block {
+        block: String,
+        #[serde(default = "default_valid")]
+        valid: bool,
+    },
+    Attestation {
+        attestation: String,
+        #[serde(default = "default_valid")]
+        valid: bool,
+    },
+    AttesterSlashing {
+        attester_slashing: String,
+        #[serde(default = "default_valid")]
+        valid: bool,
+    },
+    PowBlock {
+        pow_block: String,
+    },
+}
+
+pub struct ForkChoiceTestCase<B> {
+    test_case_path: String,
+    steps: Vec<Step>,
+    config: Config,
+    _block: PhantomData<B>,
 }
 
-pub struct OnBlockTestCase;
+pub type ExAnteTestCase<B> = ForkChoiceTestCase<B>;
+pub type GetHeadTestCase<B> = ForkChoiceTestCase<B>;
+pub type OnBlockTestCase<B> = ForkChoiceTestCase<B>;
+pub type OnMergeBlockTestCase<B> = ForkChoiceTestCase<B>;
 
-impl OnBlockTestCase {
+impl<B> ForkChoiceTestCase<B>
+where
+    B: ssz_rs::Deserialize,
+{
     pub fn from(test_case_path: &str) -> Self {
-        Self
+        let path = test_case_path.to_string() + "/steps.yaml";
+        let steps: Vec<Step> = load_yaml(&path);
+
+        let config =
+            if test_case_path.contains("minimal") { Config::Minimal } else { Config::Mainnet };
+
+        Self { test_case_path: test_case_path.to_string(), steps, config, _block: PhantomData }
     }
 
-    pub fn execute(&self) {
-        unimplemented!();
+    fn load<T: ssz_rs::Deserialize>(&self, name: &str) -> T {
+        let path = format!("{}/{}.ssz_snappy", self.test_case_path, name);
+        load_snappy_ssz(&path).unwrap()
     }
-}
 
-pub struct OnMergeBlockTestCase;
+    pub fn execute<
+        const SLOTS_PER_HISTORICAL_ROOT: usize,
+        const HISTORICAL_ROOTS_LIMIT: usize,
+        const ETH1_DATA_VOTES_BOUND: usize,
+        const VALIDATOR_REGISTRY_LIMIT: usize,
+        const EPOCHS_PER_HISTORICAL_VECTOR: usize,
+        const EPOCHS_PER_SLASHINGS_VECTOR: usize,
+        const MAX_VALIDATORS_PER_COMMITTEE: usize,
+        const PENDING_ATTESTATIONS_BOUND: usize,
+        const SYNC_COMMITTEE_SIZE: usize,
+        const BYTES_PER_LOGS_BLOOM: usize,
+        const MAX_EXTRA_DATA_BYTES: usize,
+        const MAX_BYTES_PER_TRANSACTION: usize,
+        const MAX_TRANSACTIONS_PER_PAYLOAD: usize,
+        const MAX_PROPOSER_SLASHINGS: usize,
+        const MAX_ATTESTER_SLASHINGS: usize,
+        const MAX_ATTESTATIONS: usize,
+        const MAX_DEPOSITS: usize,
+        const MAX_VOLUNTARY_EXITS: usize,
+        const MAX_WITHDRAWALS_PER_PAYLOAD: usize,
+        const MAX_BLS_TO_EXECUTION_CHANGES: usize,
+        const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize,
+        F,
+        S,
+        A,
+    >(
+        &mut self,
+        f: F,
+    ) where
+        F: FnOnce(
+            S,
+            A,
+            Context,
+        ) -> Result<
+            Store<
+                SLOTS_PER_HISTORICAL_ROOT,
+                HISTORICAL_ROOTS_LIMIT,
+                ETH1_DATA_VOTES_BOUND,
+                VALIDATOR_REGISTRY_LIMIT,
+                EPOCHS_PER_HISTORICAL_VECTOR,
+                EPOCHS_PER_SLASHINGS_VECTOR,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                PENDING_ATTESTATIONS_BOUND,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_PROPOSER_SLASHINGS,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+                bellatrix::DefaultExecutionEngine,
+                capella::DefaultExecutionEngine,
+                deneb::DefaultExecutionEngine,
+            >,
+        >,
+        S: ssz_rs::Deserialize,
+        A: ssz_rs::Deserialize,
+        B: Into<
+            SignedBeaconBlock<
+                MAX_PROPOSER_SLASHINGS,
+                MAX_VALIDATORS_PER_COMMITTEE,
+                MAX_ATTESTER_SLASHINGS,
+                MAX_ATTESTATIONS,
+                MAX_DEPOSITS,
+                MAX_VOLUNTARY_EXITS,
+                SYNC_COMMITTEE_SIZE,
+                BYTES_PER_LOGS_BLOOM,
+                MAX_EXTRA_DATA_BYTES,
+                MAX_BYTES_PER_TRANSACTION,
+                MAX_TRANSACTIONS_PER_PAYLOAD,
+                MAX_WITHDRAWALS_PER_PAYLOAD,
+                MAX_BLS_TO_EXECUTION_CHANGES,
+                MAX_BLOB_COMMITMENTS_PER_BLOCK,
+            >,
+        >,
+    {
+        let anchor_state: S = self.load("anchor_state");
+        let anchor_block: A = self.load("anchor_block");
 
-impl OnMergeBlockTestCase {
-    pub fn from(test_case_path: &str) -> Self {
-        Self
-    }
+        let mut context = match self.config {
+            Config::Minimal => Context::for_minimal(),
+            Config::Mainnet => Context::for_mainnet(),
+        };
+        // NOTE: the pinned spec tests were generated with the proposer boost of their spec version
+        context.proposer_score_boost = 70;
+        let mut store = f(anchor_state, anchor_block, context).unwrap();
 
-    pub fn execute(&self) {
-        unimplemented!();
+        for step in &self.steps {
+            match step {
+                Step::Tick { tick } => store.on_tick(store.genesis_time + tick),
+                Step::Block { block, valid } => {
+                    let block: B = self.load(block);
+                    let mut block = block.into();
+                    let result = store.on_block(&mut block).and_then(|_| {
+                        for attestation in block.attestations().iter() {
+                            store.on_attestation(attestation, true)?;
+                        }
+                        for attester_slashing in block.attester_slashings().iter() {
+                            store.on_attester_slashing(attester_slashing)?;
+                        }
+                        Ok(())
+                    });
+                    assert_eq!(result.is_ok(), *valid, "{result:?}");
+                }
+                Step::Attestation { attestation, valid } => {
+                    let attestation: Attestation<MAX_VALIDATORS_PER_COMMITTEE> =
+                        self.load(attestation);
+                    let result = store.on_attestation(&attestation, false);
+                    assert_eq!(result.is_ok(), *valid, "{result:?}");
+                }
+                Step::AttesterSlashing { attester_slashing, valid } => {
+                    let attester_slashing: AttesterSlashing<MAX_VALIDATORS_PER_COMMITTEE> =
+                        self.load(attester_slashing);
+                    let result = store.on_attester_slashing(&attester_slashing);
+                    assert_eq!(result.is_ok(), *valid, "{result:?}");
+                }
+                Step::PowBlock { pow_block } => {
+                    let pow_block: PowBlock = self.load(pow_block);
+                    store.pow_blocks.insert(pow_block.block_hash.clone(), pow_block);
+                }
+                Step::Checks { checks } => {
+                    if let Some(time) = checks.time {
+                        assert_eq!(store.time, time);
+                    }
+                    if let Some(head) = &checks.head {
+                        let head_root = store.get_head().unwrap();
+                        assert_eq!(head_root, head.root);
+                        assert_eq!(store.block_states[&head_root].slot(), head.slot);
+                    }
+                    if let Some(checkpoint) = &checks.justified_checkpoint {
+                        assert_eq!(store.justified_checkpoint.epoch, checkpoint.epoch);
+                        assert_eq!(store.justified_checkpoint.root, checkpoint.root);
+                    }
+                    if let Some(checkpoint) = &checks.finalized_checkpoint {
+                        assert_eq!(store.finalized_checkpoint.epoch, checkpoint.epoch);
+                        assert_eq!(store.finalized_checkpoint.root, checkpoint.root);
+                    }
+                    if let Some(checkpoint) = &checks.best_justified_checkpoint {
+                        assert_eq!(store.best_justified_checkpoint.epoch, checkpoint.epoch);
+                        assert_eq!(store.best_justified_checkpoint.root, checkpoint.root);
+                    }
+                    if let Some(proposer_boost_root) = &checks.proposer_boost_root {
+                        assert_eq!(&store.proposer_boost_root, proposer_boost_root);
+                    }
+                }
+            }
+        }
     }
 }
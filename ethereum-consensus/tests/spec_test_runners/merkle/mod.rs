@@ -1,11 +1,48 @@
-pub struct SingleProofTestCase;
+use crate::test_utils::{load_snappy_ssz, load_yaml};
+use ethereum_consensus::{
+    primitives::Root,
+    ssz::{
+        prelude::*,
+        proofs::{compute_merkle_node, GeneralizedIndex},
+    },
+};
+use serde::Deserialize;
 
-impl SingleProofTestCase {
+#[derive(Deserialize)]
+struct Proof {
+    leaf: Root,
+    leaf_index: GeneralizedIndex,
+    branch: Vec<Root>,
+}
+
+pub struct SingleProofTestCase<O> {
+    object: O,
+    proof: Proof,
+}
+
+impl<O> SingleProofTestCase<O>
+where
+    O: ssz_rs::Deserialize + Merkleized + Prove,
+{
     pub fn from(test_case_path: &str) -> Self {
-        Self
+        let path = test_case_path.to_string() + "/object.ssz_snappy";
+        let object: O = load_snappy_ssz(&path).unwrap();
+
+        let path = test_case_path.to_string() + "/proof.yaml";
+        let proof: Proof = load_yaml(&path);
+
+        Self { object, proof }
     }
 
-    pub fn execute(&self) {
-        unimplemented!();
+    pub fn execute(&mut self) {
+        let leaf_index = self.proof.leaf_index;
+        let leaf = compute_merkle_node(&mut self.object, leaf_index).unwrap();
+        assert_eq!(leaf, self.proof.leaf);
+
+        let branch = compute_merkle_proof(&mut self.object, leaf_index).unwrap();
+        assert_eq!(branch, self.proof.branch);
+
+        let root = self.object.hash_tree_root().unwrap();
+        assert!(verify_merkle_proof(&leaf, &branch, leaf_index, &root));
     }
 }
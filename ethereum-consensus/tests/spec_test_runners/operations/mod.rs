@@ -1,5 +1,5 @@
 use crate::test_utils::{load_snappy_ssz, load_yaml, Config};
-use ethereum_consensus::state_transition::{Context, Result};
+use ethereum_consensus::state_transition::{Context, Observer, Result, SignatureSets};
 use serde::Deserialize;
 use std::fmt;
 
@@ -33,14 +33,14 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &T, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &T, &mut SignatureSets, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
 
-        let result = f(&mut self.pre, &self.operation, &context);
+        let result = f(&mut self.pre, &self.operation, &mut SignatureSets::default(), &context);
 
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
@@ -80,14 +80,15 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &mut T, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &mut T, &mut SignatureSets, &mut dyn Observer, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
 
-        let result = f(&mut self.pre, &mut self.operation, &context);
+        let result =
+            f(&mut self.pre, &mut self.operation, &mut SignatureSets::default(), &mut (), &context);
 
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
@@ -174,7 +175,7 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &mut T, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &mut T, &mut dyn Observer, &Context) -> Result<()>,
     {
         if let Some(operation) = self.operation.as_mut() {
             let context = match self.config {
@@ -182,7 +183,7 @@ where
                 Config::Mainnet => Context::for_mainnet(),
             };
 
-            let result = f(&mut self.pre, operation, &context);
+            let result = f(&mut self.pre, operation, &mut (), &context);
 
             if let Some(post) = self.post.as_ref() {
                 assert_eq!(&self.pre, post);
@@ -225,7 +226,7 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &mut T, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &mut T, &mut SignatureSets, &mut dyn Observer, &Context) -> Result<()>,
     {
         if let Some(operation) = self.operation.as_mut() {
             let context = match self.config {
@@ -233,7 +234,8 @@ where
                 Config::Mainnet => Context::for_mainnet(),
             };
 
-            let result = f(&mut self.pre, operation, &context);
+            let result =
+                f(&mut self.pre, operation, &mut SignatureSets::default(), &mut (), &context);
 
             if let Some(post) = self.post.as_ref() {
                 assert_eq!(&self.pre, post);
@@ -276,14 +278,15 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &mut T, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &mut T, &mut SignatureSets, &mut dyn Observer, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
 
-        let result = f(&mut self.pre, &mut self.operation, &context);
+        let result =
+            f(&mut self.pre, &mut self.operation, &mut SignatureSets::default(), &mut (), &context);
 
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
@@ -323,14 +326,14 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &T, &Context) -> Result<()>,
+        F: FnOnce(&mut S, &T, &mut SignatureSets, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
 
-        let result = f(&mut self.pre, &self.operation, &context);
+        let result = f(&mut self.pre, &self.operation, &mut SignatureSets::default(), &context);
 
         if let Some(post) = self.post.as_ref() {
             assert_eq!(&self.pre, post);
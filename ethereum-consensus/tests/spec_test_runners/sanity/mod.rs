@@ -21,8 +21,8 @@ struct BlocksMeta {
 
 impl<S, B> BlocksTestCase<S, B>
 where
-    S: fmt::Debug + ssz_rs::Deserialize + PartialEq<S>,
-    B: fmt::Debug + ssz_rs::Deserialize + PartialEq<B>,
+    S: fmt::Debug + Clone + ssz_rs::Deserialize + PartialEq<S>,
+    B: fmt::Debug + Clone + ssz_rs::Deserialize + PartialEq<B>,
 {
     pub fn from(test_case_path: &str) -> Self {
         let path = test_case_path.to_string() + "/pre.ssz_snappy";
@@ -50,17 +50,23 @@ where
 
     pub fn execute<F>(&mut self, f: F)
     where
-        F: FnOnce(&mut S, &mut [B], Validation, &Context) -> Result<()>,
+        F: Fn(&mut S, &mut [B], Validation, &Context) -> Result<()>,
     {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
-        let result = f(&mut self.pre, &mut self.blocks, Validation::Enabled, &context);
-        if let Some(post) = self.post.as_ref() {
-            assert_eq!(&self.pre, post);
-        } else {
-            assert!(result.is_err())
+        // NOTE: batched signature verification must agree with verifying each signature
+        let batched = Validation { batch_signatures: true, ..Validation::enabled() };
+        for validation in [Validation::enabled(), batched] {
+            let mut pre = self.pre.clone();
+            let mut blocks = self.blocks.clone();
+            let result = f(&mut pre, &mut blocks, validation, &context);
+            if let Some(post) = self.post.as_ref() {
+                assert_eq!(&pre, post);
+            } else {
+                assert!(result.is_err())
+            }
         }
     }
 }
@@ -1,6 +1,8 @@
 use crate::test_utils::{load_yaml, Config};
 use ethereum_consensus::{
-    phase0::compute_shuffled_index, primitives::Bytes32, state_transition::Context,
+    phase0::{compute_shuffled_index, compute_shuffled_list},
+    primitives::Bytes32,
+    state_transition::Context,
 };
 use serde::Deserialize;
 
@@ -30,19 +32,25 @@ impl CoreTestCase {
     }
 
     pub fn execute(&self) {
-        self.test_unoptimized_forward_shuffle();
-    }
-
-    fn test_unoptimized_forward_shuffle(&self) {
         let context = match self.config {
             Config::Minimal => Context::for_minimal(),
             Config::Mainnet => Context::for_mainnet(),
         };
+        self.test_unoptimized_forward_shuffle(&context);
+        self.test_whole_list_shuffle(&context);
+    }
 
+    fn test_unoptimized_forward_shuffle(&self, context: &Context) {
         for index in 0..self.data.count {
             let result =
-                compute_shuffled_index(index, self.data.count, &self.data.seed, &context).unwrap();
+                compute_shuffled_index(index, self.data.count, &self.data.seed, context).unwrap();
             assert_eq!(result, self.data.mapping[index]);
         }
     }
+
+    fn test_whole_list_shuffle(&self, context: &Context) {
+        let indices = (0..self.data.count).collect::<Vec<_>>();
+        let result = compute_shuffled_list(&indices, &self.data.seed, context);
+        assert_eq!(result, self.data.mapping);
+    }
 }
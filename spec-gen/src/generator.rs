@@ -33,6 +33,7 @@ impl Fork {
                 "beacon_block",
                 "beacon_state",
                 "block_processing",
+                "block_production",
                 "constants",
                 "epoch_processing",
                 "genesis",
@@ -46,6 +47,7 @@ impl Fork {
                 "beacon_block",
                 "beacon_state",
                 "block_processing",
+                "block_production",
                 "constants",
                 "epoch_processing",
                 "genesis",
@@ -59,6 +61,7 @@ impl Fork {
                 "beacon_state",
                 "blinded_beacon_block",
                 "block_processing",
+                "block_production",
                 "epoch_processing",
                 "execution_engine",
                 "execution_payload",
@@ -72,12 +75,14 @@ impl Fork {
                 "beacon_state",
                 "blinded_beacon_block",
                 "block_processing",
+                "block_production",
                 "bls_to_execution_change",
                 "epoch_processing",
                 "execution_engine",
                 "execution_payload",
                 "genesis",
                 "helpers",
+                "light_client",
                 "withdrawal",
             ],
             Self::Deneb => &[
@@ -87,11 +92,13 @@ impl Fork {
                 "blinded_blob_sidecar",
                 "blob_sidecar",
                 "block_processing",
+                "block_production",
                 "epoch_processing",
                 "execution_engine",
                 "execution_payload",
                 "genesis",
                 "helpers",
+                "light_client",
             ],
         }
     }
@@ -113,7 +120,8 @@ impl Fork {
                         "get_attestation_deltas" |
                         "get_attestation_component_deltas" |
                         "get_attesting_balance" |
-                        "process_participation_record_updates"
+                        "process_participation_record_updates" |
+                        "get_validator_participation"
                 )
             }
             _ => false,
@@ -128,7 +136,7 @@ impl Fork {
                     use std::cmp;
                     use std::collections::HashSet;
                     use crate::ssz::prelude::*;
-                    use crate::crypto::{hash, fast_aggregate_verify};
+                    use crate::crypto::hash;
 
                     pub use crate::altair::fork::upgrade_to_altair;
                 };
@@ -138,12 +146,13 @@ impl Fork {
                 let fragment: syn::File = parse_quote! {
                     use std::cmp;
                     use std::mem;
-                    use std::collections::{HashSet, HashMap};
+                    use std::collections::HashSet;
                     use std::iter::zip;
                     use crate::ssz::prelude::*;
                     use integer_sqrt::IntegerSquareRoot;
-                    use crate::crypto::{hash, fast_aggregate_verify, eth_aggregate_public_keys, eth_fast_aggregate_verify};
+                    use crate::crypto::{hash, aggregate, eth_aggregate_public_keys, eth_fast_aggregate_verify};
 
+                    use crate::rewards::{AttestationRewards, BlockRewards, IdealAttestationRewards, SyncCommitteeReward, TotalAttestationRewards};
                     pub use crate::bellatrix::fork::upgrade_to_bellatrix;
                 };
                 fragment.items
@@ -152,12 +161,13 @@ impl Fork {
                 let fragment: syn::File = parse_quote! {
                     use std::cmp;
                     use std::mem;
-                    use std::collections::{HashSet, HashMap};
+                    use std::collections::HashSet;
                     use std::iter::zip;
                     use crate::ssz::prelude::*;
                     use integer_sqrt::IntegerSquareRoot;
-                    use crate::crypto::{hash, fast_aggregate_verify, eth_aggregate_public_keys, eth_fast_aggregate_verify};
+                    use crate::crypto::{hash, aggregate, eth_aggregate_public_keys, eth_fast_aggregate_verify};
 
+                    use crate::rewards::{AttestationRewards, BlockRewards, IdealAttestationRewards, SyncCommitteeReward, TotalAttestationRewards};
                     pub use crate::capella::fork::upgrade_to_capella;
                 };
                 fragment.items
@@ -166,12 +176,13 @@ impl Fork {
                 let fragment: syn::File = parse_quote! {
                     use std::cmp;
                     use std::mem;
-                    use std::collections::{HashSet, HashMap};
+                    use std::collections::HashSet;
                     use std::iter::zip;
                     use crate::ssz::prelude::*;
                     use integer_sqrt::IntegerSquareRoot;
-                    use crate::crypto::{hash, fast_aggregate_verify, eth_aggregate_public_keys, eth_fast_aggregate_verify};
+                    use crate::crypto::{hash, aggregate, eth_aggregate_public_keys, eth_fast_aggregate_verify, SignatureSet};
 
+                    use crate::rewards::{AttestationRewards, BlockRewards, IdealAttestationRewards, SyncCommitteeReward, TotalAttestationRewards};
                     pub use crate::deneb::fork::upgrade_to_deneb;
                 };
                 fragment.items
@@ -405,11 +416,19 @@ impl Spec {
                 let mut all_arguments = vec![];
                 for name in type_names {
                     if let Some(target_module) = index.get(&name) {
-                        let target_module = self.diff.modules.get(target_module).unwrap();
-                        let container = target_module
-                            .containers
-                            .iter()
-                            .find(|&c| c.name == name)
+                        // NOTE: containers this fork inherits without changes may not have been
+                        // merged into this fork yet, so fall back to the previous definition
+                        let find_container = |module: &Module| {
+                            module.containers.iter().find(|&c| c.name == name).cloned()
+                        };
+                        let container = self
+                            .diff
+                            .modules
+                            .get(target_module)
+                            .and_then(find_container)
+                            .or_else(|| {
+                                previous.diff.modules.get(target_module).and_then(find_container)
+                            })
                             .expect("internal state integrity");
 
                         let arguments = generics_to_arguments(&container.item.generics);
@@ -594,6 +613,9 @@ fn parse_fork_diff_with_symbol_index(fork: &Fork) -> (ForkDiff, HashMap<String,
                 Item::Impl(_) => {
                     println!("skipping item: `impl` block in {source_path}");
                 }
+                Item::Mod(_) => {
+                    println!("skipping item: `mod` item in {source_path}");
+                }
                 Item::Trait(item) => {
                     let item = TraitDef::new(item, *fork);
                     if item.is_pub() {
@@ -616,7 +638,7 @@ fn render(fork: &Fork, items: &[Item]) {
         pub use crate::signing::*;
     };
     let state_transition_import = parse_quote! {
-        pub use crate::state_transition::{Result, Context, Validation};
+        pub use crate::state_transition::{Result, Context, Validation, SignatureSets, SignatureStrategy, Event, Observer};
     };
     let error_import = parse_quote! {
         pub use crate::error::*;
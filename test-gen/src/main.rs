@@ -193,6 +193,13 @@ fn generate_suite_src(
             };
             writeln!(src, "use ethereum_consensus::{pre_fork}::{config} as pre_spec;",).unwrap();
         }
+        "fork_choice" => {
+            writeln!(src, "use ethereum_consensus::fork_choice::{config}::Store;").unwrap();
+            writeln!(src, "use ethereum_consensus::phase0::BeaconBlockHeader;").unwrap();
+            writeln!(src, "use ethereum_consensus::ssz::prelude::*;").unwrap();
+            writeln!(src, "use ethereum_consensus::state_transition::{config}::ExecutionEngine;")
+                .unwrap();
+        }
         "fork" => match spec {
             Spec::Altair => {
                 writeln!(src, "use ethereum_consensus::phase0::{config} as phase0;").unwrap();
@@ -6,6 +6,18 @@ pub type TestIndex = HashMap<
     HashMap<String, HashMap<String, HashMap<String, HashMap<String, HashMap<String, String>>>>>,
 >;
 
+// shared by every handler of the `fork_choice` runner
+const FORK_CHOICE_EXECUTION_HANDLER: &str = "execute(|anchor_state: spec::BeaconState, mut anchor_block: spec::BeaconBlock, context| {
+                    let anchor_block = BeaconBlockHeader {
+                        slot: anchor_block.slot,
+                        proposer_index: anchor_block.proposer_index,
+                        parent_root: anchor_block.parent_root,
+                        state_root: anchor_block.state_root,
+                        body_root: anchor_block.body.hash_tree_root()?,
+                    };
+                    Store::new(anchor_state.into(), &anchor_block, ExecutionEngine::default(), context)
+                })";
+
 pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>> {
     HashMap::from([
         ("operations",
@@ -73,7 +85,7 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     preamble: Default::default(),
                     execution_handler: HashMap::from_iter([(Spec::All, "execute(|state, operation, context, execution_valid| {
                     let execution_engine = spec::DefaultExecutionEngine::new(execution_valid);
-                    spec::process_execution_payload(state, operation, &execution_engine, context)
+                    spec::process_execution_payload(state, operation, &execution_engine, spec::Validation::default(), context)
                 })"
                     .to_string())]),
                 },
@@ -222,6 +234,41 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                 },
             ),
         ])),
+        ("fork_choice",
+        HashMap::from([
+            (
+                "ex_ante",
+                Auxillary {
+                    test_case_type_generics: "spec::SignedBeaconBlock".to_string(),
+                    preamble: Default::default(),
+                    execution_handler: HashMap::from_iter([(Spec::All, FORK_CHOICE_EXECUTION_HANDLER.to_string())]),
+                },
+            ),
+            (
+                "get_head",
+                Auxillary {
+                    test_case_type_generics: "spec::SignedBeaconBlock".to_string(),
+                    preamble: Default::default(),
+                    execution_handler: HashMap::from_iter([(Spec::All, FORK_CHOICE_EXECUTION_HANDLER.to_string())]),
+                },
+            ),
+            (
+                "on_block",
+                Auxillary {
+                    test_case_type_generics: "spec::SignedBeaconBlock".to_string(),
+                    preamble: Default::default(),
+                    execution_handler: HashMap::from_iter([(Spec::All, FORK_CHOICE_EXECUTION_HANDLER.to_string())]),
+                },
+            ),
+            (
+                "on_merge_block",
+                Auxillary {
+                    test_case_type_generics: "spec::SignedBeaconBlock".to_string(),
+                    preamble: Default::default(),
+                    execution_handler: HashMap::from_iter([(Spec::All, FORK_CHOICE_EXECUTION_HANDLER.to_string())]),
+                },
+            ),
+        ])),
         ("sanity",
         HashMap::from([
             (
@@ -231,7 +278,7 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     preamble: Default::default(),
                     execution_handler: HashMap::from_iter([(Spec::All, "execute(|state, offset, context| {
                     let target_slot = state.slot + offset;
-                    spec::process_slots(state, target_slot, context).unwrap();
+                    spec::process_slots(state, target_slot, &mut (), context).unwrap();
                 })"
                     .to_string())]),
                 },
@@ -244,17 +291,17 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     preamble: HashMap::from_iter([(Spec::Bellatrix, "let execution_engine = spec::DefaultExecutionEngine::default();".to_string())]),
                     execution_handler: HashMap::from_iter([(Spec::Phase0, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, validation, context)?;
+                            spec::state_transition(state, block, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string()), (Spec::Altair, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, validation, context)?;
+                            spec::state_transition(state, block, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string()), (Spec::Bellatrix, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, &execution_engine, validation, context)?;
+                            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string())]),
@@ -394,23 +441,34 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     preamble: HashMap::from_iter([(Spec::Bellatrix, "let execution_engine = spec::DefaultExecutionEngine::default();".to_string())]),
                     execution_handler: HashMap::from_iter([(Spec::Phase0, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, validation, context)?;
+                            spec::state_transition(state, block, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string()), (Spec::Altair, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, validation, context)?;
+                            spec::state_transition(state, block, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string()), (Spec::Bellatrix, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, &execution_engine, validation, context)?;
+                            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string())]),
                 },
             ),
         ])),
+        ("merkle",
+        HashMap::from([
+            (
+                "single_proof",
+                Auxillary {
+                    test_case_type_generics: "spec::BeaconState".to_string(),
+                    preamble: Default::default(),
+                    execution_handler: HashMap::from_iter([(Spec::All, "execute()".to_string())]),
+                },
+            ),
+        ])),
         ("ssz_static",
         HashMap::from([
             (
@@ -956,17 +1014,17 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     preamble: HashMap::from_iter([(Spec::Bellatrix, "let execution_engine = spec::DefaultExecutionEngine::default();".to_string())]),
                     execution_handler: HashMap::from_iter([(Spec::Phase0, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, validation, context)?;
+                            spec::state_transition(state, block, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string()), (Spec::Altair, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, validation, context)?;
+                            spec::state_transition(state, block, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string()), (Spec::Bellatrix, "execute(|state, blocks, validation, context| {
                         for block in blocks.iter_mut() {
-                            spec::state_transition(state, block, &execution_engine, validation, context)?;
+                            spec::state_transition(state, block, &execution_engine, validation, &mut (), context)?;
                         }
                         Ok(())
                     })".to_string())]),
@@ -981,7 +1039,7 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     test_case_type_generics: "spec::BeaconState".to_string(),
                     preamble: Default::default(),
                     execution_handler: HashMap::from_iter([(Spec::All, "execute(|state, context| {
-                        spec::process_effective_balance_updates(state, context);
+                        spec::process_effective_balance_updates(state, &mut (), context);
                         Ok(())
                 })"
                     .to_string())]),
@@ -1064,7 +1122,7 @@ pub fn build_index() -> HashMap<&'static str, HashMap<&'static str, Auxillary>>
                     test_case_type_generics: "spec::BeaconState".to_string(),
                     preamble: Default::default(),
                     execution_handler: HashMap::from_iter([(Spec::All, "execute(|state, context| {
-                        spec::process_registry_updates(state, context);
+                        spec::process_registry_updates(state, &mut (), context);
                         Ok(())
                 })"
                     .to_string())]),